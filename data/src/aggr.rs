@@ -1,5 +1,8 @@
+pub mod range;
+pub mod renko;
 pub mod ticks;
 pub mod time;
+pub mod volume;
 
 use serde::{Deserialize, Serialize};
 
@@ -27,3 +30,23 @@ impl std::fmt::Display for TickCount {
         write!(f, "{}T", self.0)
     }
 }
+
+/// What closes a bar on an index-based basis, every variant is driven by trades
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum BarRule {
+    Ticks(TickCount),
+    Volume(volume::VolumeSize),
+    Range(range::RangeTicks),
+    Renko(renko::RenkoBox),
+}
+
+impl BarRule {
+    /// X-axis units a single bar advances on index-based charts, which is its trade count
+    /// for tick bars and one for bars that don't hold a fixed number of trades
+    pub fn index_units_per_bar(&self) -> u64 {
+        match self {
+            BarRule::Ticks(count) => u64::from(count.0),
+            _ => 1,
+        }
+    }
+}
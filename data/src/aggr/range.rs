@@ -0,0 +1,36 @@
+use exchange::util::{Price, PriceStep};
+use serde::{Deserialize, Serialize};
+
+/// High-low span of a range bar, in multiples of the chart's tick size
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct RangeTicks(pub u16);
+
+impl RangeTicks {
+    pub const ALL: [RangeTicks; 6] = [
+        RangeTicks(5),
+        RangeTicks(10),
+        RangeTicks(20),
+        RangeTicks(50),
+        RangeTicks(100),
+        RangeTicks(200),
+    ];
+
+    pub fn is_custom(&self) -> bool {
+        !Self::ALL.contains(self)
+    }
+
+    /// Whether a trade at `price` would stretch a bar spanning `low..=high` past the range,
+    /// in which case it opens the next bar instead
+    pub fn is_exceeded_by(&self, low: Price, high: Price, price: Price, step: PriceStep) -> bool {
+        let low = low.min(price);
+        let high = high.max(price);
+
+        low.add_steps(i64::from(self.0), step) < high
+    }
+}
+
+impl std::fmt::Display for RangeTicks {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        write!(f, "{}R", self.0)
+    }
+}
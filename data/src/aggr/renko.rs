@@ -0,0 +1,60 @@
+use exchange::util::{Price, PriceStep};
+use serde::{Deserialize, Serialize};
+
+/// Renko brick size in multiples of the chart's tick size, and how many boxes
+/// price has to travel against the last brick before a reversal brick prints
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct RenkoBox {
+    pub size: u16,
+    pub reversal: u16,
+}
+
+impl RenkoBox {
+    pub const DEFAULT_REVERSAL: u16 = 2;
+
+    /// Preset box sizes, in ticks
+    pub const SIZES: [u16; 6] = [5, 10, 20, 50, 100, 200];
+
+    pub fn is_custom_size(&self) -> bool {
+        !Self::SIZES.contains(&self.size)
+    }
+
+    /// Brick that a trade at `price` completes, as `(open, close)`, given the level the
+    /// forming brick started from and whether the previous brick was rising
+    ///
+    /// Called repeatedly for the same price, since a single trade can print several bricks.
+    pub fn next_brick(
+        &self,
+        anchor: Price,
+        prev_rising: Option<bool>,
+        price: Price,
+        step: PriceStep,
+    ) -> Option<(Price, Price)> {
+        let size = i64::from(self.size.max(1));
+        let reversal = i64::from(self.reversal.max(1));
+
+        let (up_boxes, down_boxes) = match prev_rising {
+            Some(true) => (1, reversal),
+            Some(false) => (reversal, 1),
+            None => (1, 1),
+        };
+
+        let up_close = anchor.add_steps(up_boxes * size, step);
+        if price >= up_close {
+            return Some((up_close.add_steps(-size, step), up_close));
+        }
+
+        let down_close = anchor.add_steps(-down_boxes * size, step);
+        if price <= down_close {
+            return Some((down_close.add_steps(size, step), down_close));
+        }
+
+        None
+    }
+}
+
+impl std::fmt::Display for RenkoBox {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        write!(f, "{}x{} Renko", self.size, self.reversal)
+    }
+}
@@ -1,6 +1,6 @@
-use crate::aggr;
+use crate::aggr::{self, BarRule, renko::RenkoBox};
 use crate::chart::kline::{ClusterKind, KlineTrades, NPoc};
-use exchange::util::{Price, PriceStep};
+use exchange::util::{Price, PriceStep, Qty};
 use exchange::{Kline, Trade};
 
 use std::collections::BTreeMap;
@@ -24,8 +24,8 @@ impl TickAccumulation {
             low: trade.price,
             close: trade.price,
             volume: (
-                if trade.is_sell { 0.0 } else { trade.qty },
-                if trade.is_sell { trade.qty } else { 0.0 },
+                if trade.is_sell { Qty::ZERO } else { trade.qty },
+                if trade.is_sell { trade.qty } else { Qty::ZERO },
             ),
         };
 
@@ -36,6 +36,25 @@ impl TickAccumulation {
         }
     }
 
+    /// Bar with no trades yet, sitting at `price`, e.g. the next Renko brick
+    /// after a single trade printed several at once
+    pub fn at_level(time: u64, price: Price) -> Self {
+        let kline = Kline {
+            time,
+            open: price,
+            high: price,
+            low: price,
+            close: price,
+            volume: (Qty::ZERO, Qty::ZERO),
+        };
+
+        Self {
+            tick_count: 0,
+            kline,
+            footprint: KlineTrades::new(),
+        }
+    }
+
     pub fn update_with_trade(&mut self, trade: &Trade, step: PriceStep) {
         self.tick_count += 1;
         self.kline.high = self.kline.high.max(trade.price);
@@ -55,9 +74,9 @@ impl TickAccumulation {
         self.footprint.add_trade_to_nearest_bin(trade, step);
     }
 
-    pub fn max_cluster_qty(&self, cluster_kind: ClusterKind, highest: Price, lowest: Price) -> f32 {
+    pub fn max_cluster_qty(&self, cluster_kind: ClusterKind, highest: Price, lowest: Price) -> Qty {
         match cluster_kind {
-            ClusterKind::BidAsk => self.footprint.max_qty_by(highest, lowest, f32::max),
+            ClusterKind::BidAsk => self.footprint.max_qty_by(highest, lowest, Qty::max),
             ClusterKind::DeltaProfile => self
                 .footprint
                 .max_qty_by(highest, lowest, |buy, sell| (buy - sell).abs()),
@@ -72,6 +91,14 @@ impl TickAccumulation {
         self.tick_count >= interval.0 as usize
     }
 
+    /// Snaps a completed Renko brick to its box levels, keeping the traded extremes as wicks
+    fn set_brick(&mut self, open: Price, close: Price) {
+        self.kline.open = open;
+        self.kline.close = close;
+        self.kline.high = self.kline.high.max(open.max(close));
+        self.kline.low = self.kline.low.min(open.min(close));
+    }
+
     pub fn poc_price(&self) -> Option<Price> {
         self.footprint.poc_price()
     }
@@ -87,12 +114,12 @@ impl TickAccumulation {
 
 pub struct TickAggr {
     pub datapoints: Vec<TickAccumulation>,
-    pub interval: aggr::TickCount,
+    pub interval: BarRule,
     pub tick_size: PriceStep,
 }
 
 impl TickAggr {
-    pub fn new(interval: aggr::TickCount, tick_size: PriceStep, raw_trades: &[Trade]) -> Self {
+    pub fn new(interval: BarRule, tick_size: PriceStep, raw_trades: &[Trade]) -> Self {
         let mut tick_aggr = Self {
             datapoints: Vec::new(),
             interval,
@@ -123,7 +150,7 @@ impl TickAggr {
             .map(|dp| (dp, self.datapoints.len() - 1))
     }
 
-    pub fn volume_data(&self) -> BTreeMap<u64, (f32, f32)> {
+    pub fn volume_data(&self) -> BTreeMap<u64, (Qty, Qty)> {
         self.into()
     }
 
@@ -131,24 +158,37 @@ impl TickAggr {
         let mut updated_indices = Vec::new();
 
         for trade in buffer {
-            if self.datapoints.is_empty() {
-                self.datapoints
-                    .push(TickAccumulation::new(trade, self.tick_size));
-                updated_indices.push(0);
+            let opens_new_bar = self
+                .datapoints
+                .last()
+                .is_none_or(|last| self.closes_before(last, trade));
+
+            if opens_new_bar {
+                let dp = match self.interval {
+                    BarRule::Renko(_) if self.datapoints.is_empty() => {
+                        let mut dp = TickAccumulation::at_level(
+                            trade.time,
+                            trade.price.round_to_step(self.tick_size),
+                        );
+                        dp.update_with_trade(trade, self.tick_size);
+                        dp
+                    }
+                    _ => TickAccumulation::new(trade, self.tick_size),
+                };
+                self.datapoints.push(dp);
+                updated_indices.push(self.datapoints.len() - 1);
             } else {
                 let last_idx = self.datapoints.len() - 1;
 
-                if self.datapoints[last_idx].is_full(self.interval) {
-                    self.datapoints
-                        .push(TickAccumulation::new(trade, self.tick_size));
-                    updated_indices.push(self.datapoints.len() - 1);
-                } else {
-                    self.datapoints[last_idx].update_with_trade(trade, self.tick_size);
-                    if !updated_indices.contains(&last_idx) {
-                        updated_indices.push(last_idx);
-                    }
+                self.datapoints[last_idx].update_with_trade(trade, self.tick_size);
+                if !updated_indices.contains(&last_idx) {
+                    updated_indices.push(last_idx);
                 }
             }
+
+            if let BarRule::Renko(renko) = self.interval {
+                self.print_renko_bricks(renko, trade, &mut updated_indices);
+            }
         }
 
         for idx in updated_indices {
@@ -160,6 +200,49 @@ impl TickAggr {
         self.update_poc_status();
     }
 
+    /// Whether the bar in formation is already complete, so `trade` has to open the next one
+    fn closes_before(&self, last: &TickAccumulation, trade: &Trade) -> bool {
+        match self.interval {
+            BarRule::Ticks(count) => last.is_full(count),
+            BarRule::Volume(size) => size.is_reached(last.kline.volume.0, last.kline.volume.1),
+            BarRule::Range(range) => {
+                range.is_exceeded_by(last.kline.low, last.kline.high, trade.price, self.tick_size)
+            }
+            // bricks close on the trade that reaches the next box, see `print_renko_bricks`
+            BarRule::Renko(_) => false,
+        }
+    }
+
+    /// Closes the forming brick for every box `trade` moved price through,
+    /// the brick left forming always starts at the last brick's close
+    fn print_renko_bricks(
+        &mut self,
+        renko: RenkoBox,
+        trade: &Trade,
+        updated_indices: &mut Vec<usize>,
+    ) {
+        loop {
+            let last_idx = self.datapoints.len() - 1;
+
+            let prev_rising = last_idx.checked_sub(1).map(|idx| {
+                let kline = &self.datapoints[idx].kline;
+                kline.close > kline.open
+            });
+            let anchor = self.datapoints[last_idx].kline.open;
+
+            let Some((open, close)) =
+                renko.next_brick(anchor, prev_rising, trade.price, self.tick_size)
+            else {
+                break;
+            };
+
+            self.datapoints[last_idx].set_brick(open, close);
+            self.datapoints
+                .push(TickAccumulation::at_level(trade.time, close));
+            updated_indices.push(self.datapoints.len() - 1);
+        }
+    }
+
     pub fn update_poc_status(&mut self) {
         let updates = self
             .datapoints
@@ -246,8 +329,8 @@ impl TickAggr {
         latest: usize,
         highest: Price,
         lowest: Price,
-    ) -> f32 {
-        let mut max_cluster_qty: f32 = 0.0;
+    ) -> Qty {
+        let mut max_cluster_qty = Qty::ZERO;
 
         self.datapoints
             .iter()
@@ -263,7 +346,7 @@ impl TickAggr {
     }
 }
 
-impl From<&TickAggr> for BTreeMap<u64, (f32, f32)> {
+impl From<&TickAggr> for BTreeMap<u64, (Qty, Qty)> {
     /// Converts datapoints into a map of timestamps and volume data
     fn from(tick_aggr: &TickAggr) -> Self {
         tick_aggr
@@ -274,3 +357,66 @@ impl From<&TickAggr> for BTreeMap<u64, (f32, f32)> {
             .collect()
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::aggr::{range::RangeTicks, volume::VolumeSize};
+
+    fn trade(time: u64, price: f32, qty: f32) -> Trade {
+        Trade {
+            time,
+            is_sell: false,
+            price: Price::from_f32(price),
+            qty: Qty::from_f32(qty),
+        }
+    }
+
+    fn closes(aggr: &TickAggr) -> Vec<f32> {
+        aggr.datapoints
+            .iter()
+            .map(|dp| dp.kline.close.to_f32())
+            .collect()
+    }
+
+    #[test]
+    fn volume_bar_keeps_crossing_trade() {
+        let trades = [
+            trade(1, 100.0, 0.6),
+            trade(2, 101.0, 0.6),
+            trade(3, 102.0, 0.3),
+        ];
+        let rule = BarRule::Volume(VolumeSize(Qty::from_f32(1.0)));
+        let aggr = TickAggr::new(rule, PriceStep::from_f32(1.0), &trades);
+
+        assert_eq!(closes(&aggr), vec![101.0, 102.0]);
+        assert_eq!(aggr.datapoints[0].tick_count, 2);
+    }
+
+    #[test]
+    fn range_bar_opens_on_breaking_trade() {
+        let trades = [
+            trade(1, 100.0, 1.0),
+            trade(2, 102.0, 1.0),
+            trade(3, 99.0, 1.0),
+            trade(4, 103.0, 1.0),
+        ];
+        let rule = BarRule::Range(RangeTicks(3));
+        let aggr = TickAggr::new(rule, PriceStep::from_f32(1.0), &trades);
+
+        assert_eq!(closes(&aggr), vec![99.0, 103.0]);
+    }
+
+    #[test]
+    fn renko_prints_one_brick_per_box() {
+        let trades = [trade(1, 100.0, 1.0), trade(2, 106.0, 1.0)];
+        let rule = BarRule::Renko(RenkoBox {
+            size: 2,
+            reversal: 2,
+        });
+        let aggr = TickAggr::new(rule, PriceStep::from_f32(1.0), &trades);
+
+        // three rising bricks and the one left forming at the last close
+        assert_eq!(closes(&aggr), vec![102.0, 104.0, 106.0, 106.0]);
+    }
+}
@@ -4,7 +4,7 @@ use crate::chart::Basis;
 use crate::chart::heatmap::HeatmapDataPoint;
 use crate::chart::kline::{ClusterKind, KlineDataPoint, KlineTrades, NPoc};
 
-use exchange::util::{Price, PriceStep};
+use exchange::util::{Price, PriceStep, Qty};
 use exchange::{Kline, Timeframe, Trade};
 
 pub trait DataPoint {
@@ -29,6 +29,9 @@ pub struct TimeSeries<D: DataPoint> {
     pub datapoints: BTreeMap<u64, D>,
     pub interval: Timeframe,
     pub tick_size: PriceStep,
+    /// Open time of the live kline the latest bucket is being resampled from,
+    /// with what the bucket held before it
+    open_base: Option<(u64, Option<Kline>)>,
 }
 
 impl<D: DataPoint> TimeSeries<D> {
@@ -71,9 +74,9 @@ impl<D: DataPoint> TimeSeries<D> {
         }
     }
 
-    pub fn volume_data<'a>(&'a self) -> BTreeMap<u64, (f32, f32)>
+    pub fn volume_data<'a>(&'a self) -> BTreeMap<u64, (Qty, Qty)>
     where
-        BTreeMap<u64, (f32, f32)>: From<&'a TimeSeries<D>>,
+        BTreeMap<u64, (Qty, Qty)>: From<&'a TimeSeries<D>>,
     {
         self.into()
     }
@@ -121,12 +124,7 @@ impl<D: DataPoint> TimeSeries<D> {
         }
     }
 
-    pub fn check_kline_integrity(
-        &self,
-        earliest: u64,
-        latest: u64,
-        interval: u64,
-    ) -> Option<Vec<u64>> {
+    pub fn check_kline_integrity(&self, earliest: u64, latest: u64) -> Option<Vec<u64>> {
         let mut time = earliest;
         let mut missing_count = 0;
 
@@ -135,10 +133,11 @@ impl<D: DataPoint> TimeSeries<D> {
                 missing_count += 1;
                 break;
             }
-            time += interval;
+            time = self.interval.next_bucket_start(time);
         }
 
         if missing_count > 0 {
+            let interval = self.interval.to_milliseconds();
             let mut missing_keys = Vec::with_capacity(((latest - earliest) / interval) as usize);
             let mut time = earliest;
 
@@ -146,7 +145,7 @@ impl<D: DataPoint> TimeSeries<D> {
                 if !self.datapoints.contains_key(&time) {
                     missing_keys.push(time);
                 }
-                time += interval;
+                time = self.interval.next_bucket_start(time);
             }
 
             log::warn!(
@@ -166,20 +165,22 @@ impl TimeSeries<KlineDataPoint> {
             datapoints: BTreeMap::new(),
             interval,
             tick_size,
+            open_base: None,
         };
 
         timeseries.insert_klines(klines);
         timeseries
     }
 
-    pub fn with_trades(&self, trades: &[Trade]) -> TimeSeries<KlineDataPoint> {
+    pub fn with_trades(&self, trades: &[Trade], build_klines: bool) -> TimeSeries<KlineDataPoint> {
         let mut new_series = Self {
             datapoints: self.datapoints.clone(),
             interval: self.interval,
             tick_size: self.tick_size,
+            open_base: self.open_base,
         };
 
-        new_series.insert_trades_or_create_bucket(trades);
+        new_series.insert_trades_or_create_bucket(trades, build_klines);
         new_series
     }
 
@@ -199,17 +200,93 @@ impl TimeSeries<KlineDataPoint> {
         self.update_poc_status();
     }
 
-    pub fn insert_trades_or_create_bucket(&mut self, buffer: &[Trade]) {
+    /// Series of a higher `interval` built from the klines already loaded, `None` unless
+    /// its buckets are made of whole buckets of this one. Footprints start out empty.
+    ///
+    /// Buckets with gaps are left out for the integrity check to fetch. The latest one
+    /// stays open to live klines of either `interval` or this series' own.
+    pub fn resample(&self, interval: Timeframe) -> Option<Self> {
+        if !interval.resamples_from(self.interval) {
+            return None;
+        }
+
+        let mut klines: Vec<Kline> = Vec::new();
+        // (base klines are contiguous from the bucket start, open time of the next base kline)
+        let mut coverage = (false, 0);
+        let mut open_base = None;
+
+        for (&time, dp) in &self.datapoints {
+            let bucket_time = interval.bucket_start(time);
+
+            match klines.last_mut() {
+                Some(bucket) if bucket.time == bucket_time => {
+                    coverage.0 &= coverage.1 == time;
+                    open_base = Some((time, Some(*bucket)));
+                    bucket.merge(&dp.kline);
+                }
+                last => {
+                    if let Some(bucket) = last
+                        && !(coverage.0 && coverage.1 == interval.next_bucket_start(bucket.time))
+                    {
+                        klines.pop();
+                    }
+
+                    coverage.0 = time == bucket_time;
+                    open_base = Some((time, None));
+                    klines.push(Kline {
+                        time: bucket_time,
+                        ..dp.kline
+                    });
+                }
+            }
+            coverage.1 = self.interval.next_bucket_start(time);
+        }
+
+        if !coverage.0 {
+            klines.pop();
+            open_base = None;
+        }
+
+        let mut series = Self::new(interval, self.tick_size, &klines);
+        series.open_base = open_base;
+        Some(series)
+    }
+
+    /// Merges a live kline of a lower timeframe the series is resampled from,
+    /// returning the updated kline of its bucket
+    pub fn insert_base_kline(&mut self, kline: &Kline) -> Kline {
+        let time = self.interval.bucket_start(kline.time);
+
+        let settled = match self.open_base {
+            Some((base_time, settled)) if base_time == kline.time => settled,
+            _ => self.datapoints.get(&time).map(|dp| dp.kline),
+        };
+        self.open_base = Some((kline.time, settled));
+
+        let merged = match settled {
+            Some(mut bucket) => {
+                bucket.merge(kline);
+                bucket
+            }
+            None => Kline { time, ..*kline },
+        };
+
+        self.insert_klines(&[merged]);
+        merged
+    }
+
+    /// With `build_klines`, OHLC and volume of the buckets come from the trades too,
+    /// for timeframes that no venue streams klines of
+    pub fn insert_trades_or_create_bucket(&mut self, buffer: &[Trade], build_klines: bool) {
         if buffer.is_empty() {
             return;
         }
-        let aggr_time = self.interval.to_milliseconds();
         let mut updated_times = Vec::new();
 
         buffer.iter().for_each(|trade| {
-            let rounded_time = (trade.time / aggr_time) * aggr_time;
+            let rounded_time = self.interval.bucket_start(trade.time);
 
-            if !updated_times.contains(&rounded_time) {
+            if updated_times.last() != Some(&rounded_time) {
                 updated_times.push(rounded_time);
             }
 
@@ -223,14 +300,20 @@ impl TimeSeries<KlineDataPoint> {
                         high: trade.price,
                         low: trade.price,
                         close: trade.price,
-                        volume: (0.0, 0.0),
+                        volume: (Qty::ZERO, Qty::ZERO),
                     },
                     footprint: KlineTrades::new(),
                 });
 
+            if build_klines {
+                entry.add_trade_to_kline(trade);
+            }
             entry.add_trade(trade, self.tick_size);
         });
 
+        updated_times.sort_unstable();
+        updated_times.dedup();
+
         for time in updated_times {
             if let Some(data_point) = self.datapoints.get_mut(&time) {
                 data_point.calculate_poc();
@@ -242,11 +325,10 @@ impl TimeSeries<KlineDataPoint> {
         if buffer.is_empty() {
             return;
         }
-        let aggr_time = self.interval.to_milliseconds();
         let mut updated_times: Vec<u64> = Vec::new();
 
         for trade in buffer {
-            let rounded_time = (trade.time / aggr_time) * aggr_time;
+            let rounded_time = self.interval.bucket_start(trade.time);
 
             if let Some(entry) = self.datapoints.get_mut(&rounded_time) {
                 if !updated_times.contains(&rounded_time) {
@@ -364,8 +446,8 @@ impl TimeSeries<KlineDataPoint> {
         latest: u64,
         highest: Price,
         lowest: Price,
-    ) -> f32 {
-        let mut max_cluster_qty: f32 = 0.0;
+    ) -> Qty {
+        let mut max_cluster_qty = Qty::ZERO;
 
         self.datapoints
             .range(earliest..=latest)
@@ -382,24 +464,27 @@ impl TimeSeries<HeatmapDataPoint> {
     pub fn new(basis: Basis, tick_size: PriceStep) -> Self {
         let timeframe = match basis {
             Basis::Time(interval) => interval,
-            Basis::Tick(_) => unimplemented!(),
+            Basis::Tick(_) | Basis::Volume(_) | Basis::Range(_) | Basis::Renko(_) => {
+                unimplemented!()
+            }
         };
 
         Self {
             datapoints: BTreeMap::new(),
             interval: timeframe,
             tick_size,
+            open_base: None,
         }
     }
 
-    pub fn max_trade_qty_and_aggr_volume(&self, earliest: u64, latest: u64) -> (f32, f32) {
-        let mut max_trade_qty = 0.0f32;
-        let mut max_aggr_volume = 0.0f32;
+    pub fn max_trade_qty_and_aggr_volume(&self, earliest: u64, latest: u64) -> (Qty, Qty) {
+        let mut max_trade_qty = Qty::ZERO;
+        let mut max_aggr_volume = Qty::ZERO;
 
         self.datapoints
             .range(earliest..=latest)
             .for_each(|(_, dp)| {
-                let (mut buy_volume, mut sell_volume) = (0.0, 0.0);
+                let (mut buy_volume, mut sell_volume) = (Qty::ZERO, Qty::ZERO);
 
                 dp.grouped_trades.iter().for_each(|trade| {
                     max_trade_qty = max_trade_qty.max(trade.qty);
@@ -418,7 +503,7 @@ impl TimeSeries<HeatmapDataPoint> {
     }
 }
 
-impl From<&TimeSeries<KlineDataPoint>> for BTreeMap<u64, (f32, f32)> {
+impl From<&TimeSeries<KlineDataPoint>> for BTreeMap<u64, (Qty, Qty)> {
     /// Converts datapoints into a map of timestamps and volume data
     fn from(timeseries: &TimeSeries<KlineDataPoint>) -> Self {
         timeseries
@@ -428,3 +513,93 @@ impl From<&TimeSeries<KlineDataPoint>> for BTreeMap<u64, (f32, f32)> {
             .collect()
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::kline;
+
+    #[test]
+    fn resample_drops_buckets_with_gaps() {
+        let minute = Timeframe::M1.to_milliseconds();
+        let start = Timeframe::M5.to_milliseconds() * 1_000;
+
+        // second bucket misses its third minute, the last one is still open
+        let klines: Vec<Kline> = (0..15)
+            .filter(|i| *i != 7)
+            .map(|i| kline(start + i * minute, 100.0 + i as f32, (1.0, 1.0)))
+            .collect();
+        let series =
+            TimeSeries::<KlineDataPoint>::new(Timeframe::M1, PriceStep::from_f32(1.0), &klines);
+
+        let resampled = series.resample(Timeframe::M5).unwrap();
+        let buckets: Vec<(u64, f32, Qty)> = resampled
+            .datapoints
+            .values()
+            .map(|dp| (dp.kline.time, dp.kline.close.to_f32(), dp.kline.volume.0))
+            .collect();
+
+        assert_eq!(
+            buckets,
+            vec![
+                (start, 104.0, Qty::from_f32(5.0)),
+                (start + 10 * minute, 114.0, Qty::from_f32(5.0)),
+            ]
+        );
+        assert!(series.resample(Timeframe::M3).is_some());
+        assert!(resampled.resample(Timeframe::M1).is_none());
+    }
+
+    #[test]
+    fn builds_klines_from_trades_across_buckets() {
+        let trade = |time: u64, price: f32, qty: f32, is_sell: bool| Trade {
+            time,
+            is_sell,
+            price: Price::from_f32(price),
+            qty: Qty::from_f32(qty),
+        };
+        let start = Timeframe::M5.to_milliseconds() * 1_000;
+        let second = 1_000;
+
+        let mut series =
+            TimeSeries::<KlineDataPoint>::new(Timeframe::Custom(5), PriceStep::from_f32(1.0), &[]);
+        series.insert_trades_or_create_bucket(
+            &[
+                trade(start + second, 100.0, 1.0, false),
+                trade(start + 2 * second, 103.0, 2.0, true),
+                trade(start + 4_999, 101.0, 0.5, false),
+                // opens the next bucket, exactly on its start
+                trade(start + 5 * second, 99.0, 3.0, true),
+            ],
+            true,
+        );
+        // a backfilled trade from before the ones already counted moves the open
+        series.insert_trades_or_create_bucket(&[trade(start, 102.0, 1.0, true)], true);
+
+        let ohlcv: Vec<(u64, [f32; 4], (Qty, Qty))> = series
+            .datapoints
+            .values()
+            .map(|dp| {
+                let k = &dp.kline;
+                let prices = [k.open, k.high, k.low, k.close].map(|p| p.to_f32());
+                (k.time, prices, k.volume)
+            })
+            .collect();
+
+        assert_eq!(
+            ohlcv,
+            vec![
+                (
+                    start,
+                    [102.0, 103.0, 100.0, 101.0],
+                    (Qty::from_f32(1.5), Qty::from_f32(3.0))
+                ),
+                (
+                    start + 5 * second,
+                    [99.0, 99.0, 99.0, 99.0],
+                    (Qty::ZERO, Qty::from_f32(3.0))
+                ),
+            ]
+        );
+    }
+}
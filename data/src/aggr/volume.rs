@@ -0,0 +1,20 @@
+use exchange::util::Qty;
+use serde::{Deserialize, Serialize};
+
+/// Traded base quantity that closes a volume bar
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct VolumeSize(pub Qty);
+
+impl VolumeSize {
+    /// A bar is complete once its total traded quantity reaches the size,
+    /// the trade crossing the threshold stays in the bar it completes
+    pub fn is_reached(&self, buy_qty: Qty, sell_qty: Qty) -> bool {
+        buy_qty + sell_qty >= self.0
+    }
+}
+
+impl std::fmt::Display for VolumeSize {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        write!(f, "{}V", self.0)
+    }
+}
@@ -69,6 +69,18 @@ pub enum Basis {
     ///
     /// The u16 value represents the number of trades per aggregation unit.
     Tick(aggr::TickCount),
+
+    /// Trade-based aggregation where each datapoint closes once its traded quantity
+    /// reaches a fixed size, in base units.
+    Volume(aggr::volume::VolumeSize),
+
+    /// Trade-based aggregation where each datapoint spans a fixed high-low range,
+    /// in multiples of the chart's tick size.
+    Range(aggr::range::RangeTicks),
+
+    /// Trade-based aggregation into Renko bricks of a fixed box size,
+    /// in multiples of the chart's tick size.
+    Renko(aggr::renko::RenkoBox),
 }
 
 impl Basis {
@@ -76,6 +88,22 @@ impl Basis {
         matches!(self, Basis::Time(_))
     }
 
+    /// See [`aggr::BarRule::index_units_per_bar`]
+    pub fn index_units_per_bar(&self) -> u64 {
+        self.bar_rule().map_or(1, |rule| rule.index_units_per_bar())
+    }
+
+    /// How trades are grouped into bars, `None` for time-based aggregation
+    pub fn bar_rule(&self) -> Option<aggr::BarRule> {
+        match self {
+            Basis::Time(_) => None,
+            Basis::Tick(count) => Some(aggr::BarRule::Ticks(*count)),
+            Basis::Volume(size) => Some(aggr::BarRule::Volume(*size)),
+            Basis::Range(range) => Some(aggr::BarRule::Range(*range)),
+            Basis::Renko(renko) => Some(aggr::BarRule::Renko(*renko)),
+        }
+    }
+
     pub fn default_heatmap_time(ticker_info: Option<exchange::TickerInfo>) -> Self {
         let fallback = Timeframe::MS500;
 
@@ -97,6 +125,9 @@ impl std::fmt::Display for Basis {
         match self {
             Basis::Time(timeframe) => write!(f, "{timeframe}"),
             Basis::Tick(count) => write!(f, "{count}"),
+            Basis::Volume(size) => write!(f, "{size}"),
+            Basis::Range(range) => write!(f, "{range}"),
+            Basis::Renko(renko) => write!(f, "{renko}"),
         }
     }
 }
@@ -1,7 +1,7 @@
 use super::Basis;
 use super::aggr::time::DataPoint;
-use exchange::util::{Price, PriceStep};
-use exchange::{adapter::MarketKind, depth::Depth, volume_size_unit};
+use exchange::util::{Price, PriceStep, Qty};
+use exchange::{SizeUnit, adapter::MarketKind, depth::Depth};
 
 use rustc_hash::{FxBuildHasher, FxHashMap};
 use serde::{Deserialize, Serialize};
@@ -19,6 +19,8 @@ pub struct Config {
     pub order_size_filter: f32,
     pub trade_size_scale: Option<i32>,
     pub coalescing: Option<CoalesceKind>,
+    #[serde(default)]
+    pub size_unit: SizeUnit,
 }
 
 impl Default for Config {
@@ -28,13 +30,14 @@ impl Default for Config {
             order_size_filter: 0.0,
             trade_size_scale: Some(100),
             coalescing: Some(CoalesceKind::Average(0.15)),
+            size_unit: SizeUnit::default(),
         }
     }
 }
 
 pub struct HeatmapDataPoint {
     pub grouped_trades: Box<[GroupedTrade]>,
-    pub buy_sell: (f32, f32),
+    pub buy_sell: (Qty, Qty),
 }
 
 impl DataPoint for HeatmapDataPoint {
@@ -69,7 +72,7 @@ impl DataPoint for HeatmapDataPoint {
 
     fn clear_trades(&mut self) {
         self.grouped_trades = Box::new([]);
-        self.buy_sell = (0.0, 0.0);
+        self.buy_sell = (Qty::ZERO, Qty::ZERO);
     }
 
     fn last_trade_time(&self) -> Option<u64> {
@@ -111,12 +114,12 @@ impl DataPoint for HeatmapDataPoint {
 pub struct OrderRun {
     pub start_time: u64,
     pub until_time: u64,
-    qty: f32,
+    qty: Qty,
     pub is_bid: bool,
 }
 
 impl OrderRun {
-    pub fn new(start_time: u64, aggr_time: u64, qty: f32, is_bid: bool) -> Self {
+    pub fn new(start_time: u64, aggr_time: u64, qty: Qty, is_bid: bool) -> Self {
         OrderRun {
             start_time,
             until_time: start_time + aggr_time,
@@ -125,7 +128,7 @@ impl OrderRun {
         }
     }
 
-    pub fn qty(&self) -> f32 {
+    pub fn qty(&self) -> Qty {
         self.qty
     }
 
@@ -152,7 +155,9 @@ impl HistoricalDepth {
             price_levels: BTreeMap::new(),
             aggr_time: match basis {
                 Basis::Time(interval) => interval.into(),
-                Basis::Tick(_) => unimplemented!(),
+                Basis::Tick(_) | Basis::Volume(_) | Basis::Range(_) | Basis::Renko(_) => {
+                    unimplemented!()
+                }
             },
             tick_size,
             min_order_qty,
@@ -164,16 +169,16 @@ impl HistoricalDepth {
         self.process_side(&depth.asks, time, false);
     }
 
-    fn process_side(&mut self, side: &BTreeMap<Price, f32>, time: u64, is_bid: bool) {
+    fn process_side(&mut self, side: &BTreeMap<Price, Qty>, time: u64, is_bid: bool) {
         let mut current_price = None;
-        let mut current_qty = 0.0;
+        let mut current_qty = Qty::ZERO;
 
         let step = self.tick_size;
 
         for (price, qty) in side {
             let rounded_price = price.round_to_side_step(is_bid, step);
             if Some(rounded_price) == current_price {
-                current_qty += qty;
+                current_qty += *qty;
             } else {
                 if let Some(price) = current_price {
                     self.update_price_level(time, price, current_qty, is_bid);
@@ -188,7 +193,7 @@ impl HistoricalDepth {
         }
     }
 
-    fn update_price_level(&mut self, time: u64, price: Price, qty: f32, is_bid: bool) {
+    fn update_price_level(&mut self, time: u64, price: Price, qty: Qty, is_bid: bool) {
         let price_level = self.price_levels.entry(price).or_default();
         let aggr_time = self.aggr_time;
 
@@ -200,8 +205,8 @@ impl HistoricalDepth {
                 }
 
                 let last_qty = last_run.qty;
-                let qty_diff_pct = if last_qty > 0.0 {
-                    (qty - last_qty).abs() / last_qty
+                let qty_diff_pct = if last_qty > Qty::ZERO {
+                    (qty - last_qty).abs().to_f32() / last_qty.to_f32()
                 } else {
                     f32::INFINITY
                 };
@@ -245,6 +250,19 @@ impl HistoricalDepth {
             })
     }
 
+    /// All order runs overlapping `[earliest, latest]`, ordered by price level
+    pub fn iter_order_runs(
+        &self,
+        earliest: u64,
+        latest: u64,
+    ) -> impl Iterator<Item = (&Price, &OrderRun)> {
+        self.price_levels.iter().flat_map(move |(price, runs)| {
+            runs.iter()
+                .filter(move |run| run.until_time >= earliest && run.start_time <= latest)
+                .map(move |run| (price, run))
+        })
+    }
+
     pub fn latest_order_runs(
         &self,
         highest: Price,
@@ -284,8 +302,6 @@ impl HistoricalDepth {
             CoalesceKind::Average(t) | CoalesceKind::First(t) | CoalesceKind::Max(t) => t,
         };
 
-        let size_in_quote_ccy = volume_size_unit() == exchange::SizeUnit::Quote;
-
         for (price_at_level, runs_at_price_level) in
             self.iter_time_filtered(earliest, latest, highest, lowest)
         {
@@ -295,12 +311,8 @@ impl HistoricalDepth {
                     if !(run_ref.until_time >= earliest && run_ref.start_time <= latest) {
                         return false;
                     }
-                    let order_size = market_type.qty_in_quote_value(
-                        run_ref.qty(),
-                        *price_at_level,
-                        size_in_quote_ccy,
-                    );
-                    order_size > order_size_filter
+                    let order_size = market_type.qty_in_quote_value(run_ref.qty(), *price_at_level);
+                    order_size.to_f32() > order_size_filter
                 })
                 .collect::<Vec<&OrderRun>>();
 
@@ -314,11 +326,13 @@ impl HistoricalDepth {
                 let run_to_process = *run_to_process_ref;
 
                 if let Some(current_accumulator) = current_accumulator_opt.as_mut() {
-                    let comparison_base_qty = current_accumulator.comparison_qty(&coalesce_kind);
+                    let comparison_base_qty =
+                        current_accumulator.comparison_qty(&coalesce_kind).to_f32();
+                    let run_qty = run_to_process.qty().to_f32();
 
                     let qty_diff_pct = if comparison_base_qty > FRACTIONAL_THRESHOLD {
-                        (run_to_process.qty() - comparison_base_qty).abs() / comparison_base_qty
-                    } else if run_to_process.qty() > FRACTIONAL_THRESHOLD {
+                        (run_qty - comparison_base_qty).abs() / comparison_base_qty
+                    } else if run_qty > FRACTIONAL_THRESHOLD {
                         f32::INFINITY
                     } else {
                         0.0
@@ -351,17 +365,16 @@ impl HistoricalDepth {
     pub fn query_grid_qtys(
         &self,
         center_time: u64,
-        center_price: f32,
+        center_price: Price,
         time_interval_offsets: &[i64],
         price_tick_offsets: &[i64],
         market_type: MarketKind,
         order_size_filter: f32,
         coalesce_kind: Option<CoalesceKind>,
-    ) -> FxHashMap<(u64, Price), (f32, bool)> {
+    ) -> FxHashMap<(u64, Price), (Qty, bool)> {
         let aggr_time = self.aggr_time;
 
         let step = self.tick_size;
-        let tick_size = step.to_f32_lossy();
 
         let query_earliest_time = time_interval_offsets
             .iter()
@@ -375,19 +388,17 @@ impl HistoricalDepth {
             .max()
             .map_or(center_time, |t| t.saturating_add(aggr_time));
 
-        let query_lowest_price = price_tick_offsets
+        let center_price = center_price.round_to_step(step);
+        let query_lowest = price_tick_offsets
             .iter()
-            .map(|offset| center_price + (*offset as f32 * tick_size))
-            .fold(f32::INFINITY, f32::min)
-            - 0.1 * tick_size;
-        let query_highest_price = price_tick_offsets
+            .map(|offset| center_price.add_steps(*offset, step))
+            .min()
+            .unwrap_or(center_price);
+        let query_highest = price_tick_offsets
             .iter()
-            .map(|offset| center_price + (*offset as f32 * tick_size))
-            .fold(f32::NEG_INFINITY, f32::max)
-            + 0.1 * tick_size;
-
-        let query_highest = Price::from_f32(query_highest_price).round_to_step(step);
-        let query_lowest = Price::from_f32(query_lowest_price).round_to_step(step);
+            .map(|offset| center_price.add_steps(*offset, step))
+            .max()
+            .unwrap_or(center_price);
 
         let runs_in_vicinity: Vec<(Price, OrderRun)> = if let Some(ck) = coalesce_kind {
             self.coalesced_runs(
@@ -413,11 +424,10 @@ impl HistoricalDepth {
         };
 
         let capacity = time_interval_offsets.len() * price_tick_offsets.len();
-        let mut grid_quantities: FxHashMap<(u64, Price), (f32, bool)> =
+        let mut grid_quantities: FxHashMap<(u64, Price), (Qty, bool)> =
             FxHashMap::with_capacity_and_hasher(capacity, FxBuildHasher);
         for price_offset in price_tick_offsets {
-            let target_price_val = center_price + (*price_offset as f32 * tick_size);
-            let target_price_key = Price::from_f32(target_price_val).round_to_step(step);
+            let target_price_key = center_price.add_steps(*price_offset, step);
 
             for time_offset in time_interval_offsets {
                 let target_time_val =
@@ -446,10 +456,8 @@ impl HistoricalDepth {
         lowest: Price,
         market_type: MarketKind,
         order_size_filter: f32,
-    ) -> f32 {
-        let mut max_depth_qty = 0.0f32;
-
-        let size_in_quote_ccy = volume_size_unit() == exchange::SizeUnit::Quote;
+    ) -> Qty {
+        let mut max_depth_qty = Qty::ZERO;
 
         self.iter_time_filtered(earliest, latest, highest, lowest)
             .for_each(|(price, runs)| {
@@ -457,13 +465,9 @@ impl HistoricalDepth {
                     .filter_map(|run| {
                         let visible_run = run.with_range(earliest, latest)?;
 
-                        let order_size = market_type.qty_in_quote_value(
-                            visible_run.qty(),
-                            *price,
-                            size_in_quote_ccy,
-                        );
+                        let order_size = market_type.qty_in_quote_value(visible_run.qty(), *price);
 
-                        if order_size > order_size_filter {
+                        if order_size.to_f32() > order_size_filter {
                             Some(visible_run)
                         } else {
                             None
@@ -516,10 +520,10 @@ pub struct CoalescingRun {
     pub start_time: u64,
     pub until_time: u64,
     pub is_bid: bool,
-    pub qty_sum: f32,
+    pub qty_sum: Qty,
     pub run_count: u32,
-    first_qty: f32,
-    max_qty: f32,
+    first_qty: Qty,
+    max_qty: Qty,
 }
 
 impl CoalescingRun {
@@ -544,18 +548,18 @@ impl CoalescingRun {
         self.max_qty = self.max_qty.max(run_qty);
     }
 
-    pub fn comparison_qty(&self, kind: &CoalesceKind) -> f32 {
+    pub fn comparison_qty(&self, kind: &CoalesceKind) -> Qty {
         match kind {
             CoalesceKind::Average(_) => self.current_average_qty(),
             CoalesceKind::Max(_) | CoalesceKind::First(_) => self.first_qty,
         }
     }
 
-    pub fn current_average_qty(&self) -> f32 {
+    pub fn current_average_qty(&self) -> Qty {
         if self.run_count == 0 {
-            0.0
+            Qty::ZERO
         } else {
-            self.qty_sum / self.run_count as f32
+            Qty::from_units(self.qty_sum.units / i128::from(self.run_count))
         }
     }
 
@@ -585,7 +589,7 @@ pub struct QtyScale {
 pub struct GroupedTrade {
     pub is_sell: bool,
     pub price: Price,
-    pub qty: f32,
+    pub qty: Qty,
 }
 
 impl GroupedTrade {
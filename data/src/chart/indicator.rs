@@ -2,7 +2,23 @@ use std::fmt::{self, Debug, Display};
 
 use enum_map::Enum;
 use exchange::adapter::MarketKind;
-use serde::{Deserialize, Serialize};
+use serde::{Deserialize, Deserializer, Serialize};
+
+pub mod average;
+pub mod bands;
+pub mod bar_trades;
+pub mod cvd;
+pub mod open_interest;
+pub mod oscillator;
+pub mod rolling;
+pub mod script;
+pub mod vwap;
+
+use average::{MaKind, PriceSource};
+use bands::BandKind;
+use cvd::{CvdReset, CvdStyle};
+use rolling::VolEstimator;
+use vwap::VwapAnchor;
 
 pub trait Indicator: PartialEq + Display + 'static {
     fn for_market(market: MarketKind) -> &'static [Self]
@@ -10,10 +26,73 @@ pub trait Indicator: PartialEq + Display + 'static {
         Self: Sized;
 }
 
-#[derive(Debug, Clone, Copy, PartialEq, Deserialize, Serialize, Eq, Enum)]
+/// A configured instance of a kline chart indicator, a pane can hold several of
+/// the same type as long as their parameters differ
+#[derive(Debug, Clone, Copy, PartialEq, Deserialize, Serialize)]
 pub enum KlineIndicator {
-    Volume,
-    OpenInterest,
+    Volume {
+        /// Buy/sell split shown as an overlay, otherwise only the total
+        delta: bool,
+    },
+    OpenInterest {
+        /// Per-bar change drawn as bars instead of the open interest itself
+        change: bool,
+    },
+    MovingAverage {
+        kind: MaKind,
+        period: u16,
+        source: PriceSource,
+    },
+    Bands {
+        kind: BandKind,
+        period: u16,
+        /// Distance of the outer lines from the middle, in standard deviations or ATRs
+        width: f32,
+    },
+    CumulativeDelta {
+        style: CvdStyle,
+        reset: CvdReset,
+    },
+    Vwap {
+        anchor: VwapAnchor,
+        /// How many standard deviation bands to draw on each side, up to 3
+        bands: u8,
+    },
+    Rsi {
+        period: u16,
+        /// Oversold guide sits as far below 50 as this one is above it
+        overbought: u8,
+    },
+    Macd {
+        fast: u16,
+        slow: u16,
+        signal: u16,
+    },
+    Stochastic {
+        period: u16,
+        smooth_k: u16,
+        smooth_d: u16,
+    },
+    Atr {
+        period: u16,
+    },
+    RealizedVolatility {
+        estimator: VolEstimator,
+        period: u16,
+    },
+    VolumeZScore {
+        period: u16,
+        /// Bars this many standard deviations above the mean volume are highlighted
+        threshold: f32,
+    },
+    /// Buy minus sell volume of each bar, with the extremes it reached intrabar
+    BarDelta,
+    TradeCount,
+    AverageTradeSize,
+    /// Script from the data folder, see [`script`]
+    Script {
+        name: script::ScriptName,
+    },
 }
 
 impl Indicator for KlineIndicator {
@@ -26,23 +105,199 @@ impl Indicator for KlineIndicator {
 }
 
 impl KlineIndicator {
+    pub const VOLUME: KlineIndicator = KlineIndicator::Volume { delta: true };
+    pub const OPEN_INTEREST: KlineIndicator = KlineIndicator::OpenInterest { change: false };
+    pub const MOVING_AVERAGE: KlineIndicator = KlineIndicator::MovingAverage {
+        kind: MaKind::Ema,
+        period: 20,
+        source: PriceSource::Close,
+    };
+    pub const BANDS: KlineIndicator = KlineIndicator::Bands {
+        kind: BandKind::Bollinger,
+        period: 20,
+        width: 2.0,
+    };
+    pub const CUMULATIVE_DELTA: KlineIndicator = KlineIndicator::CumulativeDelta {
+        style: CvdStyle::Candles,
+        reset: CvdReset::DailyUtc,
+    };
+    pub const VWAP: KlineIndicator = KlineIndicator::Vwap {
+        anchor: VwapAnchor::Daily,
+        bands: 2,
+    };
+    pub const RSI: KlineIndicator = KlineIndicator::Rsi {
+        period: 14,
+        overbought: 70,
+    };
+    pub const MACD: KlineIndicator = KlineIndicator::Macd {
+        fast: 12,
+        slow: 26,
+        signal: 9,
+    };
+    pub const STOCHASTIC: KlineIndicator = KlineIndicator::Stochastic {
+        period: 14,
+        smooth_k: 3,
+        smooth_d: 3,
+    };
+    pub const ATR: KlineIndicator = KlineIndicator::Atr { period: 14 };
+    pub const REALIZED_VOLATILITY: KlineIndicator = KlineIndicator::RealizedVolatility {
+        estimator: VolEstimator::CloseToClose,
+        period: 30,
+    };
+    pub const VOLUME_Z_SCORE: KlineIndicator = KlineIndicator::VolumeZScore {
+        period: 20,
+        threshold: 2.5,
+    };
+
     // Indicator togglers on UI menus depend on these arrays.
-    // Every variant needs to be in either SPOT, PERPS or both.
+    // Every variant needs to be in either SPOT, PERPS or both, with its default parameters,
+    // except scripts, which are listed from the data folder and work with any market.
     /// Indicators that can be used with spot market tickers
-    const FOR_SPOT: [KlineIndicator; 1] = [KlineIndicator::Volume];
+    const FOR_SPOT: [KlineIndicator; 14] = [
+        Self::VOLUME,
+        KlineIndicator::BarDelta,
+        KlineIndicator::TradeCount,
+        KlineIndicator::AverageTradeSize,
+        Self::CUMULATIVE_DELTA,
+        Self::MOVING_AVERAGE,
+        Self::BANDS,
+        Self::VWAP,
+        Self::RSI,
+        Self::MACD,
+        Self::STOCHASTIC,
+        Self::ATR,
+        Self::REALIZED_VOLATILITY,
+        Self::VOLUME_Z_SCORE,
+    ];
     /// Indicators that can be used with perpetual swap market tickers
-    const FOR_PERPS: [KlineIndicator; 2] = [KlineIndicator::Volume, KlineIndicator::OpenInterest];
+    const FOR_PERPS: [KlineIndicator; 15] = [
+        Self::VOLUME,
+        Self::OPEN_INTEREST,
+        KlineIndicator::BarDelta,
+        KlineIndicator::TradeCount,
+        KlineIndicator::AverageTradeSize,
+        Self::CUMULATIVE_DELTA,
+        Self::MOVING_AVERAGE,
+        Self::BANDS,
+        Self::VWAP,
+        Self::RSI,
+        Self::MACD,
+        Self::STOCHASTIC,
+        Self::ATR,
+        Self::REALIZED_VOLATILITY,
+        Self::VOLUME_Z_SCORE,
+    ];
+
+    pub fn is_same_type(&self, other: &Self) -> bool {
+        std::mem::discriminant(self) == std::mem::discriminant(other)
+    }
+
+    /// Whether an indicator of this type can be used with `market`, whatever its parameters
+    pub fn is_available_for(&self, market: MarketKind) -> bool {
+        if let KlineIndicator::Script { .. } = self {
+            return true;
+        }
+        Self::for_market(market)
+            .iter()
+            .any(|available| available.is_same_type(self))
+    }
+
+    /// Drawn over the price chart instead of in a panel of its own
+    pub fn is_overlay(&self) -> bool {
+        matches!(
+            self,
+            KlineIndicator::MovingAverage { .. }
+                | KlineIndicator::Bands { .. }
+                | KlineIndicator::Vwap { .. }
+        )
+    }
 }
 
 impl Display for KlineIndicator {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
         match self {
-            KlineIndicator::Volume => write!(f, "Volume"),
-            KlineIndicator::OpenInterest => write!(f, "Open Interest"),
+            KlineIndicator::Volume { delta: true } => write!(f, "Volume"),
+            KlineIndicator::Volume { delta: false } => write!(f, "Volume (total)"),
+            KlineIndicator::OpenInterest { change: false } => write!(f, "Open Interest"),
+            KlineIndicator::OpenInterest { change: true } => write!(f, "OI Change"),
+            KlineIndicator::MovingAverage {
+                kind,
+                period,
+                source: PriceSource::Close,
+            } => write!(f, "{kind} {period}"),
+            KlineIndicator::MovingAverage {
+                kind,
+                period,
+                source,
+            } => write!(f, "{kind} {period} ({source})"),
+            KlineIndicator::Bands {
+                kind,
+                period,
+                width,
+            } => write!(f, "{kind} {period}, {width}"),
+            KlineIndicator::CumulativeDelta { style, reset } => match style {
+                CvdStyle::Candles => write!(f, "CVD ({reset})"),
+                CvdStyle::Line => write!(f, "CVD line ({reset})"),
+            },
+            KlineIndicator::Vwap { anchor, bands: 0 } => write!(f, "VWAP ({anchor})"),
+            KlineIndicator::Vwap { anchor, bands } => write!(f, "VWAP ({anchor}, {bands} SD)"),
+            KlineIndicator::Rsi { period, overbought } => {
+                write!(f, "RSI {period} ({}/{overbought})", 100 - overbought)
+            }
+            KlineIndicator::Macd { fast, slow, signal } => {
+                write!(f, "MACD {fast}, {slow}, {signal}")
+            }
+            KlineIndicator::Stochastic {
+                period,
+                smooth_k,
+                smooth_d,
+            } => write!(f, "Stoch {period}, {smooth_k}, {smooth_d}"),
+            KlineIndicator::Atr { period } => write!(f, "ATR {period}"),
+            KlineIndicator::RealizedVolatility {
+                estimator: VolEstimator::CloseToClose,
+                period,
+            } => write!(f, "RV {period}"),
+            KlineIndicator::RealizedVolatility { estimator, period } => {
+                write!(f, "RV {period} ({estimator})")
+            }
+            KlineIndicator::VolumeZScore { period, threshold } => {
+                write!(f, "Volume Z {period}, {threshold}")
+            }
+            KlineIndicator::BarDelta => write!(f, "Bar Delta"),
+            KlineIndicator::TradeCount => write!(f, "Trade Count"),
+            KlineIndicator::AverageTradeSize => write!(f, "Avg Trade Size"),
+            KlineIndicator::Script { name } => write!(f, "{name}"),
         }
     }
 }
 
+/// Reads the indicators of a kline pane, dropping entries that don't parse. Layouts saved
+/// before indicators had parameters list them by name, those get their defaults
+pub fn deserialize_kline_indicators<'de, D>(
+    deserializer: D,
+) -> Result<Vec<KlineIndicator>, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    let value = serde_json::Value::deserialize(deserializer)?;
+
+    let indicators = value
+        .as_array()
+        .map(|entries| {
+            entries
+                .iter()
+                .filter_map(|entry| match entry.as_str() {
+                    Some("Volume") => Some(KlineIndicator::VOLUME),
+                    Some("OpenInterest") => Some(KlineIndicator::OPEN_INTEREST),
+                    _ => KlineIndicator::deserialize(entry).ok(),
+                })
+                .collect()
+        })
+        .unwrap_or_default();
+
+    Ok(indicators)
+}
+
 #[derive(Debug, Clone, Copy, PartialEq, Deserialize, Serialize, Eq, Enum)]
 pub enum HeatmapIndicator {
     Volume,
@@ -93,3 +348,37 @@ impl From<HeatmapIndicator> for UiIndicator {
         UiIndicator::Heatmap(h)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn reads_indicators_saved_by_name() {
+        let json = r#"["Volume", "OpenInterest", {"Volume": {"delta": false}}, "Unknown"]"#;
+        let mut deserializer = serde_json::Deserializer::from_str(json);
+
+        assert_eq!(
+            deserialize_kline_indicators(&mut deserializer).unwrap(),
+            vec![
+                KlineIndicator::VOLUME,
+                KlineIndicator::OPEN_INTEREST,
+                KlineIndicator::Volume { delta: false },
+            ]
+        );
+    }
+
+    #[test]
+    fn scripts_are_saved_by_name() {
+        let script = KlineIndicator::Script {
+            name: script::ScriptName::new("session_range").unwrap(),
+        };
+        let json = serde_json::to_string(&script).unwrap();
+
+        assert_eq!(json, r#"{"Script":{"name":"session_range"}}"#);
+        assert_eq!(
+            serde_json::from_str::<KlineIndicator>(&json).unwrap(),
+            script
+        );
+    }
+}
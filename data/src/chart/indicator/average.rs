@@ -0,0 +1,272 @@
+use std::collections::BTreeMap;
+use std::fmt::{self, Display};
+
+use exchange::Kline;
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Deserialize, Serialize)]
+pub enum MaKind {
+    Sma,
+    Ema,
+    Wma,
+    Hma,
+}
+
+impl MaKind {
+    pub const ALL: [MaKind; 4] = [MaKind::Sma, MaKind::Ema, MaKind::Wma, MaKind::Hma];
+}
+
+impl Display for MaKind {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            MaKind::Sma => write!(f, "SMA"),
+            MaKind::Ema => write!(f, "EMA"),
+            MaKind::Wma => write!(f, "WMA"),
+            MaKind::Hma => write!(f, "HMA"),
+        }
+    }
+}
+
+/// Price of a bar an average is taken over
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Deserialize, Serialize)]
+pub enum PriceSource {
+    Open,
+    High,
+    Low,
+    Close,
+    Hl2,
+    Hlc3,
+    Ohlc4,
+}
+
+impl PriceSource {
+    pub const ALL: [PriceSource; 7] = [
+        PriceSource::Close,
+        PriceSource::Open,
+        PriceSource::High,
+        PriceSource::Low,
+        PriceSource::Hl2,
+        PriceSource::Hlc3,
+        PriceSource::Ohlc4,
+    ];
+
+    pub fn value(&self, kline: &Kline) -> f32 {
+        let (open, high, low, close) = (
+            kline.open.to_f32(),
+            kline.high.to_f32(),
+            kline.low.to_f32(),
+            kline.close.to_f32(),
+        );
+
+        match self {
+            PriceSource::Open => open,
+            PriceSource::High => high,
+            PriceSource::Low => low,
+            PriceSource::Close => close,
+            PriceSource::Hl2 => (high + low) / 2.0,
+            PriceSource::Hlc3 => (high + low + close) / 3.0,
+            PriceSource::Ohlc4 => (open + high + low + close) / 4.0,
+        }
+    }
+}
+
+impl Display for PriceSource {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            PriceSource::Open => write!(f, "Open"),
+            PriceSource::High => write!(f, "High"),
+            PriceSource::Low => write!(f, "Low"),
+            PriceSource::Close => write!(f, "Close"),
+            PriceSource::Hl2 => write!(f, "HL2"),
+            PriceSource::Hlc3 => write!(f, "HLC3"),
+            PriceSource::Ohlc4 => write!(f, "OHLC4"),
+        }
+    }
+}
+
+/// Moving average over a keyed series of inputs, keys being bar open times or tick bar indices.
+///
+/// Inserting recomputes only from the earliest key that changed, so updating the latest
+/// bar costs a single window while prepending history recomputes what follows it
+pub struct AverageSeries {
+    kind: MaKind,
+    period: usize,
+    inputs: BTreeMap<u64, f32>,
+    /// HMA only, the twice half period WMA minus the full period WMA that gets smoothed
+    hull_diff: BTreeMap<u64, f32>,
+    values: BTreeMap<u64, f32>,
+}
+
+impl AverageSeries {
+    pub fn new(kind: MaKind, period: usize) -> Self {
+        Self {
+            kind,
+            period: period.max(1),
+            inputs: BTreeMap::new(),
+            hull_diff: BTreeMap::new(),
+            values: BTreeMap::new(),
+        }
+    }
+
+    pub fn values(&self) -> &BTreeMap<u64, f32> {
+        &self.values
+    }
+
+    pub fn inputs(&self) -> &BTreeMap<u64, f32> {
+        &self.inputs
+    }
+
+    pub fn clear(&mut self) {
+        self.inputs.clear();
+        self.hull_diff.clear();
+        self.values.clear();
+    }
+
+    pub fn insert(&mut self, points: impl IntoIterator<Item = (u64, f32)>) {
+        let mut earliest: Option<u64> = None;
+
+        for (key, value) in points {
+            self.inputs.insert(key, value);
+            earliest = Some(earliest.map_or(key, |e| e.min(key)));
+        }
+
+        if let Some(from) = earliest {
+            self.recompute_from(from);
+        }
+    }
+
+    fn recompute_from(&mut self, from: u64) {
+        let keys: Vec<u64> = self.inputs.range(from..).map(|(key, _)| *key).collect();
+
+        for key in keys {
+            let value = match self.kind {
+                MaKind::Sma => window(&self.inputs, key, self.period).map(|w| sma(&w)),
+                MaKind::Wma => window(&self.inputs, key, self.period).map(|w| wma(&w)),
+                MaKind::Ema => self.ema_at(key),
+                MaKind::Hma => self.hma_at(key),
+            };
+
+            match value {
+                Some(value) => self.values.insert(key, value),
+                None => self.values.remove(&key),
+            };
+        }
+    }
+
+    /// Seeded with the SMA of the first full window, then chained from the previous value
+    fn ema_at(&self, key: u64) -> Option<f32> {
+        let input = *self.inputs.get(&key)?;
+
+        match self.values.range(..key).next_back() {
+            Some((_, prev)) => {
+                let alpha = 2.0 / (self.period as f32 + 1.0);
+                Some(alpha * input + (1.0 - alpha) * prev)
+            }
+            None => window(&self.inputs, key, self.period).map(|w| sma(&w)),
+        }
+    }
+
+    fn hma_at(&mut self, key: u64) -> Option<f32> {
+        let half = (self.period / 2).max(1);
+        let smoothing = ((self.period as f32).sqrt().round() as usize).max(1);
+
+        let half_wma = window(&self.inputs, key, half).map(|w| wma(&w));
+        let full_wma = window(&self.inputs, key, self.period).map(|w| wma(&w));
+
+        match half_wma.zip(full_wma) {
+            Some((half_wma, full_wma)) => {
+                self.hull_diff.insert(key, 2.0 * half_wma - full_wma);
+            }
+            None => {
+                self.hull_diff.remove(&key);
+                return None;
+            }
+        }
+
+        window(&self.hull_diff, key, smoothing).map(|w| wma(&w))
+    }
+}
+
+/// The `len` values up to and including `key`, latest first, if there are that many
+pub(crate) fn window(series: &BTreeMap<u64, f32>, key: u64, len: usize) -> Option<Vec<f32>> {
+    let values: Vec<f32> = series
+        .range(..=key)
+        .rev()
+        .take(len)
+        .map(|(_, value)| *value)
+        .collect();
+
+    (values.len() == len).then_some(values)
+}
+
+pub(crate) fn sma(values: &[f32]) -> f32 {
+    values.iter().sum::<f32>() / values.len() as f32
+}
+
+/// Weighted towards the front, `values` are expected latest first
+fn wma(values: &[f32]) -> f32 {
+    let len = values.len();
+    let weighted: f32 = values
+        .iter()
+        .enumerate()
+        .map(|(i, value)| value * (len - i) as f32)
+        .sum();
+
+    weighted / (len * (len + 1) / 2) as f32
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn approx(a: f32, b: f32) -> bool {
+        (a - b).abs() < 1e-4
+    }
+
+    #[test]
+    fn incremental_matches_full_rebuild() {
+        let points: Vec<(u64, f32)> = (0..40u64)
+            .map(|i| (i * 60_000, 100.0 + ((i * 7) % 11) as f32))
+            .collect();
+
+        for kind in MaKind::ALL {
+            let mut full = AverageSeries::new(kind, 9);
+            full.insert(points.iter().copied());
+
+            // history arrives after the live bars, and the latest bar updates in place
+            let mut incremental = AverageSeries::new(kind, 9);
+            incremental.insert(points[20..].iter().copied());
+            incremental.insert(points[..20].iter().copied());
+            incremental.insert([(39 * 60_000, 0.0)]);
+            incremental.insert([points[39]]);
+
+            assert_eq!(full.values().len(), incremental.values().len(), "{kind}");
+            for ((k1, v1), (k2, v2)) in full.values().iter().zip(incremental.values()) {
+                assert_eq!(k1, k2);
+                assert!(approx(*v1, *v2), "{kind} at {k1}: {v1} != {v2}");
+            }
+        }
+    }
+
+    #[test]
+    fn averages_of_a_known_window() {
+        let points = [(0, 1.0), (1, 2.0), (2, 3.0), (3, 4.0)];
+
+        let mut series = AverageSeries::new(MaKind::Sma, 3);
+        series.insert(points);
+        assert_eq!(series.values().get(&1), None);
+        assert!(approx(series.values()[&3], 3.0));
+
+        let mut series = AverageSeries::new(MaKind::Wma, 3);
+        series.insert(points);
+        assert!(approx(
+            series.values()[&3],
+            (4.0 * 3.0 + 3.0 * 2.0 + 2.0) / 6.0
+        ));
+
+        let mut series = AverageSeries::new(MaKind::Ema, 3);
+        series.insert(points);
+        assert!(approx(series.values()[&2], 2.0));
+        assert!(approx(series.values()[&3], 0.5 * 4.0 + 0.5 * 2.0));
+    }
+}
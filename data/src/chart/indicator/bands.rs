@@ -0,0 +1,155 @@
+use std::collections::BTreeMap;
+use std::fmt::{self, Display};
+
+use exchange::Kline;
+use serde::{Deserialize, Serialize};
+
+use super::average::{AverageSeries, MaKind, sma, window};
+use super::oscillator::true_range;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Deserialize, Serialize)]
+pub enum BandKind {
+    /// SMA of the close, offset by standard deviations of the close
+    Bollinger,
+    /// EMA of the close, offset by the average true range
+    Keltner,
+}
+
+impl BandKind {
+    pub const ALL: [BandKind; 2] = [BandKind::Bollinger, BandKind::Keltner];
+}
+
+impl Display for BandKind {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            BandKind::Bollinger => write!(f, "Bollinger"),
+            BandKind::Keltner => write!(f, "Keltner"),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Band {
+    pub lower: f32,
+    pub middle: f32,
+    pub upper: f32,
+}
+
+/// Envelope around a moving average of the close, keyed like [`AverageSeries`] and
+/// recomputed from the earliest key that changed
+pub struct BandSeries {
+    kind: BandKind,
+    period: usize,
+    width: f32,
+    middle: AverageSeries,
+    /// Keltner only, `(high, low, close)` of each bar for its true range
+    bars: BTreeMap<u64, (f32, f32, f32)>,
+    /// Keltner only, EMA of the true range
+    true_range: AverageSeries,
+    values: BTreeMap<u64, Band>,
+}
+
+impl BandSeries {
+    pub fn new(kind: BandKind, period: usize, width: f32) -> Self {
+        let period = period.max(1);
+        let middle_kind = match kind {
+            BandKind::Bollinger => MaKind::Sma,
+            BandKind::Keltner => MaKind::Ema,
+        };
+
+        Self {
+            kind,
+            period,
+            width,
+            middle: AverageSeries::new(middle_kind, period),
+            bars: BTreeMap::new(),
+            true_range: AverageSeries::new(MaKind::Ema, period),
+            values: BTreeMap::new(),
+        }
+    }
+
+    pub fn values(&self) -> &BTreeMap<u64, Band> {
+        &self.values
+    }
+
+    pub fn clear(&mut self) {
+        self.middle.clear();
+        self.bars.clear();
+        self.true_range.clear();
+        self.values.clear();
+    }
+
+    pub fn insert<'a>(&mut self, klines: impl IntoIterator<Item = (u64, &'a Kline)>) {
+        let mut closes = vec![];
+
+        for (key, kline) in klines {
+            let close = kline.close.to_f32();
+            closes.push((key, close));
+
+            if self.kind == BandKind::Keltner {
+                self.bars
+                    .insert(key, (kline.high.to_f32(), kline.low.to_f32(), close));
+            }
+        }
+
+        let Some(from) = closes.iter().map(|(key, _)| *key).min() else {
+            return;
+        };
+
+        self.middle.insert(closes);
+
+        if self.kind == BandKind::Keltner {
+            // a bar's true range depends on the close before it
+            let true_ranges: Vec<(u64, f32)> = self
+                .bars
+                .range(from..)
+                .map(|(key, &(high, low, close))| {
+                    let prev_close = self
+                        .bars
+                        .range(..*key)
+                        .next_back()
+                        .map_or(close, |(_, (_, _, prev_close))| *prev_close);
+
+                    (*key, true_range(high, low, prev_close))
+                })
+                .collect();
+
+            self.true_range.insert(true_ranges);
+        }
+
+        let keys: Vec<u64> = self
+            .middle
+            .inputs()
+            .range(from..)
+            .map(|(key, _)| *key)
+            .collect();
+
+        for key in keys {
+            match self.band_at(key) {
+                Some(band) => self.values.insert(key, band),
+                None => self.values.remove(&key),
+            };
+        }
+    }
+
+    fn band_at(&self, key: u64) -> Option<Band> {
+        let middle = *self.middle.values().get(&key)?;
+
+        let offset = match self.kind {
+            BandKind::Bollinger => {
+                let closes = window(self.middle.inputs(), key, self.period)?;
+                let mean = sma(&closes);
+                let variance =
+                    closes.iter().map(|c| (c - mean).powi(2)).sum::<f32>() / closes.len() as f32;
+                variance.sqrt()
+            }
+            BandKind::Keltner => *self.true_range.values().get(&key)?,
+        } * self.width;
+
+        Some(Band {
+            lower: middle - offset,
+            middle,
+            upper: middle + offset,
+        })
+    }
+}
@@ -0,0 +1,121 @@
+use exchange::Kline;
+use exchange::util::Qty;
+
+use crate::chart::kline::KlineTrades;
+
+/// What the trades loaded for a bar add up to
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct BarTrades {
+    pub buy_qty: Qty,
+    pub sell_qty: Qty,
+    pub buy_count: usize,
+    pub sell_count: usize,
+}
+
+impl BarTrades {
+    /// `None` for bars without any trades loaded
+    pub fn of(footprint: &KlineTrades) -> Option<Self> {
+        if footprint.trades.is_empty() {
+            return None;
+        }
+
+        let mut totals = BarTrades {
+            buy_qty: Qty::ZERO,
+            sell_qty: Qty::ZERO,
+            buy_count: 0,
+            sell_count: 0,
+        };
+
+        for group in footprint.trades.values() {
+            totals.buy_qty += group.buy_qty;
+            totals.sell_qty += group.sell_qty;
+            totals.buy_count += group.buy_count;
+            totals.sell_count += group.sell_count;
+        }
+
+        Some(totals)
+    }
+
+    pub fn count(&self) -> usize {
+        self.buy_count + self.sell_count
+    }
+}
+
+/// Buy minus sell volume of a single bar, with the extremes it reached while the bar formed
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct DeltaBar {
+    pub delta: f32,
+    pub lowest: f32,
+    pub highest: f32,
+    /// Whether the extremes were followed trade by trade, otherwise they are the final delta
+    pub has_extremes: bool,
+}
+
+impl DeltaBar {
+    /// Taken from the kline when its volume is split into buys and sells, from its
+    /// trades otherwise. `None` when neither is available
+    pub fn new(kline: &Kline, footprint: &KlineTrades) -> Option<Self> {
+        let (buy, sell) = kline.volume;
+
+        let delta = if buy != Kline::TOTAL_ONLY {
+            (buy - sell).to_f32()
+        } else {
+            let trades = BarTrades::of(footprint)?;
+            (trades.buy_qty - trades.sell_qty).to_f32()
+        };
+
+        let has_extremes = !footprint.trades.is_empty();
+        let (lowest, highest) = if has_extremes {
+            let (lowest, highest) = footprint.delta_range();
+            (lowest.to_f32(), highest.to_f32())
+        } else {
+            (0.0, 0.0)
+        };
+
+        // trades loaded for part of the bar only may not have reached where the bar closed
+        Some(DeltaBar {
+            delta,
+            lowest: lowest.min(delta).min(0.0),
+            highest: highest.max(delta).max(0.0),
+            has_extremes,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use exchange::Trade;
+    use exchange::util::{Price, PriceStep};
+
+    #[test]
+    fn follows_delta_extremes_trade_by_trade() {
+        let step = PriceStep::from_f32(1.0);
+        let trade = |qty: f32, is_sell: bool| Trade {
+            time: 0,
+            is_sell,
+            price: Price::from_f32(100.0),
+            qty: Qty::from_f32(qty),
+        };
+
+        let mut footprint = KlineTrades::new();
+        for (qty, is_sell) in [(2.0, false), (5.0, true), (4.0, false)] {
+            footprint.add_trade_to_nearest_bin(&trade(qty, is_sell), step);
+        }
+
+        let kline = Kline {
+            time: 0,
+            open: Price::from_f32(100.0),
+            high: Price::from_f32(100.0),
+            low: Price::from_f32(100.0),
+            close: Price::from_f32(100.0),
+            volume: (Kline::TOTAL_ONLY, Qty::from_f32(11.0)),
+        };
+
+        let bar = DeltaBar::new(&kline, &footprint).unwrap();
+        assert_eq!((bar.delta, bar.lowest, bar.highest), (1.0, -3.0, 2.0));
+
+        let trades = BarTrades::of(&footprint).unwrap();
+        assert_eq!((trades.buy_count, trades.sell_count), (2, 1));
+    }
+}
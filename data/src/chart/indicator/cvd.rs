@@ -0,0 +1,225 @@
+use std::collections::BTreeMap;
+use std::fmt::{self, Display};
+
+use exchange::Kline;
+use serde::{Deserialize, Serialize};
+
+const DAY_MS: u64 = 86_400_000;
+const MINUTE_MS: u64 = 60_000;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Deserialize, Serialize)]
+pub enum CvdStyle {
+    Line,
+    Candles,
+}
+
+impl CvdStyle {
+    pub const ALL: [CvdStyle; 2] = [CvdStyle::Candles, CvdStyle::Line];
+}
+
+impl Display for CvdStyle {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            CvdStyle::Line => write!(f, "Line"),
+            CvdStyle::Candles => write!(f, "Candles"),
+        }
+    }
+}
+
+/// Where the cumulative delta starts over from zero
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Deserialize, Serialize)]
+pub enum CvdReset {
+    None,
+    DailyUtc,
+    /// Daily, at `start_minute` minutes past midnight UTC
+    Session {
+        start_minute: u16,
+    },
+    /// From the earliest visible bar, the rest is accumulated without resets
+    VisibleRange,
+}
+
+impl CvdReset {
+    pub const DEFAULT_SESSION: CvdReset = CvdReset::Session { start_minute: 0 };
+
+    /// Which session the bar opening at `time` belongs to
+    fn session_of(&self, time: u64) -> u64 {
+        match self {
+            CvdReset::None | CvdReset::VisibleRange => 0,
+            CvdReset::DailyUtc => time / DAY_MS,
+            CvdReset::Session { start_minute } => {
+                (time + DAY_MS - u64::from(*start_minute) * MINUTE_MS) / DAY_MS
+            }
+        }
+    }
+}
+
+impl Display for CvdReset {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            CvdReset::None => write!(f, "No reset"),
+            CvdReset::DailyUtc => write!(f, "Daily"),
+            CvdReset::Session { start_minute } => {
+                write!(
+                    f,
+                    "Session {:02}:{:02}",
+                    start_minute / 60,
+                    start_minute % 60
+                )
+            }
+            CvdReset::VisibleRange => write!(f, "Visible range"),
+        }
+    }
+}
+
+/// Cumulative delta of a single bar
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct CvdBar {
+    pub open: f32,
+    pub high: f32,
+    pub low: f32,
+    pub close: f32,
+}
+
+impl CvdBar {
+    pub fn delta(&self) -> f32 {
+        self.close - self.open
+    }
+}
+
+#[derive(Debug, Clone, Copy)]
+struct BarDelta {
+    time: u64,
+    delta: f32,
+    /// Extremes the delta reached while the bar was updated live, relative to its open
+    highest: f32,
+    lowest: f32,
+}
+
+/// Buy minus sell volume accumulated bar by bar, keyed by bar open time or tick bar index
+pub struct CvdSeries {
+    reset: CvdReset,
+    deltas: BTreeMap<u64, BarDelta>,
+    bars: BTreeMap<u64, CvdBar>,
+    /// Whether any bar had its volume split into buys and sells
+    has_split_volume: bool,
+}
+
+impl CvdSeries {
+    pub fn new(reset: CvdReset) -> Self {
+        Self {
+            reset,
+            deltas: BTreeMap::new(),
+            bars: BTreeMap::new(),
+            has_split_volume: false,
+        }
+    }
+
+    pub fn bars(&self) -> &BTreeMap<u64, CvdBar> {
+        &self.bars
+    }
+
+    /// Venues reporting only the total volume leave nothing to accumulate
+    pub fn has_split_volume(&self) -> bool {
+        self.has_split_volume
+    }
+
+    pub fn clear(&mut self) {
+        self.deltas.clear();
+        self.bars.clear();
+        self.has_split_volume = false;
+    }
+
+    /// `(key, kline)` pairs, a key seen before is taken as a live update of that bar
+    pub fn insert<'a>(&mut self, klines: impl IntoIterator<Item = (u64, &'a Kline)>) {
+        let mut earliest: Option<u64> = None;
+
+        for (key, kline) in klines {
+            let (buy, sell) = kline.volume;
+            let delta = if buy == Kline::TOTAL_ONLY {
+                0.0
+            } else {
+                self.has_split_volume = true;
+                (buy - sell).to_f32()
+            };
+
+            let (highest, lowest) = match self.deltas.get(&key) {
+                Some(prev) => (prev.highest.max(delta), prev.lowest.min(delta)),
+                None => (delta.max(0.0), delta.min(0.0)),
+            };
+
+            self.deltas.insert(
+                key,
+                BarDelta {
+                    time: kline.time,
+                    delta,
+                    highest,
+                    lowest,
+                },
+            );
+            earliest = Some(earliest.map_or(key, |e| e.min(key)));
+        }
+
+        if let Some(from) = earliest {
+            self.recompute_from(from);
+        }
+    }
+
+    fn recompute_from(&mut self, from: u64) {
+        let mut prev = self
+            .deltas
+            .range(..from)
+            .next_back()
+            .and_then(|(key, bar)| Some((bar.time, self.bars.get(key)?.close)));
+
+        for (key, bar) in self.deltas.range(from..) {
+            let open = match prev {
+                Some((prev_time, prev_close))
+                    if self.reset.session_of(prev_time) == self.reset.session_of(bar.time) =>
+                {
+                    prev_close
+                }
+                _ => 0.0,
+            };
+            let close = open + bar.delta;
+
+            self.bars.insert(
+                *key,
+                CvdBar {
+                    open,
+                    high: open + bar.highest,
+                    low: open + bar.lowest,
+                    close,
+                },
+            );
+            prev = Some((bar.time, close));
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::kline;
+
+    #[test]
+    fn resets_at_session_start() {
+        let hour = 3_600_000;
+        let klines: Vec<Kline> = (0..4)
+            .map(|i| kline(DAY_MS - 2 * hour + i * hour, 100.0, (5.0, 2.0)))
+            .collect();
+
+        let mut series = CvdSeries::new(CvdReset::DailyUtc);
+        series.insert(klines.iter().map(|k| (k.time, k)));
+        let closes: Vec<f32> = series.bars().values().map(|bar| bar.close).collect();
+        assert_eq!(closes, vec![3.0, 6.0, 3.0, 6.0]);
+
+        // a session opening at 23:00 UTC starts over one bar earlier
+        let mut series = CvdSeries::new(CvdReset::Session {
+            start_minute: 23 * 60,
+        });
+        series.insert(klines.iter().map(|k| (k.time, k)));
+        let closes: Vec<f32> = series.bars().values().map(|bar| bar.close).collect();
+        assert_eq!(closes, vec![3.0, 3.0, 6.0, 9.0]);
+    }
+}
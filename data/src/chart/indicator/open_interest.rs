@@ -0,0 +1,162 @@
+use std::collections::BTreeMap;
+
+use exchange::Timeframe;
+
+/// Open interest a bar closed with, and how much it moved since the bar before
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct BarOi {
+    pub value: f32,
+    pub change: Option<f32>,
+}
+
+/// Open interest samples mapped onto bars keyed by open time or tick bar index
+pub struct OpenInterestSeries {
+    /// Samples as fetched, keyed by the time they were taken
+    samples: BTreeMap<u64, f32>,
+    /// Open time of each bar, by chart key
+    bars: BTreeMap<u64, u64>,
+    values: BTreeMap<u64, BarOi>,
+    /// How long a sample is held for the bars after it, the period it was fetched at
+    period_ms: u64,
+}
+
+impl Default for OpenInterestSeries {
+    fn default() -> Self {
+        Self {
+            samples: BTreeMap::new(),
+            bars: BTreeMap::new(),
+            values: BTreeMap::new(),
+            period_ms: Timeframe::M5.to_milliseconds(),
+        }
+    }
+}
+
+impl OpenInterestSeries {
+    pub fn samples(&self) -> &BTreeMap<u64, f32> {
+        &self.samples
+    }
+
+    pub fn values(&self) -> &BTreeMap<u64, BarOi> {
+        &self.values
+    }
+
+    /// Earliest and latest sample times, `latest_kline` for both when there are none
+    pub fn timerange(&self, latest_kline: u64) -> (u64, u64) {
+        let earliest = self
+            .samples
+            .keys()
+            .next()
+            .map_or(latest_kline, |t| (*t).min(latest_kline));
+        let latest = self.samples.keys().next_back().copied().unwrap_or(u64::MIN);
+
+        (earliest, latest)
+    }
+
+    /// Forgets the bars, samples are kept to be mapped onto the next ones
+    pub fn clear_bars(&mut self, period: Timeframe) {
+        self.period_ms = period.to_milliseconds();
+        self.bars.clear();
+        self.values.clear();
+    }
+
+    /// Records bars by key and open time, then maps samples onto them and the bar before,
+    /// whose span ends where the first of these opens
+    pub fn insert_bars(&mut self, bars: impl IntoIterator<Item = (u64, u64)>) {
+        let mut earliest = None;
+        for (key, open_time) in bars {
+            earliest = Some(earliest.map_or(key, |earliest: u64| earliest.min(key)));
+            self.bars.insert(key, open_time);
+        }
+
+        if let Some(earliest) = earliest {
+            let from = self
+                .bars
+                .range(..earliest)
+                .next_back()
+                .map_or(earliest, |(key, _)| *key);
+            self.map_samples_from(from);
+        }
+    }
+
+    pub fn insert_samples(&mut self, samples: impl IntoIterator<Item = (u64, f32)>) {
+        self.samples.extend(samples);
+        self.map_samples_from(u64::MIN);
+    }
+
+    /// Each bar gets the latest sample taken before the next bar opened, held for one
+    /// period at most so gaps in the samples stay empty
+    fn map_samples_from(&mut self, from: u64) {
+        self.values.split_off(&from);
+
+        let mut prev = self
+            .bars
+            .range(..from)
+            .next_back()
+            .and_then(|(key, _)| self.values.get(key))
+            .map(|oi| oi.value);
+
+        let mut bars = self.bars.range(from..).peekable();
+        while let Some((&key, &open_time)) = bars.next() {
+            let next_open = bars.peek().map_or(u64::MAX, |(_, open_time)| **open_time);
+
+            let sample = self
+                .samples
+                .range(open_time.saturating_sub(self.period_ms)..next_open)
+                .next_back()
+                .map(|(_, value)| *value);
+
+            if let Some(value) = sample {
+                let change = prev.map(|prev| value - prev);
+                self.values.insert(key, BarOi { value, change });
+            }
+            prev = sample;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const MINUTE: u64 = 60_000;
+
+    #[test]
+    fn maps_samples_onto_tick_bars_by_open_time() {
+        let mut series = OpenInterestSeries::default();
+        series.clear_bars(Timeframe::M5);
+
+        // tick bars are keyed by index, they open at uneven times
+        let opens = [0, 2 * MINUTE, 3 * MINUTE, 11 * MINUTE, 30 * MINUTE];
+        series.insert_bars(opens.iter().enumerate().map(|(idx, t)| (idx as u64, *t)));
+        series.insert_samples([
+            (0, 100.0),
+            (5 * MINUTE, 110.0),
+            (10 * MINUTE, 105.0),
+            (15 * MINUTE, 120.0),
+        ]);
+
+        let values: Vec<(u64, BarOi)> = series.values().iter().map(|(k, v)| (*k, *v)).collect();
+        let oi = |value: f32, change: Option<f32>| BarOi { value, change };
+        assert_eq!(
+            values,
+            vec![
+                (0, oi(100.0, None)),
+                // nothing new before bar 2 opens, the sample is held
+                (1, oi(100.0, Some(0.0))),
+                // the latest sample before bar 3 opens at 11m
+                (2, oi(105.0, Some(5.0))),
+                (3, oi(120.0, Some(15.0))),
+                // samples stop 15m before bar 4, longer than they are held
+            ]
+        );
+
+        // the forming bar's own sample shows up once it's taken, changing from the bar before
+        series.insert_samples([(31 * MINUTE, 118.0)]);
+        assert_eq!(series.values()[&4], oi(118.0, Some(-2.0)));
+
+        series.insert_bars([(5, 32 * MINUTE)]);
+        assert_eq!(series.values()[&4], oi(118.0, Some(-2.0)));
+        assert_eq!(series.values()[&5], oi(118.0, Some(0.0)));
+        assert_eq!(series.timerange(40 * MINUTE), (0, 31 * MINUTE));
+    }
+}
@@ -0,0 +1,403 @@
+use std::collections::BTreeMap;
+
+use exchange::Kline;
+
+use super::average::{AverageSeries, MaKind};
+
+/// Wilder's smoothing, an EMA over `2 * period - 1` bars
+fn wilder(period: usize) -> AverageSeries {
+    AverageSeries::new(MaKind::Ema, (2 * period).saturating_sub(1))
+}
+
+/// Greatest of the bar's range and its gap from the close before it
+pub(crate) fn true_range(high: f32, low: f32, prev_close: f32) -> f32 {
+    (high - low)
+        .max((high - prev_close).abs())
+        .max((low - prev_close).abs())
+}
+
+/// Relative strength index of the close, keyed by bar open time or tick bar index
+pub struct RsiSeries {
+    closes: BTreeMap<u64, f32>,
+    gains: AverageSeries,
+    losses: AverageSeries,
+    values: BTreeMap<u64, f32>,
+}
+
+impl RsiSeries {
+    pub fn new(period: usize) -> Self {
+        let period = period.max(1);
+
+        Self {
+            closes: BTreeMap::new(),
+            gains: wilder(period),
+            losses: wilder(period),
+            values: BTreeMap::new(),
+        }
+    }
+
+    pub fn values(&self) -> &BTreeMap<u64, f32> {
+        &self.values
+    }
+
+    pub fn clear(&mut self) {
+        self.closes.clear();
+        self.gains.clear();
+        self.losses.clear();
+        self.values.clear();
+    }
+
+    pub fn insert<'a>(&mut self, klines: impl IntoIterator<Item = (u64, &'a Kline)>) {
+        let mut earliest: Option<u64> = None;
+
+        for (key, kline) in klines {
+            self.closes.insert(key, kline.close.to_f32());
+            earliest = Some(earliest.map_or(key, |e| e.min(key)));
+        }
+
+        let Some(from) = earliest else {
+            return;
+        };
+
+        // a bar's change depends on the close before it, the first bar has none
+        let changes: Vec<(u64, f32)> = self
+            .closes
+            .range(from..)
+            .filter_map(|(key, close)| {
+                let (_, prev_close) = self.closes.range(..*key).next_back()?;
+                Some((*key, close - prev_close))
+            })
+            .collect();
+
+        self.gains
+            .insert(changes.iter().map(|&(key, change)| (key, change.max(0.0))));
+        self.losses.insert(
+            changes
+                .iter()
+                .map(|&(key, change)| (key, (-change).max(0.0))),
+        );
+
+        let keys: Vec<u64> = self.closes.range(from..).map(|(key, _)| *key).collect();
+
+        for key in keys {
+            let value = self
+                .gains
+                .values()
+                .get(&key)
+                .zip(self.losses.values().get(&key))
+                .map(|(&gain, &loss)| {
+                    if loss == 0.0 {
+                        if gain == 0.0 { 50.0 } else { 100.0 }
+                    } else {
+                        100.0 - 100.0 / (1.0 + gain / loss)
+                    }
+                });
+
+            match value {
+                Some(value) => self.values.insert(key, value),
+                None => self.values.remove(&key),
+            };
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct MacdPoint {
+    pub macd: f32,
+    pub signal: f32,
+}
+
+impl MacdPoint {
+    pub fn histogram(&self) -> f32 {
+        self.macd - self.signal
+    }
+}
+
+/// Fast EMA minus slow EMA of the close, with an EMA of that as its signal line
+pub struct MacdSeries {
+    fast: AverageSeries,
+    slow: AverageSeries,
+    signal: AverageSeries,
+    values: BTreeMap<u64, MacdPoint>,
+}
+
+impl MacdSeries {
+    pub fn new(fast: usize, slow: usize, signal: usize) -> Self {
+        Self {
+            fast: AverageSeries::new(MaKind::Ema, fast),
+            slow: AverageSeries::new(MaKind::Ema, slow),
+            signal: AverageSeries::new(MaKind::Ema, signal),
+            values: BTreeMap::new(),
+        }
+    }
+
+    pub fn values(&self) -> &BTreeMap<u64, MacdPoint> {
+        &self.values
+    }
+
+    pub fn clear(&mut self) {
+        self.fast.clear();
+        self.slow.clear();
+        self.signal.clear();
+        self.values.clear();
+    }
+
+    pub fn insert<'a>(&mut self, klines: impl IntoIterator<Item = (u64, &'a Kline)>) {
+        let closes: Vec<(u64, f32)> = klines
+            .into_iter()
+            .map(|(key, kline)| (key, kline.close.to_f32()))
+            .collect();
+
+        let Some(from) = closes.iter().map(|(key, _)| *key).min() else {
+            return;
+        };
+
+        self.fast.insert(closes.iter().copied());
+        self.slow.insert(closes);
+
+        let macd: Vec<(u64, f32)> = self
+            .fast
+            .values()
+            .range(from..)
+            .filter_map(|(key, fast)| Some((*key, fast - self.slow.values().get(key)?)))
+            .collect();
+        self.signal.insert(macd.iter().copied());
+
+        let keys: Vec<u64> = self
+            .fast
+            .inputs()
+            .range(from..)
+            .map(|(key, _)| *key)
+            .collect();
+
+        for key in keys {
+            let point = self.signal.inputs().get(&key).and_then(|&macd| {
+                let signal = *self.signal.values().get(&key)?;
+                Some(MacdPoint { macd, signal })
+            });
+
+            match point {
+                Some(point) => self.values.insert(key, point),
+                None => self.values.remove(&key),
+            };
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct StochasticPoint {
+    pub k: f32,
+    pub d: f32,
+}
+
+/// Where the close sits within the highs and lows of the last `period` bars, as a
+/// smoothed %K line and its %D average
+pub struct StochasticSeries {
+    period: usize,
+    /// `(high, low, close)` of each bar
+    bars: BTreeMap<u64, (f32, f32, f32)>,
+    k: AverageSeries,
+    d: AverageSeries,
+    values: BTreeMap<u64, StochasticPoint>,
+}
+
+impl StochasticSeries {
+    pub fn new(period: usize, smooth_k: usize, smooth_d: usize) -> Self {
+        Self {
+            period: period.max(1),
+            bars: BTreeMap::new(),
+            k: AverageSeries::new(MaKind::Sma, smooth_k),
+            d: AverageSeries::new(MaKind::Sma, smooth_d),
+            values: BTreeMap::new(),
+        }
+    }
+
+    pub fn values(&self) -> &BTreeMap<u64, StochasticPoint> {
+        &self.values
+    }
+
+    pub fn clear(&mut self) {
+        self.bars.clear();
+        self.k.clear();
+        self.d.clear();
+        self.values.clear();
+    }
+
+    pub fn insert<'a>(&mut self, klines: impl IntoIterator<Item = (u64, &'a Kline)>) {
+        let mut earliest: Option<u64> = None;
+
+        for (key, kline) in klines {
+            self.bars.insert(
+                key,
+                (
+                    kline.high.to_f32(),
+                    kline.low.to_f32(),
+                    kline.close.to_f32(),
+                ),
+            );
+            earliest = Some(earliest.map_or(key, |e| e.min(key)));
+        }
+
+        let Some(from) = earliest else {
+            return;
+        };
+
+        let raw_k: Vec<(u64, f32)> = self
+            .bars
+            .range(from..)
+            .filter_map(|(key, &(_, _, close))| {
+                let window: Vec<(f32, f32)> = self
+                    .bars
+                    .range(..=*key)
+                    .rev()
+                    .take(self.period)
+                    .map(|(_, &(high, low, _))| (high, low))
+                    .collect();
+
+                if window.len() < self.period {
+                    return None;
+                }
+
+                let highest = window
+                    .iter()
+                    .map(|(high, _)| *high)
+                    .fold(f32::MIN, f32::max);
+                let lowest = window.iter().map(|(_, low)| *low).fold(f32::MAX, f32::min);
+
+                let raw = if highest > lowest {
+                    100.0 * (close - lowest) / (highest - lowest)
+                } else {
+                    50.0
+                };
+                Some((*key, raw))
+            })
+            .collect();
+
+        self.k.insert(raw_k);
+
+        let k: Vec<(u64, f32)> = self
+            .k
+            .values()
+            .range(from..)
+            .map(|(key, k)| (*key, *k))
+            .collect();
+        self.d.insert(k);
+
+        let keys: Vec<u64> = self.bars.range(from..).map(|(key, _)| *key).collect();
+
+        for key in keys {
+            let point = self
+                .k
+                .values()
+                .get(&key)
+                .zip(self.d.values().get(&key))
+                .map(|(&k, &d)| StochasticPoint { k, d });
+
+            match point {
+                Some(point) => self.values.insert(key, point),
+                None => self.values.remove(&key),
+            };
+        }
+    }
+}
+
+/// Average true range, smoothed the way Wilder did
+pub struct AtrSeries {
+    closes: BTreeMap<u64, f32>,
+    true_range: AverageSeries,
+    /// `(high, low)` of each bar
+    ranges: BTreeMap<u64, (f32, f32)>,
+}
+
+impl AtrSeries {
+    pub fn new(period: usize) -> Self {
+        Self {
+            closes: BTreeMap::new(),
+            true_range: wilder(period.max(1)),
+            ranges: BTreeMap::new(),
+        }
+    }
+
+    pub fn values(&self) -> &BTreeMap<u64, f32> {
+        self.true_range.values()
+    }
+
+    pub fn clear(&mut self) {
+        self.closes.clear();
+        self.true_range.clear();
+        self.ranges.clear();
+    }
+
+    pub fn insert<'a>(&mut self, klines: impl IntoIterator<Item = (u64, &'a Kline)>) {
+        let mut earliest: Option<u64> = None;
+
+        for (key, kline) in klines {
+            self.closes.insert(key, kline.close.to_f32());
+            self.ranges
+                .insert(key, (kline.high.to_f32(), kline.low.to_f32()));
+            earliest = Some(earliest.map_or(key, |e| e.min(key)));
+        }
+
+        let Some(from) = earliest else {
+            return;
+        };
+
+        let true_ranges: Vec<(u64, f32)> = self
+            .ranges
+            .range(from..)
+            .map(|(key, &(high, low))| {
+                let prev_close = self
+                    .closes
+                    .range(..*key)
+                    .next_back()
+                    .map_or(self.closes[key], |(_, close)| *close);
+
+                (*key, true_range(high, low, prev_close))
+            })
+            .collect();
+
+        self.true_range.insert(true_ranges);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::kline;
+    use exchange::util::Price;
+
+    #[test]
+    fn oscillators_of_a_steady_rise() {
+        // each bar closes at its high, one above the last, with a range of two
+        let klines: Vec<Kline> = (0..40u64)
+            .map(|i| {
+                let close = 100.0 + i as f32;
+                Kline {
+                    low: Price::from_f32(close - 2.0),
+                    ..kline(i, close, (1.0, 1.0))
+                }
+            })
+            .collect();
+
+        let mut rsi = RsiSeries::new(14);
+        rsi.insert(klines.iter().map(|k| (k.time, k)));
+        assert_eq!(rsi.values().get(&10), None);
+        assert_eq!(rsi.values()[&39], 100.0);
+
+        let mut stochastic = StochasticSeries::new(14, 3, 3);
+        stochastic.insert(klines.iter().map(|k| (k.time, k)));
+        assert_eq!(stochastic.values()[&39].k, 100.0);
+        assert_eq!(stochastic.values()[&39].d, 100.0);
+
+        let mut atr = AtrSeries::new(14);
+        atr.insert(klines.iter().map(|k| (k.time, k)));
+        assert!((atr.values()[&39] - 2.0).abs() < 1e-4);
+
+        // both EMAs lag a linear rise by a constant, so the MACD settles above zero
+        let mut macd = MacdSeries::new(12, 26, 9);
+        macd.insert(klines.iter().map(|k| (k.time, k)));
+        let latest = macd.values()[&39];
+        assert!((latest.macd - 7.0).abs() < 0.1, "{latest:?}");
+        assert!(latest.histogram().abs() < 0.1);
+    }
+}
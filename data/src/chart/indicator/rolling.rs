@@ -0,0 +1,341 @@
+use std::collections::BTreeMap;
+use std::fmt::{self, Display};
+
+use exchange::Kline;
+use serde::{Deserialize, Serialize};
+
+use super::average::{sma, window};
+
+const YEAR_MS: f32 = 365.0 * 24.0 * 60.0 * 60.0 * 1000.0;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Rolling {
+    pub mean: f32,
+    pub std_dev: f32,
+}
+
+/// Mean and standard deviation of the last `period` inputs at each key, keyed like
+/// [`super::average::AverageSeries`] and recomputed from the earliest key that changed
+pub struct RollingSeries {
+    period: usize,
+    inputs: BTreeMap<u64, f32>,
+    values: BTreeMap<u64, Rolling>,
+}
+
+impl RollingSeries {
+    pub fn new(period: usize) -> Self {
+        Self {
+            period: period.max(1),
+            inputs: BTreeMap::new(),
+            values: BTreeMap::new(),
+        }
+    }
+
+    pub fn inputs(&self) -> &BTreeMap<u64, f32> {
+        &self.inputs
+    }
+
+    pub fn values(&self) -> &BTreeMap<u64, Rolling> {
+        &self.values
+    }
+
+    pub fn clear(&mut self) {
+        self.inputs.clear();
+        self.values.clear();
+    }
+
+    pub fn insert(&mut self, points: impl IntoIterator<Item = (u64, f32)>) {
+        let mut earliest: Option<u64> = None;
+
+        for (key, value) in points {
+            self.inputs.insert(key, value);
+            earliest = Some(earliest.map_or(key, |e| e.min(key)));
+        }
+
+        let Some(from) = earliest else {
+            return;
+        };
+
+        let keys: Vec<u64> = self.inputs.range(from..).map(|(key, _)| *key).collect();
+
+        for key in keys {
+            match window(&self.inputs, key, self.period) {
+                Some(values) => {
+                    let mean = sma(&values);
+                    let variance = values.iter().map(|v| (v - mean).powi(2)).sum::<f32>()
+                        / values.len() as f32;
+
+                    self.values.insert(
+                        key,
+                        Rolling {
+                            mean,
+                            std_dev: variance.sqrt(),
+                        },
+                    );
+                }
+                None => {
+                    self.values.remove(&key);
+                }
+            }
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Deserialize, Serialize)]
+pub enum VolEstimator {
+    /// Log returns from one close to the next
+    CloseToClose,
+    /// Log range from the high to the low of each bar, doesn't see gaps between bars
+    Parkinson,
+}
+
+impl VolEstimator {
+    pub const ALL: [VolEstimator; 2] = [VolEstimator::CloseToClose, VolEstimator::Parkinson];
+}
+
+impl Display for VolEstimator {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            VolEstimator::CloseToClose => write!(f, "Close-to-close"),
+            VolEstimator::Parkinson => write!(f, "Parkinson"),
+        }
+    }
+}
+
+/// Annualized realized volatility in percent, over the last `period` bars.
+///
+/// Variance of the window is scaled by how long its bars lasted, the timeframe for time
+/// based charts, the gaps between bar opens for tick charts
+pub struct RealizedVolSeries {
+    estimator: VolEstimator,
+    /// Bar length of time based charts, `None` for tick bars
+    bar_ms: Option<u64>,
+    bars: BTreeMap<u64, Kline>,
+    variance: RollingSeries,
+    duration: RollingSeries,
+    values: BTreeMap<u64, f32>,
+}
+
+impl RealizedVolSeries {
+    pub fn new(estimator: VolEstimator, period: usize) -> Self {
+        Self {
+            estimator,
+            bar_ms: None,
+            bars: BTreeMap::new(),
+            variance: RollingSeries::new(period),
+            duration: RollingSeries::new(period),
+            values: BTreeMap::new(),
+        }
+    }
+
+    pub fn values(&self) -> &BTreeMap<u64, f32> {
+        &self.values
+    }
+
+    /// Empties the series for bars `bar_ms` long, `None` for tick bars
+    pub fn clear(&mut self, bar_ms: Option<u64>) {
+        self.bar_ms = bar_ms;
+        self.bars.clear();
+        self.variance.clear();
+        self.duration.clear();
+        self.values.clear();
+    }
+
+    pub fn insert<'a>(&mut self, klines: impl IntoIterator<Item = (u64, &'a Kline)>) {
+        let mut earliest: Option<u64> = None;
+
+        for (key, kline) in klines {
+            self.bars.insert(key, *kline);
+            earliest = Some(earliest.map_or(key, |e| e.min(key)));
+        }
+
+        let Some(from) = earliest else {
+            return;
+        };
+
+        // a bar's terms depend on the bar before it
+        let mut variances = vec![];
+        let mut durations = vec![];
+
+        for (key, kline) in self.bars.range(from..) {
+            let prev = self.bars.range(..*key).next_back().map(|(_, prev)| prev);
+
+            let duration = match self.bar_ms {
+                Some(bar_ms) => Some(bar_ms),
+                None => prev.map(|prev| kline.time.saturating_sub(prev.time)),
+            };
+            let variance = match self.estimator {
+                VolEstimator::CloseToClose => {
+                    prev.map(|prev| (kline.close.to_f32() / prev.close.to_f32()).ln().powi(2))
+                }
+                VolEstimator::Parkinson => Some(
+                    (kline.high.to_f32() / kline.low.to_f32()).ln().powi(2)
+                        / (4.0 * std::f32::consts::LN_2),
+                ),
+            };
+
+            if let Some((variance, duration)) = variance.zip(duration)
+                && variance.is_finite()
+            {
+                variances.push((*key, variance));
+                durations.push((*key, duration as f32));
+            }
+        }
+
+        self.variance.insert(variances);
+        self.duration.insert(durations);
+
+        let keys: Vec<u64> = self.bars.range(from..).map(|(key, _)| *key).collect();
+
+        for key in keys {
+            let variance = self.variance.values().get(&key);
+            let duration = self.duration.values().get(&key);
+
+            match variance.zip(duration) {
+                Some((variance, duration)) if duration.mean > 0.0 => {
+                    let annualized = variance.mean * YEAR_MS / duration.mean;
+                    self.values.insert(key, annualized.sqrt() * 100.0);
+                }
+                _ => {
+                    self.values.remove(&key);
+                }
+            }
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct VolumeZScore {
+    pub volume: f32,
+    /// Standard deviations above the mean volume of the bars before
+    pub z: f32,
+}
+
+/// Volume of each bar measured against the `period` bars before it
+pub struct VolumeZScoreSeries {
+    volume: RollingSeries,
+    values: BTreeMap<u64, VolumeZScore>,
+}
+
+impl VolumeZScoreSeries {
+    pub fn new(period: usize) -> Self {
+        Self {
+            volume: RollingSeries::new(period),
+            values: BTreeMap::new(),
+        }
+    }
+
+    pub fn values(&self) -> &BTreeMap<u64, VolumeZScore> {
+        &self.values
+    }
+
+    pub fn clear(&mut self) {
+        self.volume.clear();
+        self.values.clear();
+    }
+
+    pub fn insert<'a>(&mut self, klines: impl IntoIterator<Item = (u64, &'a Kline)>) {
+        let volumes: Vec<(u64, f32)> = klines
+            .into_iter()
+            .map(|(key, kline)| (key, kline.total_volume().to_f32()))
+            .collect();
+
+        let Some(from) = volumes.iter().map(|(key, _)| *key).min() else {
+            return;
+        };
+
+        self.volume.insert(volumes);
+
+        let keys: Vec<u64> = self
+            .volume
+            .inputs()
+            .range(from..)
+            .map(|(key, _)| *key)
+            .collect();
+
+        for key in keys {
+            let volume = self.volume.inputs()[&key];
+            let before = self
+                .volume
+                .values()
+                .range(..key)
+                .next_back()
+                .map(|(_, rolling)| rolling);
+
+            match before {
+                Some(before) if before.std_dev > 0.0 => {
+                    let z = (volume - before.mean) / before.std_dev;
+                    self.values.insert(key, VolumeZScore { volume, z });
+                }
+                _ => {
+                    self.values.remove(&key);
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::kline;
+    use exchange::util::{Price, Qty};
+
+    #[test]
+    fn volatility_of_steady_returns_and_volume_spikes() {
+        const MINUTE: u64 = 60_000;
+
+        // every minute closes 1% (in log terms) above the last, with a 2% range
+        let klines: Vec<Kline> = (0..40u64)
+            .map(|i| {
+                let close = 100.0 * (0.01 * i as f32).exp();
+                let volume = if i == 39 {
+                    10.0
+                } else {
+                    [1.0, 3.0][i as usize % 2]
+                };
+                Kline {
+                    high: Price::from_f32(close * 1.01),
+                    low: Price::from_f32(close / 1.01),
+                    volume: (Kline::TOTAL_ONLY, Qty::from_f32(volume)),
+                    ..kline(i * MINUTE, close, (0.0, 0.0))
+                }
+            })
+            .collect();
+        let minutes_per_year = YEAR_MS / MINUTE as f32;
+
+        let mut close_to_close = RealizedVolSeries::new(VolEstimator::CloseToClose, 20);
+        close_to_close.clear(Some(MINUTE));
+        close_to_close.insert(klines.iter().map(|k| (k.time, k)));
+        let expected = 0.01 * minutes_per_year.sqrt() * 100.0;
+        let latest = close_to_close.values()[&(39 * MINUTE)];
+        assert!((latest - expected).abs() / expected < 0.01, "{latest}");
+
+        // tick bars keyed by index are timed by their opens, a minute apart here
+        let mut ticks = RealizedVolSeries::new(VolEstimator::CloseToClose, 20);
+        ticks.clear(None);
+        ticks.insert(klines.iter().enumerate().map(|(i, k)| (i as u64, k)));
+        assert!((ticks.values()[&39] - latest).abs() < 1e-3);
+
+        let mut parkinson = RealizedVolSeries::new(VolEstimator::Parkinson, 20);
+        parkinson.clear(Some(MINUTE));
+        parkinson.insert(klines.iter().map(|k| (k.time, k)));
+        let expected =
+            (0.02f32.powi(2) / (4.0 * std::f32::consts::LN_2) * minutes_per_year).sqrt() * 100.0;
+        let latest = parkinson.values()[&(39 * MINUTE)];
+        assert!((latest - expected).abs() / expected < 0.01, "{latest}");
+
+        // alternating 1 and 3 have a mean of 2 and a deviation of 1
+        let mut volume = VolumeZScoreSeries::new(20);
+        volume.insert(klines.iter().map(|k| (k.time, k)));
+        assert!((volume.values()[&(39 * MINUTE)].z - 8.0).abs() < 1e-3);
+
+        // the forming bar updates in place
+        let updated = Kline {
+            volume: (Kline::TOTAL_ONLY, Qty::from_f32(3.0)),
+            ..kline(39 * MINUTE, 1.0, (0.0, 0.0))
+        };
+        volume.insert([(updated.time, &updated)]);
+        assert!((volume.values()[&(39 * MINUTE)].z - 1.0).abs() < 1e-3);
+    }
+}
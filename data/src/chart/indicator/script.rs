@@ -0,0 +1,599 @@
+//! Indicators written by users as [evalexpr](https://docs.rs/evalexpr) scripts, read from
+//! the `indicators` folder under the data folder, one `.expr` file each.
+//!
+//! A script runs once per bar, oldest first, and plots the value its last expression
+//! ends with, `()` leaves the bar empty. Variables it assigns carry over to the next
+//! bar, statements above a `---` line run once before the first bar to set them up.
+//! A `// plot: line`, `// plot: histogram` or `// plot: markers` comment picks how the
+//! values are drawn, lines by default.
+//!
+//! ```text
+//! // plot: line
+//! ema = 0.0;
+//! ---
+//! ema = if(first, close, ema + (close - ema) * 2.0 / 21.0);
+//! ema
+//! ```
+//!
+//! Each bar sets `first`, `bar` (index from the oldest), `time`, `open`, `high`, `low`,
+//! `close`, `volume`, `buy_volume` and `sell_volume`, `delta`, `oi`, and from the
+//! footprint `poc`, `poc_volume` and `levels`. Values that aren't known are NaN,
+//! `math::is_nan` tells them apart. `oi` is only there while open interest is loaded
+//! for the chart, by its indicator on the same pane.
+
+use std::collections::BTreeMap;
+use std::fmt;
+
+use evalexpr::{
+    ContextWithMutableVariables, EvalexprError, HashMapContext, Node, Value, build_operator_tree,
+};
+use exchange::Kline;
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+use crate::chart::kline::KlineTrades;
+
+pub const SCRIPTS_DIR: &str = "indicators";
+const SCRIPT_EXT: &str = "expr";
+const INIT_SEPARATOR: &str = "---";
+
+/// File stem of a script, kept inline so indicator configs stay `Copy`
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub struct ScriptName {
+    len: u8,
+    bytes: [u8; ScriptName::MAX_LEN],
+}
+
+impl ScriptName {
+    pub const MAX_LEN: usize = 40;
+
+    /// `None` for names longer than [`Self::MAX_LEN`] bytes
+    pub fn new(name: &str) -> Option<Self> {
+        if name.is_empty() || name.len() > Self::MAX_LEN {
+            return None;
+        }
+
+        let mut bytes = [0; Self::MAX_LEN];
+        bytes[..name.len()].copy_from_slice(name.as_bytes());
+
+        Some(Self {
+            len: name.len() as u8,
+            bytes,
+        })
+    }
+
+    pub fn as_str(&self) -> &str {
+        std::str::from_utf8(&self.bytes[..usize::from(self.len)]).unwrap_or_default()
+    }
+}
+
+impl fmt::Display for ScriptName {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl fmt::Debug for ScriptName {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{:?}", self.as_str())
+    }
+}
+
+impl Serialize for ScriptName {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(self.as_str())
+    }
+}
+
+impl<'de> Deserialize<'de> for ScriptName {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let name = String::deserialize(deserializer)?;
+        ScriptName::new(&name).ok_or_else(|| serde::de::Error::custom("invalid script name"))
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ScriptPlot {
+    Line,
+    Histogram,
+    Markers,
+}
+
+#[derive(thiserror::Error, Debug, Clone)]
+pub enum ScriptError {
+    #[error("Couldn't read the script: {0}")]
+    Io(String),
+    #[error("Unknown plot kind `{0}`, expected line, histogram or markers")]
+    Plot(String),
+    #[error("Couldn't parse the script: {0}")]
+    Parse(EvalexprError),
+    #[error("Setup failed: {0}")]
+    Init(EvalexprError),
+    #[error("Failed on bar {bar}: {error}")]
+    Bar { bar: usize, error: EvalexprError },
+    #[error("Bar {0} ended with {1}, expected a number or ()")]
+    Output(usize, Value),
+}
+
+pub struct Script {
+    pub plot: ScriptPlot,
+    init: Option<Node>,
+    per_bar: Node,
+}
+
+impl Script {
+    pub fn parse(source: &str) -> Result<Self, ScriptError> {
+        let plot = match source
+            .lines()
+            .filter_map(|line| line.trim().strip_prefix("//"))
+            .find_map(|comment| comment.trim().strip_prefix("plot:"))
+            .map(str::trim)
+        {
+            None | Some("line") => ScriptPlot::Line,
+            Some("histogram") => ScriptPlot::Histogram,
+            Some("markers") => ScriptPlot::Markers,
+            Some(other) => return Err(ScriptError::Plot(other.to_string())),
+        };
+
+        let lines: Vec<&str> = source.lines().collect();
+        let (init, per_bar) = match lines.iter().position(|line| line.trim() == INIT_SEPARATOR) {
+            Some(at) => (Some(lines[..at].join("\n")), lines[at + 1..].join("\n")),
+            None => (None, lines.join("\n")),
+        };
+
+        Ok(Self {
+            plot,
+            init: init
+                .map(|init| build_operator_tree(&init))
+                .transpose()
+                .map_err(ScriptError::Parse)?,
+            per_bar: build_operator_tree(&per_bar).map_err(ScriptError::Parse)?,
+        })
+    }
+
+    pub fn load(name: ScriptName) -> Result<Self, ScriptError> {
+        let path = crate::data_path(Some(&format!("{SCRIPTS_DIR}/{name}.{SCRIPT_EXT}")));
+        let source = std::fs::read_to_string(path).map_err(|e| ScriptError::Io(e.to_string()))?;
+
+        Self::parse(&source)
+    }
+}
+
+/// Names of the scripts in the scripts folder, sorted
+pub fn available() -> Vec<ScriptName> {
+    let Ok(entries) = std::fs::read_dir(crate::data_path(Some(SCRIPTS_DIR))) else {
+        return vec![];
+    };
+
+    let mut names: Vec<ScriptName> = entries
+        .filter_map(Result::ok)
+        .map(|entry| entry.path())
+        .filter(|path| path.extension().is_some_and(|ext| ext == SCRIPT_EXT))
+        .filter_map(|path| {
+            let stem = path.file_stem()?.to_str()?;
+            let name = ScriptName::new(stem);
+            if name.is_none() {
+                log::warn!(
+                    "Skipping script {stem}, names are limited to {} bytes",
+                    ScriptName::MAX_LEN
+                );
+            }
+            name
+        })
+        .collect();
+
+    names.sort_by(|a, b| a.as_str().cmp(b.as_str()));
+    names
+}
+
+/// What the footprint of a bar adds up to, when trades were loaded for it
+#[derive(Debug, Clone, Copy, PartialEq)]
+struct FootprintInputs {
+    buy_volume: f32,
+    sell_volume: f32,
+    poc: f32,
+    poc_volume: f32,
+    levels: usize,
+}
+
+impl FootprintInputs {
+    fn of(footprint: &KlineTrades) -> Option<Self> {
+        let (poc, group) = footprint
+            .trades
+            .iter()
+            .max_by_key(|(_, group)| group.total_qty())?;
+
+        let (buy_volume, sell_volume) = footprint
+            .trades
+            .values()
+            .fold((0.0, 0.0), |(buy, sell), group| {
+                (buy + group.buy_qty.to_f32(), sell + group.sell_qty.to_f32())
+            });
+
+        Some(Self {
+            buy_volume,
+            sell_volume,
+            poc: poc.to_f32(),
+            poc_volume: group.total_qty().to_f32(),
+            levels: footprint.trades.len(),
+        })
+    }
+}
+
+/// What a script sees of a bar
+#[derive(Debug, Clone, Copy)]
+pub struct BarInputs {
+    kline: Kline,
+    footprint: Option<FootprintInputs>,
+}
+
+impl BarInputs {
+    pub fn new(kline: &Kline, footprint: &KlineTrades) -> Self {
+        Self {
+            kline: *kline,
+            footprint: FootprintInputs::of(footprint),
+        }
+    }
+
+    /// Buy and sell volume of the kline when it's split, of the loaded trades otherwise
+    fn buy_sell(&self) -> (f32, f32) {
+        let (buy, sell) = self.kline.volume;
+
+        if buy != Kline::TOTAL_ONLY {
+            (buy.to_f32(), sell.to_f32())
+        } else {
+            self.footprint
+                .map_or((f32::NAN, f32::NAN), |fp| (fp.buy_volume, fp.sell_volume))
+        }
+    }
+
+    fn set_on(
+        &self,
+        context: &mut HashMapContext,
+        bar: usize,
+        oi: Option<f32>,
+    ) -> Result<(), EvalexprError> {
+        let kline = &self.kline;
+        let (buy, sell) = self.buy_sell();
+        let float = |value: f32| Value::Float(f64::from(value));
+
+        let inputs = [
+            ("first", Value::Boolean(bar == 0)),
+            ("bar", Value::Int(bar as i64)),
+            ("time", Value::Int(kline.time as i64)),
+            ("open", float(kline.open.to_f32())),
+            ("high", float(kline.high.to_f32())),
+            ("low", float(kline.low.to_f32())),
+            ("close", float(kline.close.to_f32())),
+            ("volume", float(kline.total_volume().to_f32())),
+            ("buy_volume", float(buy)),
+            ("sell_volume", float(sell)),
+            ("delta", float(buy - sell)),
+            ("oi", float(oi.unwrap_or(f32::NAN))),
+            ("poc", float(self.footprint.map_or(f32::NAN, |fp| fp.poc))),
+            (
+                "poc_volume",
+                float(self.footprint.map_or(f32::NAN, |fp| fp.poc_volume)),
+            ),
+            (
+                "levels",
+                Value::Int(self.footprint.map_or(0, |fp| fp.levels as i64)),
+            ),
+        ];
+
+        for (name, value) in inputs {
+            context.set_value(name.to_string(), value)?;
+        }
+        Ok(())
+    }
+}
+
+/// Runs a script over bars keyed by open time or tick bar index. Variables carry over
+/// from bar to bar, so bars before the earliest changed one are only evaluated again
+/// when that can't be avoided
+pub struct ScriptSeries {
+    script: Script,
+    bars: BTreeMap<u64, BarInputs>,
+    /// Samples by the time they were taken, each bar gets the latest one before the next opens
+    open_interest: BTreeMap<u64, f32>,
+    values: BTreeMap<u64, f32>,
+    context: HashMapContext,
+    /// Key of the last evaluated bar, and the context as it was before it. The latest bar
+    /// keeps changing while it forms, it's evaluated again from there
+    latest: Option<(u64, HashMapContext)>,
+    error: Option<ScriptError>,
+}
+
+impl ScriptSeries {
+    pub fn new(script: Script) -> Self {
+        Self {
+            script,
+            bars: BTreeMap::new(),
+            open_interest: BTreeMap::new(),
+            values: BTreeMap::new(),
+            context: HashMapContext::new(),
+            latest: None,
+            error: None,
+        }
+    }
+
+    pub fn plot(&self) -> ScriptPlot {
+        self.script.plot
+    }
+
+    pub fn values(&self) -> &BTreeMap<u64, f32> {
+        &self.values
+    }
+
+    pub fn error(&self) -> Option<&ScriptError> {
+        self.error.as_ref()
+    }
+
+    /// Swaps in an edited version of the script, bars have to be inserted again
+    pub fn reload(&mut self, script: Script) {
+        self.script = script;
+        self.clear();
+    }
+
+    pub fn clear(&mut self) {
+        self.bars.clear();
+        self.values.clear();
+        self.latest = None;
+        self.error = None;
+    }
+
+    pub fn insert(&mut self, bars: impl IntoIterator<Item = (u64, BarInputs)>) {
+        let mut earliest: Option<u64> = None;
+
+        for (key, inputs) in bars {
+            self.bars.insert(key, inputs);
+            earliest = Some(earliest.map_or(key, |e| e.min(key)));
+        }
+
+        if let Some(from) = earliest {
+            self.evaluate_from(from);
+        }
+    }
+
+    /// Klines carry no trades, bars keep the footprint they had
+    pub fn insert_klines<'a>(&mut self, klines: impl IntoIterator<Item = &'a Kline>) {
+        let bars: Vec<(u64, BarInputs)> = klines
+            .into_iter()
+            .map(|kline| {
+                let footprint = self.bars.get(&kline.time).and_then(|bar| bar.footprint);
+                let inputs = BarInputs {
+                    kline: *kline,
+                    footprint,
+                };
+                (kline.time, inputs)
+            })
+            .collect();
+
+        self.insert(bars);
+    }
+
+    /// Bars from the one open when the earliest new sample was taken are evaluated again
+    pub fn insert_open_interest(&mut self, samples: impl IntoIterator<Item = (u64, f32)>) {
+        let mut earliest: Option<u64> = None;
+
+        for (time, value) in samples {
+            self.open_interest.insert(time, value);
+            earliest = Some(earliest.map_or(time, |e| e.min(time)));
+        }
+
+        let Some(earliest) = earliest else {
+            return;
+        };
+
+        // keys of tick bars are indices, bars are found by their open time
+        let from = self
+            .bars
+            .iter()
+            .rev()
+            .find(|(_, bar)| bar.kline.time <= earliest)
+            .or_else(|| self.bars.iter().next())
+            .map(|(key, _)| *key);
+
+        if let Some(from) = from {
+            self.evaluate_from(from);
+        }
+    }
+
+    /// Stops at the first failing bar, the error stays until the script is reloaded
+    fn evaluate_from(&mut self, from: u64) {
+        if self.error.is_some() {
+            return;
+        }
+
+        let from = match self.latest.take() {
+            Some((latest, _)) if from > latest => from,
+            Some((latest, before)) if from == latest => {
+                self.context = before;
+                latest
+            }
+            _ => match self.reset() {
+                Some(first) => first,
+                None => return,
+            },
+        };
+
+        if self.error.is_some() {
+            return;
+        }
+        self.values.split_off(&from);
+
+        let bar_offset = self.bars.range(..from).count();
+        let mut bars = self.bars.range(from..).enumerate().peekable();
+
+        while let Some((offset, (&key, inputs))) = bars.next() {
+            let bar = bar_offset + offset;
+            let next_open = bars
+                .peek()
+                .map_or(u64::MAX, |(_, (_, next))| next.kline.time);
+            let oi = self
+                .open_interest
+                .range(..next_open)
+                .next_back()
+                .map(|(_, value)| *value);
+
+            let before = self.context.clone();
+
+            let result = inputs
+                .set_on(&mut self.context, bar, oi)
+                .and_then(|_| self.script.per_bar.eval_with_context_mut(&mut self.context));
+
+            match result {
+                Ok(Value::Float(value)) if value.is_finite() => {
+                    self.values.insert(key, value as f32);
+                }
+                Ok(Value::Int(value)) => {
+                    self.values.insert(key, value as f32);
+                }
+                Ok(Value::Float(_) | Value::Empty) => {}
+                Ok(other) => {
+                    self.error = Some(ScriptError::Output(bar, other));
+                    return;
+                }
+                Err(error) => {
+                    self.error = Some(ScriptError::Bar { bar, error });
+                    return;
+                }
+            }
+
+            self.latest = Some((key, before));
+        }
+    }
+
+    /// Fresh context with the setup statements run, and the key of the first bar
+    fn reset(&mut self) -> Option<u64> {
+        self.values.clear();
+        self.context = HashMapContext::new();
+
+        if let Some(init) = &self.script.init
+            && let Err(error) = init.eval_with_context_mut(&mut self.context)
+        {
+            self.error = Some(ScriptError::Init(error));
+        }
+
+        self.bars.keys().next().copied()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use exchange::util::{Price, Qty};
+
+    fn bar(time: u64, close: f32) -> (u64, BarInputs) {
+        let price = Price::from_f32(close);
+        let kline = Kline {
+            time,
+            open: price,
+            high: price,
+            low: price,
+            close: price,
+            volume: (Qty::from_f32(1.0), Qty::from_f32(2.0)),
+        };
+        (time, BarInputs::new(&kline, &KlineTrades::new()))
+    }
+
+    #[test]
+    fn carries_variables_and_reevaluates_the_forming_bar() {
+        let script =
+            Script::parse("// plot: histogram\ntotal = 0.0;\n---\ntotal = total + close;\ntotal")
+                .unwrap();
+        assert_eq!(script.plot, ScriptPlot::Histogram);
+
+        let mut series = ScriptSeries::new(script);
+        series.insert([bar(0, 1.0), bar(1, 2.0)]);
+        // the forming bar updates, its earlier value must not be added twice
+        series.insert([bar(1, 3.0)]);
+        series.insert([bar(2, 4.0)]);
+
+        assert!(series.error().is_none());
+        assert_eq!(
+            series.values().values().copied().collect::<Vec<_>>(),
+            vec![1.0, 4.0, 8.0]
+        );
+    }
+
+    #[test]
+    fn parses_plot_kind_and_setup() {
+        assert!(matches!(
+            Script::parse("// plot: candles\nclose"),
+            Err(ScriptError::Plot(kind)) if kind == "candles"
+        ));
+        assert!(matches!(
+            Script::parse("(close"),
+            Err(ScriptError::Parse(_))
+        ));
+        assert!(matches!(
+            Script::parse("x = (;\n---\nclose"),
+            Err(ScriptError::Parse(_))
+        ));
+
+        let plain = Script::parse("close").unwrap();
+        assert_eq!(plain.plot, ScriptPlot::Line);
+        assert!(plain.init.is_none());
+
+        // setup runs once, so the per-bar part sees its value on every bar
+        let script = Script::parse("//plot:markers\nstep = 10;\n  ---  \nstep * bar").unwrap();
+        assert_eq!(script.plot, ScriptPlot::Markers);
+        assert!(script.init.is_some());
+
+        let mut series = ScriptSeries::new(script);
+        series.insert([bar(0, 1.0), bar(1, 1.0), bar(2, 1.0)]);
+        assert_eq!(
+            series.values().values().copied().collect::<Vec<_>>(),
+            vec![0.0, 10.0, 20.0]
+        );
+    }
+
+    #[test]
+    fn errors_stay_until_reloaded() {
+        let mut series = ScriptSeries::new(Script::parse("close + \"a\"").unwrap());
+        series.insert([bar(0, 1.0)]);
+        assert!(matches!(
+            series.error(),
+            Some(ScriptError::Bar { bar: 0, .. })
+        ));
+
+        let script = || Script::parse("if(close > 2.0, \"spike\", close)").unwrap();
+        let mut series = ScriptSeries::new(script());
+        series.insert([bar(0, 1.0), bar(1, 3.0), bar(2, 1.0)]);
+        assert!(matches!(
+            series.error(),
+            Some(ScriptError::Output(1, Value::String(_)))
+        ));
+        assert_eq!(series.values().len(), 1);
+
+        // later updates don't start over from the first bar
+        series.insert([bar(1, 1.0)]);
+        assert!(matches!(series.error(), Some(ScriptError::Output(1, _))));
+        assert_eq!(series.values().len(), 1);
+
+        series.reload(script());
+        assert!(series.error().is_none());
+        series.insert([bar(0, 1.0), bar(1, 1.0)]);
+        assert_eq!(series.values().len(), 2);
+    }
+
+    #[test]
+    fn open_interest_goes_to_the_bar_open_when_sampled() {
+        let mut series = ScriptSeries::new(Script::parse("oi").unwrap());
+        series.insert([bar(0, 1.0), bar(60, 1.0), bar(120, 1.0)]);
+        assert!(series.values().is_empty());
+
+        series.insert_open_interest([(30, 5.0), (90, 7.0)]);
+        assert_eq!(
+            series.values().values().copied().collect::<Vec<_>>(),
+            vec![5.0, 7.0, 7.0]
+        );
+
+        // a sample on the forming bar leaves the ones before it alone
+        series.insert_open_interest([(150, 9.0)]);
+        assert_eq!(
+            series.values().values().copied().collect::<Vec<_>>(),
+            vec![5.0, 7.0, 9.0]
+        );
+    }
+}
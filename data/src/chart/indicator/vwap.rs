@@ -0,0 +1,285 @@
+use std::collections::BTreeMap;
+use std::fmt::{self, Display};
+
+use chrono::Datelike;
+use exchange::Kline;
+use serde::{Deserialize, Serialize};
+
+use crate::chart::kline::KlineTrades;
+
+const DAY_MS: u64 = 86_400_000;
+
+/// Where the volume weighted average starts over
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Deserialize, Serialize)]
+pub enum VwapAnchor {
+    Daily,
+    /// Weeks start on Monday, UTC
+    Weekly,
+    Monthly,
+    /// At the bars the user placed anchors on, nothing before the first one
+    Custom,
+}
+
+impl VwapAnchor {
+    pub const ALL: [VwapAnchor; 4] = [
+        VwapAnchor::Daily,
+        VwapAnchor::Weekly,
+        VwapAnchor::Monthly,
+        VwapAnchor::Custom,
+    ];
+}
+
+impl Display for VwapAnchor {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            VwapAnchor::Daily => write!(f, "Daily"),
+            VwapAnchor::Weekly => write!(f, "Weekly"),
+            VwapAnchor::Monthly => write!(f, "Monthly"),
+            VwapAnchor::Custom => write!(f, "Anchored"),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct VwapPoint {
+    pub vwap: f32,
+    /// Volume weighted standard deviation of the traded prices around `vwap`
+    pub std_dev: f32,
+    /// Bars of the same session are drawn as one line
+    pub session: u64,
+}
+
+#[derive(Debug, Clone, Copy, Default)]
+struct Sums {
+    price_volume: f64,
+    price_sq_volume: f64,
+    volume: f64,
+}
+
+impl Sums {
+    fn add(&mut self, price: f64, volume: f64) {
+        self.price_volume += price * volume;
+        self.price_sq_volume += price * price * volume;
+        self.volume += volume;
+    }
+
+    fn merged(self, other: Sums) -> Sums {
+        Sums {
+            price_volume: self.price_volume + other.price_volume,
+            price_sq_volume: self.price_sq_volume + other.price_sq_volume,
+            volume: self.volume + other.volume,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy)]
+struct Bar {
+    time: u64,
+    sums: Sums,
+    /// Summed from the footprint rather than the typical price of the kline
+    from_trades: bool,
+}
+
+/// Volume weighted average price by session, keyed by bar open time or tick bar index
+pub struct VwapSeries {
+    anchor: VwapAnchor,
+    /// Open times of the bars anchored on, sorted, for [`VwapAnchor::Custom`]
+    anchors: Vec<u64>,
+    bars: BTreeMap<u64, Bar>,
+    cumulative: BTreeMap<u64, (u64, Sums)>,
+    values: BTreeMap<u64, VwapPoint>,
+}
+
+impl VwapSeries {
+    pub fn new(anchor: VwapAnchor) -> Self {
+        Self {
+            anchor,
+            anchors: vec![],
+            bars: BTreeMap::new(),
+            cumulative: BTreeMap::new(),
+            values: BTreeMap::new(),
+        }
+    }
+
+    pub fn values(&self) -> &BTreeMap<u64, VwapPoint> {
+        &self.values
+    }
+
+    pub fn clear(&mut self) {
+        self.bars.clear();
+        self.cumulative.clear();
+        self.values.clear();
+    }
+
+    pub fn set_anchors(&mut self, anchors: &[u64]) {
+        let mut anchors = anchors.to_vec();
+        anchors.sort_unstable();
+        anchors.dedup();
+
+        if anchors != self.anchors {
+            self.anchors = anchors;
+
+            if self.anchor == VwapAnchor::Custom
+                && let Some(&first) = self.bars.keys().next()
+            {
+                self.recompute_from(first);
+            }
+        }
+    }
+
+    /// Bars without their trades, weighted at the typical price. Bars that were summed
+    /// from trades before are left to [`Self::insert_datapoints`]
+    pub fn insert_klines<'a>(&mut self, klines: impl IntoIterator<Item = (u64, &'a Kline)>) {
+        let bars = klines.into_iter().filter_map(|(key, kline)| {
+            if self.bars.get(&key).is_some_and(|bar| bar.from_trades) {
+                return None;
+            }
+            Some((key, Self::typical_price_bar(kline)))
+        });
+
+        let bars: Vec<(u64, Bar)> = bars.collect();
+        self.insert_bars(bars);
+    }
+
+    /// Bars summed from their footprint, or the typical price if it holds no trades
+    pub fn insert_datapoints<'a>(
+        &mut self,
+        datapoints: impl IntoIterator<Item = (u64, &'a Kline, &'a KlineTrades)>,
+    ) {
+        let bars: Vec<(u64, Bar)> = datapoints
+            .into_iter()
+            .map(|(key, kline, footprint)| {
+                if footprint.trades.is_empty() {
+                    return (key, Self::typical_price_bar(kline));
+                }
+
+                let mut sums = Sums::default();
+                for (price, group) in &footprint.trades {
+                    let qty = (group.buy_qty + group.sell_qty).to_f64();
+                    sums.add(f64::from(price.to_f32()), qty);
+                }
+
+                let bar = Bar {
+                    time: kline.time,
+                    sums,
+                    from_trades: true,
+                };
+                (key, bar)
+            })
+            .collect();
+
+        self.insert_bars(bars);
+    }
+
+    fn typical_price_bar(kline: &Kline) -> Bar {
+        let typical = (kline.high.to_f32() + kline.low.to_f32() + kline.close.to_f32()) / 3.0;
+
+        let mut sums = Sums::default();
+        sums.add(f64::from(typical), kline.total_volume().to_f64());
+
+        Bar {
+            time: kline.time,
+            sums,
+            from_trades: false,
+        }
+    }
+
+    fn insert_bars(&mut self, bars: Vec<(u64, Bar)>) {
+        let Some(from) = bars.iter().map(|(key, _)| *key).min() else {
+            return;
+        };
+
+        self.bars.extend(bars);
+        self.recompute_from(from);
+    }
+
+    /// Which session the bar opening at `time` belongs to
+    fn session_of(&self, time: u64) -> Option<u64> {
+        match self.anchor {
+            VwapAnchor::Daily => Some(time / DAY_MS),
+            // the epoch fell on a Thursday
+            VwapAnchor::Weekly => Some((time / DAY_MS + 3) / 7),
+            VwapAnchor::Monthly => chrono::DateTime::from_timestamp_millis(time as i64)
+                .map(|date| date.year() as u64 * 12 + u64::from(date.month0())),
+            VwapAnchor::Custom => {
+                let passed = self.anchors.partition_point(|anchor| *anchor <= time);
+                (passed > 0).then_some(passed as u64)
+            }
+        }
+    }
+
+    fn recompute_from(&mut self, from: u64) {
+        let mut prev = self
+            .cumulative
+            .range(..from)
+            .next_back()
+            .map(|(_, cumulative)| *cumulative);
+
+        let keys: Vec<u64> = self.bars.range(from..).map(|(key, _)| *key).collect();
+
+        for key in keys {
+            let bar = self.bars[&key];
+
+            let Some(session) = self.session_of(bar.time) else {
+                self.cumulative.remove(&key);
+                self.values.remove(&key);
+                prev = None;
+                continue;
+            };
+
+            let sums = match prev {
+                Some((prev_session, prev_sums)) if prev_session == session => {
+                    prev_sums.merged(bar.sums)
+                }
+                _ => bar.sums,
+            };
+
+            self.cumulative.insert(key, (session, sums));
+            prev = Some((session, sums));
+
+            if sums.volume > 0.0 {
+                let vwap = sums.price_volume / sums.volume;
+                let variance = (sums.price_sq_volume / sums.volume - vwap * vwap).max(0.0);
+
+                self.values.insert(
+                    key,
+                    VwapPoint {
+                        vwap: vwap as f32,
+                        std_dev: variance.sqrt() as f32,
+                        session,
+                    },
+                );
+            } else {
+                self.values.remove(&key);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::kline;
+
+    #[test]
+    fn restarts_at_custom_anchors() {
+        let klines = [
+            kline(0, 10.0, (1.0, 0.0)),
+            kline(60_000, 20.0, (1.0, 0.0)),
+            kline(120_000, 40.0, (3.0, 0.0)),
+        ];
+
+        let mut series = VwapSeries::new(VwapAnchor::Custom);
+        series.insert_klines(klines.iter().map(|k| (k.time, k)));
+        assert!(series.values().is_empty());
+
+        series.set_anchors(&[0]);
+        assert_eq!(series.values()[&60_000].vwap, 15.0);
+        assert_eq!(series.values()[&60_000].std_dev, 5.0);
+        assert_eq!(series.values()[&120_000].vwap, 30.0);
+
+        series.set_anchors(&[0, 120_000]);
+        assert_eq!(series.values()[&120_000].vwap, 40.0);
+        assert_eq!(series.values()[&120_000].std_dev, 0.0);
+    }
+}
@@ -1,6 +1,6 @@
 use exchange::{
-    Kline, Trade,
-    util::{Price, PriceStep},
+    Kline, SizeUnit, Trade,
+    util::{Price, PriceStep, Qty},
 };
 use rustc_hash::FxHashMap;
 use serde::{Deserialize, Serialize};
@@ -14,9 +14,9 @@ pub struct KlineDataPoint {
 }
 
 impl KlineDataPoint {
-    pub fn max_cluster_qty(&self, cluster_kind: ClusterKind, highest: Price, lowest: Price) -> f32 {
+    pub fn max_cluster_qty(&self, cluster_kind: ClusterKind, highest: Price, lowest: Price) -> Qty {
         match cluster_kind {
-            ClusterKind::BidAsk => self.footprint.max_qty_by(highest, lowest, f32::max),
+            ClusterKind::BidAsk => self.footprint.max_qty_by(highest, lowest, Qty::max),
             ClusterKind::DeltaProfile => self
                 .footprint
                 .max_qty_by(highest, lowest, |buy, sell| (buy - sell).abs()),
@@ -31,6 +31,35 @@ impl KlineDataPoint {
         self.footprint.add_trade_to_nearest_bin(trade, step);
     }
 
+    /// Extends the kline with a trade, to be called before adding it to the footprint
+    /// as its first and last trade times decide the open and close
+    pub fn add_trade_to_kline(&mut self, trade: &Trade) {
+        let kline = &mut self.kline;
+
+        if self
+            .footprint
+            .first_trade_t()
+            .is_none_or(|first| trade.time < first)
+        {
+            kline.open = trade.price;
+        }
+        if self
+            .footprint
+            .last_trade_t()
+            .is_none_or(|last| trade.time >= last)
+        {
+            kline.close = trade.price;
+        }
+        kline.high = kline.high.max(trade.price);
+        kline.low = kline.low.min(trade.price);
+
+        if trade.is_sell {
+            kline.volume.1 += trade.qty;
+        } else {
+            kline.volume.0 += trade.qty;
+        }
+    }
+
     pub fn poc_price(&self) -> Option<Price> {
         self.footprint.poc_price()
     }
@@ -92,8 +121,8 @@ impl DataPoint for KlineDataPoint {
 
 #[derive(Debug, Clone, Default)]
 pub struct GroupedTrades {
-    pub buy_qty: f32,
-    pub sell_qty: f32,
+    pub buy_qty: Qty,
+    pub sell_qty: Qty,
     pub first_time: u64,
     pub last_time: u64,
     pub buy_count: usize,
@@ -103,8 +132,8 @@ pub struct GroupedTrades {
 impl GroupedTrades {
     fn new(trade: &Trade) -> Self {
         Self {
-            buy_qty: if trade.is_sell { 0.0 } else { trade.qty },
-            sell_qty: if trade.is_sell { trade.qty } else { 0.0 },
+            buy_qty: if trade.is_sell { Qty::ZERO } else { trade.qty },
+            sell_qty: if trade.is_sell { trade.qty } else { Qty::ZERO },
             first_time: trade.time,
             last_time: trade.time,
             buy_count: if trade.is_sell { 0 } else { 1 },
@@ -123,11 +152,11 @@ impl GroupedTrades {
         self.last_time = trade.time;
     }
 
-    pub fn total_qty(&self) -> f32 {
+    pub fn total_qty(&self) -> Qty {
         self.buy_qty + self.sell_qty
     }
 
-    pub fn delta_qty(&self) -> f32 {
+    pub fn delta_qty(&self) -> Qty {
         self.buy_qty - self.sell_qty
     }
 }
@@ -136,6 +165,10 @@ impl GroupedTrades {
 pub struct KlineTrades {
     pub trades: FxHashMap<Price, GroupedTrades>,
     pub poc: Option<PointOfControl>,
+    /// Running buy minus sell quantity, with the lowest and highest it reached
+    /// in the order trades were added
+    delta: Qty,
+    delta_range: (Qty, Qty),
 }
 
 impl KlineTrades {
@@ -143,9 +176,16 @@ impl KlineTrades {
         Self {
             trades: FxHashMap::default(),
             poc: None,
+            delta: Qty::ZERO,
+            delta_range: (Qty::ZERO, Qty::ZERO),
         }
     }
 
+    /// Lowest and highest the delta reached while the trades were added, starting from zero
+    pub fn delta_range(&self) -> (Qty, Qty) {
+        self.delta_range
+    }
+
     pub fn first_trade_t(&self) -> Option<u64> {
         self.trades.values().map(|group| group.first_time).min()
     }
@@ -172,17 +212,27 @@ impl KlineTrades {
     pub fn add_trade_to_nearest_bin(&mut self, trade: &Trade, step: PriceStep) {
         let price = trade.price.round_to_step(step);
 
+        if trade.is_sell {
+            self.delta -= trade.qty;
+        } else {
+            self.delta += trade.qty;
+        }
+        self.delta_range = (
+            self.delta_range.0.min(self.delta),
+            self.delta_range.1.max(self.delta),
+        );
+
         self.trades
             .entry(price)
             .and_modify(|group| group.add_trade(trade))
             .or_insert_with(|| GroupedTrades::new(trade));
     }
 
-    pub fn max_qty_by<F>(&self, highest: Price, lowest: Price, f: F) -> f32
+    pub fn max_qty_by<F>(&self, highest: Price, lowest: Price, f: F) -> Qty
     where
-        F: Fn(f32, f32) -> f32,
+        F: Fn(Qty, Qty) -> Qty,
     {
-        let mut max_qty: f32 = 0.0;
+        let mut max_qty = Qty::ZERO;
         for (price, group) in &self.trades {
             if *price >= lowest && *price <= highest {
                 max_qty = max_qty.max(f(group.buy_qty, group.sell_qty));
@@ -196,7 +246,7 @@ impl KlineTrades {
             return;
         }
 
-        let mut max_volume = 0.0;
+        let mut max_volume = Qty::ZERO;
         let mut poc_price = Price::from_f32(0.0);
 
         for (price, group) in &self.trades {
@@ -227,6 +277,8 @@ impl KlineTrades {
     pub fn clear(&mut self) {
         self.trades.clear();
         self.poc = None;
+        self.delta = Qty::ZERO;
+        self.delta_range = (Qty::ZERO, Qty::ZERO);
     }
 }
 
@@ -320,7 +372,10 @@ impl std::fmt::Display for ClusterKind {
 }
 
 #[derive(Debug, Default, Copy, Clone, PartialEq, Deserialize, Serialize)]
-pub struct Config {}
+#[serde(default)]
+pub struct Config {
+    pub size_unit: SizeUnit,
+}
 
 #[derive(Default, Clone, Copy, Debug, PartialEq, Deserialize, Serialize)]
 pub enum ClusterScaling {
@@ -402,7 +457,7 @@ impl std::fmt::Display for FootprintStudy {
 #[derive(Debug, Clone, Copy)]
 pub struct PointOfControl {
     pub price: Price,
-    pub volume: f32,
+    pub volume: Qty,
     pub status: NPoc,
 }
 
@@ -410,7 +465,7 @@ impl Default for PointOfControl {
     fn default() -> Self {
         Self {
             price: Price::from_f32(0.0),
-            volume: 0.0,
+            volume: Qty::ZERO,
             status: NPoc::default(),
         }
     }
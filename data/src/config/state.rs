@@ -24,7 +24,9 @@ pub struct State {
     pub scale_factor: ScaleFactor,
     pub audio_cfg: AudioStream,
     pub trade_fetch_enabled: bool,
-    pub size_in_quote_ccy: exchange::SizeUnit,
+    /// Size unit older versions applied to every pane, read only to migrate it
+    #[serde(skip_serializing)]
+    size_in_quote_ccy: Option<exchange::SizeUnit>,
 }
 
 impl State {
@@ -37,7 +39,6 @@ impl State {
         sidebar: Sidebar,
         scale_factor: ScaleFactor,
         audio_cfg: AudioStream,
-        volume_size_unit: exchange::SizeUnit,
     ) -> Self {
         State {
             layout_manager,
@@ -49,7 +50,99 @@ impl State {
             scale_factor,
             audio_cfg,
             trade_fetch_enabled: exchange::fetcher::is_trade_fetch_enabled(),
-            size_in_quote_ccy: volume_size_unit,
+            size_in_quote_ccy: None,
         }
     }
+
+    /// Moves the app-wide size unit of older versions into the panes, which pick their own now
+    pub fn migrate_size_unit(&mut self) {
+        let Some(unit) = self.size_in_quote_ccy.take() else {
+            return;
+        };
+
+        for layout in &mut self.layout_manager.layouts {
+            layout.dashboard.pane.migrate_size_unit(unit);
+            for (pane, _) in &mut layout.dashboard.popout {
+                pane.migrate_size_unit(unit);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::layout::{Dashboard, pane::Pane, pane::Settings, pane::VisualConfig};
+    use crate::panel::{ladder, timeandsales};
+    use exchange::SizeUnit;
+
+    #[test]
+    fn moves_the_global_size_unit_into_panes() {
+        let configured = timeandsales::Config {
+            trade_size_filter: 5.0,
+            ..Default::default()
+        };
+        let time_and_sales = Pane::TimeAndSales {
+            stream_type: vec![],
+            settings: Settings {
+                visual_config: Some(VisualConfig::TimeAndSales(configured)),
+                ..Default::default()
+            },
+            link_group: None,
+        };
+        let ladder = Pane::Ladder {
+            stream_type: vec![],
+            settings: Settings::default(),
+            link_group: None,
+        };
+
+        let mut state: State = serde_json::from_str(r#"{"size_in_quote_ccy":"Base"}"#).unwrap();
+        state.layout_manager.layouts = vec![Layout {
+            name: "Legacy".to_string(),
+            dashboard: Dashboard {
+                pane: Pane::Split {
+                    axis: crate::layout::pane::Axis::Vertical,
+                    ratio: 0.5,
+                    a: Box::new(time_and_sales),
+                    b: Box::new(Pane::Starter { link_group: None }),
+                },
+                popout: vec![(ladder, WindowSpec::default())],
+            },
+        }];
+        state.migrate_size_unit();
+
+        let dashboard = &state.layout_manager.layouts[0].dashboard;
+        let Pane::Split { a, .. } = &dashboard.pane else {
+            panic!("split pane expected");
+        };
+        let Pane::TimeAndSales { settings, .. } = a.as_ref() else {
+            panic!("time and sales pane expected");
+        };
+        let migrated = settings
+            .visual_config
+            .as_ref()
+            .and_then(VisualConfig::time_and_sales)
+            .unwrap();
+        assert_eq!(migrated.size_unit, SizeUnit::Base);
+        assert_eq!(migrated.trade_size_filter, 5.0);
+
+        let Pane::Ladder { settings, .. } = &dashboard.popout[0].0 else {
+            panic!("ladder pane expected");
+        };
+        assert_eq!(
+            settings
+                .visual_config
+                .as_ref()
+                .and_then(VisualConfig::ladder),
+            Some(ladder::Config {
+                size_unit: SizeUnit::Base,
+                ..Default::default()
+            })
+        );
+
+        // the old setting isn't saved again, so it can't override later choices
+        let saved = serde_json::to_string(&state).unwrap();
+        assert!(!saved.contains("size_in_quote_ccy"));
+        assert!(state.size_in_quote_ccy.is_none());
+    }
 }
@@ -39,7 +39,7 @@ impl UserTimezone {
     {
         let interval = timeframe.to_milliseconds();
 
-        if interval < 10000 {
+        if interval < 60_000 {
             datetime.format("%M:%S").to_string()
         } else if datetime.format("%H:%M").to_string() == "00:00" {
             datetime.format("%-d").to_string()
@@ -56,6 +56,8 @@ impl UserTimezone {
                     let time_with_zone = datetime.with_timezone(&chrono::Local);
                     if interval < 10000 {
                         time_with_zone.format("%M:%S.%3f").to_string()
+                    } else if interval < 60_000 {
+                        time_with_zone.format("%a %b %-d %H:%M:%S").to_string()
                     } else {
                         time_with_zone.format("%a %b %-d %H:%M").to_string()
                     }
@@ -64,6 +66,8 @@ impl UserTimezone {
                     let time_with_zone = datetime.with_timezone(&chrono::Utc);
                     if interval < 10000 {
                         time_with_zone.format("%M:%S.%3f").to_string()
+                    } else if interval < 60_000 {
+                        time_with_zone.format("%a %b %-d %H:%M:%S").to_string()
                     } else {
                         time_with_zone.format("%a %b %-d %H:%M").to_string()
                     }
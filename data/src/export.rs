@@ -0,0 +1,533 @@
+//! Tabular export of chart and panel data to CSV or Parquet files
+
+use crate::aggr::ticks::TickAggr;
+use crate::aggr::time::TimeSeries;
+use crate::chart::heatmap::HistoricalDepth;
+use crate::chart::kline::{KlineDataPoint, KlineTrades};
+use crate::panel::timeandsales::TradeEntry;
+
+use exchange::Kline;
+use exchange::util::{Price, Qty};
+use parquet::basic::Compression;
+use parquet::data_type::{BoolType, DoubleType, Int64Type};
+use parquet::file::properties::WriterProperties;
+use parquet::file::writer::SerializedFileWriter;
+use parquet::schema::parser::parse_message_type;
+use serde::{Deserialize, Serialize};
+
+use std::collections::BTreeMap;
+use std::fs::File;
+use std::io::{BufWriter, Write};
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+
+pub const EXPORTS_DIR: &str = "exports";
+
+#[derive(thiserror::Error, Debug)]
+pub enum ExportError {
+    #[error("Nothing to export in the selected range")]
+    Empty,
+    #[error("IO error: {0}")]
+    Io(#[from] std::io::Error),
+    #[error("Parquet error: {0}")]
+    Parquet(#[from] parquet::errors::ParquetError),
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Deserialize, Serialize)]
+pub enum Format {
+    #[default]
+    Csv,
+    Parquet,
+}
+
+impl Format {
+    pub const ALL: [Format; 2] = [Format::Csv, Format::Parquet];
+
+    pub fn extension(&self) -> &'static str {
+        match self {
+            Format::Csv => "csv",
+            Format::Parquet => "parquet",
+        }
+    }
+}
+
+impl std::fmt::Display for Format {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Format::Csv => write!(f, "CSV"),
+            Format::Parquet => write!(f, "Parquet"),
+        }
+    }
+}
+
+/// Which part of the loaded data gets exported
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Deserialize, Serialize)]
+pub enum Scope {
+    #[default]
+    Visible,
+    Full,
+}
+
+impl Scope {
+    pub const ALL: [Scope; 2] = [Scope::Visible, Scope::Full];
+}
+
+impl std::fmt::Display for Scope {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Scope::Visible => write!(f, "Visible range"),
+            Scope::Full => write!(f, "Full history"),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize, Serialize)]
+pub enum Dataset {
+    /// OHLC with buy/sell volume, plus open interest when available
+    Klines,
+    /// Per-price buy/sell clusters of each footprint bar
+    Footprint,
+    /// Individual trades, as shown in time&sales
+    Trades,
+    /// Resting order runs of the heatmap
+    OrderRuns,
+}
+
+impl Dataset {
+    pub fn file_stem(&self) -> &'static str {
+        match self {
+            Dataset::Klines => "klines",
+            Dataset::Footprint => "footprint",
+            Dataset::Trades => "trades",
+            Dataset::OrderRuns => "orderbook",
+        }
+    }
+}
+
+impl std::fmt::Display for Dataset {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Dataset::Klines => write!(f, "Candles"),
+            Dataset::Footprint => write!(f, "Footprint clusters"),
+            Dataset::Trades => write!(f, "Trades"),
+            Dataset::OrderRuns => write!(f, "Orderbook runs"),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum Values {
+    /// Unix timestamps in milliseconds
+    Time(Vec<u64>),
+    Count(Vec<u64>),
+    Float(Vec<f64>),
+    Bool(Vec<bool>),
+}
+
+impl Values {
+    fn len(&self) -> usize {
+        match self {
+            Values::Time(v) | Values::Count(v) => v.len(),
+            Values::Float(v) => v.len(),
+            Values::Bool(v) => v.len(),
+        }
+    }
+
+    /// Schema field definition, e.g. `REQUIRED DOUBLE price;`
+    fn parquet_field(&self, name: &str) -> String {
+        match self {
+            Values::Time(_) => format!("REQUIRED INT64 {name} (TIMESTAMP(MILLIS, true));"),
+            Values::Count(_) => format!("REQUIRED INT64 {name};"),
+            Values::Float(_) => format!("REQUIRED DOUBLE {name};"),
+            Values::Bool(_) => format!("REQUIRED BOOLEAN {name};"),
+        }
+    }
+
+    fn write_cell<W: Write>(&self, row: usize, out: &mut W) -> std::io::Result<()> {
+        match self {
+            Values::Time(v) | Values::Count(v) => write!(out, "{}", v[row]),
+            Values::Float(v) => write!(out, "{}", v[row]),
+            Values::Bool(v) => write!(out, "{}", v[row]),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+struct Column {
+    name: &'static str,
+    values: Values,
+}
+
+/// Column-oriented table that can be written to any [`Format`]
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Table {
+    columns: Vec<Column>,
+}
+
+impl Table {
+    fn with_columns(columns: Vec<Column>) -> Self {
+        debug_assert!(
+            columns
+                .windows(2)
+                .all(|w| w[0].values.len() == w[1].values.len())
+        );
+        Self { columns }
+    }
+
+    pub fn rows(&self) -> usize {
+        self.columns.first().map_or(0, |c| c.values.len())
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.rows() == 0
+    }
+
+    pub fn write(&self, format: Format, path: &Path) -> Result<(), ExportError> {
+        if self.is_empty() {
+            return Err(ExportError::Empty);
+        }
+
+        if let Some(parent) = path.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+
+        let file = File::create(path)?;
+        match format {
+            Format::Csv => self.write_csv(BufWriter::new(file)),
+            Format::Parquet => self.write_parquet(file),
+        }
+    }
+
+    fn write_csv<W: Write>(&self, mut out: W) -> Result<(), ExportError> {
+        let header = self
+            .columns
+            .iter()
+            .map(|c| c.name)
+            .collect::<Vec<_>>()
+            .join(",");
+        writeln!(out, "{header}")?;
+
+        for row in 0..self.rows() {
+            for (i, column) in self.columns.iter().enumerate() {
+                if i > 0 {
+                    out.write_all(b",")?;
+                }
+                column.values.write_cell(row, &mut out)?;
+            }
+            out.write_all(b"\n")?;
+        }
+
+        out.flush()?;
+        Ok(())
+    }
+
+    fn write_parquet(&self, file: File) -> Result<(), ExportError> {
+        let fields = self
+            .columns
+            .iter()
+            .map(|c| c.values.parquet_field(c.name))
+            .collect::<Vec<_>>()
+            .join(" ");
+        let schema = Arc::new(parse_message_type(&format!(
+            "message export {{ {fields} }}"
+        ))?);
+        let props = Arc::new(
+            WriterProperties::builder()
+                .set_compression(Compression::SNAPPY)
+                .build(),
+        );
+
+        let mut writer = SerializedFileWriter::new(file, schema, props)?;
+        let mut row_group = writer.next_row_group()?;
+
+        for column in &self.columns {
+            let Some(mut col_writer) = row_group.next_column()? else {
+                break;
+            };
+            match &column.values {
+                Values::Time(v) | Values::Count(v) => {
+                    let v = v.iter().map(|&x| x as i64).collect::<Vec<_>>();
+                    col_writer
+                        .typed::<Int64Type>()
+                        .write_batch(&v, None, None)?;
+                }
+                Values::Float(v) => {
+                    col_writer
+                        .typed::<DoubleType>()
+                        .write_batch(v, None, None)?;
+                }
+                Values::Bool(v) => {
+                    col_writer.typed::<BoolType>().write_batch(v, None, None)?;
+                }
+            }
+            col_writer.close()?;
+        }
+
+        row_group.close()?;
+        writer.close()?;
+        Ok(())
+    }
+}
+
+/// Where an export of `dataset` for `ticker` ends up, e.g. `exports/BTCUSDT_klines_1700000000000.csv`
+pub fn export_path(ticker: &str, dataset: Dataset, format: Format, now_ms: u64) -> PathBuf {
+    let ticker = ticker
+        .chars()
+        .map(|c| if c.is_ascii_alphanumeric() { c } else { '_' })
+        .collect::<String>();
+
+    crate::data_path(None).join(EXPORTS_DIR).join(format!(
+        "{ticker}_{}_{now_ms}.{}",
+        dataset.file_stem(),
+        format.extension()
+    ))
+}
+
+/// OHLCV rows with an `open_interest` column when `open_interest` is given,
+/// each kline is matched with the latest open interest value at or before its open time
+pub fn klines<'a>(
+    klines: impl IntoIterator<Item = &'a Kline>,
+    open_interest: Option<&BTreeMap<u64, f32>>,
+) -> Table {
+    let mut time = vec![];
+    let (mut open, mut high, mut low, mut close) = (vec![], vec![], vec![], vec![]);
+    let (mut buy_volume, mut sell_volume) = (vec![], vec![]);
+    let mut oi = vec![];
+
+    for kline in klines {
+        time.push(kline.time);
+        open.push(kline.open.to_f64());
+        high.push(kline.high.to_f64());
+        low.push(kline.low.to_f64());
+        close.push(kline.close.to_f64());
+        buy_volume.push(kline.volume.0.to_f64());
+        sell_volume.push(kline.volume.1.to_f64());
+
+        if let Some(data) = open_interest {
+            let value = data
+                .range(..=kline.time)
+                .next_back()
+                .map_or(f64::NAN, |(_, v)| f64::from(*v));
+            oi.push(value);
+        }
+    }
+
+    let mut columns = vec![
+        Column {
+            name: "time",
+            values: Values::Time(time),
+        },
+        Column {
+            name: "open",
+            values: Values::Float(open),
+        },
+        Column {
+            name: "high",
+            values: Values::Float(high),
+        },
+        Column {
+            name: "low",
+            values: Values::Float(low),
+        },
+        Column {
+            name: "close",
+            values: Values::Float(close),
+        },
+        Column {
+            name: "buy_volume",
+            values: Values::Float(buy_volume),
+        },
+        Column {
+            name: "sell_volume",
+            values: Values::Float(sell_volume),
+        },
+    ];
+
+    if open_interest.is_some() {
+        columns.push(Column {
+            name: "open_interest",
+            values: Values::Float(oi),
+        });
+    }
+
+    Table::with_columns(columns)
+}
+
+/// One row per price level of each bar, `time` being the bar's open time
+pub fn footprint<'a>(bars: impl IntoIterator<Item = (u64, &'a KlineTrades)>) -> Table {
+    let mut time = vec![];
+    let mut price = vec![];
+    let (mut buy_qty, mut sell_qty) = (vec![], vec![]);
+    let (mut buy_count, mut sell_count) = (vec![], vec![]);
+
+    for (bar_time, footprint) in bars {
+        let mut levels = footprint.trades.iter().collect::<Vec<_>>();
+        levels.sort_unstable_by_key(|(p, _)| **p);
+
+        for (level, group) in levels {
+            time.push(bar_time);
+            price.push(level.to_f64());
+            buy_qty.push(group.buy_qty.to_f64());
+            sell_qty.push(group.sell_qty.to_f64());
+            buy_count.push(group.buy_count as u64);
+            sell_count.push(group.sell_count as u64);
+        }
+    }
+
+    Table::with_columns(vec![
+        Column {
+            name: "time",
+            values: Values::Time(time),
+        },
+        Column {
+            name: "price",
+            values: Values::Float(price),
+        },
+        Column {
+            name: "buy_qty",
+            values: Values::Float(buy_qty),
+        },
+        Column {
+            name: "sell_qty",
+            values: Values::Float(sell_qty),
+        },
+        Column {
+            name: "buy_count",
+            values: Values::Count(buy_count),
+        },
+        Column {
+            name: "sell_count",
+            values: Values::Count(sell_count),
+        },
+    ])
+}
+
+pub fn trades(trades: impl IntoIterator<Item = (u64, Price, Qty, bool)>) -> Table {
+    let mut time = vec![];
+    let mut price = vec![];
+    let mut qty = vec![];
+    let mut is_sell = vec![];
+
+    for (t, p, q, sell) in trades {
+        time.push(t);
+        price.push(p.to_f64());
+        qty.push(q.to_f64());
+        is_sell.push(sell);
+    }
+
+    Table::with_columns(vec![
+        Column {
+            name: "time",
+            values: Values::Time(time),
+        },
+        Column {
+            name: "price",
+            values: Values::Float(price),
+        },
+        Column {
+            name: "qty",
+            values: Values::Float(qty),
+        },
+        Column {
+            name: "is_sell",
+            values: Values::Bool(is_sell),
+        },
+    ])
+}
+
+pub fn time_and_sales<'a>(entries: impl IntoIterator<Item = &'a TradeEntry>) -> Table {
+    trades(entries.into_iter().map(|entry| {
+        (
+            entry.ts_ms,
+            entry.display.price,
+            entry.display.qty,
+            entry.display.is_sell,
+        )
+    }))
+}
+
+/// Resting order runs overlapping `[earliest, latest]`, one row per run
+pub fn order_runs(depth: &HistoricalDepth, earliest: u64, latest: u64) -> Table {
+    let mut price = vec![];
+    let (mut start_time, mut until_time) = (vec![], vec![]);
+    let mut qty = vec![];
+    let mut is_bid = vec![];
+
+    for (level, run) in depth.iter_order_runs(earliest, latest) {
+        price.push(level.to_f64());
+        start_time.push(run.start_time);
+        until_time.push(run.until_time);
+        qty.push(run.qty().to_f64());
+        is_bid.push(run.is_bid);
+    }
+
+    Table::with_columns(vec![
+        Column {
+            name: "price",
+            values: Values::Float(price),
+        },
+        Column {
+            name: "start_time",
+            values: Values::Time(start_time),
+        },
+        Column {
+            name: "until_time",
+            values: Values::Time(until_time),
+        },
+        Column {
+            name: "qty",
+            values: Values::Float(qty),
+        },
+        Column {
+            name: "is_bid",
+            values: Values::Bool(is_bid),
+        },
+    ])
+}
+
+/// Klines of a time based series within `[earliest, latest]`
+pub fn time_series_klines(
+    series: &TimeSeries<KlineDataPoint>,
+    earliest: u64,
+    latest: u64,
+) -> impl Iterator<Item = &Kline> {
+    series
+        .datapoints
+        .range(earliest..=latest)
+        .map(|(_, dp)| &dp.kline)
+}
+
+/// Bars of a tick based series within the reversed index range `[earliest, latest]`,
+/// where index 0 is the latest bar
+pub fn tick_aggr_bars(
+    aggr: &TickAggr,
+    earliest: usize,
+    latest: usize,
+) -> impl Iterator<Item = &crate::aggr::ticks::TickAccumulation> {
+    let len = aggr.datapoints.len();
+    let start = len.saturating_sub(latest.saturating_add(1));
+    let end = len.saturating_sub(earliest);
+
+    aggr.datapoints[start.min(end)..end].iter()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn csv_rows_match_columns() {
+        let table = trades([
+            (1_000, Price::from_f32(100.5), Qty::from_f32(0.5), false),
+            (2_000, Price::from_f32(100.25), Qty::from_f32(1.25), true),
+        ]);
+
+        let mut out = vec![];
+        table.write_csv(&mut out).unwrap();
+
+        assert_eq!(
+            String::from_utf8(out).unwrap(),
+            "time,price,qty,is_sell\n1000,100.5,0.5,false\n2000,100.25,1.25,true\n"
+        );
+    }
+}
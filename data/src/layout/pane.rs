@@ -1,6 +1,7 @@
 use exchange::adapter::PersistStreamKind;
-use exchange::{TickMultiplier, TickerInfo, Timeframe};
+use exchange::{SizeUnit, TickMultiplier, Ticker, TickerInfo, Timeframe};
 use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
 
 use crate::chart::{comparison, heatmap, kline};
 use crate::panel::{ladder, timeandsales};
@@ -9,7 +10,7 @@ use crate::util::ok_or_default;
 use crate::chart::{
     Basis, ViewConfig,
     heatmap::HeatmapStudy,
-    indicator::{HeatmapIndicator, KlineIndicator},
+    indicator::{HeatmapIndicator, KlineIndicator, deserialize_kline_indicators},
     kline::KlineChartKind,
 };
 
@@ -51,7 +52,7 @@ pub enum Pane {
         stream_type: Vec<PersistStreamKind>,
         #[serde(deserialize_with = "ok_or_default")]
         settings: Settings,
-        #[serde(deserialize_with = "ok_or_default", default)]
+        #[serde(deserialize_with = "deserialize_kline_indicators", default)]
         indicators: Vec<KlineIndicator>,
         #[serde(deserialize_with = "ok_or_default", default)]
         link_group: Option<LinkGroup>,
@@ -83,12 +84,65 @@ impl Default for Pane {
     }
 }
 
+impl Pane {
+    /// Sets `unit` on this pane and the ones split from it, for layouts saved while
+    /// the size unit was an app-wide setting
+    pub fn migrate_size_unit(&mut self, unit: SizeUnit) {
+        match self {
+            Pane::Split { a, b, .. } => {
+                a.migrate_size_unit(unit);
+                b.migrate_size_unit(unit);
+            }
+            Pane::HeatmapChart { settings, .. } => {
+                let mut config = settings
+                    .visual_config
+                    .as_ref()
+                    .and_then(VisualConfig::heatmap)
+                    .unwrap_or_default();
+                config.size_unit = unit;
+                settings.visual_config = Some(VisualConfig::Heatmap(config));
+            }
+            Pane::KlineChart { settings, .. } => {
+                let mut config = settings
+                    .visual_config
+                    .as_ref()
+                    .and_then(VisualConfig::kline)
+                    .unwrap_or_default();
+                config.size_unit = unit;
+                settings.visual_config = Some(VisualConfig::Kline(config));
+            }
+            Pane::TimeAndSales { settings, .. } => {
+                let mut config = settings
+                    .visual_config
+                    .as_ref()
+                    .and_then(VisualConfig::time_and_sales)
+                    .unwrap_or_default();
+                config.size_unit = unit;
+                settings.visual_config = Some(VisualConfig::TimeAndSales(config));
+            }
+            Pane::Ladder { settings, .. } => {
+                let mut config = settings
+                    .visual_config
+                    .as_ref()
+                    .and_then(VisualConfig::ladder)
+                    .unwrap_or_default();
+                config.size_unit = unit;
+                settings.visual_config = Some(VisualConfig::Ladder(config));
+            }
+            Pane::Starter { .. } | Pane::ComparisonChart { .. } => {}
+        }
+    }
+}
+
 #[derive(Debug, Clone, Deserialize, Serialize, Default)]
 #[serde(default)]
 pub struct Settings {
     pub tick_multiply: Option<exchange::TickMultiplier>,
     pub visual_config: Option<VisualConfig>,
     pub selected_basis: Option<Basis>,
+    /// Open times of the bars an anchored VWAP starts from, for each ticker the pane has shown
+    #[serde(deserialize_with = "ok_or_default")]
+    pub vwap_anchors: HashMap<Ticker, Vec<u64>>,
 }
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Deserialize, Serialize)]
@@ -253,15 +307,23 @@ impl PaneSetup {
                 });
                 Some(current.unwrap_or_else(|| Basis::default_heatmap_time(Some(base_ticker))))
             }
+            // bar bases built from trades only apply to kline charts
             ContentKind::Ladder => Some(
-                current_basis.unwrap_or_else(|| Basis::default_heatmap_time(Some(base_ticker))),
+                current_basis
+                    .filter(Basis::is_time)
+                    .unwrap_or_else(|| Basis::default_heatmap_time(Some(base_ticker))),
             ),
             ContentKind::FootprintChart => {
                 Some(current_basis.unwrap_or(Basis::Time(Timeframe::M5)))
             }
-            ContentKind::CandlestickChart | ContentKind::ComparisonChart => {
+            ContentKind::CandlestickChart => {
                 Some(current_basis.unwrap_or(Basis::Time(Timeframe::M15)))
             }
+            ContentKind::ComparisonChart => Some(
+                current_basis
+                    .filter(Basis::is_time)
+                    .unwrap_or(Basis::Time(Timeframe::M15)),
+            ),
             ContentKind::Starter | ContentKind::TimeAndSales => None,
         };
 
@@ -314,3 +376,34 @@ impl PaneSetup {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::aggr::renko::RenkoBox;
+    use exchange::adapter::Exchange;
+
+    #[test]
+    fn bar_bases_stay_with_kline_charts() {
+        let ticker_info = TickerInfo::new(
+            Ticker::new("BTCUSDT", Exchange::BinanceLinear),
+            0.1,
+            0.001,
+            None,
+        );
+        let renko = Basis::Renko(RenkoBox {
+            size: 10,
+            reversal: RenkoBox::DEFAULT_REVERSAL,
+        });
+        let setup = |kind| PaneSetup::new(kind, ticker_info, None, Some(renko), None).basis;
+
+        assert_eq!(setup(ContentKind::CandlestickChart), Some(renko));
+        assert_eq!(setup(ContentKind::FootprintChart), Some(renko));
+        assert_eq!(
+            setup(ContentKind::ComparisonChart),
+            Some(Basis::Time(Timeframe::M15))
+        );
+        assert!(setup(ContentKind::HeatmapChart).is_some_and(|basis| basis.is_time()));
+        assert!(setup(ContentKind::Ladder).is_some_and(|basis| basis.is_time()));
+    }
+}
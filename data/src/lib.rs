@@ -2,8 +2,10 @@ pub mod aggr;
 pub mod audio;
 pub mod chart;
 pub mod config;
+pub mod export;
 pub mod layout;
 pub mod log;
+pub mod open_interest;
 pub mod panel;
 pub mod tickers_table;
 pub mod util;
@@ -62,8 +64,11 @@ pub fn read_from_file(file_name: &str) -> Result<State, Box<dyn std::error::Erro
         return Err(Box::new(e));
     }
 
-    match serde_json::from_str(&contents) {
-        Ok(state) => Ok(state),
+    match serde_json::from_str::<State>(&contents) {
+        Ok(mut state) => {
+            state.migrate_size_unit();
+            Ok(state)
+        }
         Err(e) => {
             // If parsing fails, backup the file
             drop(file); // Close the file before renaming
@@ -184,15 +189,96 @@ fn cleanup_directory(data_path: &PathBuf) -> usize {
 }
 
 pub fn cleanup_old_market_data() -> usize {
-    let paths = ["um", "cm"].map(|market_type| {
+    let paths = ["futures/um", "futures/cm", "spot"].map(|market_type| {
         data_path(Some(&format!(
-            "market_data/binance/data/futures/{}/daily/aggTrades",
+            "market_data/binance/data/{}/daily/aggTrades",
             market_type
         )))
     });
 
     let total_deleted: usize = paths.iter().map(cleanup_directory).sum();
+    let partial_deleted = remove_market_data_files(|path| {
+        path.extension()
+            .is_some_and(|ext| ext == exchange::adapter::binance::archive::PARTIAL_EXT)
+    });
 
-    info!("File cleanup completed. Deleted {} files", total_deleted);
+    info!(
+        "File cleanup completed. Deleted {} files, {} partial downloads",
+        total_deleted, partial_deleted
+    );
     total_deleted
 }
+
+/// Total size in bytes of the cached exchange archives under the data folder
+pub fn market_data_cache_size() -> u64 {
+    fn dir_size(path: &std::path::Path) -> u64 {
+        let Ok(entries) = std::fs::read_dir(path) else {
+            return 0;
+        };
+
+        entries
+            .filter_map(Result::ok)
+            .map(|entry| match entry.file_type() {
+                Ok(ft) if ft.is_dir() => dir_size(&entry.path()),
+                Ok(_) => entry.metadata().map_or(0, |m| m.len()),
+                Err(_) => 0,
+            })
+            .sum()
+    }
+
+    dir_size(&market_data_dir())
+}
+
+/// Deletes every cached exchange archive, returns the number of removed files
+pub fn prune_market_data() -> usize {
+    let deleted = remove_market_data_files(|_| true);
+
+    info!("Pruned market data cache. Deleted {} files", deleted);
+    deleted
+}
+
+fn remove_market_data_files(should_remove: impl Fn(&std::path::Path) -> bool) -> usize {
+    fn walk(path: &std::path::Path, should_remove: &dyn Fn(&std::path::Path) -> bool) -> usize {
+        let Ok(entries) = std::fs::read_dir(path) else {
+            return 0;
+        };
+
+        let mut deleted = 0;
+        for entry in entries.filter_map(Result::ok) {
+            let path = entry.path();
+
+            if path.is_dir() {
+                deleted += walk(&path, should_remove);
+            } else if should_remove(&path) {
+                if let Err(e) = std::fs::remove_file(&path) {
+                    error!("Failed to remove file {:?}: {}", path, e);
+                } else {
+                    deleted += 1;
+                }
+            }
+        }
+        deleted
+    }
+
+    walk(&market_data_dir(), &should_remove)
+}
+
+fn market_data_dir() -> PathBuf {
+    data_path(None).join("market_data")
+}
+
+/// Test bar trading flat at `close` with the given buy and sell volume
+#[cfg(test)]
+pub(crate) fn kline(time: u64, close: f32, (buy, sell): (f32, f32)) -> exchange::Kline {
+    use exchange::util::{Price, Qty};
+
+    let price = Price::from_f32(close);
+    exchange::Kline {
+        time,
+        open: price,
+        high: price,
+        low: price,
+        close: price,
+        volume: (Qty::from_f32(buy), Qty::from_f32(sell)),
+    }
+}
@@ -0,0 +1,203 @@
+//! Open interest samples recorded on disk, so charts can show it for venues without
+//! history and further back than venues keep it
+
+use exchange::{OpenInterest, Ticker};
+
+use std::collections::BTreeMap;
+use std::fs::{File, OpenOptions};
+use std::io::{ErrorKind, Read, Seek, SeekFrom, Write};
+use std::path::{Path, PathBuf};
+
+pub const OPEN_INTEREST_DIR: &str = "open_interest";
+
+/// One `time,value` line per sample
+fn file_path(ticker: Ticker) -> PathBuf {
+    let (symbol, _) = ticker.to_full_symbol_and_type();
+    let symbol: String = symbol
+        .chars()
+        .map(|c| if c.is_ascii_alphanumeric() { c } else { '_' })
+        .collect();
+
+    crate::data_path(Some(&format!(
+        "{OPEN_INTEREST_DIR}/{:?}/{symbol}.csv",
+        ticker.exchange
+    )))
+}
+
+fn parse(contents: &str) -> BTreeMap<u64, f32> {
+    contents
+        .lines()
+        .filter_map(|line| {
+            let (time, value) = line.split_once(',')?;
+            Some((time.parse().ok()?, value.parse().ok()?))
+        })
+        .collect()
+}
+
+fn read(path: &Path) -> std::io::Result<BTreeMap<u64, f32>> {
+    match std::fs::read_to_string(path) {
+        Ok(contents) => Ok(parse(&contents)),
+        Err(e) if e.kind() == ErrorKind::NotFound => Ok(BTreeMap::new()),
+        Err(e) => Err(e),
+    }
+}
+
+/// Time of the last line, read from the end of the file so polling stays cheap
+fn last_recorded(path: &Path) -> std::io::Result<Option<u64>> {
+    const TAIL_LEN: u64 = 128;
+
+    let mut file = match File::open(path) {
+        Ok(file) => file,
+        Err(e) if e.kind() == ErrorKind::NotFound => return Ok(None),
+        Err(e) => return Err(e),
+    };
+    let len = file.metadata()?.len();
+    file.seek(SeekFrom::Start(len.saturating_sub(TAIL_LEN)))?;
+
+    let mut tail = String::new();
+    file.read_to_string(&mut tail)?;
+
+    Ok(parse(tail.lines().last().unwrap_or_default())
+        .into_keys()
+        .next())
+}
+
+/// Samples recorded for `ticker` within `range`, all of them without one
+pub fn load(ticker: Ticker, range: Option<(u64, u64)>) -> std::io::Result<Vec<OpenInterest>> {
+    load_from(&file_path(ticker), range)
+}
+
+fn load_from(path: &Path, range: Option<(u64, u64)>) -> std::io::Result<Vec<OpenInterest>> {
+    let recorded = read(path)?;
+    let (start, end) = range.unwrap_or((0, u64::MAX));
+
+    Ok(recorded
+        .range(start..=end)
+        .map(|(time, value)| OpenInterest {
+            time: *time,
+            value: *value,
+        })
+        .collect())
+}
+
+/// Adds `samples` to what's recorded for `ticker`. New samples after the last recorded
+/// one are appended, earlier ones make the file get rewritten in order
+pub fn record(ticker: Ticker, samples: &[OpenInterest]) -> std::io::Result<()> {
+    record_to(&file_path(ticker), samples)
+}
+
+fn record_to(path: &Path, samples: &[OpenInterest]) -> std::io::Result<()> {
+    let last_recorded = last_recorded(path)?;
+
+    // polls often return the sample that was recorded last
+    let mut new_samples: Vec<&OpenInterest> = samples
+        .iter()
+        .filter(|oi| last_recorded != Some(oi.time))
+        .collect();
+    new_samples.sort_by_key(|oi| oi.time);
+    new_samples.dedup_by_key(|oi| oi.time);
+
+    let Some(first) = new_samples.first() else {
+        return Ok(());
+    };
+
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent)?;
+    }
+
+    if last_recorded.is_none_or(|last| first.time > last) {
+        let mut file = OpenOptions::new().create(true).append(true).open(path)?;
+        for oi in new_samples {
+            writeln!(file, "{},{}", oi.time, oi.value)?;
+        }
+        return Ok(());
+    }
+
+    let mut recorded = read(path)?;
+    let len = recorded.len();
+    for oi in new_samples {
+        recorded.entry(oi.time).or_insert(oi.value);
+    }
+    if recorded.len() == len {
+        return Ok(());
+    }
+
+    let mut contents = String::with_capacity(recorded.len() * 24);
+    for (time, value) in &recorded {
+        contents.push_str(&format!("{time},{value}\n"));
+    }
+    File::create(path)?.write_all(contents.as_bytes())
+}
+
+/// Recorded samples together with fetched history, history wins where both have a sample
+pub fn merge(recorded: &[OpenInterest], history: &[OpenInterest]) -> Vec<OpenInterest> {
+    let merged: BTreeMap<u64, f32> = recorded
+        .iter()
+        .chain(history)
+        .map(|oi| (oi.time, oi.value))
+        .collect();
+
+    merged
+        .into_iter()
+        .map(|(time, value)| OpenInterest { time, value })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn skips_lines_that_dont_parse() {
+        let recorded = parse("1000,52.5\nnot,a sample\n\n2000,53\n1000,52.5\n");
+
+        assert_eq!(
+            recorded.into_iter().collect::<Vec<_>>(),
+            vec![(1000, 52.5), (2000, 53.0)]
+        );
+    }
+
+    fn oi(time: u64, value: f32) -> OpenInterest {
+        OpenInterest { time, value }
+    }
+
+    #[test]
+    fn appends_new_samples_and_rewrites_for_earlier_ones() {
+        let path = std::env::temp_dir()
+            .join(format!("flowsurface-oi-{}", std::process::id()))
+            .join("BTCUSDT.csv");
+
+        record_to(&path, &[oi(2000, 2.0), oi(1000, 1.0)]).unwrap();
+        // the last sample polled again is left alone
+        record_to(&path, &[oi(2000, 2.0), oi(3000, 3.0)]).unwrap();
+        assert_eq!(
+            std::fs::read_to_string(&path).unwrap(),
+            "1000,1\n2000,2\n3000,3\n"
+        );
+        assert_eq!(last_recorded(&path).unwrap(), Some(3000));
+
+        // history from before the last sample is merged in order, recorded values kept
+        record_to(&path, &[oi(500, 0.5), oi(1000, 9.0), oi(2500, 2.5)]).unwrap();
+        assert_eq!(
+            std::fs::read_to_string(&path).unwrap(),
+            "500,0.5\n1000,1\n2000,2\n2500,2.5\n3000,3\n"
+        );
+        assert_eq!(
+            load_from(&path, Some((1000, 2500))).unwrap(),
+            vec![oi(1000, 1.0), oi(2000, 2.0), oi(2500, 2.5)]
+        );
+
+        std::fs::remove_dir_all(path.parent().unwrap()).unwrap();
+    }
+
+    #[test]
+    fn fetched_history_overrides_recorded_samples() {
+        let recorded = [oi(1000, 1.0), oi(2000, 2.0), oi(4000, 4.0)];
+        let history = [oi(2000, 2.5), oi(3000, 3.0)];
+
+        assert_eq!(
+            merge(&recorded, &history),
+            vec![oi(1000, 1.0), oi(2000, 2.5), oi(3000, 3.0), oi(4000, 4.0)]
+        );
+    }
+}
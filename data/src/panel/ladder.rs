@@ -1,8 +1,8 @@
 use crate::chart::kline::KlineTrades;
 use crate::util::ok_or_default;
 use exchange::{
-    Trade,
-    util::{Price, PriceStep},
+    SizeUnit, Trade,
+    util::{Price, PriceStep, Qty},
 };
 
 use serde::{Deserialize, Serialize};
@@ -20,6 +20,8 @@ pub struct Config {
     #[serde(deserialize_with = "ok_or_default", default)]
     pub show_chase_tracker: bool,
     pub trade_retention: Duration,
+    #[serde(deserialize_with = "ok_or_default", default)]
+    pub size_unit: SizeUnit,
 }
 
 impl Default for Config {
@@ -28,6 +30,7 @@ impl Default for Config {
             show_spread: false,
             show_chase_tracker: true,
             trade_retention: Duration::from_millis(TRADE_RETENTION_MS),
+            size_unit: SizeUnit::default(),
         }
     }
 }
@@ -59,7 +62,7 @@ impl Side {
 
 #[derive(Default)]
 pub struct GroupedDepth {
-    pub orders: BTreeMap<Price, f32>,
+    pub orders: BTreeMap<Price, Qty>,
     pub chase: ChaseTracker,
 }
 
@@ -71,11 +74,11 @@ impl GroupedDepth {
         }
     }
 
-    pub fn regroup_from_raw(&mut self, levels: &BTreeMap<Price, f32>, side: Side, step: PriceStep) {
+    pub fn regroup_from_raw(&mut self, levels: &BTreeMap<Price, Qty>, side: Side, step: PriceStep) {
         self.orders.clear();
         for (price, qty) in levels.iter() {
             let grouped_price = price.round_to_side_step(side.is_bid(), step);
-            *self.orders.entry(grouped_price).or_insert(0.0) += *qty;
+            *self.orders.entry(grouped_price).or_default() += *qty;
         }
     }
 
@@ -125,11 +128,11 @@ impl TradeStore {
         }
     }
 
-    pub fn trade_qty_at(&self, price: Price) -> (f32, f32) {
+    pub fn trade_qty_at(&self, price: Price) -> (Qty, Qty) {
         if let Some(g) = self.grouped.trades.get(&price) {
             (g.buy_qty, g.sell_qty)
         } else {
-            (0.0, 0.0)
+            (Qty::ZERO, Qty::ZERO)
         }
     }
 
@@ -1,6 +1,7 @@
 use std::time::Duration;
 
-use exchange::util::Price;
+use exchange::util::{Price, Qty};
+use exchange::{SizeUnit, adapter::MarketKind};
 use serde::{Deserialize, Serialize};
 
 use crate::util::ok_or_default;
@@ -14,6 +15,8 @@ pub struct Config {
     pub trade_retention: Duration,
     #[serde(deserialize_with = "ok_or_default", default)]
     pub stacked_bar: Option<StackedBar>,
+    #[serde(deserialize_with = "ok_or_default", default)]
+    pub size_unit: SizeUnit,
 }
 
 impl Default for Config {
@@ -22,6 +25,7 @@ impl Default for Config {
             trade_size_filter: 0.0,
             trade_retention: Duration::from_millis(TRADE_RETENTION_MS),
             stacked_bar: StackedBar::Compact(StackedBarRatio::default()).into(),
+            size_unit: SizeUnit::default(),
         }
     }
 }
@@ -34,7 +38,7 @@ fn default_buffer_filter() -> Duration {
 pub struct TradeDisplay {
     pub time_str: String,
     pub price: Price,
-    pub qty: f32,
+    pub qty: Qty,
     pub is_sell: bool,
 }
 
@@ -91,40 +95,74 @@ impl StackedBarRatio {
     ];
 }
 
-#[derive(Default)]
+/// Rolling buy/sell totals, kept in both size units so the display unit can change freely
 pub struct HistAgg {
+    market: MarketKind,
     buy_count: u64,
     sell_count: u64,
-    buy_sum: f64,
-    sell_sum: f64,
+    buy_sum: Qty,
+    sell_sum: Qty,
+    buy_notional: Qty,
+    sell_notional: Qty,
 }
 
 impl HistAgg {
+    pub fn new(market: MarketKind) -> Self {
+        Self {
+            market,
+            buy_count: 0,
+            sell_count: 0,
+            buy_sum: Qty::ZERO,
+            sell_sum: Qty::ZERO,
+            buy_notional: Qty::ZERO,
+            sell_notional: Qty::ZERO,
+        }
+    }
+
     pub fn add(&mut self, trade: &TradeDisplay) {
-        let qty = trade.qty as f64;
+        let qty = trade.qty;
+        let notional = self.market.qty_in_quote_value(qty, trade.price);
 
         if trade.is_sell {
             self.sell_count += 1;
             self.sell_sum += qty;
+            self.sell_notional += notional;
         } else {
             self.buy_count += 1;
             self.buy_sum += qty;
+            self.buy_notional += notional;
         }
     }
 
     pub fn remove(&mut self, trade: &TradeDisplay) {
-        let qty = trade.qty as f64;
+        let qty = trade.qty;
+        let notional = self.market.qty_in_quote_value(qty, trade.price);
 
         if trade.is_sell {
             self.sell_count = self.sell_count.saturating_sub(1);
             self.sell_sum -= qty;
+            self.sell_notional -= notional;
         } else {
             self.buy_count = self.buy_count.saturating_sub(1);
             self.buy_sum -= qty;
+            self.buy_notional -= notional;
         }
     }
 
-    pub fn values_for(&self, ratio_kind: StackedBarRatio) -> Option<(f64, f64, f32)> {
+    fn sums(&self, unit: SizeUnit) -> (Qty, Qty) {
+        match unit {
+            SizeUnit::Base => (self.buy_sum, self.sell_sum),
+            SizeUnit::Quote => (self.buy_notional, self.sell_notional),
+        }
+    }
+
+    pub fn values_for(
+        &self,
+        ratio_kind: StackedBarRatio,
+        unit: SizeUnit,
+    ) -> Option<(f64, f64, f32)> {
+        let (buy_sum, sell_sum) = self.sums(unit);
+
         match ratio_kind {
             StackedBarRatio::Count => {
                 let buy = self.buy_count as f64;
@@ -139,8 +177,8 @@ impl HistAgg {
                 Some((buy, sell, buy_ratio))
             }
             StackedBarRatio::Volume => {
-                let buy = self.buy_sum;
-                let sell = self.sell_sum;
+                let buy = buy_sum.to_f64();
+                let sell = sell_sum.to_f64();
                 let total = buy + sell;
 
                 if total <= 0.0 {
@@ -152,12 +190,12 @@ impl HistAgg {
             }
             StackedBarRatio::AverageSize => {
                 let buy_avg = if self.buy_count > 0 {
-                    self.buy_sum / self.buy_count as f64
+                    buy_sum.to_f64() / self.buy_count as f64
                 } else {
                     0.0
                 };
                 let sell_avg = if self.sell_count > 0 {
-                    self.sell_sum / self.sell_count as f64
+                    sell_sum.to_f64() / self.sell_count as f64
                 } else {
                     0.0
                 };
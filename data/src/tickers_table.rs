@@ -1,8 +1,18 @@
 use exchange::{
-    Ticker, TickerStats,
+    Asset, Ticker, TickerInfo, TickerStats,
     adapter::{Exchange, ExchangeInclusive, MarketKind},
 };
+use rustc_hash::FxHashMap;
 use serde::{Deserialize, Serialize};
+use std::cmp::Ordering;
+use std::collections::HashMap;
+use std::path::PathBuf;
+use std::time::Duration;
+
+const TICKERS_CACHE_DIR: &str = "tickers_cache";
+
+/// Cached ticker metadata older than this is ignored, tick sizes do change over time
+pub const TICKERS_CACHE_TTL: Duration = Duration::from_secs(7 * 24 * 60 * 60);
 
 #[derive(Clone, Debug, Serialize, Deserialize, PartialEq)]
 pub struct Settings {
@@ -11,6 +21,9 @@ pub struct Settings {
     pub selected_sort_option: SortOptions,
     pub selected_exchanges: Vec<ExchangeInclusive>,
     pub selected_markets: Vec<MarketKind>,
+    /// Only list pairs quoted in this asset, all of them when `None`
+    #[serde(default)]
+    pub selected_quote: Option<Asset>,
 }
 
 impl Default for Settings {
@@ -21,6 +34,7 @@ impl Default for Settings {
             selected_sort_option: SortOptions::VolumeDesc,
             selected_exchanges: ExchangeInclusive::ALL.to_vec(),
             selected_markets: MarketKind::ALL.into_iter().collect(),
+            selected_quote: None,
         }
     }
 }
@@ -48,6 +62,56 @@ pub struct TickerRowData {
     pub stats: TickerStats,
     pub previous_stats: Option<TickerStats>,
     pub is_favorited: bool,
+    /// Quote asset the daily volume is in, unknown for imported datasets
+    pub quote: Asset,
+    /// Daily volume in USD, `None` when there's no USD price for the quote asset
+    pub volume_usd: Option<f32>,
+}
+
+impl TickerRowData {
+    pub fn update_volume_usd(&mut self, usd_prices: &FxHashMap<Asset, f32>) {
+        self.volume_usd = if self.quote.is_usd() {
+            Some(self.stats.daily_volume)
+        } else {
+            usd_prices
+                .get(&self.quote.canonical())
+                .map(|price| self.stats.daily_volume * price)
+        };
+    }
+
+    /// Orders by USD volume, rows that couldn't be converted rank below every converted one
+    pub fn cmp_volume(&self, other: &Self) -> Ordering {
+        match (self.volume_usd, other.volume_usd) {
+            (Some(a), Some(b)) => a.total_cmp(&b),
+            (Some(_), None) => Ordering::Greater,
+            (None, Some(_)) => Ordering::Less,
+            (None, None) => self.stats.daily_volume.total_cmp(&other.stats.daily_volume),
+        }
+    }
+}
+
+/// USD price of each asset that is listed against a USD quote, spot pairs preferred
+pub fn usd_prices<'a>(
+    pairs: impl IntoIterator<Item = (&'a TickerInfo, &'a TickerStats)>,
+) -> FxHashMap<Asset, f32> {
+    let mut prices = FxHashMap::default();
+
+    for (info, stats) in pairs {
+        // multiplied contracts like 1000PEPE aren't priced per unit of the asset
+        let multiplied = info.base.as_str().starts_with(|c: char| c.is_ascii_digit());
+        if !info.quote.is_usd() || info.base.is_unknown() || multiplied || stats.mark_price <= 0.0 {
+            continue;
+        }
+
+        let base = info.base.canonical();
+        if info.market_type() == MarketKind::Spot {
+            prices.insert(base, stats.mark_price);
+        } else {
+            prices.entry(base).or_insert(stats.mark_price);
+        }
+    }
+
+    prices
 }
 
 #[derive(Clone)]
@@ -62,12 +126,9 @@ pub struct TickerDisplayData {
     pub card_color_alpha: f32,
 }
 
-pub fn compute_display_data(
-    ticker: &Ticker,
-    stats: &TickerStats,
-    previous_price: Option<f32>,
-) -> TickerDisplayData {
-    let (display_ticker, _market) = ticker.display_symbol_and_type();
+pub fn compute_display_data(row: &TickerRowData, previous_price: Option<f32>) -> TickerDisplayData {
+    let (display_ticker, _market) = row.ticker.display_symbol_and_type();
+    let stats = &row.stats;
 
     let current_price = stats.mark_price;
     let (price_unchanged_part, price_changed_part, price_change_direction) =
@@ -84,7 +145,11 @@ pub fn compute_display_data(
     TickerDisplayData {
         display_ticker,
         daily_change_pct: super::util::pct_change(stats.daily_price_chg),
-        volume_display: super::util::currency_abbr(stats.daily_volume),
+        volume_display: match row.volume_usd {
+            Some(volume) => super::util::currency_abbr(volume),
+            None if row.quote.is_unknown() => super::util::abbr(stats.daily_volume),
+            None => format!("{} {}", super::util::abbr(stats.daily_volume), row.quote),
+        },
         mark_price_display: stats.mark_price.to_string(),
         price_unchanged_part,
         price_changed_part,
@@ -134,3 +199,157 @@ fn split_price_changes(
 
     (unchanged_part, changed_part, direction)
 }
+
+/// Ticker metadata from the last successful fetch of an exchange
+#[derive(Serialize, Deserialize)]
+struct CachedTickersInfo {
+    fetched_at: u64,
+    tickers: Vec<TickerInfo>,
+}
+
+fn tickers_cache_path(exchange: Exchange) -> PathBuf {
+    crate::data_path(None)
+        .join(TICKERS_CACHE_DIR)
+        .join(format!("{exchange:?}.json"))
+}
+
+fn now_ms() -> u64 {
+    chrono::Utc::now().timestamp_millis().max(0) as u64
+}
+
+/// Stores the supported tickers of a successful fetch, imported datasets are skipped
+pub fn save_tickers_cache(
+    exchange: Exchange,
+    info: &HashMap<Ticker, Option<TickerInfo>>,
+) -> std::io::Result<()> {
+    if exchange == Exchange::Local {
+        return Ok(());
+    }
+
+    let cached = CachedTickersInfo {
+        fetched_at: now_ms(),
+        tickers: info.values().flatten().copied().collect(),
+    };
+    let json = serde_json::to_string(&cached).map_err(std::io::Error::other)?;
+
+    let path = tickers_cache_path(exchange);
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent)?;
+    }
+    std::fs::write(path, json)
+}
+
+/// Returns the cached tickers of `exchange` unless missing, unreadable or past [`TICKERS_CACHE_TTL`]
+pub fn load_tickers_cache(exchange: Exchange) -> Option<HashMap<Ticker, Option<TickerInfo>>> {
+    if exchange == Exchange::Local {
+        return None;
+    }
+
+    let path = tickers_cache_path(exchange);
+    let json = std::fs::read_to_string(&path).ok()?;
+
+    let cached: CachedTickersInfo = match serde_json::from_str(&json) {
+        Ok(cached) => cached,
+        Err(e) => {
+            log::warn!("Ignoring unreadable tickers cache {path:?}: {e}");
+            return None;
+        }
+    };
+
+    let age_ms = now_ms().saturating_sub(cached.fetched_at);
+    if age_ms > TICKERS_CACHE_TTL.as_millis() as u64 {
+        log::info!("Tickers cache of {exchange} expired");
+        return None;
+    }
+
+    Some(
+        cached
+            .tickers
+            .into_iter()
+            .map(|info| (info.ticker, Some(info)))
+            .collect(),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn pair(symbol: &str, base: &str, quote: &str, price: f32, volume: f32) -> TickerRowData {
+        let info = TickerInfo::new(
+            Ticker::new(symbol, Exchange::BinanceSpot),
+            0.01,
+            0.001,
+            None,
+        )
+        .with_assets(base, quote);
+
+        TickerRowData {
+            exchange: Exchange::BinanceSpot,
+            ticker: info.ticker,
+            stats: TickerStats {
+                mark_price: price,
+                daily_price_chg: 0.0,
+                daily_volume: volume,
+            },
+            previous_stats: None,
+            is_favorited: false,
+            quote: info.quote,
+            volume_usd: None,
+        }
+    }
+
+    #[test]
+    fn volumes_are_converted_to_usd_through_the_quote_asset() {
+        let infos = [
+            TickerInfo::new(
+                Ticker::new("BTCUSDT", Exchange::BinanceSpot),
+                0.01,
+                0.001,
+                None,
+            )
+            .with_assets("BTC", "USDT"),
+            TickerInfo::new(
+                Ticker::new("1000PEPEUSDT", Exchange::BinanceSpot),
+                0.01,
+                1.0,
+                None,
+            )
+            .with_assets("1000PEPE", "USDT"),
+        ];
+        let stats = [
+            TickerStats {
+                mark_price: 50_000.0,
+                daily_price_chg: 0.0,
+                daily_volume: 1e9,
+            },
+            TickerStats {
+                mark_price: 0.01,
+                daily_price_chg: 0.0,
+                daily_volume: 1e6,
+            },
+        ];
+        let prices = usd_prices(infos.iter().zip(stats.iter()));
+        assert_eq!(prices.get(&Asset::new("BTC")), Some(&50_000.0));
+        assert!(!prices.contains_key(&Asset::new("PEPE")));
+
+        let mut usdt = pair("ETHUSDT", "ETH", "USDT", 2_000.0, 2e6);
+        let mut btc = pair("ETHBTC", "ETH", "BTC", 0.04, 100.0);
+        let mut unpriced = pair("ETHTRY", "ETH", "TRY", 80_000.0, 1e12);
+        for row in [&mut usdt, &mut btc, &mut unpriced] {
+            row.update_volume_usd(&prices);
+        }
+
+        assert_eq!(usdt.volume_usd, Some(2e6));
+        assert_eq!(btc.volume_usd, Some(5e6));
+        assert_eq!(unpriced.volume_usd, None);
+        assert_eq!(btc.cmp_volume(&usdt), Ordering::Greater);
+        assert_eq!(unpriced.cmp_volume(&usdt), Ordering::Less);
+
+        assert_eq!(compute_display_data(&btc, None).volume_display, "$5.0m");
+        assert_eq!(
+            compute_display_data(&unpriced, None).volume_display,
+            "1000.00b TRY"
+        );
+    }
+}
@@ -36,7 +36,9 @@ pub fn abbr_large_numbers(value: f32) -> String {
             if abs_value == 0.0 {
                 "0".to_string()
             } else {
-                let s = format!("{}{:.3}", sign, abs_value);
+                // keep three significant digits for micro-priced values
+                let decimals = (-abs_value.log10().floor()) as usize + 2;
+                let s = format!("{}{:.*}", sign, decimals, abs_value);
                 s.trim_end_matches('0').trim_end_matches('.').to_string()
             }
         }
@@ -126,11 +128,16 @@ pub fn round_to_next_tick(value: f32, tick_size: f32, down: bool) -> f32 {
 }
 
 pub fn currency_abbr(price: f32) -> String {
-    match price {
-        p if p > 1_000_000_000.0 => format!("${:.2}b", p / 1_000_000_000.0),
-        p if p > 1_000_000.0 => format!("${:.1}m", p / 1_000_000.0),
-        p if p > 1000.0 => format!("${:.2}k", p / 1000.0),
-        _ => format!("${:.2}", price),
+    format!("${}", abbr(price))
+}
+
+/// Value shortened with a b/m/k suffix, without a currency sign
+pub fn abbr(value: f32) -> String {
+    match value {
+        v if v > 1_000_000_000.0 => format!("{:.2}b", v / 1_000_000_000.0),
+        v if v > 1_000_000.0 => format!("{:.1}m", v / 1_000_000.0),
+        v if v > 1000.0 => format!("{:.2}k", v / 1000.0),
+        _ => format!("{:.2}", value),
     }
 }
 
@@ -155,6 +162,7 @@ pub fn guesstimate_ticks(range: f32) -> f32 {
         r if r > 1.0 => 0.01,
         r if r > 0.1 => 0.001,
         r if r > 0.01 => 0.0001,
+        r if r > 0.0 => 10f32.powi(r.log10().floor() as i32 - 2),
         _ => 0.00001,
     }
 }
@@ -191,6 +199,23 @@ pub fn format_duration_ms(diff_ms: u64) -> String {
     }
 }
 
+pub fn format_bytes(bytes: u64) -> String {
+    const KB: f64 = 1024.0;
+    const MB: f64 = KB * 1024.0;
+    const GB: f64 = MB * 1024.0;
+
+    let value = bytes as f64;
+    if value >= GB {
+        format!("{:.2} GB", value / GB)
+    } else if value >= MB {
+        format!("{:.1} MB", value / MB)
+    } else if value >= KB {
+        format!("{:.0} KB", value / KB)
+    } else {
+        format!("{} B", bytes)
+    }
+}
+
 /// Shrinks main panel if needed when adding a new panel.
 /// Ensures indicators never shrink below `MIN_PANEL_HEIGHT`
 pub fn calc_panel_splits(
@@ -258,3 +283,22 @@ pub fn reset_to_start_of_month_utc(dt: DateTime<chrono::Utc>) -> DateTime<chrono
 pub fn reset_to_start_of_year_utc(dt: DateTime<chrono::Utc>) -> DateTime<chrono::Utc> {
     reset_to_start_of_month_utc(dt.with_month(1).unwrap_or(dt))
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn abbreviates_micro_values() {
+        assert_eq!(abbr_large_numbers(0.000_001_234), "0.00000123");
+        assert_eq!(abbr_large_numbers(-0.000_000_005_6), "-0.0000000056");
+        assert_eq!(abbr_large_numbers(0.000_02), "0.00002");
+    }
+
+    #[test]
+    fn guesstimates_sub_satoshi_ticks() {
+        assert_eq!(guesstimate_ticks(0.05), 0.0001);
+        assert!((guesstimate_ticks(0.000_000_5) - 1e-9).abs() < 1e-15);
+        assert_eq!(count_decimals(0.000_000_000_1), 10);
+    }
+}
@@ -1,7 +1,7 @@
 use super::{Ticker, Timeframe};
 use crate::{
-    Kline, OpenInterest, Price, PushFrequency, TickMultiplier, TickerInfo, TickerStats, Trade,
-    depth::Depth,
+    Kline, OpenInterest, Price, PushFrequency, SizeUnit, TickMultiplier, TickerInfo, TickerStats,
+    Trade, depth::Depth, util::Qty,
 };
 
 use enum_map::{Enum, EnumMap};
@@ -12,6 +12,7 @@ use std::{collections::HashMap, str::FromStr, sync::Arc, time::Instant};
 pub mod binance;
 pub mod bybit;
 pub mod hyperliquid;
+pub mod local;
 pub mod okex;
 
 /// Persisted stream resolution to avoid loop retries
@@ -62,9 +63,12 @@ impl ResolvedStream {
         Some(streams.clone())
     }
 
+    /// Kline streams also match the lower timeframe they are resampled from
     pub fn matches_stream(&self, stream: &StreamKind) -> bool {
         match self {
-            ResolvedStream::Ready(existing) => existing.iter().any(|s| s == stream),
+            ResolvedStream::Ready(existing) => existing
+                .iter()
+                .any(|s| s == stream || s.kline_source().as_ref() == Some(stream)),
             _ => false,
         }
     }
@@ -170,16 +174,19 @@ impl MarketKind {
         MarketKind::InversePerps,
     ];
 
-    pub fn qty_in_quote_value(&self, qty: f32, price: Price, size_in_quote_ccy: bool) -> f32 {
+    /// Notional value of a base quantity, inverse perps are already sized in USD
+    pub fn qty_in_quote_value(&self, qty: Qty, price: Price) -> Qty {
         match self {
             MarketKind::InversePerps => qty,
-            _ => {
-                if size_in_quote_ccy {
-                    qty
-                } else {
-                    price.to_f32() * qty
-                }
-            }
+            _ => qty.to_quote(price),
+        }
+    }
+
+    /// Converts a base quantity into the unit a pane displays sizes in
+    pub fn qty_in_size_unit(&self, qty: Qty, price: Price, unit: SizeUnit) -> Qty {
+        match unit {
+            SizeUnit::Base => qty,
+            SizeUnit::Quote => self.qty_in_quote_value(qty, price),
         }
     }
 }
@@ -231,6 +238,24 @@ impl StreamKind {
         }
     }
 
+    /// The stream actually subscribed to for a kline stream, whose timeframe
+    /// may be a lower one the venue serves natively
+    pub fn kline_source(&self) -> Option<StreamKind> {
+        match self {
+            StreamKind::Kline {
+                ticker_info,
+                timeframe,
+            } => ticker_info
+                .exchange()
+                .kline_source(*timeframe)
+                .map(|timeframe| StreamKind::Kline {
+                    ticker_info: *ticker_info,
+                    timeframe,
+                }),
+            _ => None,
+        }
+    }
+
     pub fn as_kline_stream(&self) -> Option<(TickerInfo, Timeframe)> {
         match self {
             StreamKind::Kline {
@@ -317,8 +342,18 @@ impl UniqueStreams {
         self.streams(exchange_filter, |_, stream| stream.as_depth_stream())
     }
 
+    /// Kline subscriptions, resampled timeframes are replaced by their native source
     pub fn kline_streams(&self, exchange_filter: Option<Exchange>) -> Vec<(TickerInfo, Timeframe)> {
-        self.streams(exchange_filter, |_, stream| stream.as_kline_stream())
+        let mut seen = FxHashSet::default();
+
+        self.streams(exchange_filter, |_, stream| {
+            stream
+                .kline_source()
+                .and_then(|source| source.as_kline_stream())
+        })
+        .into_iter()
+        .filter(|stream| seen.insert(*stream))
+        .collect()
     }
 
     pub fn combined_used(&self) -> impl Iterator<Item = (Exchange, &StreamSpecs)> {
@@ -428,6 +463,8 @@ pub enum ExchangeInclusive {
     Binance,
     Hyperliquid,
     Okex,
+    /// User-imported datasets, not listed among the venue filters
+    Local,
 }
 
 impl ExchangeInclusive {
@@ -446,6 +483,7 @@ impl ExchangeInclusive {
             }
             Exchange::HyperliquidLinear | Exchange::HyperliquidSpot => Self::Hyperliquid,
             Exchange::OkexLinear | Exchange::OkexInverse | Exchange::OkexSpot => Self::Okex,
+            Exchange::Local => Self::Local,
         }
     }
 }
@@ -463,6 +501,8 @@ pub enum Exchange {
     OkexLinear,
     OkexInverse,
     OkexSpot,
+    /// Trades or candles imported from user files, see [`local`]
+    Local,
 }
 
 impl std::fmt::Display for Exchange {
@@ -482,6 +522,7 @@ impl std::fmt::Display for Exchange {
                 Exchange::OkexLinear => "Okex Linear",
                 Exchange::OkexInverse => "Okex Inverse",
                 Exchange::OkexSpot => "Okex Spot",
+                Exchange::Local => "Local",
             }
         )
     }
@@ -503,13 +544,14 @@ impl FromStr for Exchange {
             "Okex Linear" => Ok(Exchange::OkexLinear),
             "Okex Inverse" => Ok(Exchange::OkexInverse),
             "Okex Spot" => Ok(Exchange::OkexSpot),
+            "Local" => Ok(Exchange::Local),
             _ => Err(format!("Invalid exchange: {}", s)),
         }
     }
 }
 
 impl Exchange {
-    pub const ALL: [Exchange; 11] = [
+    pub const ALL: [Exchange; 12] = [
         Exchange::BinanceLinear,
         Exchange::BinanceInverse,
         Exchange::BinanceSpot,
@@ -521,6 +563,7 @@ impl Exchange {
         Exchange::OkexLinear,
         Exchange::OkexInverse,
         Exchange::OkexSpot,
+        Exchange::Local,
     ];
 
     pub fn market_type(&self) -> MarketKind {
@@ -535,7 +578,8 @@ impl Exchange {
             Exchange::BinanceSpot
             | Exchange::BybitSpot
             | Exchange::HyperliquidSpot
-            | Exchange::OkexSpot => MarketKind::Spot,
+            | Exchange::OkexSpot
+            | Exchange::Local => MarketKind::Spot,
         }
     }
 
@@ -578,6 +622,98 @@ impl Exchange {
         }
     }
 
+    /// Kline intervals served by the venue, ascending
+    pub fn kline_timeframes(&self) -> &'static [Timeframe] {
+        const H6: Timeframe = Timeframe::Custom(6 * 3_600);
+        const H8: Timeframe = Timeframe::Custom(8 * 3_600);
+        const D2: Timeframe = Timeframe::Custom(2 * 86_400);
+        const D3: Timeframe = Timeframe::Custom(3 * 86_400);
+
+        match self {
+            Exchange::BinanceLinear | Exchange::BinanceInverse | Exchange::BinanceSpot => &[
+                Timeframe::M1,
+                Timeframe::M3,
+                Timeframe::M5,
+                Timeframe::M15,
+                Timeframe::M30,
+                Timeframe::H1,
+                Timeframe::H2,
+                Timeframe::H4,
+                H6,
+                H8,
+                Timeframe::H12,
+                Timeframe::D1,
+                D3,
+                Timeframe::W1,
+                Timeframe::MN1,
+            ],
+            Exchange::BybitLinear | Exchange::BybitInverse | Exchange::BybitSpot => &[
+                Timeframe::M1,
+                Timeframe::M3,
+                Timeframe::M5,
+                Timeframe::M15,
+                Timeframe::M30,
+                Timeframe::H1,
+                Timeframe::H2,
+                Timeframe::H4,
+                H6,
+                Timeframe::H12,
+                Timeframe::D1,
+                Timeframe::W1,
+                Timeframe::MN1,
+            ],
+            Exchange::HyperliquidLinear | Exchange::HyperliquidSpot => &[
+                Timeframe::M1,
+                Timeframe::M3,
+                Timeframe::M5,
+                Timeframe::M15,
+                Timeframe::M30,
+                Timeframe::H1,
+                Timeframe::H2,
+                Timeframe::H4,
+                H8,
+                Timeframe::H12,
+                Timeframe::D1,
+                D3,
+                Timeframe::W1,
+                Timeframe::MN1,
+            ],
+            Exchange::OkexLinear | Exchange::OkexInverse | Exchange::OkexSpot => &[
+                Timeframe::M1,
+                Timeframe::M3,
+                Timeframe::M5,
+                Timeframe::M15,
+                Timeframe::M30,
+                Timeframe::H1,
+                Timeframe::H2,
+                Timeframe::H4,
+                H6,
+                Timeframe::H12,
+                Timeframe::D1,
+                D2,
+                D3,
+                Timeframe::W1,
+                Timeframe::MN1,
+            ],
+            // imported datasets are aggregated to any timeframe by the adapter itself
+            Exchange::Local => &Timeframe::KLINE,
+        }
+    }
+
+    /// Native timeframe to fetch and stream for `timeframe`, either itself or the largest
+    /// served interval its buckets are made of
+    pub fn kline_source(&self, timeframe: Timeframe) -> Option<Timeframe> {
+        if *self == Exchange::Local || self.kline_timeframes().contains(&timeframe) {
+            return Some(timeframe);
+        }
+
+        self.kline_timeframes()
+            .iter()
+            .rev()
+            .find(|base| timeframe.resamples_from(**base))
+            .copied()
+    }
+
     pub fn is_perps(&self) -> bool {
         matches!(
             self,
@@ -655,6 +791,7 @@ pub async fn fetch_ticker_info(
         Exchange::OkexLinear | Exchange::OkexInverse | Exchange::OkexSpot => {
             okex::fetch_ticksize(market_type).await
         }
+        Exchange::Local => local::fetch_ticksize().await,
     }
 }
 
@@ -676,13 +813,66 @@ pub async fn fetch_ticker_prices(
         Exchange::OkexLinear | Exchange::OkexInverse | Exchange::OkexSpot => {
             okex::fetch_ticker_prices(market_type).await
         }
+        Exchange::Local => local::fetch_ticker_prices().await,
     }
 }
 
+/// Klines of any timeframe, resampled from a native one when the venue doesn't serve it
 pub async fn fetch_klines(
     ticker_info: TickerInfo,
     timeframe: Timeframe,
     range: Option<(u64, u64)>,
+) -> Result<Vec<Kline>, AdapterError> {
+    let exchange = ticker_info.exchange();
+    let source = exchange.kline_source(timeframe).ok_or_else(|| {
+        AdapterError::InvalidRequest(format!("{timeframe} klines can't be built on {exchange}"))
+    })?;
+
+    if source == timeframe {
+        return fetch_native_klines(ticker_info, timeframe, range).await;
+    }
+
+    let range = range.map(|(start, end)| (timeframe.bucket_start(start), end));
+    let klines = fetch_native_klines(ticker_info, source, range).await?;
+
+    Ok(resample_klines(&klines, source, timeframe))
+}
+
+/// Merges klines of `source` into buckets of `timeframe`. A bucket cut off at the start is
+/// dropped, so is the source kline still open at the end, which the live stream takes over
+fn resample_klines(klines: &[Kline], source: Timeframe, timeframe: Timeframe) -> Vec<Kline> {
+    let now = chrono::Utc::now().timestamp_millis() as u64;
+
+    let first_full = klines.first().map_or(0, |k| {
+        let start = timeframe.bucket_start(k.time);
+        if start == k.time {
+            start
+        } else {
+            timeframe.next_bucket_start(start)
+        }
+    });
+
+    let mut resampled: Vec<Kline> = Vec::new();
+
+    for kline in klines
+        .iter()
+        .filter(|k| k.time >= first_full && source.next_bucket_start(k.time) <= now)
+    {
+        let time = timeframe.bucket_start(kline.time);
+
+        match resampled.last_mut() {
+            Some(bucket) if bucket.time == time => bucket.merge(kline),
+            _ => resampled.push(Kline { time, ..*kline }),
+        }
+    }
+
+    resampled
+}
+
+async fn fetch_native_klines(
+    ticker_info: TickerInfo,
+    timeframe: Timeframe,
+    range: Option<(u64, u64)>,
 ) -> Result<Vec<Kline>, AdapterError> {
     match ticker_info.ticker.exchange {
         Exchange::BinanceLinear | Exchange::BinanceInverse | Exchange::BinanceSpot => {
@@ -697,6 +887,7 @@ pub async fn fetch_klines(
         Exchange::OkexLinear | Exchange::OkexInverse | Exchange::OkexSpot => {
             okex::fetch_klines(ticker_info, timeframe, range).await
         }
+        Exchange::Local => local::fetch_klines(ticker_info, timeframe, range).await,
     }
 }
 
@@ -718,3 +909,72 @@ pub async fn fetch_open_interest(
         _ => Err(AdapterError::InvalidRequest("Invalid exchange".to_string())),
     }
 }
+
+/// Open interest of `ticker` as of now, for recording venues without history or
+/// beyond how far theirs goes back
+pub async fn fetch_current_oi(ticker: Ticker) -> Result<OpenInterest, AdapterError> {
+    match ticker.exchange {
+        Exchange::BinanceLinear | Exchange::BinanceInverse => {
+            binance::fetch_current_oi(ticker).await
+        }
+        Exchange::BybitLinear | Exchange::BybitInverse => bybit::fetch_current_oi(ticker).await,
+        Exchange::HyperliquidLinear => hyperliquid::fetch_current_oi(ticker).await,
+        Exchange::OkexLinear | Exchange::OkexInverse => okex::fetch_current_oi(ticker).await,
+        _ => Err(AdapterError::InvalidRequest("Invalid exchange".to_string())),
+    }
+}
+
+/// Whether the venue serves open interest history, otherwise only what was recorded is shown
+pub fn has_oi_history(exchange: Exchange) -> bool {
+    exchange.is_perps() && exchange != Exchange::HyperliquidLinear
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::util::MinTicksize;
+
+    #[test]
+    fn resampled_klines_aggregate_whole_buckets() {
+        const MINUTE: u64 = 60_000;
+        // 2024-01-01 00:00 UTC
+        const START: u64 = 1_704_067_200_000;
+
+        let kline = |time, open, high, low, close, buy, sell| {
+            let volume = (Qty::from_f32(buy), Qty::from_f32(sell));
+            Kline::new(
+                time,
+                open,
+                high,
+                low,
+                close,
+                volume,
+                MinTicksize::from(0.01),
+            )
+        };
+
+        let source = [
+            // the 00:00 bucket is cut off at the start
+            kline(START + 45 * MINUTE, 9.0, 9.0, 9.0, 9.0, 5.0, 5.0),
+            kline(START + 60 * MINUTE, 10.0, 12.0, 9.5, 11.0, 1.0, 2.0),
+            kline(START + 75 * MINUTE, 11.0, 11.5, 8.0, 9.0, 3.0, 0.0),
+            kline(START + 90 * MINUTE, 9.0, 13.0, 9.0, 12.5, 0.5, 0.5),
+            kline(START + 105 * MINUTE, 12.5, 12.5, 12.0, 12.0, 2.0, 1.0),
+            kline(START + 120 * MINUTE, 12.0, 12.25, 11.75, 12.25, 1.0, 1.0),
+        ];
+
+        let resampled = resample_klines(&source, Timeframe::M15, Timeframe::H1);
+        assert_eq!(resampled.len(), 2);
+
+        let hour = &resampled[0];
+        assert_eq!(hour.time, START + 60 * MINUTE);
+        assert_eq!(hour.open, Price::from_f32(10.0));
+        assert_eq!(hour.high, Price::from_f32(13.0));
+        assert_eq!(hour.low, Price::from_f32(8.0));
+        assert_eq!(hour.close, Price::from_f32(12.0));
+        assert_eq!(hour.volume, (Qty::from_f32(6.5), Qty::from_f32(3.5)));
+
+        assert_eq!(resampled[1].time, START + 120 * MINUTE);
+        assert_eq!(resampled[1].close, Price::from_f32(12.25));
+    }
+}
@@ -1,14 +1,15 @@
 use super::{
     super::{
-        Exchange, Kline, MarketKind, OpenInterest, Price, PushFrequency, SizeUnit, StreamKind,
-        Ticker, TickerInfo, TickerStats, Timeframe, Trade,
+        Exchange, Kline, MarketKind, OpenInterest, Price, PushFrequency, StreamKind, Ticker,
+        TickerInfo, TickerStats, Timeframe, Trade,
         adapter::StreamTicksize,
         connect::{State, connect_ws},
         de_string_to_f32,
         depth::{DeOrder, DepthPayload, DepthUpdate, LocalDepthCache},
         is_symbol_supported,
         limiter::{self, RateLimiter},
-        str_f32_parse, volume_size_unit,
+        str_f32_parse,
+        util::{MinTicksize, Qty},
     },
     AdapterError, Event,
 };
@@ -25,6 +26,10 @@ use tokio::sync::Mutex;
 
 use std::{collections::HashMap, io::BufReader, path::PathBuf, sync::LazyLock, time::Duration};
 
+pub mod archive;
+
+use archive::{Archive, ArchivePeriod, DownloadProgress};
+
 const SPOT_DOMAIN: &str = "https://api.binance.com";
 const LINEAR_PERP_DOMAIN: &str = "https://fapi.binance.com";
 const INVERSE_PERP_DOMAIN: &str = "https://dapi.binance.com";
@@ -136,10 +141,10 @@ struct SonicKline {
     low: f32,
     #[serde(rename = "c", deserialize_with = "de_string_to_f32")]
     close: f32,
-    #[serde(rename = "v", deserialize_with = "de_string_to_f32")]
-    volume: f32,
-    #[serde(rename = "V", deserialize_with = "de_string_to_f32")]
-    taker_buy_base_asset_volume: f32,
+    #[serde(rename = "v")]
+    volume: Qty,
+    #[serde(rename = "V")]
+    taker_buy_base_asset_volume: Qty,
     #[serde(rename = "i")]
     interval: String,
 }
@@ -158,8 +163,8 @@ struct SonicTrade {
     time: u64,
     #[serde(rename = "p", deserialize_with = "de_string_to_f32")]
     price: f32,
-    #[serde(rename = "q", deserialize_with = "de_string_to_f32")]
-    qty: f32,
+    #[serde(rename = "q")]
+    qty: Qty,
     #[serde(rename = "m")]
     is_sell: bool,
 }
@@ -347,7 +352,6 @@ pub fn connect_market_stream(
         let mut prev_id: u64 = 0;
 
         let contract_size = get_contract_size(&ticker, market);
-        let size_in_quote_ccy = volume_size_unit() == SizeUnit::Quote;
 
         loop {
             match &mut state {
@@ -413,14 +417,7 @@ pub fn connect_market_stream(
                                         StreamData::Trade(de_trade) => {
                                             let price = Price::from_f32(de_trade.price)
                                                 .round_to_min_tick(ticker_info.min_ticksize);
-                                            let qty = contract_size.map_or(
-                                                if size_in_quote_ccy {
-                                                    (de_trade.qty * de_trade.price).round()
-                                                } else {
-                                                    de_trade.qty
-                                                },
-                                                |size| de_trade.qty * size,
-                                            );
+                                            let qty = calc_qty(de_trade.qty, contract_size);
 
                                             let trade = Trade {
                                                 time: de_trade.time,
@@ -614,8 +611,6 @@ pub fn connect_kline_stream(
             .map(|(ticker_info, _)| (ticker_info.ticker, *ticker_info))
             .collect::<HashMap<Ticker, TickerInfo>>();
 
-        let size_in_quote_ccy = volume_size_unit() == SizeUnit::Quote;
-
         loop {
             match &mut state {
                 State::Disconnected => {
@@ -659,16 +654,8 @@ pub fn connect_kline_stream(
                                     let buy_volume = de_kline.taker_buy_base_asset_volume;
                                     let sell_volume = de_kline.volume - buy_volume;
 
-                                    if let Some(c_size) = get_contract_size(&ticker, market) {
-                                        (buy_volume * c_size, sell_volume * c_size)
-                                    } else if size_in_quote_ccy {
-                                        (
-                                            (buy_volume * de_kline.close).round(),
-                                            (sell_volume * de_kline.close).round(),
-                                        )
-                                    } else {
-                                        (buy_volume, sell_volume)
-                                    }
+                                    let c_size = get_contract_size(&ticker, market);
+                                    (calc_qty(buy_volume, c_size), calc_qty(sell_volume, c_size))
                                 };
 
                                 if let Some((_, tf)) = streams
@@ -749,8 +736,6 @@ fn new_depth_cache(depth: &SonicDepth, contract_size: Option<f32>) -> DepthPaylo
         SonicDepth::Perp(de) => (de.time, de.final_id, &de.bids, &de.asks),
     };
 
-    let size_in_quote_ccy = volume_size_unit() == SizeUnit::Quote;
-
     DepthPayload {
         last_update_id: final_id,
         time,
@@ -758,14 +743,14 @@ fn new_depth_cache(depth: &SonicDepth, contract_size: Option<f32>) -> DepthPaylo
             .iter()
             .map(|x| DeOrder {
                 price: x.price,
-                qty: calc_qty(x.qty, x.price, contract_size, size_in_quote_ccy),
+                qty: calc_qty(x.qty, contract_size),
             })
             .collect(),
         asks: asks
             .iter()
             .map(|x| DeOrder {
                 price: x.price,
-                qty: calc_qty(x.qty, x.price, contract_size, size_in_quote_ccy),
+                qty: calc_qty(x.qty, contract_size),
             })
             .collect(),
     }
@@ -815,8 +800,6 @@ async fn fetch_depth(
     let limiter = limiter_from_market_type(market_type);
     let text = crate::limiter::http_request_with_limiter(&url, limiter, weight, None, None).await?;
 
-    let size_in_quote_ccy = volume_size_unit() == SizeUnit::Quote;
-
     match market_type {
         MarketKind::Spot => {
             let fetched_depth: FetchedSpotDepth =
@@ -830,7 +813,7 @@ async fn fetch_depth(
                     .iter()
                     .map(|x| DeOrder {
                         price: x.price,
-                        qty: calc_qty(x.qty, x.price, contract_size, size_in_quote_ccy),
+                        qty: calc_qty(x.qty, contract_size),
                     })
                     .collect(),
                 asks: fetched_depth
@@ -838,7 +821,7 @@ async fn fetch_depth(
                     .iter()
                     .map(|x| DeOrder {
                         price: x.price,
-                        qty: calc_qty(x.qty, x.price, contract_size, size_in_quote_ccy),
+                        qty: calc_qty(x.qty, contract_size),
                     })
                     .collect(),
             };
@@ -857,7 +840,7 @@ async fn fetch_depth(
                     .iter()
                     .map(|x| DeOrder {
                         price: x.price,
-                        qty: calc_qty(x.qty, x.price, contract_size, size_in_quote_ccy),
+                        qty: calc_qty(x.qty, contract_size),
                     })
                     .collect(),
                 asks: fetched_depth
@@ -865,7 +848,7 @@ async fn fetch_depth(
                     .iter()
                     .map(|x| DeOrder {
                         price: x.price,
-                        qty: calc_qty(x.qty, x.price, contract_size, size_in_quote_ccy),
+                        qty: calc_qty(x.qty, contract_size),
                     })
                     .collect(),
             };
@@ -875,17 +858,8 @@ async fn fetch_depth(
     }
 }
 
-fn calc_qty(qty: f32, price: f32, contract_size: Option<f32>, size_in_quote_ccy: bool) -> f32 {
-    match contract_size {
-        Some(size) => qty * size,
-        None => {
-            if size_in_quote_ccy {
-                (qty * price).round()
-            } else {
-                qty
-            }
-        }
-    }
+fn calc_qty(qty: Qty, contract_size: Option<f32>) -> Qty {
+    contract_size.map_or(qty, |size| qty.scale_by(size))
 }
 
 #[allow(dead_code)]
@@ -896,11 +870,11 @@ struct FetchedKlines(
     #[serde(deserialize_with = "de_string_to_f32")] f32,
     #[serde(deserialize_with = "de_string_to_f32")] f32,
     #[serde(deserialize_with = "de_string_to_f32")] f32,
-    #[serde(deserialize_with = "de_string_to_f32")] f32,
+    Qty,
     u64,
     String,
     u32,
-    #[serde(deserialize_with = "de_string_to_f32")] f32,
+    Qty,
     String,
     String,
 );
@@ -978,8 +952,6 @@ pub async fn fetch_klines(
     let fetched_klines: Vec<FetchedKlines> =
         limiter::http_parse_with_limiter(&url, limiter, weight, None, None).await?;
 
-    let size_in_quote_ccy = volume_size_unit() == SizeUnit::Quote;
-
     let klines: Vec<_> = fetched_klines
         .into_iter()
         .map(|k| Kline {
@@ -989,19 +961,7 @@ pub async fn fetch_klines(
             low: Price::from_f32(k.3).round_to_min_tick(ticker_info.min_ticksize),
             close: Price::from_f32(k.4).round_to_min_tick(ticker_info.min_ticksize),
             volume: match market_type {
-                MarketKind::Spot | MarketKind::LinearPerps => {
-                    let sell_volume = if size_in_quote_ccy {
-                        ((k.5 - k.9) * k.4).round()
-                    } else {
-                        k.5 - k.9
-                    };
-                    let buy_volume = if size_in_quote_ccy {
-                        (k.9 * k.4).round()
-                    } else {
-                        k.9
-                    };
-                    (buy_volume, sell_volume)
-                }
+                MarketKind::Spot | MarketKind::LinearPerps => (k.9, k.5 - k.9),
                 MarketKind::InversePerps => {
                     let contract_size = if symbol_str == "BTCUSD_PERP" {
                         100.0
@@ -1010,7 +970,10 @@ pub async fn fetch_klines(
                     };
 
                     let sell_volume = k.5 - k.9;
-                    (k.9 * contract_size, sell_volume * contract_size)
+                    (
+                        k.9.scale_by(contract_size),
+                        sell_volume.scale_by(contract_size),
+                    )
                 }
             },
         })
@@ -1040,11 +1003,18 @@ pub async fn fetch_ticksize(
     let exchange_info: serde_json::Value = serde_json::from_str(&response_text)
         .map_err(|e| AdapterError::ParseError(format!("Failed to parse exchange info: {e}")))?;
 
+    ticker_infos(&exchange_info, exchange_from_market_type(market))
+}
+
+/// Listings of an `exchangeInfo` response, `None` for symbols without a price filter
+fn ticker_infos(
+    exchange_info: &serde_json::Value,
+    exchange: Exchange,
+) -> Result<HashMap<Ticker, Option<TickerInfo>>, AdapterError> {
     let symbols = exchange_info["symbols"]
         .as_array()
         .ok_or_else(|| AdapterError::ParseError("Missing symbols array".to_string()))?;
 
-    let exchange = exchange_from_market_type(market);
     let mut ticker_info_map = HashMap::new();
 
     for item in symbols {
@@ -1061,12 +1031,6 @@ pub async fn fetch_ticksize(
         {
             continue;
         }
-        if let Some(quote_asset) = item["quoteAsset"].as_str()
-            && quote_asset != "USDT"
-            && quote_asset != "USD"
-        {
-            continue;
-        }
         if let Some(status) = item["status"].as_str()
             && status != "TRADING"
             && status != "HALT"
@@ -1094,6 +1058,9 @@ pub async fn fetch_ticksize(
 
         let contract_size = item["contractSize"].as_f64().map(|v| v as f32);
 
+        let base_asset = item["baseAsset"].as_str().unwrap_or_default();
+        let quote_asset = item["quoteAsset"].as_str().unwrap_or_default();
+
         let ticker = Ticker::new(symbol_str, exchange);
 
         if let Some(price_filter) = price_filter {
@@ -1103,7 +1070,9 @@ pub async fn fetch_ticksize(
                 .parse::<f32>()
                 .map_err(|e| AdapterError::ParseError(format!("Failed to parse tickSize: {e}")))?;
 
-            let info = TickerInfo::new(ticker, min_ticksize, min_qty, contract_size);
+            let info = TickerInfo::new(ticker, min_ticksize, min_qty, contract_size)
+                .with_assets(base_asset, quote_asset)
+                .with_settlement(item["marginAsset"].as_str().unwrap_or_default());
 
             ticker_info_map.insert(ticker, Some(info));
         } else {
@@ -1302,37 +1271,106 @@ pub async fn fetch_historical_oi(
     Ok(open_interest)
 }
 
+#[derive(Deserialize, Debug, Clone)]
+struct DeCurrentOpenInterest {
+    #[serde(rename = "openInterest", deserialize_with = "de_string_to_f32")]
+    pub value: f32,
+    pub time: u64,
+}
+
+/// Open interest right now, history only goes back 30 days
+pub async fn fetch_current_oi(ticker: Ticker) -> Result<OpenInterest, AdapterError> {
+    let (ticker_str, market) = ticker.to_full_symbol_and_type();
+
+    let url = match market {
+        MarketKind::LinearPerps => {
+            format!("{LINEAR_PERP_DOMAIN}/fapi/v1/openInterest?symbol={ticker_str}")
+        }
+        MarketKind::InversePerps => {
+            format!("{INVERSE_PERP_DOMAIN}/dapi/v1/openInterest?symbol={ticker_str}")
+        }
+        MarketKind::Spot => {
+            let err_msg = format!("Unsupported market type for open interest: {market:?}");
+            return Err(AdapterError::InvalidRequest(err_msg));
+        }
+    };
+
+    let limiter = limiter_from_market_type(market);
+    let oi: DeCurrentOpenInterest =
+        limiter::http_parse_with_limiter(&url, limiter, 1, None, None).await?;
+
+    let contract_size = get_contract_size(&ticker, market);
+
+    Ok(OpenInterest {
+        time: oi.time,
+        value: contract_size.map_or(oi.value, |size| oi.value * size),
+    })
+}
+
+/// Sends the trades in `[from_time, to_time]` to `batches` and returns the time they're
+/// complete up to, archives are read as a stream so at most [`TRADE_BATCH_LEN`] are held at once
 pub async fn fetch_trades(
     ticker_info: TickerInfo,
     from_time: u64,
+    to_time: u64,
     data_path: PathBuf,
-) -> Result<Vec<Trade>, AdapterError> {
-    let today_midnight = chrono::Utc::now()
-        .date_naive()
-        .and_hms_opt(0, 0, 0)
-        .unwrap()
-        .and_utc();
+    progress: Option<mpsc::Sender<DownloadProgress>>,
+    batches: tokio::sync::mpsc::Sender<Vec<Trade>>,
+) -> Result<u64, AdapterError> {
+    let today = chrono::Utc::now().date_naive();
+    let today_midnight = today.and_hms_opt(0, 0, 0).unwrap().and_utc();
 
     if from_time as i64 >= today_midnight.timestamp_millis() {
-        return fetch_intraday_trades(ticker_info, from_time).await;
+        return send_intraday_trades(ticker_info, from_time, to_time, &batches).await;
     }
 
     let from_date = chrono::DateTime::from_timestamp_millis(from_time as i64)
         .ok_or_else(|| AdapterError::ParseError("Invalid timestamp".into()))?
         .date_naive();
-
-    match get_hist_trades(ticker_info, from_date, data_path).await {
-        Ok(trades) => Ok(trades),
+    let to_date = chrono::DateTime::from_timestamp_millis(to_time as i64)
+        .map_or(from_date, |dt| dt.date_naive());
+
+    let period = ArchivePeriod::for_range(from_date, to_date, today);
+
+    match get_hist_trades(
+        ticker_info,
+        period,
+        (from_time, to_time),
+        data_path,
+        progress,
+        batches.clone(),
+    )
+    .await
+    {
+        Ok(()) => Ok(period.end_time().min(to_time)),
         Err(e) => {
             log::warn!(
                 "Historical trades fetch failed: {}, falling back to intraday fetch",
                 e
             );
-            fetch_intraday_trades(ticker_info, from_time).await
+            send_intraday_trades(ticker_info, from_time, to_time, &batches).await
         }
     }
 }
 
+async fn send_intraday_trades(
+    ticker_info: TickerInfo,
+    from_time: u64,
+    to_time: u64,
+    batches: &tokio::sync::mpsc::Sender<Vec<Trade>>,
+) -> Result<u64, AdapterError> {
+    let mut trades = fetch_intraday_trades(ticker_info, from_time).await?;
+    trades.retain(|trade| trade.time <= to_time);
+
+    // responses are capped, an empty one means there's nothing left
+    let Some(latest_trade_time) = trades.last().map(|trade| trade.time) else {
+        return Ok(to_time);
+    };
+    // a closed channel means the fetch was aborted
+    let _ = batches.send(trades).await;
+    Ok(latest_trade_time)
+}
+
 pub async fn fetch_intraday_trades(
     ticker_info: TickerInfo,
     from: u64,
@@ -1356,19 +1394,13 @@ pub async fn fetch_intraday_trades(
         let de_trades: Vec<SonicTrade> = sonic_rs::from_str(&text)
             .map_err(|e| AdapterError::ParseError(format!("Failed to parse trades: {e}")))?;
 
-        let size_in_quote_ccy = volume_size_unit() == SizeUnit::Quote;
-
         de_trades
             .into_iter()
             .map(|de_trade| Trade {
                 time: de_trade.time,
                 is_sell: de_trade.is_sell,
                 price: Price::from_f32(de_trade.price).round_to_min_tick(ticker_info.min_ticksize),
-                qty: if size_in_quote_ccy {
-                    (de_trade.qty * de_trade.price).round()
-                } else {
-                    de_trade.qty
-                },
+                qty: de_trade.qty,
             })
             .collect()
     };
@@ -1376,121 +1408,178 @@ pub async fn fetch_intraday_trades(
     Ok(trades)
 }
 
+/// Trades sent at once while reading an archive
+const TRADE_BATCH_LEN: usize = 100_000;
+
 pub async fn get_hist_trades(
     ticker_info: TickerInfo,
-    date: chrono::NaiveDate,
+    period: ArchivePeriod,
+    (from_time, to_time): (u64, u64),
     base_path: PathBuf,
-) -> Result<Vec<Trade>, AdapterError> {
+    mut progress: Option<mpsc::Sender<DownloadProgress>>,
+    batches: tokio::sync::mpsc::Sender<Vec<Trade>>,
+) -> Result<(), AdapterError> {
     let ticker = ticker_info.ticker;
     let (symbol, market_type) = ticker.to_full_symbol_and_type();
 
-    let market_subpath = match market_type {
-        MarketKind::Spot => format!("data/spot/daily/aggTrades/{symbol}"),
-        MarketKind::LinearPerps => {
-            format!("data/futures/um/daily/aggTrades/{symbol}")
-        }
-        MarketKind::InversePerps => {
-            format!("data/futures/cm/daily/aggTrades/{symbol}")
-        }
-    };
-
-    let zip_file_name = format!(
-        "{}-aggTrades-{}.zip",
-        symbol.to_uppercase(),
-        date.format("%Y-%m-%d"),
-    );
-
-    let base_path = base_path.join(&market_subpath);
-
-    std::fs::create_dir_all(&base_path)
-        .map_err(|e| AdapterError::ParseError(format!("Failed to create directories: {e}")))?;
-
-    let zip_path = format!("{market_subpath}/{zip_file_name}",);
-    let base_zip_path = base_path.join(&zip_file_name);
-
-    if std::fs::metadata(&base_zip_path).is_ok() {
-        log::info!("Using cached {}", zip_path);
-    } else {
-        let url = format!("https://data.binance.vision/{zip_path}");
+    let archive = Archive::agg_trades(&symbol, market_type, period);
+    let base_zip_path = archive.ensure_cached(&base_path, &mut progress).await?;
+    drop(progress);
+
+    let min_ticksize = ticker_info.min_ticksize;
+    tokio::task::spawn_blocking(move || {
+        read_agg_trades(
+            &base_zip_path,
+            min_ticksize,
+            (from_time, to_time),
+            TRADE_BATCH_LEN,
+            &batches,
+        )
+    })
+    .await
+    .map_err(|e| AdapterError::ParseError(format!("Archive reader stopped: {e}")))?
+}
 
-        log::info!("Downloading from {}", url);
+/// Reads an `aggTrades` archive in time order and sends the trades within
+/// `[from_time, to_time]` in batches of `batch_len`
+fn read_agg_trades(
+    path: &std::path::Path,
+    min_ticksize: MinTicksize,
+    (from_time, to_time): (u64, u64),
+    batch_len: usize,
+    batches: &tokio::sync::mpsc::Sender<Vec<Trade>>,
+) -> Result<(), AdapterError> {
+    let file = std::fs::File::open(path)
+        .map_err(|e| AdapterError::ParseError(format!("Failed to open compressed file: {e}")))?;
+    let mut archive = zip::ZipArchive::new(file)
+        .map_err(|e| AdapterError::ParseError(format!("Failed to unzip file: {e}")))?;
+
+    let mut batch = Vec::with_capacity(batch_len);
+
+    'files: for i in 0..archive.len() {
+        let csv_file = archive
+            .by_index(i)
+            .map_err(|e| AdapterError::ParseError(format!("Failed to read csv: {e}")))?;
+
+        let mut csv_reader = ReaderBuilder::new()
+            .has_headers(false)
+            .from_reader(BufReader::new(csv_file));
+
+        for record in csv_reader.records() {
+            let Some(trade) = record
+                .ok()
+                .and_then(|record| parse_agg_trade(&record, min_ticksize))
+            else {
+                continue;
+            };
 
-        let resp = reqwest::get(&url).await.map_err(AdapterError::FetchError)?;
+            if trade.time < from_time {
+                continue;
+            }
+            if trade.time > to_time {
+                break 'files;
+            }
 
-        if !resp.status().is_success() {
-            return Err(AdapterError::InvalidRequest(format!(
-                "Failed to fetch from {}: {}",
-                url,
-                resp.status()
-            )));
+            batch.push(trade);
+            if batch.len() >= batch_len
+                && batches.blocking_send(std::mem::take(&mut batch)).is_err()
+            {
+                // the fetch was aborted
+                return Ok(());
+            }
         }
+    }
 
-        let body = resp.bytes().await.map_err(AdapterError::FetchError)?;
-
-        std::fs::write(&base_zip_path, &body).map_err(|e| {
-            AdapterError::ParseError(format!("Failed to write zip file: {e}, {base_zip_path:?}"))
-        })?;
+    if !batch.is_empty() {
+        let _ = batches.blocking_send(batch);
     }
+    Ok(())
+}
 
-    match std::fs::File::open(&base_zip_path) {
-        Ok(file) => {
-            let mut archive = zip::ZipArchive::new(file)
-                .map_err(|e| AdapterError::ParseError(format!("Failed to unzip file: {e}")))?;
-
-            let size_in_quote_ccy = volume_size_unit() == SizeUnit::Quote;
-
-            let mut trades = Vec::new();
-            for i in 0..archive.len() {
-                let csv_file = archive
-                    .by_index(i)
-                    .map_err(|e| AdapterError::ParseError(format!("Failed to read csv: {e}")))?;
-
-                let mut csv_reader = ReaderBuilder::new()
-                    .has_headers(false)
-                    .from_reader(BufReader::new(csv_file));
-
-                trades.extend(csv_reader.records().filter_map(|record| {
-                    record.ok().and_then(|record| {
-                        let time = record[5].parse::<u64>().ok()?;
-                        let is_sell = record[6].parse::<bool>().ok()?;
-                        let price_f32 = str_f32_parse(&record[1]);
-
-                        let price =
-                            Price::from_f32(price_f32).round_to_min_tick(ticker_info.min_ticksize);
-
-                        let mut qty = str_f32_parse(&record[2]);
-
-                        qty = if size_in_quote_ccy {
-                            (qty * price_f32).round()
-                        } else {
-                            qty
-                        };
-
-                        Some(Trade {
-                            time,
-                            is_sell,
-                            price,
-                            qty,
-                        })
-                    })
-                }));
-            }
+fn parse_agg_trade(record: &csv::StringRecord, min_ticksize: MinTicksize) -> Option<Trade> {
+    let time = record.get(5)?.parse::<u64>().ok()?;
+    let is_sell = record.get(6)?.parse::<bool>().ok()?;
+    let price = Price::from_f32(str_f32_parse(record.get(1)?)).round_to_min_tick(min_ticksize);
+    let qty = record.get(2)?.parse::<Qty>().ok()?;
 
-            if let Some(latest_trade) = trades.last() {
-                match fetch_intraday_trades(ticker_info, latest_trade.time).await {
-                    Ok(intraday_trades) => {
-                        trades.extend(intraday_trades);
-                    }
-                    Err(e) => {
-                        log::error!("Failed to fetch intraday trades: {}", e);
-                    }
+    Some(Trade {
+        time,
+        is_sell,
+        price,
+        qty,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{Asset, depth::Depth};
+
+    #[test]
+    fn lists_quotes_priced_in_the_millions() {
+        let exchange_info = serde_json::json!({
+            "symbols": [
+                {
+                    "symbol": "BTCNGN",
+                    "status": "TRADING",
+                    "baseAsset": "BTC",
+                    "quoteAsset": "NGN",
+                    "filters": [
+                        {
+                            "filterType": "PRICE_FILTER",
+                            "tickSize": "1.00",
+                            "maxPrice": "999996000.00"
+                        },
+                        { "filterType": "LOT_SIZE", "minQty": "0.00001000" }
+                    ]
                 }
-            }
+            ]
+        });
+
+        let infos = ticker_infos(&exchange_info, Exchange::BinanceSpot).unwrap();
+        let info = infos[&Ticker::new("BTCNGN", Exchange::BinanceSpot)].unwrap();
+        assert_eq!(info.quote, Asset::new("NGN"));
+
+        let tick = info.min_ticksize;
+        let bid: Price = "152340000".parse().unwrap();
+        let depth = Depth {
+            bids: [(bid, Qty::from_f32(0.1))].into(),
+            asks: [(bid.add_steps(10, tick.as_step()), Qty::from_f32(0.1))].into(),
+        };
+        assert_eq!(depth.mid_price().unwrap().to_string(tick), "152340005");
+    }
 
-            Ok(trades)
+    #[test]
+    fn streams_archive_trades_within_the_range_in_batches() {
+        use std::io::Write;
+
+        let path =
+            std::env::temp_dir().join(format!("flowsurface-aggTrades-{}.zip", std::process::id()));
+        let mut zip = zip::ZipWriter::new(std::fs::File::create(&path).unwrap());
+        zip.start_file(
+            "BTCUSDT-aggTrades-2024-01.csv",
+            zip::write::SimpleFileOptions::default()
+                .compression_method(zip::CompressionMethod::Stored),
+        )
+        .unwrap();
+        for (id, time) in (1_000..1_010).enumerate() {
+            writeln!(zip, "{id},42000.10,0.5,{id},{id},{time},false").unwrap();
+        }
+        zip.finish().unwrap();
+
+        let (batches, mut received) = tokio::sync::mpsc::channel(8);
+        let tick = MinTicksize::from(0.1);
+        read_agg_trades(&path, tick, (1_002, 1_006), 2, &batches).unwrap();
+        std::fs::remove_file(&path).unwrap();
+        drop(batches);
+
+        let mut sizes = vec![];
+        let mut times = vec![];
+        while let Ok(batch) = received.try_recv() {
+            sizes.push(batch.len());
+            times.extend(batch.iter().map(|trade| trade.time));
         }
-        Err(e) => Err(AdapterError::ParseError(format!(
-            "Failed to open compressed file: {e}"
-        ))),
+        assert_eq!(sizes, vec![2, 2, 1]);
+        assert_eq!(times, vec![1_002, 1_003, 1_004, 1_005, 1_006]);
     }
 }
@@ -0,0 +1,275 @@
+//! Downloads, verifies and caches `aggTrades` archives from data.binance.vision
+
+use super::super::{AdapterError, MarketKind};
+
+use chrono::{Datelike, NaiveDate};
+use iced_futures::futures::channel::mpsc;
+use sha2::{Digest, Sha256};
+
+use std::io::Write;
+use std::path::{Path, PathBuf};
+
+const ARCHIVE_DOMAIN: &str = "https://data.binance.vision";
+
+/// Monthly archives are only used for ranges spanning at least this many days
+const MONTHLY_MIN_SPAN_DAYS: i64 = 7;
+
+/// Minimum amount of bytes between two progress reports
+const PROGRESS_STEP_BYTES: u64 = 256 * 1024;
+
+pub const PARTIAL_EXT: &str = "part";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ArchivePeriod {
+    Daily(NaiveDate),
+    Monthly { year: i32, month: u32 },
+}
+
+impl ArchivePeriod {
+    /// Picks a monthly archive when the range covers a good part of an already completed month,
+    /// otherwise the daily archive of `from`
+    pub fn for_range(from: NaiveDate, to: NaiveDate, today: NaiveDate) -> Self {
+        let monthly = ArchivePeriod::Monthly {
+            year: from.year(),
+            month: from.month(),
+        };
+
+        let month_completed = monthly.last_day() < today.with_day(1).unwrap_or(today);
+        let span_days = to
+            .min(monthly.last_day())
+            .signed_duration_since(from)
+            .num_days();
+
+        if month_completed && span_days >= MONTHLY_MIN_SPAN_DAYS {
+            monthly
+        } else {
+            ArchivePeriod::Daily(from)
+        }
+    }
+
+    pub fn first_day(&self) -> NaiveDate {
+        match *self {
+            ArchivePeriod::Daily(date) => date,
+            ArchivePeriod::Monthly { year, month } => {
+                NaiveDate::from_ymd_opt(year, month, 1).expect("valid archive month")
+            }
+        }
+    }
+
+    pub fn last_day(&self) -> NaiveDate {
+        match *self {
+            ArchivePeriod::Daily(date) => date,
+            ArchivePeriod::Monthly { year, month } => {
+                let (next_year, next_month) = if month == 12 {
+                    (year + 1, 1)
+                } else {
+                    (year, month + 1)
+                };
+                NaiveDate::from_ymd_opt(next_year, next_month, 1)
+                    .and_then(|d| d.pred_opt())
+                    .expect("valid archive month")
+            }
+        }
+    }
+
+    /// Last millisecond the archive covers, in UTC
+    pub fn end_time(&self) -> u64 {
+        self.last_day()
+            .and_hms_milli_opt(23, 59, 59, 999)
+            .map_or(0, |dt| dt.and_utc().timestamp_millis() as u64)
+    }
+
+    fn dir(&self) -> &'static str {
+        match self {
+            ArchivePeriod::Daily(_) => "daily",
+            ArchivePeriod::Monthly { .. } => "monthly",
+        }
+    }
+
+    fn suffix(&self) -> String {
+        match self {
+            ArchivePeriod::Daily(date) => date.format("%Y-%m-%d").to_string(),
+            ArchivePeriod::Monthly { year, month } => format!("{year:04}-{month:02}"),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DownloadProgress {
+    pub file_name: String,
+    pub downloaded: u64,
+    pub total: Option<u64>,
+}
+
+/// Location of a single archive, both on data.binance.vision and in the local cache
+#[derive(Debug, Clone)]
+pub struct Archive {
+    subpath: String,
+    file_name: String,
+}
+
+impl Archive {
+    pub fn agg_trades(symbol: &str, market: MarketKind, period: ArchivePeriod) -> Self {
+        let market_dir = match market {
+            MarketKind::Spot => "spot",
+            MarketKind::LinearPerps => "futures/um",
+            MarketKind::InversePerps => "futures/cm",
+        };
+
+        Self {
+            subpath: format!("data/{market_dir}/{}/aggTrades/{symbol}", period.dir()),
+            file_name: format!(
+                "{}-aggTrades-{}.zip",
+                symbol.to_uppercase(),
+                period.suffix()
+            ),
+        }
+    }
+
+    pub fn file_name(&self) -> &str {
+        &self.file_name
+    }
+
+    pub fn local_path(&self, base_path: &Path) -> PathBuf {
+        base_path.join(&self.subpath).join(&self.file_name)
+    }
+
+    fn url(&self) -> String {
+        format!("{ARCHIVE_DOMAIN}/{}/{}", self.subpath, self.file_name)
+    }
+
+    /// Returns the cached archive, downloading and verifying it first if needed
+    ///
+    /// The archive is streamed into a `.part` file which is only moved into place
+    /// once its SHA-256 matches the published `.CHECKSUM`, so an aborted or corrupted
+    /// download never ends up in the cache.
+    pub async fn ensure_cached(
+        &self,
+        base_path: &Path,
+        progress: &mut Option<mpsc::Sender<DownloadProgress>>,
+    ) -> Result<PathBuf, AdapterError> {
+        let path = self.local_path(base_path);
+
+        if path.exists() {
+            log::info!("Using cached {}", self.file_name);
+            return Ok(path);
+        }
+
+        let dir = base_path.join(&self.subpath);
+        std::fs::create_dir_all(&dir)
+            .map_err(|e| AdapterError::ParseError(format!("Failed to create directories: {e}")))?;
+
+        let partial_path = path.with_extension(format!("zip.{PARTIAL_EXT}"));
+        if partial_path.exists() {
+            let _ = std::fs::remove_file(&partial_path);
+        }
+
+        // without a checksum the download couldn't be told apart from a corrupted one
+        let expected_checksum = self.fetch_checksum().await?;
+
+        let url = self.url();
+        log::info!("Downloading from {}", url);
+
+        let mut resp = reqwest::get(&url).await.map_err(AdapterError::FetchError)?;
+
+        if !resp.status().is_success() {
+            return Err(AdapterError::InvalidRequest(format!(
+                "Failed to fetch from {}: {}",
+                url,
+                resp.status()
+            )));
+        }
+
+        let total = resp.content_length();
+        let mut file = std::fs::File::create(&partial_path).map_err(|e| {
+            AdapterError::ParseError(format!("Failed to create {partial_path:?}: {e}"))
+        })?;
+
+        let mut hasher = Sha256::new();
+        let mut downloaded = 0u64;
+        let mut last_report = 0u64;
+
+        while let Some(chunk) = resp.chunk().await.map_err(AdapterError::FetchError)? {
+            hasher.update(&chunk);
+            file.write_all(&chunk).map_err(|e| {
+                AdapterError::ParseError(format!("Failed to write {partial_path:?}: {e}"))
+            })?;
+
+            downloaded += chunk.len() as u64;
+            if downloaded - last_report >= PROGRESS_STEP_BYTES {
+                last_report = downloaded;
+                self.report(progress, downloaded, total);
+            }
+        }
+        self.report(progress, downloaded, total);
+
+        drop(file);
+
+        let actual = to_hex(&hasher.finalize());
+        if !actual.eq_ignore_ascii_case(&expected_checksum) {
+            let _ = std::fs::remove_file(&partial_path);
+            return Err(AdapterError::ParseError(format!(
+                "Checksum mismatch for {}: expected {expected_checksum}, got {actual}",
+                self.file_name
+            )));
+        }
+
+        std::fs::rename(&partial_path, &path).map_err(|e| {
+            AdapterError::ParseError(format!("Failed to move archive into cache: {e}, {path:?}"))
+        })?;
+
+        Ok(path)
+    }
+
+    async fn fetch_checksum(&self) -> Result<String, AdapterError> {
+        let url = format!("{}.CHECKSUM", self.url());
+
+        let resp = reqwest::get(&url).await.map_err(AdapterError::FetchError)?;
+        if !resp.status().is_success() {
+            return Err(AdapterError::InvalidRequest(format!(
+                "Failed to fetch checksum {}: {}",
+                url,
+                resp.status()
+            )));
+        }
+
+        // formatted as `<sha256>  <file name>`
+        let text = resp.text().await.map_err(AdapterError::FetchError)?;
+        parse_checksum(&text).ok_or_else(|| {
+            AdapterError::ParseError(format!("Unreadable checksum for {}", self.file_name))
+        })
+    }
+
+    fn report(
+        &self,
+        progress: &mut Option<mpsc::Sender<DownloadProgress>>,
+        downloaded: u64,
+        total: Option<u64>,
+    ) {
+        if let Some(sender) = progress {
+            // progress is best-effort, dropping updates when the receiver lags behind is fine
+            let _ = sender.try_send(DownloadProgress {
+                file_name: self.file_name.clone(),
+                downloaded,
+                total,
+            });
+        }
+    }
+}
+
+/// The hash out of a `<sha256>  <file name>` line, if it looks like one
+fn parse_checksum(text: &str) -> Option<String> {
+    text.split_whitespace()
+        .next()
+        .filter(|hash| hash.len() == 64 && hash.bytes().all(|b| b.is_ascii_hexdigit()))
+        .map(str::to_owned)
+}
+
+fn to_hex(bytes: &[u8]) -> String {
+    use std::fmt::Write;
+
+    bytes.iter().fold(String::with_capacity(64), |mut out, b| {
+        let _ = write!(out, "{b:02x}");
+        out
+    })
+}
@@ -1,14 +1,14 @@
 use super::{
     super::{
-        Exchange, Kline, MarketKind, OpenInterest, Price, PushFrequency, SizeUnit, StreamKind,
-        Ticker, TickerInfo, TickerStats, Timeframe, Trade,
+        Exchange, Kline, MarketKind, OpenInterest, Price, PushFrequency, StreamKind, Ticker,
+        TickerInfo, TickerStats, Timeframe, Trade,
         adapter::StreamTicksize,
         connect::{State, connect_ws},
         de_string_to_f32, de_string_to_u64,
         depth::{DeOrder, DepthPayload, DepthUpdate, LocalDepthCache},
         is_symbol_supported,
         limiter::{self, http_request_with_limiter},
-        volume_size_unit,
+        util::Qty,
     },
     AdapterError, Event,
 };
@@ -86,8 +86,8 @@ struct SonicTrade {
     pub time: u64,
     #[serde(rename = "p", deserialize_with = "de_string_to_f32")]
     pub price: f32,
-    #[serde(rename = "v", deserialize_with = "de_string_to_f32")]
-    pub qty: f32,
+    #[serde(rename = "v")]
+    pub qty: Qty,
     #[serde(rename = "S")]
     pub is_sell: String,
 }
@@ -104,8 +104,8 @@ pub struct SonicKline {
     pub low: f32,
     #[serde(rename = "close", deserialize_with = "de_string_to_f32")]
     pub close: f32,
-    #[serde(rename = "volume", deserialize_with = "de_string_to_f32")]
-    pub volume: f32,
+    #[serde(rename = "volume")]
+    pub volume: Qty,
     #[serde(rename = "interval")]
     pub interval: String,
 }
@@ -316,9 +316,6 @@ pub fn connect_market_stream(
         let mut trades_buffer: Vec<Trade> = Vec::new();
         let mut orderbook = LocalDepthCache::default();
 
-        let size_in_quote_ccy =
-            volume_size_unit() == SizeUnit::Quote && market_type != MarketKind::InversePerps;
-
         loop {
             match &mut state {
                 State::Disconnected => {
@@ -357,17 +354,11 @@ pub fn connect_market_stream(
                                         for de_trade in &de_trade_vec {
                                             let price = Price::from_f32(de_trade.price)
                                                 .round_to_min_tick(ticker_info.min_ticksize);
-                                            let qty = if size_in_quote_ccy {
-                                                (de_trade.qty * de_trade.price).round()
-                                            } else {
-                                                de_trade.qty
-                                            };
-
                                             let trade = Trade {
                                                 time: de_trade.time,
                                                 is_sell: de_trade.is_sell == "Sell",
                                                 price,
-                                                qty,
+                                                qty: de_trade.qty,
                                             };
 
                                             trades_buffer.push(trade);
@@ -382,11 +373,7 @@ pub fn connect_market_stream(
                                                 .iter()
                                                 .map(|x| DeOrder {
                                                     price: x.price,
-                                                    qty: if size_in_quote_ccy {
-                                                        (x.qty * x.price).round()
-                                                    } else {
-                                                        x.qty
-                                                    },
+                                                    qty: x.qty,
                                                 })
                                                 .collect(),
                                             asks: de_depth
@@ -394,11 +381,7 @@ pub fn connect_market_stream(
                                                 .iter()
                                                 .map(|x| DeOrder {
                                                     price: x.price,
-                                                    qty: if size_in_quote_ccy {
-                                                        (x.qty * x.price).round()
-                                                    } else {
-                                                        x.qty
-                                                    },
+                                                    qty: x.qty,
                                                 })
                                                 .collect(),
                                         };
@@ -470,8 +453,6 @@ pub fn connect_kline_stream(
         let mut state = State::Disconnected;
 
         let exchange = exchange_from_market_type(market_type);
-        let size_in_quote_ccy =
-            volume_size_unit() == SizeUnit::Quote && market_type != MarketKind::InversePerps;
 
         let ticker_info_map = streams
             .iter()
@@ -485,15 +466,9 @@ pub fn connect_kline_stream(
                         .iter()
                         .map(|(ticker_info, timeframe)| {
                             let ticker = ticker_info.ticker;
-                            let timeframe_str = {
-                                if Timeframe::D1 == *timeframe {
-                                    "D".to_string()
-                                } else {
-                                    timeframe.to_minutes().to_string()
-                                }
-                            };
                             format!(
-                                "kline.{timeframe_str}.{}",
+                                "kline.{}.{}",
+                                timeframe_to_interval(*timeframe),
                                 ticker.to_full_symbol_and_type().0
                             )
                         })
@@ -512,12 +487,6 @@ pub fn connect_kline_stream(
                                 feed_de(&msg.payload[..], None, market_type)
                             {
                                 for de_kline in &de_kline_vec {
-                                    let volume = if size_in_quote_ccy {
-                                        (de_kline.volume * de_kline.close).round()
-                                    } else {
-                                        de_kline.volume
-                                    };
-
                                     if let Some(timeframe) = string_to_timeframe(&de_kline.interval)
                                     {
                                         if let Some(info) = ticker_info_map.get(&ticker) {
@@ -529,7 +498,7 @@ pub fn connect_kline_stream(
                                                 de_kline.high,
                                                 de_kline.low,
                                                 de_kline.close,
-                                                (-1.0, volume),
+                                                (Kline::TOTAL_ONLY, de_kline.volume),
                                                 ticker_info.min_ticksize,
                                             );
 
@@ -585,20 +554,23 @@ pub fn connect_kline_stream(
 }
 
 fn string_to_timeframe(interval: &str) -> Option<Timeframe> {
-    Timeframe::KLINE
+    Exchange::BybitLinear
+        .kline_timeframes()
         .iter()
-        .find(|&tf| {
-            tf.to_minutes().to_string() == interval || {
-                if tf == &Timeframe::D1 {
-                    interval == "D"
-                } else {
-                    false
-                }
-            }
-        })
+        .find(|&tf| timeframe_to_interval(*tf) == interval)
         .copied()
 }
 
+/// Interval in minutes, or "D", "W" and "M" from a day up
+fn timeframe_to_interval(timeframe: Timeframe) -> String {
+    match timeframe {
+        Timeframe::D1 => "D".to_string(),
+        Timeframe::W1 => "W".to_string(),
+        Timeframe::MN1 => "M".to_string(),
+        _ => timeframe.to_minutes().to_string(),
+    }
+}
+
 #[derive(Debug, Clone, Copy, PartialEq, Deserialize)]
 #[serde(rename_all = "camelCase")]
 struct DeOpenInterest {
@@ -715,6 +687,39 @@ fn parse_kline_field<T: std::str::FromStr>(field: Option<&str>) -> Result<T, Ada
         })
 }
 
+/// Open interest right now, from the ticker snapshot
+pub async fn fetch_current_oi(ticker: Ticker) -> Result<OpenInterest, AdapterError> {
+    let (symbol_str, market_type) = ticker.to_full_symbol_and_type();
+
+    let market = match market_type {
+        MarketKind::LinearPerps => "linear",
+        MarketKind::InversePerps => "inverse",
+        MarketKind::Spot => {
+            return Err(AdapterError::InvalidRequest(
+                "Open interest is only available for perpetuals".to_string(),
+            ));
+        }
+    };
+
+    let url = format!(
+        "{FETCH_DOMAIN}/v5/market/tickers?category={market}&symbol={}",
+        symbol_str.to_uppercase()
+    );
+
+    let parsed_response: Value =
+        limiter::http_parse_with_limiter(&url, &BYBIT_LIMITER, 1, None, None).await?;
+
+    let time = parsed_response["time"]
+        .as_u64()
+        .ok_or_else(|| AdapterError::ParseError("Response time not found".to_string()))?;
+    let value = parsed_response["result"]["list"][0]["openInterest"]
+        .as_str()
+        .and_then(|oi| oi.parse::<f32>().ok())
+        .ok_or_else(|| AdapterError::ParseError("Open interest not found".to_string()))?;
+
+    Ok(OpenInterest { time, value })
+}
+
 pub async fn fetch_klines(
     ticker_info: TickerInfo,
     timeframe: Timeframe,
@@ -723,13 +728,7 @@ pub async fn fetch_klines(
     let ticker = ticker_info.ticker;
 
     let (symbol_str, market_type) = &ticker.to_full_symbol_and_type();
-    let timeframe_str = {
-        if Timeframe::D1 == timeframe {
-            "D".to_string()
-        } else {
-            timeframe.to_minutes().to_string()
-        }
-    };
+    let timeframe_str = timeframe_to_interval(timeframe);
 
     let market = match market_type {
         MarketKind::Spot => "spot",
@@ -754,9 +753,6 @@ pub async fn fetch_klines(
     let response: ApiResponse =
         limiter::http_parse_with_limiter(&url, &BYBIT_LIMITER, 1, None, None).await?;
 
-    let size_in_quote_ccy =
-        volume_size_unit() == SizeUnit::Quote && *market_type != MarketKind::InversePerps;
-
     let klines: Result<Vec<Kline>, AdapterError> = response
         .result
         .list
@@ -769,12 +765,7 @@ pub async fn fetch_klines(
             let low = parse_kline_field::<f32>(kline[3].as_str())?;
             let close = parse_kline_field::<f32>(kline[4].as_str())?;
 
-            let mut volume = parse_kline_field::<f32>(kline[5].as_str())?;
-            volume = if size_in_quote_ccy {
-                (volume * close).round()
-            } else {
-                volume
-            };
+            let volume = parse_kline_field::<Qty>(kline[5].as_str())?;
 
             let kline = Kline::new(
                 time,
@@ -782,7 +773,7 @@ pub async fn fetch_klines(
                 high,
                 low,
                 close,
-                (-1.0, volume),
+                (Kline::TOTAL_ONLY, volume),
                 ticker_info.min_ticksize,
             );
 
@@ -840,13 +831,6 @@ pub async fn fetch_ticksize(
             continue;
         }
 
-        if let Some(quote_asset) = item["quoteCoin"].as_str()
-            && quote_asset != "USDT"
-            && quote_asset != "USD"
-        {
-            continue;
-        }
-
         let lot_size_filter = item["lotSizeFilter"]
             .as_object()
             .ok_or_else(|| AdapterError::ParseError("Lot size filter not found".to_string()))?;
@@ -867,8 +851,13 @@ pub async fn fetch_ticksize(
             .parse::<f32>()
             .map_err(|_| AdapterError::ParseError("Failed to parse tick size".to_string()))?;
 
+        let base_asset = item["baseCoin"].as_str().unwrap_or_default();
+        let quote_asset = item["quoteCoin"].as_str().unwrap_or_default();
+
         let ticker = Ticker::new(symbol, exchange);
-        let info = TickerInfo::new(ticker, min_ticksize, min_qty, None);
+        let info = TickerInfo::new(ticker, min_ticksize, min_qty, None)
+            .with_assets(base_asset, quote_asset)
+            .with_settlement(item["settleCoin"].as_str().unwrap_or_default());
 
         ticker_info_map.insert(ticker, Some(info));
     }
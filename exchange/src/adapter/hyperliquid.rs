@@ -1,12 +1,12 @@
 use super::{
     super::{
-        Exchange, Kline, MarketKind, Price, PushFrequency, SizeUnit, StreamKind, TickMultiplier,
-        Ticker, TickerInfo, TickerStats, Timeframe, Trade,
+        Exchange, Kline, MarketKind, OpenInterest, Price, PushFrequency, StreamKind,
+        TickMultiplier, Ticker, TickerInfo, TickerStats, Timeframe, Trade,
         connect::{State, connect_ws},
         de_string_to_f32,
         depth::{DeOrder, DepthPayload, DepthUpdate, LocalDepthCache},
         limiter::{self, RateLimiter},
-        volume_size_unit,
+        util::Qty,
     },
     AdapterError, Event,
 };
@@ -30,6 +30,8 @@ const WS_DOMAIN: &str = "api.hyperliquid.xyz";
 
 const _MAX_DECIMALS_SPOT: u8 = 8;
 const MAX_DECIMALS_PERP: u8 = 6;
+/// Perps are margined and quoted in USDC
+const PERPS_QUOTE_ASSET: &str = "USDC";
 
 const ALLOWED_MANTISSA: [i32; 3] = [1, 2, 5];
 const SIG_FIG_LIMIT: i32 = 5;
@@ -140,9 +142,13 @@ struct HyperliquidAssetContext {
     mid_price: f32,
     #[serde(rename = "prevDayPx", deserialize_with = "de_string_to_f32")]
     prev_day_price: f32,
-    // TODO: Add open interest
-    // #[serde(rename = "openInterest", deserialize_with = "de_string_to_f32", default)]
-    // open_interest: f32, // Only available for perps
+    /// Only available for perps
+    #[serde(
+        rename = "openInterest",
+        deserialize_with = "de_string_to_f32",
+        default
+    )]
+    open_interest: f32,
 }
 
 impl HyperliquidAssetContext {
@@ -174,8 +180,8 @@ struct HyperliquidKline {
     low: f32,
     #[serde(rename = "c", deserialize_with = "de_string_to_f32")]
     close: f32,
-    #[serde(rename = "v", deserialize_with = "de_string_to_f32")]
-    volume: f32,
+    #[serde(rename = "v")]
+    volume: Qty,
     #[serde(rename = "n")]
     trade_count: u64,
 }
@@ -193,8 +199,7 @@ struct HyperliquidDepth {
 struct HyperliquidLevel {
     #[serde(deserialize_with = "de_string_to_f32")]
     px: f32,
-    #[serde(deserialize_with = "de_string_to_f32")]
-    sz: f32,
+    sz: Qty,
     n: u32,
 }
 
@@ -205,8 +210,7 @@ struct HyperliquidTrade {
     side: String,
     #[serde(deserialize_with = "de_string_to_f32")]
     px: f32,
-    #[serde(deserialize_with = "de_string_to_f32")]
-    sz: f32,
+    sz: Qty,
     time: u64,
 }
 
@@ -242,6 +246,42 @@ pub async fn fetch_ticker_prices(
     Ok(ticker_stats_map)
 }
 
+/// Open interest of a perp right now, in coins. The venue keeps no history of it
+pub async fn fetch_current_oi(ticker: Ticker) -> Result<OpenInterest, AdapterError> {
+    let (symbol_str, _) = ticker.to_full_symbol_and_type();
+
+    // builder-deployed perps are only listed in their DEX's context
+    let body = match symbol_str.split_once(':') {
+        Some((dex, _)) => json!({ "type": "metaAndAssetCtxs", "dex": dex }),
+        None => json!({ "type": "metaAndAssetCtxs" }),
+    };
+
+    let response_json: Value = post_info(&body).await?;
+
+    let index = response_json
+        .get(0)
+        .and_then(|metadata| metadata.get("universe"))
+        .and_then(|universe| universe.as_array())
+        .and_then(|universe| {
+            universe
+                .iter()
+                .position(|asset| asset["name"].as_str() == Some(symbol_str.as_str()))
+        })
+        .ok_or_else(|| AdapterError::ParseError(format!("{symbol_str} not found in universe")))?;
+
+    let asset_ctx = response_json
+        .get(1)
+        .and_then(|contexts| contexts.get(index))
+        .ok_or_else(|| AdapterError::ParseError("Missing asset context".to_string()))?;
+    let ctx = serde_json::from_value::<HyperliquidAssetContext>(asset_ctx.clone())
+        .map_err(|e| AdapterError::ParseError(e.to_string()))?;
+
+    Ok(OpenInterest {
+        time: chrono::Utc::now().timestamp_millis() as u64,
+        value: ctx.open_interest,
+    })
+}
+
 async fn post_info<T: DeserializeOwned>(body: &Value) -> Result<T, AdapterError> {
     let url = format!("{}/info", API_DOMAIN);
     let response_text = limiter::http_request_with_limiter(
@@ -345,6 +385,7 @@ async fn fetch_spot_metadata() -> Result<TickerMetadata, AdapterError> {
 
 fn insert_ticker_from_ctx(
     ticker: Ticker,
+    (base, quote): (&str, &str),
     sz_decimals: u32,
     ctx: &HyperliquidAssetContext,
     ticker_info_map: &mut HashMap<Ticker, Option<TickerInfo>>,
@@ -355,7 +396,7 @@ fn insert_ticker_from_ctx(
         return;
     }
 
-    let ticker_info = create_ticker_info(ticker, price, sz_decimals);
+    let ticker_info = create_ticker_info(ticker, price, sz_decimals).with_assets(base, quote);
     ticker_info_map.insert(ticker, Some(ticker_info));
 
     ticker_stats_map.insert(
@@ -387,8 +428,15 @@ fn process_perp_assets(
             && let Ok(ctx) = serde_json::from_value::<HyperliquidAssetContext>(asset_ctx.clone())
         {
             let ticker = Ticker::new(&asset_info.name, exchange);
+            // builder-deployed perps are prefixed with their DEX, e.g. "xyz:TSLA"
+            let base = asset_info
+                .name
+                .split_once(':')
+                .map_or(asset_info.name.as_str(), |(_, coin)| coin);
+
             insert_ticker_from_ctx(
                 ticker,
+                (base, PERPS_QUOTE_ASSET),
                 asset_info.sz_decimals,
                 &ctx,
                 &mut ticker_info_map,
@@ -415,12 +463,14 @@ fn process_spot_assets(
         if let Some(asset_ctx) = asset_contexts.get(pair.index as usize)
             && let Ok(ctx) = serde_json::from_value::<HyperliquidAssetContext>(asset_ctx.clone())
             && let Some(base_token) = spot_meta.tokens.iter().find(|t| t.index == pair.tokens[0])
+            && let Some(quote_token) = spot_meta.tokens.iter().find(|t| t.index == pair.tokens[1])
         {
             let display_symbol = create_display_symbol(&pair.name, &spot_meta.tokens, &pair.tokens);
             let ticker = Ticker::new_with_display(&pair.name, exchange, Some(&display_symbol));
 
             insert_ticker_from_ctx(
                 ticker,
+                (&base_token.name, &quote_token.name),
                 base_token.sz_decimals,
                 &ctx,
                 &mut ticker_info_map,
@@ -714,24 +764,16 @@ pub async fn fetch_klines(
     )
     .await?;
 
-    let size_in_quote_ccy = volume_size_unit() == SizeUnit::Quote;
-
     let mut klines = vec![];
     for kline_data in klines_data {
         if let Ok(hl_kline) = serde_json::from_value::<HyperliquidKline>(kline_data) {
-            let volume = if size_in_quote_ccy {
-                (hl_kline.volume * hl_kline.close).round()
-            } else {
-                hl_kline.volume
-            };
-
             let kline = Kline::new(
                 hl_kline.time,
                 hl_kline.open,
                 hl_kline.high,
                 hl_kline.low,
                 hl_kline.close,
-                (-1.0, volume),
+                (Kline::TOTAL_ONLY, hl_kline.volume),
                 ticker_info.min_ticksize,
             );
             klines.push(kline);
@@ -795,7 +837,6 @@ pub fn connect_market_stream(
         let mut local_depth_cache = LocalDepthCache::default();
         let mut trades_buffer = Vec::new();
 
-        let size_in_quote_ccy = volume_size_unit() == SizeUnit::Quote;
         let user_multiplier = tick_multiplier.unwrap_or(TickMultiplier(1)).0;
 
         let (symbol_str, _) = ticker.to_full_symbol_and_type();
@@ -911,17 +952,12 @@ pub fn connect_market_stream(
                                             for hl_trade in trades {
                                                 let price = Price::from_f32(hl_trade.px)
                                                     .round_to_min_tick(ticker_info.min_ticksize);
-                                                let qty = if size_in_quote_ccy {
-                                                    (hl_trade.sz * hl_trade.px).round()
-                                                } else {
-                                                    hl_trade.sz
-                                                };
 
                                                 let trade = Trade {
                                                     time: hl_trade.time,
                                                     is_sell: hl_trade.side == "A", // A for Ask/Sell, B for Bid/Buy
                                                     price,
-                                                    qty,
+                                                    qty: hl_trade.sz,
                                                 };
                                                 trades_buffer.push(trade);
                                             }
@@ -931,22 +967,14 @@ pub fn connect_market_stream(
                                                 .iter()
                                                 .map(|level| DeOrder {
                                                     price: level.px,
-                                                    qty: if size_in_quote_ccy {
-                                                        (level.sz * level.px).round()
-                                                    } else {
-                                                        level.sz
-                                                    },
+                                                    qty: level.sz,
                                                 })
                                                 .collect();
                                             let asks = depth.levels[1]
                                                 .iter()
                                                 .map(|level| DeOrder {
                                                     price: level.px,
-                                                    qty: if size_in_quote_ccy {
-                                                        (level.sz * level.px).round()
-                                                    } else {
-                                                        level.sz
-                                                    },
+                                                    qty: level.sz,
                                                 })
                                                 .collect();
 
@@ -1029,8 +1057,6 @@ pub fn connect_kline_stream(
             .map(|(t, _)| t.exchange())
             .unwrap_or(Exchange::HyperliquidLinear);
 
-        let size_in_quote_ccy = volume_size_unit() == SizeUnit::Quote;
-
         loop {
             match &mut state {
                 State::Disconnected => match connect_websocket(WS_DOMAIN, "/ws").await {
@@ -1084,19 +1110,13 @@ pub fn connect_kline_stream(
                                             && tf.to_string() == hl_kline.interval.as_str()
                                     })
                             {
-                                let volume = if size_in_quote_ccy {
-                                    (hl_kline.volume * hl_kline.close).round()
-                                } else {
-                                    hl_kline.volume
-                                };
-
                                 let kline = Kline::new(
                                     hl_kline.time,
                                     hl_kline.open,
                                     hl_kline.high,
                                     hl_kline.low,
                                     hl_kline.close,
-                                    (-1.0, volume),
+                                    (Kline::TOTAL_ONLY, hl_kline.volume),
                                     ticker_info.min_ticksize,
                                 );
 
@@ -1178,28 +1198,18 @@ async fn fetch_orderbook(
     let depth: HyperliquidDepth = serde_json::from_str(&response_text)
         .map_err(|e| AdapterError::ParseError(e.to_string()))?;
 
-    let size_in_quote_ccy = volume_size_unit() == SizeUnit::Quote;
-
     let bids = depth.levels[0]
         .iter()
         .map(|level| DeOrder {
             price: level.px,
-            qty: if size_in_quote_ccy {
-                (level.sz * level.px).round()
-            } else {
-                level.sz
-            },
+            qty: level.sz,
         })
         .collect();
     let asks = depth.levels[1]
         .iter()
         .map(|level| DeOrder {
             price: level.px,
-            qty: if size_in_quote_ccy {
-                (level.sz * level.px).round()
-            } else {
-                level.sz
-            },
+            qty: level.sz,
         })
         .collect();
 
@@ -0,0 +1,1240 @@
+//! Trades and candles imported from user files
+//!
+//! Imported rows are normalized into one CSV per symbol inside the storage directory.
+//! The directory is scanned on first use and its datasets are then served like any other venue,
+//! with klines aggregated or resampled locally for the requested timeframe.
+
+use super::{AdapterError, Exchange};
+use crate::{
+    Asset, Kline, Price, Ticker, TickerInfo, TickerStats, Timeframe, Trade,
+    util::{MinQtySize, MinTicksize, Qty},
+};
+
+use chrono::{DateTime, NaiveDate, NaiveDateTime};
+use csv::ReaderBuilder;
+use enum_map::{Enum, EnumMap};
+use rustc_hash::FxHashMap;
+
+use std::collections::HashMap;
+use std::io::{BufRead, BufReader, BufWriter, Read, Write};
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, LazyLock, OnceLock, RwLock};
+
+/// Amount of bars returned when the chart asks for the latest klines without a range
+const KLINE_LIMIT: u64 = 400;
+
+const PREVIEW_ROWS: usize = 6;
+
+const DELIMITERS: [u8; 4] = [b',', b';', b'\t', b'|'];
+
+const TRADES_HEADER: [&str; 4] = ["time", "price", "qty", "is_sell"];
+const KLINES_HEADER: [&str; 7] = [
+    "time",
+    "open",
+    "high",
+    "low",
+    "close",
+    "buy_volume",
+    "sell_volume",
+];
+
+static STORAGE_DIR: OnceLock<PathBuf> = OnceLock::new();
+
+static DATASETS: LazyLock<RwLock<FxHashMap<Ticker, Arc<Dataset>>>> =
+    LazyLock::new(|| RwLock::new(load_all()));
+
+/// Sets where imported datasets are stored, must be called before any other function here
+pub fn set_storage_dir(path: PathBuf) {
+    if STORAGE_DIR.set(path).is_err() {
+        log::warn!("Local storage directory is already set");
+    }
+}
+
+fn storage_dir() -> Option<&'static Path> {
+    STORAGE_DIR.get().map(PathBuf::as_path)
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum DataKind {
+    #[default]
+    Trades,
+    Klines,
+}
+
+impl DataKind {
+    pub const ALL: [DataKind; 2] = [DataKind::Trades, DataKind::Klines];
+
+    pub fn fields(&self) -> &'static [Field] {
+        match self {
+            DataKind::Trades => &[Field::Time, Field::Price, Field::Qty, Field::Side],
+            DataKind::Klines => &[
+                Field::Time,
+                Field::Open,
+                Field::High,
+                Field::Low,
+                Field::Close,
+                Field::Volume,
+                Field::BuyVolume,
+                Field::SellVolume,
+            ],
+        }
+    }
+}
+
+impl std::fmt::Display for DataKind {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            DataKind::Trades => write!(f, "Trades"),
+            DataKind::Klines => write!(f, "Candles"),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Enum)]
+pub enum Field {
+    Time,
+    Price,
+    Qty,
+    /// Aggressor side; `true` is read as a sell, matching Binance's `is_buyer_maker`
+    Side,
+    Open,
+    High,
+    Low,
+    Close,
+    Volume,
+    BuyVolume,
+    SellVolume,
+}
+
+impl Field {
+    pub fn is_required(&self) -> bool {
+        !matches!(
+            self,
+            Field::Side | Field::Volume | Field::BuyVolume | Field::SellVolume
+        )
+    }
+}
+
+impl std::fmt::Display for Field {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let label = match self {
+            Field::Time => "Time",
+            Field::Price => "Price",
+            Field::Qty => "Quantity",
+            Field::Side => "Side",
+            Field::Open => "Open",
+            Field::High => "High",
+            Field::Low => "Low",
+            Field::Close => "Close",
+            Field::Volume => "Volume",
+            Field::BuyVolume => "Buy volume",
+            Field::SellVolume => "Sell volume",
+        };
+        write!(f, "{label}")
+    }
+}
+
+/// Which column holds each [`Field`] of the rows to import
+#[derive(Debug, Clone, PartialEq)]
+pub struct CsvMapping {
+    pub kind: DataKind,
+    pub delimiter: u8,
+    pub has_header: bool,
+    pub columns: EnumMap<Field, Option<usize>>,
+}
+
+impl CsvMapping {
+    fn new(kind: DataKind, columns: &[(Field, usize)]) -> Self {
+        let mut map = EnumMap::default();
+        for &(field, idx) in columns {
+            map[field] = Some(idx);
+        }
+
+        Self {
+            kind,
+            delimiter: b',',
+            has_header: false,
+            columns: map,
+        }
+    }
+
+    /// Layout of the `aggTrades` archives from data.binance.vision
+    pub fn binance_agg_trades() -> Self {
+        Self::new(
+            DataKind::Trades,
+            &[
+                (Field::Price, 1),
+                (Field::Qty, 2),
+                (Field::Time, 5),
+                (Field::Side, 6),
+            ],
+        )
+    }
+
+    /// Layout of the `klines` archives from data.binance.vision
+    pub fn binance_klines() -> Self {
+        Self::new(
+            DataKind::Klines,
+            &[
+                (Field::Time, 0),
+                (Field::Open, 1),
+                (Field::High, 2),
+                (Field::Low, 3),
+                (Field::Close, 4),
+                (Field::Volume, 5),
+                (Field::BuyVolume, 9),
+            ],
+        )
+    }
+
+    fn normalized(kind: DataKind) -> Self {
+        let mapping = match kind {
+            DataKind::Trades => Self::new(
+                kind,
+                &[
+                    (Field::Time, 0),
+                    (Field::Price, 1),
+                    (Field::Qty, 2),
+                    (Field::Side, 3),
+                ],
+            ),
+            DataKind::Klines => Self::new(
+                kind,
+                &[
+                    (Field::Time, 0),
+                    (Field::Open, 1),
+                    (Field::High, 2),
+                    (Field::Low, 3),
+                    (Field::Close, 4),
+                    (Field::BuyVolume, 5),
+                    (Field::SellVolume, 6),
+                ],
+            ),
+        };
+
+        Self {
+            has_header: true,
+            ..mapping
+        }
+    }
+
+    /// Best guess from the header names, or from the column count for headerless files
+    fn guess(headers: Option<&[String]>, column_count: usize) -> Self {
+        let Some(headers) = headers else {
+            return match column_count {
+                7 | 8 => Self::binance_agg_trades(),
+                12 => Self::binance_klines(),
+                5 | 6 => Self::new(
+                    DataKind::Klines,
+                    &[
+                        (Field::Time, 0),
+                        (Field::Open, 1),
+                        (Field::High, 2),
+                        (Field::Low, 3),
+                        (Field::Close, 4),
+                        (Field::Volume, 5),
+                    ],
+                ),
+                _ => Self::new(
+                    DataKind::Trades,
+                    &[(Field::Time, 0), (Field::Price, 1), (Field::Qty, 2)],
+                ),
+            };
+        };
+
+        let names = headers
+            .iter()
+            .map(|h| h.trim().to_lowercase())
+            .collect::<Vec<_>>();
+        let find = |pred: &dyn Fn(&str) -> bool| names.iter().position(|n| pred(n));
+
+        let close = find(&|n| n == "close" || n == "c");
+        let kind = if close.is_some() {
+            DataKind::Klines
+        } else {
+            DataKind::Trades
+        };
+
+        let mut columns = EnumMap::default();
+        columns[Field::Time] =
+            find(&|n| n.contains("time") || n.contains("date") || n == "ts" || n == "timestamp");
+
+        match kind {
+            DataKind::Trades => {
+                columns[Field::Price] = find(&|n| n == "price" || n == "p");
+                columns[Field::Qty] = find(&|n| {
+                    ["qty", "quantity", "size", "amount", "volume"]
+                        .iter()
+                        .any(|k| n.contains(k))
+                });
+                columns[Field::Side] = find(&|n| n.contains("side") || n.contains("maker"));
+            }
+            DataKind::Klines => {
+                columns[Field::Open] = find(&|n| n == "open" || n == "o");
+                columns[Field::High] = find(&|n| n == "high" || n == "h");
+                columns[Field::Low] = find(&|n| n == "low" || n == "l");
+                columns[Field::Close] = close;
+                columns[Field::BuyVolume] = find(&|n| n.contains("buy") && n.contains("vol"));
+                columns[Field::SellVolume] = find(&|n| n.contains("sell") && n.contains("vol"));
+                columns[Field::Volume] = find(&|n| n == "volume" || n == "vol" || n == "v");
+            }
+        }
+
+        Self {
+            kind,
+            delimiter: b',',
+            has_header: true,
+            columns,
+        }
+    }
+
+    fn validate(&self) -> Result<(), AdapterError> {
+        let missing = self
+            .kind
+            .fields()
+            .iter()
+            .filter(|f| f.is_required() && self.columns[**f].is_none())
+            .map(ToString::to_string)
+            .collect::<Vec<_>>();
+
+        if missing.is_empty() {
+            Ok(())
+        } else {
+            Err(AdapterError::InvalidRequest(format!(
+                "Missing columns: {}",
+                missing.join(", ")
+            )))
+        }
+    }
+}
+
+/// First rows of a file along with a guessed [`CsvMapping`]
+#[derive(Debug, Clone)]
+pub struct Preview {
+    pub headers: Vec<String>,
+    pub rows: Vec<Vec<String>>,
+    pub mapping: CsvMapping,
+}
+
+/// Summary of an imported dataset
+#[derive(Debug, Clone, Copy)]
+pub struct Imported {
+    pub ticker_info: TickerInfo,
+    pub kind: DataKind,
+    pub rows: usize,
+    pub range: (u64, u64),
+}
+
+#[derive(Debug)]
+enum Rows {
+    /// Sorted by time, quantities in base units
+    Trades(Vec<Trade>),
+    /// Sorted by time, volumes in base units
+    Klines { interval: u64, klines: Vec<Kline> },
+}
+
+#[derive(Debug)]
+struct Dataset {
+    info: TickerInfo,
+    rows: Rows,
+}
+
+impl Dataset {
+    fn kind(&self) -> DataKind {
+        match self.rows {
+            Rows::Trades(_) => DataKind::Trades,
+            Rows::Klines { .. } => DataKind::Klines,
+        }
+    }
+
+    fn len(&self) -> usize {
+        match &self.rows {
+            Rows::Trades(trades) => trades.len(),
+            Rows::Klines { klines, .. } => klines.len(),
+        }
+    }
+
+    fn time_range(&self) -> (u64, u64) {
+        let times = match &self.rows {
+            Rows::Trades(trades) => trades
+                .first()
+                .zip(trades.last())
+                .map(|(a, b)| (a.time, b.time)),
+            Rows::Klines { klines, .. } => klines
+                .first()
+                .zip(klines.last())
+                .map(|(a, b)| (a.time, b.time)),
+        };
+        times.unwrap_or_default()
+    }
+
+    /// `(time, close, base volume)` points used for ticker stats
+    fn points(&self) -> Vec<(u64, Price, Qty)> {
+        match &self.rows {
+            Rows::Trades(trades) => trades.iter().map(|t| (t.time, t.price, t.qty)).collect(),
+            Rows::Klines { klines, .. } => klines
+                .iter()
+                .map(|k| (k.time, k.close, k.total_volume()))
+                .collect(),
+        }
+    }
+}
+
+/// A parsed but not yet normalized row
+enum Parsed {
+    Trade {
+        time: u64,
+        price: Price,
+        qty: Qty,
+        is_sell: Option<bool>,
+    },
+    Kline {
+        time: u64,
+        ohlc: [Price; 4],
+        volume: (Qty, Qty),
+    },
+}
+
+/// Tick and lot size as far as the rows show them
+#[derive(Default)]
+struct Precision {
+    /// Greatest common divisor of the moves between prices read one after another,
+    /// zero while the price hasn't moved
+    tick_units: i128,
+    last_price: Option<Price>,
+    price_decimals: u8,
+    qty_decimals: u8,
+}
+
+impl Precision {
+    fn observe_price(&mut self, price: Price, text: &str) {
+        if let Some(last) = self.last_price {
+            self.tick_units = gcd(self.tick_units, (price.units - last.units).abs());
+        }
+        self.last_price = Some(price);
+        self.price_decimals = self.price_decimals.max(decimals(text));
+    }
+
+    fn observe_qty(&mut self, text: &str) {
+        self.qty_decimals = self.qty_decimals.max(decimals(text));
+    }
+
+    /// The finest price step seen, or the decimals prices were written with when they
+    /// never moved
+    fn min_ticksize(&self) -> MinTicksize {
+        if self.tick_units > 0 {
+            MinTicksize {
+                units: self.tick_units,
+            }
+        } else {
+            MinTicksize::from_power(-(self.price_decimals as i8))
+        }
+    }
+
+    fn ticker_info(&self, symbol: &str) -> TickerInfo {
+        TickerInfo {
+            ticker: Ticker::new(symbol, Exchange::Local),
+            min_ticksize: self.min_ticksize(),
+            min_qty: MinQtySize::new(-(self.qty_decimals as i8)),
+            contract_size: None,
+            base: Asset::default(),
+            quote: Asset::default(),
+            settlement: Asset::default(),
+        }
+    }
+}
+
+/// Significant fractional digits, "16590.21000000" has two
+fn decimals(value: &str) -> u8 {
+    value.trim().split_once('.').map_or(0, |(_, frac)| {
+        frac.trim_end_matches('0').len().min(u8::MAX as usize) as u8
+    })
+}
+
+fn gcd(a: i128, b: i128) -> i128 {
+    if b == 0 { a } else { gcd(b, a % b) }
+}
+
+/// Parses epoch timestamps in s/ms/µs/ns, fractional seconds, or common date formats into ms
+pub fn parse_time(value: &str) -> Option<u64> {
+    let value = value.trim();
+
+    if let Ok(v) = value.parse::<u64>() {
+        return Some(match v {
+            0..100_000_000_000 => v * 1_000,
+            100_000_000_000..100_000_000_000_000 => v,
+            100_000_000_000_000..100_000_000_000_000_000 => v / 1_000,
+            _ => v / 1_000_000,
+        });
+    }
+
+    if let Ok(v) = value.parse::<f64>() {
+        return (0.0..1e11).contains(&v).then_some((v * 1_000.0) as u64);
+    }
+
+    if let Ok(dt) = DateTime::parse_from_rfc3339(value) {
+        return u64::try_from(dt.timestamp_millis()).ok();
+    }
+
+    const FORMATS: [&str; 4] = [
+        "%Y-%m-%d %H:%M:%S%.f",
+        "%Y-%m-%dT%H:%M:%S%.f",
+        "%Y-%m-%d %H:%M",
+        "%Y/%m/%d %H:%M:%S%.f",
+    ];
+
+    FORMATS
+        .iter()
+        .find_map(|fmt| NaiveDateTime::parse_from_str(value, fmt).ok())
+        .or_else(|| {
+            NaiveDate::parse_from_str(value, "%Y-%m-%d")
+                .ok()
+                .and_then(|d| d.and_hms_opt(0, 0, 0))
+        })
+        .and_then(|dt| u64::try_from(dt.and_utc().timestamp_millis()).ok())
+}
+
+/// Returns `Some(true)` for a sell aggressor
+fn parse_side(value: &str) -> Option<bool> {
+    match value.trim().to_lowercase().as_str() {
+        "sell" | "s" | "ask" | "true" | "1" => Some(true),
+        "buy" | "b" | "bid" | "false" | "0" => Some(false),
+        _ => None,
+    }
+}
+
+fn parse_f32(value: &str) -> Option<f32> {
+    value.trim().parse::<f32>().ok().filter(|v| v.is_finite())
+}
+
+fn parse_price(value: &str) -> Option<Price> {
+    value.trim().parse::<Price>().ok().filter(|p| p.units > 0)
+}
+
+fn parse_qty(value: &str) -> Option<Qty> {
+    value.trim().parse::<Qty>().ok()
+}
+
+/// Calls `f` with a reader for the file itself, or for every entry of a zip archive
+fn for_each_source(
+    path: &Path,
+    mut f: impl FnMut(&mut dyn Read) -> Result<bool, AdapterError>,
+) -> Result<(), AdapterError> {
+    let file = std::fs::File::open(path)
+        .map_err(|e| AdapterError::InvalidRequest(format!("Failed to open {path:?}: {e}")))?;
+
+    let is_zip = path
+        .extension()
+        .is_some_and(|ext| ext.eq_ignore_ascii_case("zip"));
+
+    if !is_zip {
+        f(&mut BufReader::new(file))?;
+        return Ok(());
+    }
+
+    let mut archive = zip::ZipArchive::new(file)
+        .map_err(|e| AdapterError::ParseError(format!("Failed to unzip file: {e}")))?;
+
+    for i in 0..archive.len() {
+        let mut entry = archive
+            .by_index(i)
+            .map_err(|e| AdapterError::ParseError(format!("Failed to read zip entry: {e}")))?;
+
+        if entry.is_dir() {
+            continue;
+        }
+        if !f(&mut BufReader::new(&mut entry))? {
+            break;
+        }
+    }
+
+    Ok(())
+}
+
+/// Reads the first rows of a file or archive and guesses how to import it
+pub fn preview(path: &Path) -> Result<Preview, AdapterError> {
+    let mut lines = Vec::with_capacity(PREVIEW_ROWS);
+
+    for_each_source(path, |reader| {
+        for line in BufReader::new(reader).lines().take(PREVIEW_ROWS) {
+            let line =
+                line.map_err(|e| AdapterError::ParseError(format!("Failed to read file: {e}")))?;
+            if !line.trim().is_empty() {
+                lines.push(line);
+            }
+        }
+        Ok(lines.is_empty())
+    })?;
+
+    let Some(first) = lines.first() else {
+        return Err(AdapterError::InvalidRequest("File is empty".to_string()));
+    };
+
+    let delimiter = DELIMITERS
+        .into_iter()
+        .max_by_key(|d| first.bytes().filter(|b| b == d).count())
+        .unwrap_or(b',');
+
+    let joined = lines.join("\n");
+    let mut reader = ReaderBuilder::new()
+        .delimiter(delimiter)
+        .has_headers(false)
+        .flexible(true)
+        .from_reader(joined.as_bytes());
+
+    let mut rows = reader
+        .records()
+        .filter_map(Result::ok)
+        .map(|record| record.iter().map(str::to_owned).collect::<Vec<_>>())
+        .collect::<Vec<_>>();
+
+    let has_header = rows.first().is_some_and(|row| {
+        row.iter()
+            .all(|cell| parse_f32(cell).is_none() && parse_time(cell).is_none())
+    });
+
+    let column_count = rows.iter().map(Vec::len).max().unwrap_or(0);
+
+    let headers = if has_header {
+        rows.remove(0)
+    } else {
+        (1..=column_count).map(|i| format!("Column {i}")).collect()
+    };
+
+    let mut mapping = CsvMapping::guess(has_header.then_some(&headers[..]), column_count);
+    mapping.delimiter = delimiter;
+    mapping.has_header = has_header;
+
+    Ok(Preview {
+        headers,
+        rows,
+        mapping,
+    })
+}
+
+fn parse_rows(path: &Path, mapping: &CsvMapping) -> Result<(Vec<Parsed>, Precision), AdapterError> {
+    mapping.validate()?;
+
+    let col = |field: Field| mapping.columns[field];
+
+    let mut parsed = Vec::new();
+    let mut precision = Precision::default();
+    let mut skipped = 0usize;
+
+    for_each_source(path, |reader| {
+        let mut csv_reader = ReaderBuilder::new()
+            .delimiter(mapping.delimiter)
+            .has_headers(mapping.has_header)
+            .flexible(true)
+            .from_reader(reader);
+
+        for record in csv_reader.records() {
+            let Ok(record) = record else {
+                skipped += 1;
+                continue;
+            };
+            let get = |field: Field| col(field).and_then(|idx| record.get(idx));
+            let time = get(Field::Time).and_then(parse_time);
+
+            let row = match mapping.kind {
+                DataKind::Trades => {
+                    let (Some(time), Some(price_str), Some(qty_str)) =
+                        (time, get(Field::Price), get(Field::Qty))
+                    else {
+                        skipped += 1;
+                        continue;
+                    };
+                    let (Some(price), Some(qty)) = (parse_price(price_str), parse_qty(qty_str))
+                    else {
+                        skipped += 1;
+                        continue;
+                    };
+
+                    precision.observe_price(price, price_str);
+                    precision.observe_qty(qty_str);
+
+                    Parsed::Trade {
+                        time,
+                        price,
+                        qty: qty.abs(),
+                        is_sell: get(Field::Side)
+                            .and_then(parse_side)
+                            .or_else(|| (qty < Qty::ZERO).then_some(true)),
+                    }
+                }
+                DataKind::Klines => {
+                    let texts = [Field::Open, Field::High, Field::Low, Field::Close].map(get);
+                    let (Some(time), [Some(o), Some(h), Some(l), Some(c)]) = (time, texts) else {
+                        skipped += 1;
+                        continue;
+                    };
+                    let ohlc = [o, h, l, c].map(parse_price);
+                    let [Some(open), Some(high), Some(low), Some(close)] = ohlc else {
+                        skipped += 1;
+                        continue;
+                    };
+
+                    for (price, text) in [(open, o), (high, h), (low, l), (close, c)] {
+                        precision.observe_price(price, text);
+                    }
+
+                    for text in [Field::Volume, Field::BuyVolume, Field::SellVolume]
+                        .into_iter()
+                        .filter_map(get)
+                    {
+                        precision.observe_qty(text);
+                    }
+
+                    let volume = get(Field::Volume).and_then(parse_qty);
+                    let buy = get(Field::BuyVolume).and_then(parse_qty);
+                    let sell = get(Field::SellVolume).and_then(parse_qty);
+
+                    let volume = match (buy, sell, volume) {
+                        (Some(buy), Some(sell), _) if buy >= Qty::ZERO && sell >= Qty::ZERO => {
+                            (buy, sell)
+                        }
+                        (Some(buy), None, Some(total)) if buy >= Qty::ZERO => {
+                            (buy, (total - buy).max(Qty::ZERO))
+                        }
+                        (None, Some(sell), Some(total)) => ((total - sell).max(Qty::ZERO), sell),
+                        (_, Some(sell), None) => (Kline::TOTAL_ONLY, sell),
+                        (_, _, Some(total)) => (Kline::TOTAL_ONLY, total),
+                        _ => (Kline::TOTAL_ONLY, Qty::ZERO),
+                    };
+
+                    Parsed::Kline {
+                        time,
+                        ohlc: [open, high, low, close],
+                        volume,
+                    }
+                }
+            };
+            parsed.push(row);
+        }
+        Ok(true)
+    })?;
+
+    if skipped > 0 {
+        log::warn!("Skipped {skipped} unreadable rows while importing {path:?}");
+    }
+
+    if parsed.is_empty() {
+        return Err(AdapterError::InvalidRequest(
+            "No rows could be read with the selected columns".to_string(),
+        ));
+    }
+
+    Ok((parsed, precision))
+}
+
+fn build_dataset(
+    symbol: &str,
+    mut parsed: Vec<Parsed>,
+    precision: &Precision,
+) -> Result<Dataset, AdapterError> {
+    let info = precision.ticker_info(symbol);
+    let min_tick = info.min_ticksize;
+
+    let row_time = |row: &Parsed| match row {
+        Parsed::Trade { time, .. } | Parsed::Kline { time, .. } => *time,
+    };
+    parsed.sort_by_key(row_time);
+
+    let rows = if matches!(parsed.first(), Some(Parsed::Trade { .. })) {
+        let mut last: Option<(Price, bool)> = None;
+
+        let trades = parsed
+            .into_iter()
+            .filter_map(|row| {
+                let Parsed::Trade {
+                    time,
+                    price,
+                    qty,
+                    is_sell,
+                } = row
+                else {
+                    return None;
+                };
+
+                // tick rule for files without an aggressor side
+                let is_sell = is_sell.unwrap_or(match last {
+                    Some((prev, prev_sell)) if price == prev => prev_sell,
+                    Some((prev, _)) => price < prev,
+                    None => false,
+                });
+                last = Some((price, is_sell));
+
+                Some(Trade {
+                    time,
+                    is_sell,
+                    price: price.round_to_min_tick(min_tick),
+                    qty,
+                })
+            })
+            .collect();
+
+        Rows::Trades(trades)
+    } else {
+        let klines = parsed
+            .into_iter()
+            .filter_map(|row| match row {
+                Parsed::Kline { time, ohlc, volume } => {
+                    let [open, high, low, close] = ohlc.map(|p| p.round_to_min_tick(min_tick));
+                    Some(Kline {
+                        time,
+                        open,
+                        high,
+                        low,
+                        close,
+                        volume,
+                    })
+                }
+                Parsed::Trade { .. } => None,
+            })
+            .collect::<Vec<_>>();
+
+        let interval = klines
+            .windows(2)
+            .map(|w| w[1].time - w[0].time)
+            .filter(|diff| *diff > 0)
+            .min()
+            .ok_or_else(|| {
+                AdapterError::InvalidRequest(
+                    "At least two candles are needed to detect the timeframe".to_string(),
+                )
+            })?;
+
+        Rows::Klines { interval, klines }
+    };
+
+    Ok(Dataset { info, rows })
+}
+
+fn write_normalized(path: &Path, dataset: &Dataset) -> Result<(), AdapterError> {
+    let io_err =
+        |e: std::io::Error| AdapterError::ParseError(format!("Failed to write {path:?}: {e}"));
+
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent).map_err(io_err)?;
+    }
+
+    let file = std::fs::File::create(path).map_err(io_err)?;
+    let mut out = BufWriter::new(file);
+
+    let min_tick = dataset.info.min_ticksize;
+    match &dataset.rows {
+        Rows::Trades(trades) => {
+            writeln!(out, "{}", TRADES_HEADER.join(",")).map_err(io_err)?;
+            for t in trades {
+                writeln!(
+                    out,
+                    "{},{},{},{}",
+                    t.time,
+                    t.price.to_string(min_tick),
+                    t.qty,
+                    t.is_sell
+                )
+                .map_err(io_err)?;
+            }
+        }
+        Rows::Klines { klines, .. } => {
+            writeln!(out, "{}", KLINES_HEADER.join(",")).map_err(io_err)?;
+            for k in klines {
+                writeln!(
+                    out,
+                    "{},{},{},{},{},{},{}",
+                    k.time,
+                    k.open.to_string(min_tick),
+                    k.high.to_string(min_tick),
+                    k.low.to_string(min_tick),
+                    k.close.to_string(min_tick),
+                    k.volume.0,
+                    k.volume.1
+                )
+                .map_err(io_err)?;
+            }
+        }
+    }
+
+    out.flush().map_err(io_err)
+}
+
+fn load_all() -> FxHashMap<Ticker, Arc<Dataset>> {
+    let mut datasets = FxHashMap::default();
+
+    let Some(dir) = storage_dir() else {
+        return datasets;
+    };
+    let Ok(entries) = std::fs::read_dir(dir) else {
+        return datasets;
+    };
+
+    for path in entries.filter_map(|e| e.ok().map(|e| e.path())) {
+        let is_csv = path.extension().is_some_and(|ext| ext == "csv");
+        let Some(symbol) = path.file_stem().and_then(|s| s.to_str()) else {
+            continue;
+        };
+        if !is_csv || validate_symbol(symbol).is_err() {
+            continue;
+        }
+
+        match load_normalized(&path, symbol) {
+            Ok(dataset) => {
+                datasets.insert(dataset.info.ticker, Arc::new(dataset));
+            }
+            Err(e) => log::error!("Failed to load imported dataset {path:?}: {e}"),
+        }
+    }
+
+    datasets
+}
+
+fn load_normalized(path: &Path, symbol: &str) -> Result<Dataset, AdapterError> {
+    let mut header = String::new();
+    std::fs::File::open(path)
+        .and_then(|file| BufReader::new(file).read_line(&mut header))
+        .map_err(|e| AdapterError::ParseError(format!("Failed to read {path:?}: {e}")))?;
+
+    let kind = if header.trim() == KLINES_HEADER.join(",") {
+        DataKind::Klines
+    } else {
+        DataKind::Trades
+    };
+
+    let (parsed, precision) = parse_rows(path, &CsvMapping::normalized(kind))?;
+    build_dataset(symbol, parsed, &precision)
+}
+
+fn validate_symbol(symbol: &str) -> Result<(), AdapterError> {
+    let valid = !symbol.is_empty()
+        && symbol.len() <= 28
+        && crate::is_symbol_supported(symbol, Exchange::Local, false);
+
+    if valid {
+        Ok(())
+    } else {
+        Err(AdapterError::InvalidRequest(format!(
+            "Invalid symbol {symbol:?}, use up to 28 letters, digits, '-' or '_'"
+        )))
+    }
+}
+
+/// Parses `path` with `mapping` and stores it as `symbol`, replacing any previous import of it
+pub fn import(path: &Path, symbol: &str, mapping: &CsvMapping) -> Result<TickerInfo, AdapterError> {
+    let symbol = symbol.trim().to_uppercase();
+    validate_symbol(&symbol)?;
+
+    let dir = storage_dir()
+        .ok_or_else(|| AdapterError::InvalidRequest("Import directory is not set".to_string()))?;
+
+    let (parsed, precision) = parse_rows(path, mapping)?;
+    let dataset = build_dataset(&symbol, parsed, &precision)?;
+
+    write_normalized(&dir.join(format!("{symbol}.csv")), &dataset)?;
+
+    let info = dataset.info;
+    log::info!("Imported {} rows from {path:?} as {symbol}", dataset.len());
+
+    DATASETS
+        .write()
+        .unwrap_or_else(|e| e.into_inner())
+        .insert(info.ticker, Arc::new(dataset));
+
+    Ok(info)
+}
+
+/// Deletes an imported dataset along with its stored file
+pub fn remove(ticker: &Ticker) -> Result<(), AdapterError> {
+    DATASETS
+        .write()
+        .unwrap_or_else(|e| e.into_inner())
+        .remove(ticker);
+
+    if let Some(dir) = storage_dir() {
+        let (symbol, _) = ticker.to_full_symbol_and_type();
+        let path = dir.join(format!("{symbol}.csv"));
+
+        if path.exists() {
+            std::fs::remove_file(&path)
+                .map_err(|e| AdapterError::ParseError(format!("Failed to remove {path:?}: {e}")))?;
+        }
+    }
+
+    Ok(())
+}
+
+pub fn imported() -> Vec<Imported> {
+    let datasets = DATASETS.read().unwrap_or_else(|e| e.into_inner());
+
+    let mut list = datasets
+        .values()
+        .map(|d| Imported {
+            ticker_info: d.info,
+            kind: d.kind(),
+            rows: d.len(),
+            range: d.time_range(),
+        })
+        .collect::<Vec<_>>();
+
+    list.sort_by_key(|i| i.ticker_info.ticker.to_full_symbol_and_type().0);
+    list
+}
+
+/// Whether the dataset holds individual trades, needed for footprints and time & sales
+pub fn has_trades(ticker: &Ticker) -> bool {
+    trades_range(ticker).is_some()
+}
+
+/// Time range of an imported trades dataset
+pub fn trades_range(ticker: &Ticker) -> Option<(u64, u64)> {
+    dataset(ticker)
+        .ok()
+        .filter(|d| d.kind() == DataKind::Trades)
+        .map(|d| d.time_range())
+}
+
+fn dataset(ticker: &Ticker) -> Result<Arc<Dataset>, AdapterError> {
+    DATASETS
+        .read()
+        .unwrap_or_else(|e| e.into_inner())
+        .get(ticker)
+        .cloned()
+        .ok_or_else(|| AdapterError::InvalidRequest(format!("No imported data for {ticker}")))
+}
+
+pub async fn fetch_ticksize() -> Result<HashMap<Ticker, Option<TickerInfo>>, AdapterError> {
+    let datasets = DATASETS.read().unwrap_or_else(|e| e.into_inner());
+
+    Ok(datasets
+        .iter()
+        .map(|(ticker, dataset)| (*ticker, Some(dataset.info)))
+        .collect())
+}
+
+/// Stats over the last 24 hours of each dataset, relative to its newest row
+pub async fn fetch_ticker_prices() -> Result<HashMap<Ticker, TickerStats>, AdapterError> {
+    let datasets = DATASETS.read().unwrap_or_else(|e| e.into_inner());
+
+    let stats = datasets
+        .iter()
+        .filter_map(|(ticker, dataset)| {
+            let points = dataset.points();
+            let &(last_time, last_price, _) = points.last()?;
+
+            let from = last_time.saturating_sub(24 * 60 * 60 * 1000);
+            let window = &points[points.partition_point(|p| p.0 < from)..];
+
+            let last_price = last_price.to_f32();
+            let open = window.first().map_or(last_price, |p| p.1.to_f32());
+            let daily_volume = window
+                .iter()
+                .map(|p| p.2.to_quote(p.1))
+                .sum::<Qty>()
+                .to_f32();
+
+            Some((
+                *ticker,
+                TickerStats {
+                    mark_price: last_price,
+                    daily_price_chg: if open > 0.0 {
+                        (last_price - open) / open * 100.0
+                    } else {
+                        0.0
+                    },
+                    daily_volume,
+                },
+            ))
+        })
+        .collect();
+
+    Ok(stats)
+}
+
+pub async fn fetch_klines(
+    ticker_info: TickerInfo,
+    timeframe: Timeframe,
+    range: Option<(u64, u64)>,
+) -> Result<Vec<Kline>, AdapterError> {
+    let dataset = dataset(&ticker_info.ticker)?;
+    let interval = timeframe.to_milliseconds();
+
+    let bucket = |time: u64| timeframe.bucket_start(time);
+
+    let (_, last_time) = dataset.time_range();
+    let (start, end) = range.unwrap_or_else(|| {
+        let start = bucket(last_time).saturating_sub((KLINE_LIMIT - 1) * interval);
+        (start, last_time)
+    });
+    let start = bucket(start);
+
+    let mut klines: Vec<Kline> = Vec::new();
+
+    match &dataset.rows {
+        Rows::Trades(trades) => {
+            let from = trades.partition_point(|t| t.time < start);
+
+            for trade in trades[from..].iter().take_while(|t| t.time <= end) {
+                let time = bucket(trade.time);
+                match klines.last_mut() {
+                    Some(kline) if kline.time == time => {
+                        kline.high = kline.high.max(trade.price);
+                        kline.low = kline.low.min(trade.price);
+                        kline.close = trade.price;
+                        if trade.is_sell {
+                            kline.volume.1 += trade.qty;
+                        } else {
+                            kline.volume.0 += trade.qty;
+                        }
+                    }
+                    _ => klines.push(Kline {
+                        time,
+                        open: trade.price,
+                        high: trade.price,
+                        low: trade.price,
+                        close: trade.price,
+                        volume: if trade.is_sell {
+                            (Qty::ZERO, trade.qty)
+                        } else {
+                            (trade.qty, Qty::ZERO)
+                        },
+                    }),
+                }
+            }
+        }
+        Rows::Klines {
+            interval: base_interval,
+            klines: source,
+        } => {
+            if !base_interval.is_multiple_of(1000) {
+                return Err(AdapterError::InvalidRequest(format!(
+                    "Imported candles {base_interval}ms apart aren't in whole seconds"
+                )));
+            }
+            let base = Timeframe::from_seconds((*base_interval / 1000) as u32);
+            if !timeframe.resamples_from(base) {
+                return Err(AdapterError::InvalidRequest(format!(
+                    "{timeframe} can't be built from the imported {}s candles",
+                    base_interval / 1000
+                )));
+            }
+
+            let from = source.partition_point(|k| k.time < start);
+
+            for k in source[from..].iter().take_while(|k| k.time <= end) {
+                let time = bucket(k.time);
+
+                match klines.last_mut() {
+                    Some(kline) if kline.time == time => kline.merge(k),
+                    _ => klines.push(Kline { time, ..*k }),
+                }
+            }
+        }
+    }
+
+    Ok(klines)
+}
+
+/// Imported trades within `[from, to]`
+pub async fn fetch_trades(
+    ticker_info: TickerInfo,
+    from: u64,
+    to: u64,
+) -> Result<Vec<Trade>, AdapterError> {
+    let dataset = dataset(&ticker_info.ticker)?;
+
+    let Rows::Trades(trades) = &dataset.rows else {
+        return Err(AdapterError::InvalidRequest(format!(
+            "{} was imported from candles, trades are not available",
+            ticker_info.ticker
+        )));
+    };
+
+    let start = trades.partition_point(|t| t.time < from);
+
+    Ok(trades[start..]
+        .iter()
+        .take_while(|t| t.time <= to)
+        .copied()
+        .collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_common_timestamp_formats() {
+        let ms = 1_700_000_000_123;
+
+        assert_eq!(parse_time("1700000000"), Some(1_700_000_000_000));
+        assert_eq!(parse_time("1700000000123"), Some(ms));
+        assert_eq!(parse_time("1700000000123456"), Some(ms));
+        assert_eq!(parse_time("1700000000123456789"), Some(ms));
+        assert_eq!(parse_time("2023-11-14T22:13:20.123Z"), Some(ms));
+        assert_eq!(parse_time("2023-11-14 22:13:20.123"), Some(ms));
+        assert_eq!(parse_time("price"), None);
+    }
+
+    fn parse_file(name: &str, contents: &str, mapping: &CsvMapping) -> (Vec<Parsed>, Precision) {
+        let path = std::env::temp_dir().join(format!("flowsurface-{name}-{}", std::process::id()));
+        std::fs::write(&path, contents).unwrap();
+        let parsed = parse_rows(&path, mapping);
+        std::fs::remove_file(&path).unwrap();
+        parsed.unwrap()
+    }
+
+    #[test]
+    fn infers_tick_from_price_moves_not_padding() {
+        // Binance pads prices to 8 decimals, the tick is 0.01
+        let klines = "\
+1672531200000,16541.77000000,16545.70000000,16508.39000000,16529.67000000,4364.83570000,1672531259999,72146933.65,4000,2200.12,36371000.12,0
+1672531260000,16529.59000000,16556.80000000,16529.58000000,16551.47000000,3364.26590000,1672531319999,55671342.19,3000,1700.50,28143000.45,0
+oops,1,2,3,4,5,6,7,8,9,10,11
+";
+        let (parsed, precision) = parse_file("klines.csv", klines, &CsvMapping::binance_klines());
+
+        assert_eq!(parsed.len(), 2);
+        assert_eq!(precision.min_ticksize(), MinTicksize::from_power(-2));
+        assert_eq!(precision.qty_decimals, 4);
+
+        let Parsed::Kline { ohlc, volume, .. } = &parsed[1] else {
+            panic!("expected a kline");
+        };
+        assert_eq!(ohlc[3].to_string(precision.min_ticksize()), "16551.47");
+        assert_eq!(volume.0.to_string(), "1700.5");
+        assert_eq!(volume.1.to_string(), "1663.7659");
+
+        // quarter ticks come out of the moves, whatever the decimals say
+        let trades = "\
+1,4312.25,1.5,10,10,1700000000000,false
+2,4312.75,0.5,11,11,1700000000001,true
+3,4311.50,2,12,12,1700000000002,false
+";
+        let (parsed, precision) =
+            parse_file("trades.csv", trades, &CsvMapping::binance_agg_trades());
+
+        let quarter: Price = "0.25".parse().unwrap();
+        assert_eq!(precision.min_ticksize().units, quarter.units);
+        let Parsed::Trade {
+            price,
+            qty,
+            is_sell,
+            ..
+        } = &parsed[1]
+        else {
+            panic!("expected a trade");
+        };
+        assert_eq!(*price, "4312.75".parse().unwrap());
+        assert_eq!(qty.to_string(), "0.5");
+        assert_eq!(*is_sell, Some(true));
+    }
+
+    #[test]
+    fn parses_prices_without_float_rounding() {
+        // past 2^24 ticks an f32 can't hold the cents any more
+        let price: Price = "9000000.01".parse().unwrap();
+        assert_eq!(price.units, 9_000_000_010_000_000_000);
+        assert_eq!(decimals("16590.21000000"), 2);
+        assert_eq!(decimals("100"), 0);
+    }
+}
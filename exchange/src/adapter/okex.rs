@@ -1,8 +1,8 @@
 use crate::{
-    OpenInterest, Price, PushFrequency, SizeUnit,
+    OpenInterest, Price, PushFrequency,
     adapter::{StreamKind, StreamTicksize},
     limiter::{self, RateLimiter},
-    volume_size_unit,
+    util::Qty,
 };
 
 use super::{
@@ -70,8 +70,8 @@ struct SonicTrade {
     pub time: u64,
     #[serde(rename = "px", deserialize_with = "de_string_to_f32")]
     pub price: f32,
-    #[serde(rename = "sz", deserialize_with = "de_string_to_f32")]
-    pub qty: f32,
+    #[serde(rename = "sz")]
+    pub qty: Qty,
     #[serde(rename = "side")]
     pub is_sell: String,
 }
@@ -208,7 +208,7 @@ pub fn connect_market_stream(
 
         let ticker = ticker_info.ticker;
 
-        let (symbol_str, market_type) = ticker.to_full_symbol_and_type();
+        let symbol_str = ticker.to_full_symbol_and_type().0;
         let exchange = ticker.exchange;
 
         let subscribe_message = serde_json::json!({
@@ -222,7 +222,6 @@ pub fn connect_market_stream(
         let mut trades_buffer: Vec<Trade> = vec![];
         let mut orderbook = LocalDepthCache::default();
 
-        let size_in_quote_ccy = volume_size_unit() == SizeUnit::Quote;
         let contract_size = ticker_info.contract_size.map(f32::from);
 
         loop {
@@ -239,13 +238,7 @@ pub fn connect_market_stream(
                                         for de_trade in &de_trade_vec {
                                             let price = Price::from_f32(de_trade.price)
                                                 .round_to_min_tick(ticker_info.min_ticksize);
-                                            let qty = calc_qty(
-                                                de_trade.qty,
-                                                de_trade.price,
-                                                size_in_quote_ccy,
-                                                contract_size,
-                                                market_type,
-                                            );
+                                            let qty = calc_qty(de_trade.qty, contract_size);
 
                                             let trade = Trade {
                                                 time: de_trade.time,
@@ -266,13 +259,7 @@ pub fn connect_market_stream(
                                                 .iter()
                                                 .map(|x| DeOrder {
                                                     price: x.price,
-                                                    qty: calc_qty(
-                                                        x.qty,
-                                                        x.price,
-                                                        size_in_quote_ccy,
-                                                        contract_size,
-                                                        market_type,
-                                                    ),
+                                                    qty: calc_qty(x.qty, contract_size),
                                                 })
                                                 .collect(),
                                             asks: de_depth
@@ -280,13 +267,7 @@ pub fn connect_market_stream(
                                                 .iter()
                                                 .map(|x| DeOrder {
                                                     price: x.price,
-                                                    qty: calc_qty(
-                                                        x.qty,
-                                                        x.price,
-                                                        size_in_quote_ccy,
-                                                        contract_size,
-                                                        market_type,
-                                                    ),
+                                                    qty: calc_qty(x.qty, contract_size),
                                                 })
                                                 .collect(),
                                         };
@@ -347,10 +328,7 @@ pub fn connect_market_stream(
     })
 }
 
-pub fn connect_kline_stream(
-    streams: Vec<(TickerInfo, Timeframe)>,
-    market_type: MarketKind,
-) -> impl Stream<Item = Event> {
+pub fn connect_kline_stream(streams: Vec<(TickerInfo, Timeframe)>) -> impl Stream<Item = Event> {
     stream::channel(100, async move |mut output| {
         let mut state = State::Disconnected;
 
@@ -380,8 +358,6 @@ pub fn connect_kline_stream(
             "args": args,
         });
 
-        let size_in_quote_ccy = volume_size_unit() == SizeUnit::Quote;
-
         loop {
             match &mut state {
                 State::Disconnected => {
@@ -434,7 +410,7 @@ pub fn connect_kline_stream(
                                         let volume = row
                                             .get(5)
                                             .and_then(|x| x.as_str())
-                                            .and_then(|s| s.parse::<f32>().ok());
+                                            .and_then(|s| s.parse::<Qty>().ok());
 
                                         let (ts, open, high, low, close) =
                                             match (time, open, high, low, close) {
@@ -449,15 +425,9 @@ pub fn connect_kline_stream(
                                             };
 
                                         let volume_in_display = if let Some(vq) = volume {
-                                            calc_qty(
-                                                vq,
-                                                close,
-                                                size_in_quote_ccy,
-                                                contract_size,
-                                                market_type,
-                                            )
+                                            calc_qty(vq, contract_size)
                                         } else {
-                                            0.0
+                                            Qty::ZERO
                                         };
 
                                         let kline = Kline::new(
@@ -466,7 +436,7 @@ pub fn connect_kline_stream(
                                             high,
                                             low,
                                             close,
-                                            (-1.0, volume_in_display),
+                                            (Kline::TOTAL_ONLY, volume_in_display),
                                             ticker_info.min_ticksize,
                                         );
                                         let _ = output
@@ -508,33 +478,8 @@ pub fn connect_kline_stream(
     })
 }
 
-fn calc_qty(
-    qty: f32,
-    price: f32,
-    size_in_quote_ccy: bool,
-    contract_size: Option<f32>,
-    market: MarketKind,
-) -> f32 {
-    let is_inverse = matches!(market, MarketKind::InversePerps);
-
-    match contract_size {
-        Some(cs) => {
-            if is_inverse {
-                if size_in_quote_ccy { qty * cs } else { qty }
-            } else if size_in_quote_ccy {
-                qty * cs * price
-            } else {
-                qty * cs
-            }
-        }
-        None => {
-            if size_in_quote_ccy {
-                qty * price
-            } else {
-                qty
-            }
-        }
-    }
+fn calc_qty(qty: Qty, contract_size: Option<f32>) -> Qty {
+    contract_size.map_or(qty, |cs| qty.scale_by(cs))
 }
 
 fn okx_inst_type(m: MarketKind) -> &'static str {
@@ -554,8 +499,13 @@ fn timeframe_to_okx_bar(tf: Timeframe) -> Option<&'static str> {
         Timeframe::H1 => "1H",
         Timeframe::H2 => "2H",
         Timeframe::H4 => "4H",
+        Timeframe::Custom(21_600) => "6Hutc",
         Timeframe::H12 => "12Hutc",
         Timeframe::D1 => "1Dutc",
+        Timeframe::Custom(172_800) => "2Dutc",
+        Timeframe::Custom(259_200) => "3Dutc",
+        Timeframe::W1 => "1Wutc",
+        Timeframe::MN1 => "1Mutc",
         _ => return None,
     })
 }
@@ -604,11 +554,8 @@ pub async fn fetch_ticksize(
         }
 
         let accept = match market_type {
-            MarketKind::Spot => item["quoteCcy"].as_str() == Some("USDT"),
-            MarketKind::LinearPerps => {
-                item["ctType"].as_str() == Some("linear")
-                    && (item["settleCcy"].as_str() == Some("USDT"))
-            }
+            MarketKind::Spot => true,
+            MarketKind::LinearPerps => item["ctType"].as_str() == Some("linear"),
             MarketKind::InversePerps => item["ctType"].as_str() == Some("inverse"),
         };
         if !accept {
@@ -633,8 +580,23 @@ pub async fn fetch_ticksize(
             item["ctVal"].as_str().and_then(|s| s.parse::<f32>().ok())
         };
 
+        // swaps only carry the pair in `instFamily`, e.g. "BTC-USD" for BTC-USD-SWAP
+        let (base_asset, quote_asset) = if market_type == MarketKind::Spot {
+            (
+                item["baseCcy"].as_str().unwrap_or_default(),
+                item["quoteCcy"].as_str().unwrap_or_default(),
+            )
+        } else {
+            item["instFamily"]
+                .as_str()
+                .and_then(|family| family.split_once('-'))
+                .unwrap_or_default()
+        };
+
         let ticker = Ticker::new(symbol, exchange);
-        let info = TickerInfo::new(ticker, min_ticksize, min_qty, contract_size);
+        let info = TickerInfo::new(ticker, min_ticksize, min_qty, contract_size)
+            .with_assets(base_asset, quote_asset)
+            .with_settlement(item["settleCcy"].as_str().unwrap_or_default());
 
         map.insert(ticker, Some(info));
     }
@@ -725,7 +687,7 @@ pub async fn fetch_klines(
 ) -> Result<Vec<Kline>, AdapterError> {
     let ticker = ticker_info.ticker;
 
-    let (symbol_str, market) = ticker.to_full_symbol_and_type();
+    let symbol_str = ticker.to_full_symbol_and_type().0;
     let contract_size = ticker_info.contract_size.map(f32::from);
 
     let bar = timeframe_to_okx_bar(timeframe).ok_or_else(|| {
@@ -754,8 +716,6 @@ pub async fn fetch_klines(
         .as_array()
         .ok_or_else(|| AdapterError::ParseError("Kline result is not an array".to_string()))?;
 
-    let size_in_quote_ccy = volume_size_unit() == SizeUnit::Quote;
-
     let mut klines: Vec<Kline> = Vec::with_capacity(list.len());
 
     for row in list {
@@ -782,16 +742,16 @@ pub async fn fetch_klines(
         let volume = row
             .get(5)
             .and_then(|v| v.as_str())
-            .and_then(|s| s.parse::<f32>().ok());
+            .and_then(|s| s.parse::<Qty>().ok());
 
         let (ts, open, high, low, close) = match (time, open, high, low, close) {
             (Some(ts), Some(o), Some(h), Some(l), Some(c)) => (ts, o, h, l, c),
             _ => continue,
         };
         let volume_in_display = if let Some(vq) = volume {
-            calc_qty(vq, close, size_in_quote_ccy, contract_size, market)
+            calc_qty(vq, contract_size)
         } else {
-            0.0
+            Qty::ZERO
         };
 
         let kline = Kline::new(
@@ -800,7 +760,7 @@ pub async fn fetch_klines(
             high,
             low,
             close,
-            (-1.0, volume_in_display),
+            (Kline::TOTAL_ONLY, volume_in_display),
             ticker_info.min_ticksize,
         );
 
@@ -811,6 +771,30 @@ pub async fn fetch_klines(
     Ok(klines)
 }
 
+/// Open interest right now, in the base currency like the history
+pub async fn fetch_current_oi(ticker: Ticker) -> Result<OpenInterest, AdapterError> {
+    let (ticker_str, _market) = ticker.to_full_symbol_and_type();
+
+    let url = format!(
+        "https://www.okx.com/api/v5/public/open-interest?instType=SWAP&instId={ticker_str}"
+    );
+
+    let doc: Value = limiter::http_parse_with_limiter(&url, &OKEX_LIMITER, 1, None, None).await?;
+
+    let entry = &doc["data"][0];
+    let time = entry["ts"].as_str().and_then(|ts| ts.parse::<u64>().ok());
+    let value = entry["oiCcy"]
+        .as_str()
+        .and_then(|oi| oi.parse::<f32>().ok());
+
+    match (time, value) {
+        (Some(time), Some(value)) => Ok(OpenInterest { time, value }),
+        _ => Err(AdapterError::ParseError(
+            "Open interest not found in response".to_string(),
+        )),
+    }
+}
+
 const TRADING_STATS_DOMAIN: &str = "https://www.okx.com/api/v5/rubik/stat";
 
 pub async fn fetch_historical_oi(
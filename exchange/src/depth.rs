@@ -1,4 +1,4 @@
-use crate::{MinTicksize, Price};
+use crate::{MinTicksize, Price, util::Qty};
 
 use serde::Deserializer;
 use serde::de::Error as SerdeError;
@@ -9,7 +9,7 @@ use std::{collections::BTreeMap, sync::Arc};
 #[derive(Clone, Copy)]
 pub struct DeOrder {
     pub price: f32,
-    pub qty: f32,
+    pub qty: Qty,
 }
 
 impl<'de> serde::Deserialize<'de> for DeOrder {
@@ -27,6 +27,13 @@ impl<'de> serde::Deserialize<'de> for DeOrder {
                 _ => None,
             }
         };
+        let parse_qty = |val: &Value| -> Option<Qty> {
+            match val {
+                Value::String(s) => s.parse::<Qty>().ok(),
+                Value::Number(n) => n.as_f64().map(Qty::from_f64),
+                _ => None,
+            }
+        };
 
         let price = match &value {
             Value::Array(arr) => arr.first().and_then(parse_f),
@@ -36,8 +43,8 @@ impl<'de> serde::Deserialize<'de> for DeOrder {
         .ok_or_else(|| SerdeError::custom("Order price not found or invalid"))?;
 
         let qty = match &value {
-            Value::Array(arr) => arr.get(1).and_then(parse_f),
-            Value::Object(map) => map.get("1").and_then(parse_f),
+            Value::Array(arr) => arr.get(1).and_then(parse_qty),
+            Value::Object(map) => map.get("1").and_then(parse_qty),
             _ => None,
         }
         .ok_or_else(|| SerdeError::custom("Order qty not found or invalid"))?;
@@ -48,7 +55,7 @@ impl<'de> serde::Deserialize<'de> for DeOrder {
 
 struct Order {
     price: Price,
-    qty: f32,
+    qty: Qty,
 }
 
 pub struct DepthPayload {
@@ -65,8 +72,8 @@ pub enum DepthUpdate {
 
 #[derive(Clone, Default)]
 pub struct Depth {
-    pub bids: BTreeMap<Price, f32>,
-    pub asks: BTreeMap<Price, f32>,
+    pub bids: BTreeMap<Price, Qty>,
+    pub asks: BTreeMap<Price, Qty>,
 }
 
 impl std::fmt::Debug for Depth {
@@ -85,7 +92,7 @@ impl Depth {
     }
 
     fn diff_price_levels(
-        price_map: &mut BTreeMap<Price, f32>,
+        price_map: &mut BTreeMap<Price, Qty>,
         orders: &[DeOrder],
         min_ticksize: MinTicksize,
     ) {
@@ -95,7 +102,7 @@ impl Depth {
                 qty: order.qty,
             };
 
-            if order.qty == 0.0 {
+            if order.qty.is_zero() {
                 price_map.remove(&order.price);
             } else {
                 price_map.insert(order.price, order.qty);
@@ -113,7 +120,7 @@ impl Depth {
                     de_order.qty,
                 )
             })
-            .collect::<BTreeMap<Price, f32>>();
+            .collect::<BTreeMap<Price, Qty>>();
         self.asks = snapshot
             .asks
             .iter()
@@ -123,12 +130,14 @@ impl Depth {
                     de_order.qty,
                 )
             })
-            .collect::<BTreeMap<Price, f32>>();
+            .collect::<BTreeMap<Price, Qty>>();
     }
 
     pub fn mid_price(&self) -> Option<Price> {
         match (self.asks.first_key_value(), self.bids.last_key_value()) {
-            (Some((ask_price, _)), Some((bid_price, _))) => Some((*ask_price + *bid_price) / 2),
+            (Some((ask_price, _)), Some((bid_price, _))) => {
+                Some(*bid_price + (*ask_price - *bid_price) / 2)
+            }
             _ => None,
         }
     }
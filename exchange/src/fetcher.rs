@@ -1,3 +1,7 @@
+mod coverage;
+
+pub use coverage::{Coverage, RangeSet, RangeState};
+
 use crate::adapter::StreamKind;
 use crate::{Kline, OpenInterest, Trade};
 
@@ -21,6 +25,7 @@ pub enum FetchedData {
     Trades {
         batch: Vec<Trade>,
         until_time: u64,
+        req_id: Option<uuid::Uuid>,
     },
     Klines {
         data: Vec<Kline>,
@@ -42,70 +47,106 @@ pub enum ReqError {
     Overlaps,
 }
 
-#[derive(PartialEq, Debug)]
-enum RequestStatus {
-    Pending,
-    Completed(u64),
-    Failed(String),
-}
-
+/// Keeps a [`Coverage`] map per fetch kind for a single stream
+///
+/// New requests are trimmed down to the sub-ranges that are neither loaded,
+/// pending nor recently failed, so overlapping requests only fetch what's missing.
 pub struct RequestHandler {
-    requests: HashMap<Uuid, FetchRequest>,
+    requests: HashMap<Uuid, FetchKind>,
+    coverage: HashMap<FetchKind, Coverage>,
 }
 
 impl RequestHandler {
     pub fn new() -> Self {
         RequestHandler {
             requests: HashMap::new(),
+            coverage: HashMap::new(),
         }
     }
 
-    pub fn add_request(&mut self, fetch: FetchRange) -> Result<Option<Uuid>, ReqError> {
-        let request = FetchRequest::new(fetch);
-        let id = Uuid::new_v4();
+    /// Registers the missing parts of `fetch` as pending and returns them with their ids
+    ///
+    /// Returns an empty list if the whole range is already loaded or being fetched,
+    /// and [`ReqError::Failed`] if the only uncovered part is a range that failed
+    /// within the retry cooldown.
+    pub fn add_request(&mut self, fetch: FetchRange) -> Result<Vec<(Uuid, FetchRange)>, ReqError> {
+        let (start, end) = fetch.bounds();
+        if start > end {
+            return Ok(vec![]);
+        }
+
+        let kind = fetch.kind();
+        let coverage = self.coverage.entry(kind).or_default();
+        coverage.retry_failed(chrono::Utc::now().timestamp_millis() as u64);
 
-        if let Some((existing_id, existing_req)) = self.requests.iter().find_map(|(k, v)| {
-            if v.same_with(&request) {
-                Some((*k, v))
-            } else {
-                None
-            }
-        }) {
-            return match &existing_req.status {
-                RequestStatus::Failed(error_msg) => Err(ReqError::Failed(error_msg.clone())),
-                RequestStatus::Completed(ts) => {
-                    // retry completed requests after a cooldown
-                    // to handle data source failures or outdated results gracefully
-                    if chrono::Utc::now().timestamp_millis() as u64 - ts > 30_000 {
-                        Ok(Some(existing_id))
-                    } else {
-                        Ok(None)
-                    }
-                }
-                RequestStatus::Pending => Err(ReqError::Overlaps),
+        let missing = coverage.missing(start, end);
+        if missing.is_empty() {
+            return match coverage.state_of(start, end) {
+                RangeState::Failed(error_msg) => Err(ReqError::Failed(error_msg)),
+                _ => Ok(vec![]),
             };
         }
 
-        self.requests.insert(id, request);
-        Ok(Some(id))
+        let mut requests = Vec::with_capacity(missing.len());
+        for (from, to) in missing {
+            let id = Uuid::new_v4();
+            coverage.mark_pending(id, from, to);
+            self.requests.insert(id, kind);
+            requests.push((id, fetch.with_bounds(from, to)));
+        }
+
+        Ok(requests)
     }
 
     pub fn mark_completed(&mut self, id: Uuid) {
-        if let Some(request) = self.requests.get_mut(&id) {
-            let timestamp = chrono::Utc::now().timestamp_millis() as u64;
-            request.status = RequestStatus::Completed(timestamp);
-        } else {
+        let marked = self
+            .requests
+            .remove(&id)
+            .and_then(|kind| self.coverage.get_mut(&kind))
+            .is_some_and(|coverage| coverage.mark_loaded(id));
+
+        if !marked {
             log::warn!("Request not found: {:?}", id);
         }
     }
 
     pub fn mark_failed(&mut self, id: Uuid, error: String) {
-        if let Some(request) = self.requests.get_mut(&id) {
-            request.status = RequestStatus::Failed(error);
-        } else {
+        let marked = self
+            .requests
+            .remove(&id)
+            .and_then(|kind| self.coverage.get_mut(&kind))
+            .is_some_and(|coverage| {
+                coverage.mark_failed(id, error, chrono::Utc::now().timestamp_millis() as u64)
+            });
+
+        if !marked {
             log::warn!("Request not found: {:?}", id);
         }
     }
+
+    /// Drops every pending request of `kind`, their ranges aren't requested again
+    /// until [`Self::clear_cancelled`] is called
+    pub fn cancel_pending(&mut self, kind: FetchKind) {
+        self.requests.retain(|id, k| {
+            if *k != kind {
+                return true;
+            }
+            if let Some(coverage) = self.coverage.get_mut(&kind) {
+                coverage.cancel(*id);
+            }
+            false
+        });
+    }
+
+    pub fn clear_cancelled(&mut self, kind: FetchKind) {
+        if let Some(coverage) = self.coverage.get_mut(&kind) {
+            coverage.clear_cancelled();
+        }
+    }
+
+    pub fn coverage(&self, kind: FetchKind) -> Option<&Coverage> {
+        self.coverage.get(&kind)
+    }
 }
 
 impl Default for RequestHandler {
@@ -121,31 +162,40 @@ pub enum FetchRange {
     Trades(u64, u64),
 }
 
-#[derive(PartialEq, Debug)]
-struct FetchRequest {
-    fetch_type: FetchRange,
-    status: RequestStatus,
-}
+impl FetchRange {
+    pub fn kind(&self) -> FetchKind {
+        match self {
+            FetchRange::Kline(..) => FetchKind::Kline,
+            FetchRange::OpenInterest(..) => FetchKind::OpenInterest,
+            FetchRange::Trades(..) => FetchKind::Trades,
+        }
+    }
 
-impl FetchRequest {
-    fn new(fetch_type: FetchRange) -> Self {
-        FetchRequest {
-            fetch_type,
-            status: RequestStatus::Pending,
+    /// Inclusive `(start, end)` timestamps in milliseconds
+    pub fn bounds(&self) -> (u64, u64) {
+        match *self {
+            FetchRange::Kline(start, end)
+            | FetchRange::OpenInterest(start, end)
+            | FetchRange::Trades(start, end) => (start, end),
         }
     }
 
-    fn same_with(&self, other: &FetchRequest) -> bool {
-        match (&self.fetch_type, &other.fetch_type) {
-            (FetchRange::Kline(s1, e1), FetchRange::Kline(s2, e2)) => e1 == e2 && s1 == s2,
-            (FetchRange::OpenInterest(s1, e1), FetchRange::OpenInterest(s2, e2)) => {
-                e1 == e2 && s1 == s2
-            }
-            _ => false,
+    pub fn with_bounds(&self, start: u64, end: u64) -> Self {
+        match self {
+            FetchRange::Kline(..) => FetchRange::Kline(start, end),
+            FetchRange::OpenInterest(..) => FetchRange::OpenInterest(start, end),
+            FetchRange::Trades(..) => FetchRange::Trades(start, end),
         }
     }
 }
 
+#[derive(PartialEq, Eq, Hash, Debug, Clone, Copy)]
+pub enum FetchKind {
+    Kline,
+    OpenInterest,
+    Trades,
+}
+
 pub struct FetchSpec {
     pub req_id: uuid::Uuid,
     pub fetch: FetchRange,
@@ -189,4 +239,37 @@ pub enum InfoKind {
     FetchingKlines,
     FetchingTrades(usize),
     FetchingOI,
+    DownloadingArchive { downloaded: u64, total: Option<u64> },
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn cancelled_ranges_stay_blocked_until_cleared() {
+        let mut handler = RequestHandler::new();
+
+        let requests = handler.add_request(FetchRange::Trades(0, 999)).unwrap();
+        assert_eq!(requests.len(), 1);
+        assert!(
+            handler
+                .add_request(FetchRange::Trades(0, 999))
+                .unwrap()
+                .is_empty()
+        );
+
+        handler.cancel_pending(FetchKind::Trades);
+        assert!(
+            handler
+                .add_request(FetchRange::Trades(0, 999))
+                .unwrap()
+                .is_empty()
+        );
+
+        handler.clear_cancelled(FetchKind::Trades);
+        let retried = handler.add_request(FetchRange::Trades(0, 999)).unwrap();
+        assert_eq!(retried.len(), 1);
+        assert_eq!(retried[0].1, FetchRange::Trades(0, 999));
+    }
 }
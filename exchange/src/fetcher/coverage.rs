@@ -0,0 +1,344 @@
+use std::collections::HashMap;
+use uuid::Uuid;
+
+/// Sorted set of disjoint, inclusive `[start, end]` millisecond ranges
+///
+/// Overlapping and adjacent ranges are merged on insert, so the set always holds
+/// the smallest number of spans describing the covered time.
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct RangeSet {
+    spans: Vec<(u64, u64)>,
+}
+
+impl RangeSet {
+    pub fn new() -> Self {
+        Self { spans: Vec::new() }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.spans.is_empty()
+    }
+
+    pub fn spans(&self) -> &[(u64, u64)] {
+        &self.spans
+    }
+
+    pub fn insert(&mut self, start: u64, end: u64) {
+        if start > end {
+            return;
+        }
+
+        let mut merged = (start, end);
+
+        // first span that could touch the new one, adjacency included
+        let first = self
+            .spans
+            .partition_point(|&(_, e)| e.saturating_add(1) < start);
+        let mut last = first;
+
+        while last < self.spans.len() && self.spans[last].0 <= end.saturating_add(1) {
+            merged.0 = merged.0.min(self.spans[last].0);
+            merged.1 = merged.1.max(self.spans[last].1);
+            last += 1;
+        }
+
+        self.spans.splice(first..last, [merged]);
+    }
+
+    pub fn remove(&mut self, start: u64, end: u64) {
+        if start > end {
+            return;
+        }
+
+        let mut kept = Vec::with_capacity(self.spans.len() + 1);
+
+        for &(s, e) in &self.spans {
+            if e < start || s > end {
+                kept.push((s, e));
+                continue;
+            }
+            if s < start {
+                kept.push((s, start - 1));
+            }
+            if e > end {
+                kept.push((end + 1, e));
+            }
+        }
+
+        self.spans = kept;
+    }
+
+    pub fn contains(&self, start: u64, end: u64) -> bool {
+        start <= end && self.gaps_within(start, end).is_empty()
+    }
+
+    pub fn overlaps(&self, start: u64, end: u64) -> bool {
+        start <= end && self.spans.iter().any(|&(s, e)| s <= end && e >= start)
+    }
+
+    /// Sub-ranges of `[start, end]` that are not covered by this set
+    pub fn gaps_within(&self, start: u64, end: u64) -> Vec<(u64, u64)> {
+        if start > end {
+            return vec![];
+        }
+
+        let mut gaps = vec![];
+        let mut cursor = start;
+
+        for &(s, e) in &self.spans {
+            if e < cursor {
+                continue;
+            }
+            if s > end {
+                break;
+            }
+            if s > cursor {
+                gaps.push((cursor, s - 1));
+            }
+            if e >= end {
+                return gaps;
+            }
+            cursor = e + 1;
+        }
+
+        gaps.push((cursor, end));
+        gaps
+    }
+
+    pub fn union(&self, other: &RangeSet) -> RangeSet {
+        let mut out = self.clone();
+        for &(s, e) in &other.spans {
+            out.insert(s, e);
+        }
+        out
+    }
+}
+
+/// How long failed ranges are left alone before they count as missing again
+const FAILED_RETRY_COOLDOWN_MS: u64 = 30_000;
+
+/// State of a time range in a [`Coverage`] map
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum RangeState {
+    Loaded,
+    Pending,
+    Failed(String),
+    Cancelled,
+    Missing,
+}
+
+/// A range that failed to load, with when and why
+#[derive(Debug, Clone)]
+struct FailedRange {
+    spans: RangeSet,
+    failed_at: u64,
+    error: String,
+}
+
+/// Tracks which time ranges of a single stream and fetch kind are loaded,
+/// currently being fetched, cancelled or known to have failed
+#[derive(Debug, Default, Clone)]
+pub struct Coverage {
+    loaded: RangeSet,
+    pending: HashMap<Uuid, (u64, u64)>,
+    failed: Vec<FailedRange>,
+    cancelled: RangeSet,
+}
+
+impl Coverage {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn loaded(&self) -> &RangeSet {
+        &self.loaded
+    }
+
+    pub fn has_pending(&self) -> bool {
+        !self.pending.is_empty()
+    }
+
+    /// Minimal sub-ranges of `[start, end]` that are neither loaded, pending, cancelled nor failed
+    pub fn missing(&self, start: u64, end: u64) -> Vec<(u64, u64)> {
+        self.known().gaps_within(start, end)
+    }
+
+    pub fn state_of(&self, start: u64, end: u64) -> RangeState {
+        if self.loaded.contains(start, end) {
+            return RangeState::Loaded;
+        }
+        if !self.missing(start, end).is_empty() {
+            return RangeState::Missing;
+        }
+        if let Some(failed) = self
+            .failed
+            .iter()
+            .rev()
+            .find(|failed| failed.spans.overlaps(start, end))
+        {
+            return RangeState::Failed(failed.error.clone());
+        }
+        if self.cancelled.overlaps(start, end) {
+            return RangeState::Cancelled;
+        }
+        RangeState::Pending
+    }
+
+    pub fn mark_pending(&mut self, id: Uuid, start: u64, end: u64) {
+        self.pending.insert(id, (start, end));
+    }
+
+    /// Moves a pending range into the loaded set, returns `false` if `id` is unknown
+    pub fn mark_loaded(&mut self, id: Uuid) -> bool {
+        let Some((start, end)) = self.pending.remove(&id) else {
+            return false;
+        };
+        self.remove_failed(start, end);
+        self.loaded.insert(start, end);
+        true
+    }
+
+    /// Moves a pending range into the failed set, returns `false` if `id` is unknown
+    pub fn mark_failed(&mut self, id: Uuid, error: String, now: u64) -> bool {
+        let Some((start, end)) = self.pending.remove(&id) else {
+            return false;
+        };
+        let mut spans = RangeSet::new();
+        spans.insert(start, end);
+        self.failed.push(FailedRange {
+            spans,
+            failed_at: now,
+            error,
+        });
+        true
+    }
+
+    /// Forgets each failed range once the cooldown since its own failure has passed
+    pub fn retry_failed(&mut self, now: u64) {
+        self.failed
+            .retain(|failed| now.saturating_sub(failed.failed_at) < FAILED_RETRY_COOLDOWN_MS);
+    }
+
+    /// Moves a pending range into the cancelled set, returns `false` if `id` is unknown
+    pub fn cancel(&mut self, id: Uuid) -> bool {
+        let Some((start, end)) = self.pending.remove(&id) else {
+            return false;
+        };
+        self.cancelled.insert(start, end);
+        true
+    }
+
+    /// Lets cancelled ranges be requested again
+    pub fn clear_cancelled(&mut self) {
+        self.cancelled = RangeSet::new();
+    }
+
+    /// Directly records a range as loaded, e.g. when data arrived from a live stream
+    pub fn insert_loaded(&mut self, start: u64, end: u64) {
+        self.remove_failed(start, end);
+        self.loaded.insert(start, end);
+    }
+
+    fn remove_failed(&mut self, start: u64, end: u64) {
+        for failed in &mut self.failed {
+            failed.spans.remove(start, end);
+        }
+        self.failed.retain(|failed| !failed.spans.is_empty());
+    }
+
+    fn known(&self) -> RangeSet {
+        let mut known = self.loaded.union(&self.cancelled);
+        for failed in &self.failed {
+            known = known.union(&failed.spans);
+        }
+        for &(s, e) in self.pending.values() {
+            known.insert(s, e);
+        }
+        known
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn insert_merges_overlapping_and_adjacent() {
+        let mut set = RangeSet::new();
+        set.insert(10, 20);
+        set.insert(30, 40);
+        set.insert(21, 25);
+        assert_eq!(set.spans(), &[(10, 25), (30, 40)]);
+
+        set.insert(5, 35);
+        assert_eq!(set.spans(), &[(5, 40)]);
+    }
+
+    #[test]
+    fn remove_splits_spans() {
+        let mut set = RangeSet::new();
+        set.insert(0, 100);
+        set.remove(40, 60);
+        assert_eq!(set.spans(), &[(0, 39), (61, 100)]);
+    }
+
+    #[test]
+    fn gaps_are_minimal() {
+        let mut set = RangeSet::new();
+        set.insert(10, 20);
+        set.insert(30, 40);
+
+        assert_eq!(set.gaps_within(0, 50), vec![(0, 9), (21, 29), (41, 50)]);
+        assert_eq!(set.gaps_within(12, 18), vec![]);
+        assert_eq!(set.gaps_within(15, 35), vec![(21, 29)]);
+    }
+
+    #[test]
+    fn coverage_excludes_pending_and_failed() {
+        let mut cov = Coverage::new();
+        let loaded = Uuid::new_v4();
+        let failed = Uuid::new_v4();
+
+        cov.mark_pending(loaded, 100, 200);
+        cov.mark_pending(failed, 0, 49);
+        assert_eq!(cov.missing(0, 300), vec![(50, 99), (201, 300)]);
+
+        cov.mark_loaded(loaded);
+        cov.mark_failed(failed, "no data".to_string(), 0);
+        assert_eq!(cov.state_of(120, 180), RangeState::Loaded);
+        assert_eq!(
+            cov.state_of(0, 49),
+            RangeState::Failed("no data".to_string())
+        );
+        assert_eq!(cov.missing(0, 300), vec![(50, 99), (201, 300)]);
+    }
+
+    #[test]
+    fn failed_ranges_are_retried_after_a_cooldown() {
+        let mut cov = Coverage::new();
+        let id = Uuid::new_v4();
+
+        cov.mark_pending(id, 0, 99);
+        cov.mark_failed(id, "timeout".to_string(), 1_000);
+
+        cov.retry_failed(1_000 + FAILED_RETRY_COOLDOWN_MS - 1);
+        assert!(cov.missing(0, 99).is_empty());
+
+        // a later failure doesn't hold back the earlier one
+        let later = Uuid::new_v4();
+        cov.mark_pending(later, 200, 299);
+        cov.mark_failed(
+            later,
+            "timeout".to_string(),
+            1_000 + FAILED_RETRY_COOLDOWN_MS - 1,
+        );
+
+        cov.retry_failed(1_000 + FAILED_RETRY_COOLDOWN_MS);
+        assert_eq!(cov.missing(0, 99), vec![(0, 99)]);
+        assert_eq!(cov.state_of(0, 99), RangeState::Missing);
+        assert!(cov.missing(200, 299).is_empty());
+
+        cov.retry_failed(1_000 + 2 * FAILED_RETRY_COOLDOWN_MS - 1);
+        assert_eq!(cov.missing(200, 299), vec![(200, 299)]);
+    }
+}
@@ -5,42 +5,40 @@ pub mod fetcher;
 mod limiter;
 pub mod util;
 
-use crate::util::{ContractSize, MinQtySize, MinTicksize, Price};
+use crate::util::{ContractSize, MinQtySize, MinTicksize, Price, PriceStep, Qty};
 pub use adapter::Event;
 use adapter::{Exchange, MarketKind, StreamKind};
 
 use serde::{Deserialize, Deserializer, Serialize};
 use serde_json::Value;
 
-use std::sync::atomic::{AtomicU8, Ordering};
-use std::{fmt, hash::Hash};
+use chrono::Datelike;
+use std::{fmt, hash::Hash, str::FromStr};
 
 /// Unit for displaying volume/quantity size values.
 ///
 /// - `Base`: Display in base asset units (e.g., BTC for BTCUSDT)
 /// - `Quote`: Display in quote currency value (e.g., USD/USDT equivalent)
 ///
-/// Note: Only applies to linear perpetuals and spot markets.
+/// Adapters always emit base quantities; this is a per-pane display choice applied when rendering.
 /// Inverse perpetuals always display in USD regardless of this setting.
-#[repr(u8)]
 #[derive(Default, Copy, Clone, Debug, Eq, PartialEq, Hash, Deserialize, Serialize)]
 pub enum SizeUnit {
-    Base = 0,
+    Base,
     #[default]
-    Quote = 1,
+    Quote,
 }
 
-static SIZE_CALC_UNIT: AtomicU8 = AtomicU8::new(SizeUnit::Base as u8);
-
-pub fn set_preferred_currency(v: SizeUnit) {
-    SIZE_CALC_UNIT.store(v as u8, Ordering::Relaxed);
+impl SizeUnit {
+    pub const ALL: [SizeUnit; 2] = [SizeUnit::Base, SizeUnit::Quote];
 }
 
-pub fn volume_size_unit() -> SizeUnit {
-    match SIZE_CALC_UNIT.load(Ordering::Relaxed) {
-        0 => SizeUnit::Base,
-        1 => SizeUnit::Quote,
-        _ => SizeUnit::Base,
+impl fmt::Display for SizeUnit {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            SizeUnit::Base => write!(f, "Base"),
+            SizeUnit::Quote => write!(f, "Quote"),
+        }
     }
 }
 
@@ -67,31 +65,74 @@ impl std::fmt::Display for PushFrequency {
 
 impl std::fmt::Display for Timeframe {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        write!(
-            f,
-            "{}",
-            match self {
-                Timeframe::MS100 => "100ms",
-                Timeframe::MS200 => "200ms",
-                Timeframe::MS300 => "300ms",
-                Timeframe::MS500 => "500ms",
-                Timeframe::MS1000 => "1s",
-                Timeframe::M1 => "1m",
-                Timeframe::M3 => "3m",
-                Timeframe::M5 => "5m",
-                Timeframe::M15 => "15m",
-                Timeframe::M30 => "30m",
-                Timeframe::H1 => "1h",
-                Timeframe::H2 => "2h",
-                Timeframe::H4 => "4h",
-                Timeframe::H12 => "12h",
-                Timeframe::D1 => "1d",
+        let label = match self {
+            Timeframe::MS100 => "100ms",
+            Timeframe::MS200 => "200ms",
+            Timeframe::MS300 => "300ms",
+            Timeframe::MS500 => "500ms",
+            Timeframe::MS1000 => "1s",
+            Timeframe::M1 => "1m",
+            Timeframe::M3 => "3m",
+            Timeframe::M5 => "5m",
+            Timeframe::M15 => "15m",
+            Timeframe::M30 => "30m",
+            Timeframe::H1 => "1h",
+            Timeframe::H2 => "2h",
+            Timeframe::H4 => "4h",
+            Timeframe::H12 => "12h",
+            Timeframe::D1 => "1d",
+            Timeframe::W1 => "1w",
+            Timeframe::MN1 => "1M",
+            Timeframe::Custom(secs) => {
+                let (count, unit) = Timeframe::UNITS
+                    .iter()
+                    .rev()
+                    .find(|(_, unit_secs)| secs.is_multiple_of(*unit_secs))
+                    .map_or((*secs, 's'), |(unit, unit_secs)| (secs / unit_secs, *unit));
+                return write!(f, "{count}{unit}");
             }
-        )
+        };
+        write!(f, "{label}")
     }
 }
 
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Deserialize, Serialize, PartialOrd, Ord)]
+impl FromStr for Timeframe {
+    type Err = String;
+
+    /// Parses intervals like "10m", "6h", "3d", "2w" or "1M"
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let s = s.trim();
+        if s == "1M" {
+            return Ok(Timeframe::MN1);
+        }
+
+        let split = s
+            .find(|c: char| !c.is_ascii_digit())
+            .ok_or_else(|| format!("Missing unit in timeframe: {s}"))?;
+        let (count, unit) = s.split_at(split);
+
+        let count: u32 = count
+            .parse()
+            .map_err(|_| format!("Invalid timeframe: {s}"))?;
+        let unit_secs = match unit {
+            "m" => 60,
+            "h" => 3_600,
+            "d" => 86_400,
+            "w" => 604_800,
+            _ => return Err(format!("Invalid timeframe unit: {unit}")),
+        };
+
+        count
+            .checked_mul(unit_secs)
+            .filter(|secs| (60..=Timeframe::MAX_CUSTOM_SECS).contains(secs))
+            .map(Timeframe::from_seconds)
+            .ok_or_else(|| format!("Timeframe out of range: {s}"))
+    }
+}
+
+/// `Custom` holds a user-defined interval in seconds, build it with [`Timeframe::from_seconds`]
+/// so that intervals matching a named variant don't end up as distinct timeframes
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Deserialize, Serialize)]
 pub enum Timeframe {
     MS100,
     MS200,
@@ -108,10 +149,26 @@ pub enum Timeframe {
     H4,
     H12,
     D1,
+    W1,
+    MN1,
+    Custom(#[serde(deserialize_with = "deserialize_custom_secs")] u32),
+}
+
+/// Buckets are aligned with `time % interval`, a zero length interval has none
+fn deserialize_custom_secs<'de, D>(deserializer: D) -> Result<u32, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    match u32::deserialize(deserializer)? {
+        0 => Err(serde::de::Error::custom(
+            "Custom timeframe must be at least 1 second",
+        )),
+        secs => Ok(secs),
+    }
 }
 
 impl Timeframe {
-    pub const KLINE: [Timeframe; 10] = [
+    pub const KLINE: [Timeframe; 12] = [
         Timeframe::M1,
         Timeframe::M3,
         Timeframe::M5,
@@ -122,6 +179,16 @@ impl Timeframe {
         Timeframe::H4,
         Timeframe::H12,
         Timeframe::D1,
+        Timeframe::W1,
+        Timeframe::MN1,
+    ];
+
+    /// Built from trades on the client, venues don't stream klines this short
+    pub const SUB_MINUTE: [Timeframe; 4] = [
+        Timeframe::MS1000,
+        Timeframe::Custom(5),
+        Timeframe::Custom(15),
+        Timeframe::Custom(30),
     ];
 
     pub const HEATMAP: [Timeframe; 5] = [
@@ -132,25 +199,46 @@ impl Timeframe {
         Timeframe::MS1000,
     ];
 
-    /// # Panics
-    ///
-    /// Will panic if the `Timeframe` is not one of the defined variants
-    pub fn to_minutes(self) -> u16 {
-        match self {
-            Timeframe::M1 => 1,
-            Timeframe::M3 => 3,
-            Timeframe::M5 => 5,
-            Timeframe::M15 => 15,
-            Timeframe::M30 => 30,
-            Timeframe::H1 => 60,
-            Timeframe::H2 => 120,
-            Timeframe::H4 => 240,
-            Timeframe::H12 => 720,
-            Timeframe::D1 => 1440,
-            _ => panic!("Invalid timeframe: {:?}", self),
-        }
+    /// Periods venues report open interest history at, shortest first
+    pub const OPEN_INTEREST: [Timeframe; 6] = [
+        Timeframe::M5,
+        Timeframe::M15,
+        Timeframe::M30,
+        Timeframe::H1,
+        Timeframe::H4,
+        Timeframe::D1,
+    ];
+
+    /// Units used to display custom intervals, smallest first
+    const UNITS: [(char, u32); 5] = [
+        ('s', 1),
+        ('m', 60),
+        ('h', 3_600),
+        ('d', 86_400),
+        ('w', 604_800),
+    ];
+
+    const MAX_CUSTOM_SECS: u32 = 52 * 604_800;
+
+    const WEEK_MS: u64 = 7 * 86_400_000;
+    /// The unix epoch is a Thursday, weekly buckets start on Mondays
+    const WEEK_OFFSET_MS: u64 = 4 * 86_400_000;
+
+    /// Named variant for `secs` if there is one, `Custom` otherwise
+    pub fn from_seconds(secs: u32) -> Self {
+        Self::KLINE
+            .into_iter()
+            .chain(Self::HEATMAP)
+            .find(|tf| *tf != Timeframe::MN1 && tf.to_milliseconds() == u64::from(secs) * 1_000)
+            .unwrap_or(Timeframe::Custom(secs))
+    }
+
+    /// Whole minutes in the interval, a month counts as 30 days
+    pub fn to_minutes(self) -> u32 {
+        (self.to_milliseconds() / 60_000) as u32
     }
 
+    /// Nominal length of the interval, a month counts as 30 days
     pub fn to_milliseconds(self) -> u64 {
         match self {
             Timeframe::MS100 => 100,
@@ -158,12 +246,111 @@ impl Timeframe {
             Timeframe::MS300 => 300,
             Timeframe::MS500 => 500,
             Timeframe::MS1000 => 1_000,
+            Timeframe::M1 => 60_000,
+            Timeframe::M3 => 3 * 60_000,
+            Timeframe::M5 => 5 * 60_000,
+            Timeframe::M15 => 15 * 60_000,
+            Timeframe::M30 => 30 * 60_000,
+            Timeframe::H1 => 3_600_000,
+            Timeframe::H2 => 2 * 3_600_000,
+            Timeframe::H4 => 4 * 3_600_000,
+            Timeframe::H12 => 12 * 3_600_000,
+            Timeframe::D1 => 86_400_000,
+            Timeframe::W1 => Self::WEEK_MS,
+            Timeframe::MN1 => 30 * 86_400_000,
+            Timeframe::Custom(secs) => u64::from(secs) * 1_000,
+        }
+    }
+
+    /// Longest open interest period that fits in a bar of this timeframe,
+    /// the shortest one for anything finer
+    pub fn open_interest_period(self) -> Timeframe {
+        Self::OPEN_INTEREST
+            .into_iter()
+            .rev()
+            .find(|period| period.to_milliseconds() <= self.to_milliseconds())
+            .unwrap_or(Timeframe::M5)
+    }
+
+    /// Weekly multiples are aligned to Mondays instead of the epoch
+    pub fn is_weekly(self) -> bool {
+        self != Timeframe::MN1 && self.to_milliseconds().is_multiple_of(Self::WEEK_MS)
+    }
+
+    /// Open time of the bucket containing `time`, in UTC
+    pub fn bucket_start(self, time: u64) -> u64 {
+        match self {
+            Timeframe::MN1 => chrono::DateTime::from_timestamp_millis(time as i64)
+                .and_then(|dt| {
+                    dt.date_naive()
+                        .with_day(1)?
+                        .and_hms_opt(0, 0, 0)
+                        .map(|start| start.and_utc().timestamp_millis() as u64)
+                })
+                .unwrap_or(time),
             _ => {
-                let minutes = self.to_minutes();
-                u64::from(minutes) * 60_000
+                let interval = self.to_milliseconds();
+                let offset = if self.is_weekly() {
+                    Self::WEEK_OFFSET_MS
+                } else {
+                    0
+                };
+                let shifted = time + interval - offset;
+                // the week of the epoch opened before it, clamped to the epoch
+                (shifted - shifted % interval + offset).saturating_sub(interval)
             }
         }
     }
+
+    /// Open time of the bucket following the one opened at `start`
+    pub fn next_bucket_start(self, start: u64) -> u64 {
+        match self {
+            Timeframe::MN1 => chrono::DateTime::from_timestamp_millis(start as i64)
+                .and_then(|dt| dt.checked_add_months(chrono::Months::new(1)))
+                .map_or(start + self.to_milliseconds(), |dt| {
+                    self.bucket_start(dt.timestamp_millis() as u64)
+                }),
+            _ => self.bucket_start(start) + self.to_milliseconds(),
+        }
+    }
+
+    /// Whether buckets of `self` are made of whole buckets of `base`
+    pub fn resamples_from(self, base: Timeframe) -> bool {
+        let (interval, base_interval) = (self.to_milliseconds(), base.to_milliseconds());
+
+        match (self, base) {
+            (_, Timeframe::MN1) => self == Timeframe::MN1,
+            (Timeframe::MN1, _) => Timeframe::D1
+                .to_milliseconds()
+                .is_multiple_of(base_interval),
+            _ if base.is_weekly() => self.is_weekly() && interval.is_multiple_of(base_interval),
+            _ => interval.is_multiple_of(base_interval),
+        }
+    }
+}
+
+impl PartialOrd for Timeframe {
+    fn partial_cmp(&self, other: &Self) -> Option<std::cmp::Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+/// Ordered by length, named variants before an equally long `Custom`
+impl Ord for Timeframe {
+    fn cmp(&self, other: &Self) -> std::cmp::Ordering {
+        let key = |tf: &Timeframe| {
+            (
+                tf.to_milliseconds(),
+                matches!(tf, Timeframe::Custom(_)),
+                *tf == Timeframe::MN1,
+                match tf {
+                    Timeframe::Custom(secs) => *secs,
+                    _ => 0,
+                },
+            )
+        };
+        key(self).cmp(&key(other))
+    }
 }
 
 impl From<Timeframe> for f32 {
@@ -222,6 +409,7 @@ impl SerTicker {
             Exchange::OkexLinear => "OkexLinear",
             Exchange::OkexInverse => "OkexInverse",
             Exchange::OkexSpot => "OkexSpot",
+            Exchange::Local => "Local",
         }
     }
 
@@ -238,6 +426,7 @@ impl SerTicker {
             "OkexLinear" => Ok(Exchange::OkexLinear),
             "OkexInverse" => Ok(Exchange::OkexInverse),
             "OkexSpot" => Ok(Exchange::OkexSpot),
+            "Local" => Ok(Exchange::Local),
             _ => Err(format!("Unknown exchange: {}", s)),
         }
     }
@@ -530,6 +719,161 @@ impl<'de> Deserialize<'de> for Ticker {
     }
 }
 
+/// Asset code such as "BTC" or "USDT", kept inline so [`TickerInfo`] stays `Copy`
+///
+/// Codes that aren't ASCII or exceed [`Asset::MAX_LEN`] end up as the empty (unknown) asset.
+/// Hyperliquid's `k` prefix for contracts of a thousand units is spelled out, `kPEPE` is
+/// kept as `1000PEPE` since codes are uppercased and `KPEPE` could be an asset of its own
+#[derive(Default, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct Asset {
+    bytes: [u8; Asset::MAX_LEN],
+}
+
+impl Asset {
+    pub const MAX_LEN: usize = 16;
+
+    pub fn new(code: &str) -> Self {
+        let thousands = code
+            .strip_prefix('k')
+            .filter(|rest| rest.starts_with(|c: char| c.is_ascii_uppercase()))
+            .map(|rest| format!("1000{rest}"));
+        let code = thousands.as_deref().unwrap_or(code);
+
+        let mut bytes = [0u8; Self::MAX_LEN];
+
+        if code.is_ascii() && code.len() <= Self::MAX_LEN {
+            bytes[..code.len()].copy_from_slice(code.to_ascii_uppercase().as_bytes());
+        } else {
+            log::warn!("Unsupported asset code: {code:?}");
+        }
+
+        Self { bytes }
+    }
+
+    pub fn as_str(&self) -> &str {
+        let end = self
+            .bytes
+            .iter()
+            .position(|&b| b == 0)
+            .unwrap_or(Self::MAX_LEN);
+        std::str::from_utf8(&self.bytes[..end]).unwrap_or_default()
+    }
+
+    pub fn is_unknown(&self) -> bool {
+        self.bytes[0] == 0
+    }
+
+    /// Venue-independent form of the code: USD stablecoins fold into `USD`, `XBT` into `BTC`
+    /// and multiplied contracts like `1000PEPE` into their underlying
+    pub fn canonical(self) -> Self {
+        let code = self.as_str();
+
+        if Self::USD_EQUIVALENTS.contains(&code) {
+            return Asset::new("USD");
+        }
+        if code == "XBT" {
+            return Asset::new("BTC");
+        }
+
+        let digits = code.bytes().take_while(u8::is_ascii_digit).count();
+        let multiplier = &code[..digits];
+        if digits >= 4
+            && digits < code.len()
+            && multiplier.starts_with('1')
+            && multiplier[1..].bytes().all(|b| b == b'0')
+        {
+            return Asset::new(&code[digits..]);
+        }
+
+        self
+    }
+
+    /// Whether the code is USD or a USD stablecoin
+    pub fn is_usd(self) -> bool {
+        Self::USD_EQUIVALENTS.contains(&self.as_str())
+    }
+
+    const USD_EQUIVALENTS: [&str; 8] = [
+        "USD", "USDT", "USDC", "FDUSD", "BUSD", "TUSD", "USDE", "USDP",
+    ];
+
+    /// Quote assets tried, in order, when a symbol has to be split without metadata
+    const COMMON_QUOTES: [&str; 11] = [
+        "FDUSD", "USDT", "USDC", "BUSD", "USD", "EUR", "TRY", "BRL", "BTC", "ETH", "BNB",
+    ];
+}
+
+impl fmt::Display for Asset {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl fmt::Debug for Asset {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "Asset({})", self.as_str())
+    }
+}
+
+impl Serialize for Asset {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer,
+    {
+        serializer.serialize_str(self.as_str())
+    }
+}
+
+impl<'de> Deserialize<'de> for Asset {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: serde::Deserializer<'de>,
+    {
+        let code = String::deserialize(deserializer)?;
+        Ok(Asset::new(&code))
+    }
+}
+
+/// Venue-independent identity of a market, built from each adapter's metadata
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Instrument {
+    pub base: Asset,
+    pub quote: Asset,
+    pub settlement: Asset,
+    pub kind: MarketKind,
+}
+
+impl Instrument {
+    /// Same underlying pair, regardless of instrument type or settlement asset
+    pub fn same_underlying(&self, other: &Instrument) -> bool {
+        self.base.canonical() == other.base.canonical()
+            && self.quote.canonical() == other.quote.canonical()
+    }
+
+    /// Same pair and instrument type, e.g. BTCUSDT on Binance and BTC-USDT-SWAP on OKX
+    pub fn same_market(&self, other: &Instrument) -> bool {
+        self.kind == other.kind && self.same_underlying(other)
+    }
+
+    /// Canonical pair without separators, e.g. "BTCUSD" for both BTCUSDT and BTC-USDC-SWAP
+    pub fn canonical_pair(&self) -> String {
+        format!("{}{}", self.base.canonical(), self.quote.canonical())
+    }
+
+    /// How well `candidate` stands in for this market, higher is closer
+    fn match_score(&self, candidate: &Instrument) -> Option<u8> {
+        if !self.same_underlying(candidate) {
+            return None;
+        }
+
+        Some(
+            u8::from(self.kind == candidate.kind) * 4
+                + u8::from(self.quote == candidate.quote) * 2
+                + u8::from(self.base == candidate.base),
+        )
+    }
+}
+
 pub enum StreamPairKind {
     SingleSource(TickerInfo),
     MultiSource(Vec<TickerInfo>),
@@ -542,6 +886,12 @@ pub struct TickerInfo {
     pub min_ticksize: MinTicksize,
     pub min_qty: MinQtySize,
     pub contract_size: Option<ContractSize>,
+    #[serde(default)]
+    pub base: Asset,
+    #[serde(default)]
+    pub quote: Asset,
+    #[serde(default)]
+    pub settlement: Asset,
 }
 
 impl TickerInfo {
@@ -556,7 +906,95 @@ impl TickerInfo {
             min_ticksize: MinTicksize::from(min_ticksize),
             min_qty: MinQtySize::from(min_qty),
             contract_size: contract_size.map(ContractSize::from),
+            base: Asset::default(),
+            quote: Asset::default(),
+            settlement: Asset::default(),
+        }
+    }
+
+    /// Inverse contracts settle in the base asset, everything else in the quote
+    /// unless the venue says otherwise through [`TickerInfo::with_settlement`]
+    pub fn with_assets(mut self, base: &str, quote: &str) -> Self {
+        self.base = Asset::new(base);
+        self.quote = Asset::new(quote);
+        self.settlement = match self.market_type() {
+            MarketKind::InversePerps => self.base,
+            MarketKind::Spot | MarketKind::LinearPerps => self.quote,
+        };
+        self
+    }
+
+    pub fn with_settlement(mut self, settlement: &str) -> Self {
+        if !settlement.is_empty() {
+            self.settlement = Asset::new(settlement);
         }
+        self
+    }
+
+    /// Venue-independent identity, guessed from the symbol when the venue gave no assets,
+    /// e.g. for imported datasets or metadata cached by older versions
+    pub fn instrument(&self) -> Option<Instrument> {
+        let kind = self.market_type();
+
+        if !self.base.is_unknown() {
+            return Some(Instrument {
+                base: self.base,
+                quote: self.quote,
+                settlement: self.settlement,
+                kind,
+            });
+        }
+
+        // case is kept for the base, see Hyperliquid's `k` prefix in [`Asset`]
+        let (symbol, _) = self.ticker.display_symbol_and_type();
+        let symbol: String = symbol.chars().filter(char::is_ascii_alphanumeric).collect();
+        let mut upper = symbol.to_ascii_uppercase();
+        // e.g. BTCUSD_PERP and BTC-USD-SWAP
+        if kind != MarketKind::Spot
+            && let Some(pair) = ["PERP", "SWAP"]
+                .iter()
+                .find_map(|suffix| upper.strip_suffix(suffix))
+        {
+            upper.truncate(pair.len());
+        }
+
+        Asset::COMMON_QUOTES.iter().find_map(|quote| {
+            let base_len = upper
+                .strip_suffix(quote)
+                .filter(|base| !base.is_empty())?
+                .len();
+            let (base, quote) = (Asset::new(&symbol[..base_len]), Asset::new(quote));
+
+            Some(Instrument {
+                base,
+                quote,
+                settlement: if kind == MarketKind::InversePerps {
+                    base
+                } else {
+                    quote
+                },
+                kind,
+            })
+        })
+    }
+
+    /// Closest listing of the same underlying on `exchange` among `candidates`
+    pub fn equivalent_on<'a>(
+        &self,
+        exchange: Exchange,
+        candidates: impl IntoIterator<Item = &'a TickerInfo>,
+    ) -> Option<TickerInfo> {
+        let instrument = self.instrument()?;
+
+        candidates
+            .into_iter()
+            .filter(|info| info.exchange() == exchange)
+            .filter_map(|info| {
+                let score = instrument.match_score(&info.instrument()?)?;
+                Some((score, info))
+            })
+            .max_by_key(|(score, info)| (*score, std::cmp::Reverse(info.ticker.to_string())))
+            .map(|(_, info)| *info)
     }
 
     pub fn market_type(&self) -> MarketKind {
@@ -579,7 +1017,7 @@ pub struct Trade {
     #[serde(deserialize_with = "bool_from_int")]
     pub is_sell: bool,
     pub price: Price,
-    pub qty: f32,
+    pub qty: Qty,
 }
 
 #[derive(Debug, Clone, Copy)]
@@ -589,28 +1027,52 @@ pub struct Kline {
     pub high: Price,
     pub low: Price,
     pub close: Price,
-    pub volume: (f32, f32),
+    pub volume: (Qty, Qty),
 }
 
 impl Kline {
+    /// Buy volume placeholder for venues that only report the total volume, e.g. Bybit,
+    /// which is then kept in the sell slot
+    pub const TOTAL_ONLY: Qty = Qty::from_units(-(10i128.pow(Qty::QTY_SCALE as u32)));
+
     pub fn new(
         time: u64,
         open: f32,
         high: f32,
         low: f32,
         close: f32,
-        volume: (f32, f32),
+        volume: (Qty, Qty),
         min_ticksize: MinTicksize,
     ) -> Self {
         Self {
             time,
-            open: Price::from_f32(open).round_to_min_tick(MinTicksize::from(min_ticksize)),
-            high: Price::from_f32(high).round_to_min_tick(MinTicksize::from(min_ticksize)),
-            low: Price::from_f32(low).round_to_min_tick(MinTicksize::from(min_ticksize)),
-            close: Price::from_f32(close).round_to_min_tick(MinTicksize::from(min_ticksize)),
+            open: Price::from_f32(open).round_to_min_tick(min_ticksize),
+            high: Price::from_f32(high).round_to_min_tick(min_ticksize),
+            low: Price::from_f32(low).round_to_min_tick(min_ticksize),
+            close: Price::from_f32(close).round_to_min_tick(min_ticksize),
             volume,
         }
     }
+
+    pub fn total_volume(&self) -> Qty {
+        if self.volume.0 == Self::TOTAL_ONLY {
+            self.volume.1
+        } else {
+            self.volume.0 + self.volume.1
+        }
+    }
+
+    /// Extends the kline with a later one of a lower or the same timeframe
+    pub fn merge(&mut self, next: &Kline) {
+        self.high = self.high.max(next.high);
+        self.low = self.low.min(next.low);
+        self.close = next.close;
+        self.volume = if self.volume.0 == Self::TOTAL_ONLY || next.volume.0 == Self::TOTAL_ONLY {
+            (Self::TOTAL_ONLY, self.total_volume() + next.total_volume())
+        } else {
+            (self.volume.0 + next.volume.0, self.volume.1 + next.volume.1)
+        };
+    }
 }
 
 #[derive(Debug, Clone, Copy, Deserialize)]
@@ -711,23 +1173,260 @@ impl TickMultiplier {
     ///
     /// Usually used for price steps in chart scales
     pub fn multiply_with_min_tick_size(&self, ticker_info: TickerInfo) -> f32 {
-        // MinTicksize is 10^p with p in [-8, 2]
-        let power = ticker_info.min_ticksize.power as i32;
-        let multiply = self.0 as f32;
+        let units = ticker_info
+            .min_ticksize
+            .units
+            .saturating_mul(i128::from(self.0));
+
+        PriceStep { units }.to_f32_lossy()
+    }
+}
 
-        let decimal_places: u32 = if power < 0 { (-power) as u32 } else { 0 };
+#[cfg(test)]
+mod tests {
+    use super::*;
 
-        let raw = if power >= 0 {
-            multiply * 10f32.powi(power)
-        } else {
-            multiply / 10f32.powi(-power)
+    const DAY_MS: u64 = 86_400_000;
+
+    fn utc(date: &str) -> u64 {
+        chrono::NaiveDateTime::parse_from_str(date, "%Y-%m-%d %H:%M")
+            .unwrap()
+            .and_utc()
+            .timestamp_millis() as u64
+    }
+
+    #[test]
+    fn weekly_buckets_open_on_mondays() {
+        // 1970-01-01 was a Thursday, the first full week opened on Monday the 5th
+        assert_eq!(Timeframe::W1.bucket_start(4 * DAY_MS), 4 * DAY_MS);
+        assert_eq!(Timeframe::W1.bucket_start(11 * DAY_MS - 1), 4 * DAY_MS);
+        assert_eq!(Timeframe::W1.bucket_start(11 * DAY_MS), 11 * DAY_MS);
+        assert_eq!(Timeframe::W1.bucket_start(3 * DAY_MS), 0);
+
+        let wednesday = utc("2024-01-03 15:30");
+        assert_eq!(
+            Timeframe::W1.bucket_start(wednesday),
+            utc("2024-01-01 00:00")
+        );
+        assert_eq!(
+            Timeframe::W1.next_bucket_start(utc("2024-01-01 00:00")),
+            utc("2024-01-08 00:00")
+        );
+
+        let two_weeks = Timeframe::from_seconds(2 * 604_800);
+        assert!(two_weeks.is_weekly());
+        assert_eq!(
+            two_weeks.bucket_start(wednesday) % Timeframe::WEEK_MS,
+            Timeframe::WEEK_OFFSET_MS
+        );
+    }
+
+    #[test]
+    fn monthly_buckets_follow_the_calendar() {
+        let mn1 = Timeframe::MN1;
+
+        assert_eq!(
+            mn1.bucket_start(utc("2024-02-29 23:59")),
+            utc("2024-02-01 00:00")
+        );
+        assert_eq!(
+            mn1.next_bucket_start(utc("2024-01-01 00:00")),
+            utc("2024-02-01 00:00")
+        );
+        assert_eq!(
+            mn1.next_bucket_start(utc("2024-02-01 00:00")),
+            utc("2024-03-01 00:00")
+        );
+        assert_eq!(
+            mn1.next_bucket_start(utc("2023-12-01 00:00")),
+            utc("2024-01-01 00:00")
+        );
+    }
+
+    #[test]
+    fn resamples_only_from_dividing_timeframes() {
+        assert!(Timeframe::H1.resamples_from(Timeframe::M15));
+        assert!(Timeframe::H12.resamples_from(Timeframe::H4));
+        assert!(!Timeframe::H2.resamples_from(Timeframe::from_seconds(7 * 60)));
+        assert!(!Timeframe::M5.resamples_from(Timeframe::M3));
+        assert!(!Timeframe::from_seconds(10 * 3_600).resamples_from(Timeframe::H4));
+
+        // weekly bars are only built from days or from weekly bars that divide them
+        assert!(Timeframe::W1.resamples_from(Timeframe::D1));
+        assert!(
+            !Timeframe::from_seconds(2 * 604_800)
+                .resamples_from(Timeframe::from_seconds(4 * 86_400))
+        );
+        assert!(!Timeframe::W1.resamples_from(Timeframe::from_seconds(2 * 604_800)));
+
+        // months hold whole days, not whole weeks
+        assert!(Timeframe::MN1.resamples_from(Timeframe::D1));
+        assert!(Timeframe::MN1.resamples_from(Timeframe::H4));
+        assert!(!Timeframe::MN1.resamples_from(Timeframe::W1));
+        assert!(!Timeframe::from_seconds(60 * 86_400).resamples_from(Timeframe::MN1));
+    }
+
+    #[test]
+    fn parses_what_it_displays() {
+        for tf in Timeframe::KLINE {
+            assert_eq!(tf.to_string().parse::<Timeframe>(), Ok(tf));
+        }
+        for text in ["10m", "6h", "3d", "2w"] {
+            let tf: Timeframe = text.parse().unwrap();
+            assert!(matches!(tf, Timeframe::Custom(_)));
+            assert_eq!(tf.to_string(), text);
+        }
+
+        assert_eq!("60m".parse(), Ok(Timeframe::H1));
+        assert_eq!("7d".parse(), Ok(Timeframe::W1));
+        assert!("0m".parse::<Timeframe>().is_err());
+        assert!("30".parse::<Timeframe>().is_err());
+        assert!("5s".parse::<Timeframe>().is_err());
+        assert!("53w".parse::<Timeframe>().is_err());
+    }
+
+    #[test]
+    fn rejects_zero_length_custom_timeframes() {
+        assert_eq!(
+            serde_json::from_str::<Timeframe>(r#"{"Custom":600}"#).unwrap(),
+            Timeframe::Custom(600)
+        );
+        assert!(serde_json::from_str::<Timeframe>(r#"{"Custom":0}"#).is_err());
+    }
+
+    #[test]
+    fn picks_the_longest_open_interest_period_within_the_timeframe() {
+        for (timeframe, period) in [
+            (Timeframe::MS100, Timeframe::M5),
+            (Timeframe::Custom(5), Timeframe::M5),
+            (Timeframe::M1, Timeframe::M5),
+            (Timeframe::M5, Timeframe::M5),
+            (Timeframe::M30, Timeframe::M30),
+            (Timeframe::H2, Timeframe::H1),
+            (Timeframe::H12, Timeframe::H4),
+            (Timeframe::D1, Timeframe::D1),
+            (Timeframe::W1, Timeframe::D1),
+            (Timeframe::MN1, Timeframe::D1),
+        ] {
+            assert_eq!(timeframe.open_interest_period(), period, "{timeframe:?}");
+        }
+    }
+
+    fn listing(symbol: &str, exchange: Exchange, assets: Option<(&str, &str)>) -> TickerInfo {
+        let info = TickerInfo::new(Ticker::new(symbol, exchange), 0.01, 1.0, None);
+        match assets {
+            Some((base, quote)) => info.with_assets(base, quote),
+            None => info,
+        }
+    }
+
+    #[test]
+    fn folds_assets_into_their_canonical_code() {
+        for (code, canonical) in [
+            ("PEPE", "PEPE"),
+            ("1000PEPE", "PEPE"),
+            ("kPEPE", "PEPE"),
+            ("1000000MOG", "MOG"),
+            ("KAVA", "KAVA"),
+            ("kava", "KAVA"),
+            ("1INCH", "1INCH"),
+            ("XBT", "BTC"),
+            ("USDT", "USD"),
+            ("USDC", "USD"),
+            ("fdusd", "USD"),
+            ("EUR", "EUR"),
+        ] {
+            assert_eq!(Asset::new(code).canonical().as_str(), canonical, "{code}");
+        }
+    }
+
+    #[test]
+    fn splits_symbols_without_assets() {
+        let split = |symbol: &str, exchange: Exchange| {
+            listing(symbol, exchange, None)
+                .instrument()
+                .map(|i| (i.base.canonical().to_string(), i.quote.to_string()))
         };
 
-        round_to_decimal_places(raw, decimal_places)
+        assert_eq!(
+            split("1000PEPEUSDT", Exchange::BinanceLinear),
+            Some(("PEPE".into(), "USDT".into()))
+        );
+        // Hyperliquid perps are listed by their base, shown against USDT
+        assert_eq!(
+            split("kPEPE", Exchange::HyperliquidLinear),
+            Some(("PEPE".into(), "USDT".into()))
+        );
+        assert_eq!(
+            split("BTCFDUSD", Exchange::BinanceSpot),
+            Some(("BTC".into(), "FDUSD".into()))
+        );
+        assert_eq!(
+            split("ETHBTC", Exchange::BinanceSpot),
+            Some(("ETH".into(), "BTC".into()))
+        );
+        assert_eq!(split("FOOBAR", Exchange::BinanceSpot), None);
+
+        let inverse = listing("BTCUSD_PERP", Exchange::BinanceInverse, None)
+            .instrument()
+            .unwrap();
+        assert_eq!(inverse.settlement, Asset::new("BTC"));
+        assert_eq!(inverse.canonical_pair(), "BTCUSD");
     }
-}
 
-fn round_to_decimal_places(value: f32, places: u32) -> f32 {
-    let factor = 10.0f32.powi(places as i32);
-    (value * factor).round() / factor
+    #[test]
+    fn finds_the_closest_listing_on_another_venue() {
+        let pepe = listing(
+            "1000PEPEUSDT",
+            Exchange::BinanceLinear,
+            Some(("1000PEPE", "USDT")),
+        );
+        let candidates = [
+            listing(
+                "kPEPE",
+                Exchange::HyperliquidLinear,
+                Some(("kPEPE", "USDC")),
+            ),
+            listing("KAVA", Exchange::HyperliquidLinear, Some(("KAVA", "USDC"))),
+            listing(
+                "PEPE-USDT-SWAP",
+                Exchange::OkexLinear,
+                Some(("PEPE", "USDT")),
+            ),
+            listing(
+                "PEPE-USDC-SWAP",
+                Exchange::OkexLinear,
+                Some(("PEPE", "USDC")),
+            ),
+            listing("PEPEUSDT", Exchange::BybitSpot, None),
+        ];
+        let on = |exchange: Exchange| pepe.equivalent_on(exchange, &candidates);
+
+        // USDT, USDC and USD quotes stand in for each other
+        assert_eq!(
+            on(Exchange::HyperliquidLinear).map(|i| i.ticker),
+            Some(candidates[0].ticker)
+        );
+        // the same quote is closer than an equivalent one
+        assert_eq!(
+            on(Exchange::OkexLinear).map(|i| i.ticker),
+            Some(candidates[2].ticker)
+        );
+        // listings without assets are matched by their symbol, across market kinds
+        assert_eq!(
+            on(Exchange::BybitSpot).map(|i| i.ticker),
+            Some(candidates[4].ticker)
+        );
+        assert_eq!(on(Exchange::BinanceSpot), None);
+
+        let linear = pepe.instrument().unwrap();
+        let spot = candidates[4].instrument().unwrap();
+        let okx = candidates[2].instrument().unwrap();
+        assert!(linear.match_score(&okx) > linear.match_score(&spot));
+        assert!(linear.same_market(&okx) && !linear.same_market(&spot));
+        assert_eq!(
+            linear.match_score(&candidates[1].instrument().unwrap()),
+            None
+        );
+    }
 }
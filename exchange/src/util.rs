@@ -1,7 +1,6 @@
 use serde::{Deserialize, Serialize};
 
 pub type ContractSize = Power10<-4, 6>;
-pub type MinTicksize = Power10<-8, 2>;
 pub type MinQtySize = Power10<-6, 8>;
 
 #[derive(Debug, Clone, Copy, PartialEq, Hash, Eq)]
@@ -62,24 +61,136 @@ impl<'de, const MIN: i8, const MAX: i8> serde::Deserialize<'de> for Power10<MIN,
     }
 }
 
+/// Smallest price increment of an instrument, kept as an exact decimal step
+/// so ticks like 0.25, 0.5 or 25 aren't snapped to a power of ten
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct MinTicksize {
+    /// tick size in atomic units (10^-PRICE_SCALE)
+    pub units: i128,
+}
+
+impl MinTicksize {
+    /// Builds a tick of `10^power`
+    pub fn from_power(power: i8) -> Self {
+        let exp = (Price::PRICE_SCALE + power as i32).clamp(0, 38);
+        Self {
+            units: 10i128.pow(exp as u32),
+        }
+    }
+
+    /// Takes the decimal the tick was written as, never below one atomic unit
+    pub fn from_f32(value: f32) -> Self {
+        Self {
+            units: decimal_units(value).max(1),
+        }
+    }
+
+    #[inline]
+    pub fn as_f32(self) -> f32 {
+        self.as_step().to_f32_lossy()
+    }
+
+    #[inline]
+    pub fn as_step(self) -> PriceStep {
+        PriceStep { units: self.units }
+    }
+
+    /// Number of fractional digits needed to print prices on this tick grid
+    pub fn decimals(self) -> u32 {
+        let mut units = self.units.max(1);
+        let mut decimals = Price::PRICE_SCALE as u32;
+        while decimals > 0 && units % 10 == 0 {
+            units /= 10;
+            decimals -= 1;
+        }
+        decimals
+    }
+}
+
+impl From<f32> for MinTicksize {
+    fn from(value: f32) -> Self {
+        Self::from_f32(value)
+    }
+}
+
+impl From<MinTicksize> for f32 {
+    fn from(v: MinTicksize) -> Self {
+        v.as_f32()
+    }
+}
+
+impl serde::Serialize for MinTicksize {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer,
+    {
+        // serialize as an exact decimal string (e.g. "0.01", "0.25", "5")
+        serializer.collect_str(&Price::from_units(self.units).to_string(*self))
+    }
+}
+
+impl<'de> serde::Deserialize<'de> for MinTicksize {
+    /// Accepts decimal strings and, from older caches, plain numbers
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: serde::Deserializer<'de>,
+    {
+        struct TickVisitor;
+
+        impl serde::de::Visitor<'_> for TickVisitor {
+            type Value = MinTicksize;
+
+            fn expecting(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+                f.write_str("a decimal string or number")
+            }
+
+            fn visit_str<E: serde::de::Error>(self, v: &str) -> Result<MinTicksize, E> {
+                let price = v.parse::<Price>().map_err(E::custom)?;
+                Ok(MinTicksize {
+                    units: price.units.max(1),
+                })
+            }
+
+            fn visit_f64<E: serde::de::Error>(self, v: f64) -> Result<MinTicksize, E> {
+                Ok(MinTicksize::from_f32(v as f32))
+            }
+
+            fn visit_i64<E: serde::de::Error>(self, v: i64) -> Result<MinTicksize, E> {
+                Ok(MinTicksize::from_f32(v as f32))
+            }
+
+            fn visit_u64<E: serde::de::Error>(self, v: u64) -> Result<MinTicksize, E> {
+                Ok(MinTicksize::from_f32(v as f32))
+            }
+        }
+
+        deserializer.deserialize_any(TickVisitor)
+    }
+}
+
+/// Atomic units of the shortest decimal that reads back as `value`, so an `f32` tick
+/// of 0.1 stays 0.1 instead of 0.100000001490
+fn decimal_units(value: f32) -> i128 {
+    value.to_string().parse::<Price>().map_or(0, |p| p.units)
+}
+
 #[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Hash, Deserialize, Serialize)]
 pub struct PriceStep {
     /// step size in atomic units (10^-PRICE_SCALE)
-    pub units: i64,
+    pub units: i128,
 }
 
 impl PriceStep {
     /// Lossy: f32 step for UI
     pub fn to_f32_lossy(self) -> f32 {
-        let scale = 10f32.powi(Price::PRICE_SCALE);
-        (self.units as f32) / scale
+        let scale = 10f64.powi(Price::PRICE_SCALE);
+        ((self.units as f64) / scale) as f32
     }
 
-    /// Lossy: from f32 step (rounds to nearest atomic unit)
+    /// Lossy: from f32 step, taking the decimal it was written as
     pub fn from_f32_lossy(step: f32) -> Self {
         assert!(step > 0.0, "step must be > 0");
-        let scale = 10f32.powi(Price::PRICE_SCALE);
-        let units = (step * scale).round() as i64;
+        let units = decimal_units(step);
         assert!(units > 0, "step too small at given PRICE_SCALE");
         Self { units }
     }
@@ -90,57 +201,41 @@ impl PriceStep {
 }
 
 /// Fixed atomic unit scale: 10^-PRICE_SCALE is the smallest stored fraction.
-/// MinTicksize has range [-8, 2], e.g. PRICE_SCALE = 8 to represent 10^-8 atomic units.
+/// PRICE_SCALE = 12 represents 10^-12 atomic units, enough for sub-satoshi ticks of
+/// micro-priced tokens. Units are `i128`, an `i64` would top out at ~9.2 million.
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Ord, PartialOrd, Deserialize, Serialize)]
 pub struct Price {
     /// number of atomic units (atomic unit = 10^-PRICE_SCALE)
-    pub units: i64,
+    pub units: i128,
 }
 
 impl Price {
-    /// number of decimal places of the atomic unit (10^-8)
-    pub const PRICE_SCALE: i32 = 8;
+    /// number of decimal places of the atomic unit (10^-12)
+    pub const PRICE_SCALE: i32 = 12;
 
     #[inline]
-    pub fn to_string<const MIN: i8, const MAX: i8>(self, precision: Power10<MIN, MAX>) -> String {
+    pub fn to_string(self, min_tick: MinTicksize) -> String {
         let mut out = String::with_capacity(24);
-        self.fmt_into(precision, &mut out).unwrap();
+        self.fmt_into(min_tick, &mut out).unwrap();
         out
     }
 
+    /// Writes the price rounded to `min_tick`, with as many decimals as the tick needs
     #[inline]
-    pub fn fmt_into<const MIN: i8, const MAX: i8, W: core::fmt::Write>(
+    pub fn fmt_into<W: core::fmt::Write>(
         self,
-        precision: Power10<MIN, MAX>,
+        min_tick: MinTicksize,
         out: &mut W,
     ) -> core::fmt::Result {
         let scale_u = Self::PRICE_SCALE as u32;
 
-        // number of atomic units for the given decade step: 10^(PRICE_SCALE + power)
-        let exp = (Self::PRICE_SCALE + precision.power as i32) as u32;
-        debug_assert!(Self::PRICE_SCALE + precision.power as i32 >= 0);
-        let unit = 10i64
-            .checked_pow(exp)
-            .expect("Price::to_string unit overflow");
-
-        let u = self.units;
-        let half = unit / 2;
-        let rounded_units = if u >= 0 {
-            ((u + half).div_euclid(unit)) * unit
-        } else {
-            ((u - half).div_euclid(unit)) * unit
-        };
-
-        let decimals: u32 = if precision.power < 0 {
-            ((-precision.power) as u32).min(scale_u)
-        } else {
-            0
-        };
+        let rounded_units = self.round_to_min_tick(min_tick).units;
+        let decimals = min_tick.decimals().min(scale_u);
 
         if rounded_units < 0 {
             core::fmt::Write::write_char(out, '-')?;
         }
-        let abs_u = (rounded_units as i128).unsigned_abs();
+        let abs_u = rounded_units.unsigned_abs();
 
         let scale_pow = 10u128.pow(scale_u);
         let int_part = abs_u / scale_pow;
@@ -157,14 +252,14 @@ impl Price {
 
     /// Lossy: convert price to f32, may lose precision if going beyond `PRICE_SCALE`
     pub fn to_f32_lossy(self) -> f32 {
-        let scale = 10f32.powi(Self::PRICE_SCALE);
-        (self.units as f32) / scale
+        self.to_f64() as f32
     }
 
     /// Lossy: create Price from f32 (rounds to nearest atomic unit)
     pub fn from_f32_lossy(v: f32) -> Self {
-        let scale = 10f32.powi(Self::PRICE_SCALE);
-        let u = (v * scale).round() as i64;
+        // f64 math so f32 inputs aren't rounded again when scaled up to atomic units
+        let scale = 10f64.powi(Self::PRICE_SCALE);
+        let u = (f64::from(v) * scale).round() as i128;
         Self { units: u }
     }
 
@@ -172,6 +267,11 @@ impl Price {
         Self::from_f32_lossy(v)
     }
 
+    /// Lossy: convert price to f64, exact for any price with up to 15 significant digits
+    pub fn to_f64(self) -> f64 {
+        (self.units as f64) / 10f64.powi(Self::PRICE_SCALE)
+    }
+
     pub fn to_f32(self) -> f32 {
         self.to_f32_lossy()
     }
@@ -204,9 +304,9 @@ impl Price {
         }
         let added = self.units.checked_add(unit - 1).unwrap_or_else(|| {
             if self.units.is_negative() {
-                i64::MIN
+                i128::MIN
             } else {
-                i64::MAX
+                i128::MAX
             }
         });
 
@@ -224,34 +324,19 @@ impl Price {
     }
 
     /// Create Price from raw atomic units (no rounding) — internal only
-    pub fn from_units(units: i64) -> Self {
+    pub fn from_units(units: i128) -> Self {
         Self { units }
     }
 
-    /// Returns the atomic-unit count that corresponds to one min tick (min_tick / atomic_unit)
-    fn min_tick_units(min_tick: MinTicksize) -> i64 {
-        let exp = Self::PRICE_SCALE + (min_tick.power as i32);
-        assert!(exp >= 0, "PRICE_SCALE must be >= -min_tick.power");
-        10i64
-            .checked_pow(exp as u32)
-            .expect("min_tick_units overflowed")
-    }
-
     /// Round this Price to the nearest multiple of the provided min_ticksize
     pub fn round_to_min_tick(self, min_tick: MinTicksize) -> Self {
-        let unit = Self::min_tick_units(min_tick);
-        if unit <= 1 {
-            return self;
-        }
-        let half = unit / 2;
-        let rounded = ((self.units + half).div_euclid(unit)) * unit;
-        Self { units: rounded }
+        self.round_to_step(min_tick.as_step())
     }
 
     pub fn add_steps(self, steps: i64, step: PriceStep) -> Self {
         Self::from_units(
             self.units
-                .checked_add(steps.saturating_mul(step.units))
+                .checked_add(i128::from(steps).saturating_mul(step.units))
                 .expect("add_steps overflowed"),
         )
     }
@@ -266,6 +351,58 @@ impl Price {
     }
 }
 
+impl std::str::FromStr for Price {
+    type Err = std::num::ParseFloatError;
+
+    /// Parses decimal strings digit by digit, falls back to float parsing for exponents
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let s = s.trim();
+        let (negative, digits) = match s.strip_prefix('-') {
+            Some(rest) => (true, rest),
+            None => (false, s.strip_prefix('+').unwrap_or(s)),
+        };
+        let (int_part, frac_part) = digits.split_once('.').unwrap_or((digits, ""));
+
+        let from_float = |s: &str| {
+            let v = s.parse::<f64>()?;
+            Ok(Self {
+                units: (v * 10f64.powi(Self::PRICE_SCALE)).round() as i128,
+            })
+        };
+
+        let is_plain = !(int_part.is_empty() && frac_part.is_empty())
+            && int_part.bytes().all(|b| b.is_ascii_digit())
+            && frac_part.bytes().all(|b| b.is_ascii_digit());
+        if !is_plain {
+            return from_float(s);
+        }
+
+        let scale = Self::PRICE_SCALE as usize;
+        let digits = int_part
+            .bytes()
+            .chain((0..scale).map(|i| frac_part.as_bytes().get(i).copied().unwrap_or(b'0')));
+
+        let mut units: i128 = 0;
+        for b in digits {
+            match units
+                .checked_mul(10)
+                .and_then(|units| units.checked_add(i128::from(b - b'0')))
+            {
+                Some(next) => units = next,
+                None => return from_float(s),
+            }
+        }
+        // round half up on the first dropped digit
+        if frac_part.as_bytes().get(scale).is_some_and(|b| *b >= b'5') {
+            units += 1;
+        }
+
+        Ok(Self {
+            units: if negative { -units } else { units },
+        })
+    }
+}
+
 impl std::ops::Add for Price {
     type Output = Self;
 
@@ -284,7 +421,7 @@ impl std::ops::Div<i64> for Price {
 
     fn div(self, rhs: i64) -> Self::Output {
         Self {
-            units: self.units.div_euclid(rhs),
+            units: self.units.div_euclid(i128::from(rhs)),
         }
     }
 }
@@ -302,10 +439,352 @@ impl std::ops::Sub for Price {
     }
 }
 
+/// Fixed-point quantity (base, quote or contracts) with 10^-QTY_SCALE resolution,
+/// so sums over many trades or levels don't drift the way `f32` accumulation does.
+/// Units are `i128`, `i64` would top out at about 9.2e12 whole units, which base volumes
+/// of low priced coins and long running sums go past
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Hash, Ord, PartialOrd)]
+pub struct Qty {
+    /// number of atomic units (atomic unit = 10^-QTY_SCALE)
+    pub units: i128,
+}
+
+impl Qty {
+    /// number of decimal places of the atomic unit (10^-6), matching the finest `MinQtySize`
+    pub const QTY_SCALE: i32 = 6;
+
+    pub const ZERO: Self = Self { units: 0 };
+
+    pub const fn from_units(units: i128) -> Self {
+        Self { units }
+    }
+
+    /// Lossy: create Qty from f32 (rounds to nearest atomic unit)
+    pub fn from_f32(v: f32) -> Self {
+        Self::from_f64(f64::from(v))
+    }
+
+    /// Lossy: create Qty from f64 (rounds to nearest atomic unit, saturating)
+    pub fn from_f64(v: f64) -> Self {
+        let scale = 10f64.powi(Self::QTY_SCALE);
+        Self {
+            units: (v * scale).round() as i128,
+        }
+    }
+
+    /// Lossy: f32 for drawing and UI math
+    pub fn to_f32(self) -> f32 {
+        self.to_f64() as f32
+    }
+
+    pub fn to_f64(self) -> f64 {
+        (self.units as f64) / 10f64.powi(Self::QTY_SCALE)
+    }
+
+    #[inline]
+    pub fn is_zero(self) -> bool {
+        self.units == 0
+    }
+
+    pub fn abs(self) -> Self {
+        Self {
+            units: self.units.checked_abs().expect("Qty abs overflowed"),
+        }
+    }
+
+    /// Notional value of this quantity at `price`, computed without leaving fixed-point
+    pub fn to_quote(self, price: Price) -> Self {
+        let units = self
+            .units
+            .checked_mul(price.units)
+            .expect("Qty to_quote overflowed");
+        Self {
+            units: units / 10i128.pow(Price::PRICE_SCALE as u32),
+        }
+    }
+
+    /// Multiplies by a non-integer factor, e.g. a contract size
+    pub fn scale_by(self, factor: f32) -> Self {
+        Self::from_f64(self.to_f64() * f64::from(factor))
+    }
+}
+
+impl std::str::FromStr for Qty {
+    type Err = std::num::ParseFloatError;
+
+    /// Parses decimal strings digit by digit, falls back to float parsing for exponents
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let s = s.trim();
+        let (negative, digits) = match s.strip_prefix('-') {
+            Some(rest) => (true, rest),
+            None => (false, s.strip_prefix('+').unwrap_or(s)),
+        };
+        let (int_part, frac_part) = digits.split_once('.').unwrap_or((digits, ""));
+
+        let is_plain = !(int_part.is_empty() && frac_part.is_empty())
+            && int_part.bytes().all(|b| b.is_ascii_digit())
+            && frac_part.bytes().all(|b| b.is_ascii_digit());
+        if !is_plain {
+            return s.parse::<f64>().map(Self::from_f64);
+        }
+
+        let scale = Self::QTY_SCALE as usize;
+        let digits = int_part
+            .bytes()
+            .chain((0..scale).map(|i| frac_part.as_bytes().get(i).copied().unwrap_or(b'0')));
+
+        let mut units: i128 = 0;
+        for b in digits {
+            match units
+                .checked_mul(10)
+                .and_then(|units| units.checked_add(i128::from(b - b'0')))
+            {
+                Some(next) => units = next,
+                // past what fits in fixed-point, only the float keeps its magnitude
+                None => return s.parse::<f64>().map(Self::from_f64),
+            }
+        }
+        // round half up on the first dropped digit
+        if frac_part.as_bytes().get(scale).is_some_and(|b| *b >= b'5') {
+            units += 1;
+        }
+
+        Ok(Self {
+            units: if negative { -units } else { units },
+        })
+    }
+}
+
+impl std::fmt::Display for Qty {
+    /// Exact decimal without trailing zeros, e.g. `0.5` or `1200`
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let scale = 10u128.pow(Self::QTY_SCALE as u32);
+        let abs = self.units.unsigned_abs();
+        if self.units < 0 {
+            write!(f, "-")?;
+        }
+        write!(f, "{}", abs / scale)?;
+
+        let frac = abs % scale;
+        if frac == 0 {
+            return Ok(());
+        }
+        let frac = format!("{:0width$}", frac, width = Self::QTY_SCALE as usize);
+        write!(f, ".{}", frac.trim_end_matches('0'))
+    }
+}
+
+impl serde::Serialize for Qty {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer,
+    {
+        serializer.serialize_f64(self.to_f64())
+    }
+}
+
+impl<'de> serde::Deserialize<'de> for Qty {
+    /// Accepts both quoted decimal strings, as most venues send them, and plain numbers
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: serde::Deserializer<'de>,
+    {
+        struct QtyVisitor;
+
+        impl serde::de::Visitor<'_> for QtyVisitor {
+            type Value = Qty;
+
+            fn expecting(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+                f.write_str("a decimal string or number")
+            }
+
+            fn visit_str<E: serde::de::Error>(self, v: &str) -> Result<Qty, E> {
+                v.parse().map_err(E::custom)
+            }
+
+            fn visit_f64<E: serde::de::Error>(self, v: f64) -> Result<Qty, E> {
+                Ok(Qty::from_f64(v))
+            }
+
+            fn visit_i64<E: serde::de::Error>(self, v: i64) -> Result<Qty, E> {
+                Ok(Qty::from_f64(v as f64))
+            }
+
+            fn visit_u64<E: serde::de::Error>(self, v: u64) -> Result<Qty, E> {
+                Ok(Qty::from_f64(v as f64))
+            }
+        }
+
+        deserializer.deserialize_any(QtyVisitor)
+    }
+}
+
+impl std::ops::Add for Qty {
+    type Output = Self;
+
+    fn add(self, rhs: Self) -> Self::Output {
+        Self {
+            units: self
+                .units
+                .checked_add(rhs.units)
+                .expect("Qty add overflowed"),
+        }
+    }
+}
+
+impl std::ops::AddAssign for Qty {
+    fn add_assign(&mut self, rhs: Self) {
+        *self = *self + rhs;
+    }
+}
+
+impl std::ops::Sub for Qty {
+    type Output = Self;
+
+    fn sub(self, rhs: Self) -> Self::Output {
+        Self {
+            units: self
+                .units
+                .checked_sub(rhs.units)
+                .expect("Qty sub overflowed"),
+        }
+    }
+}
+
+impl std::ops::SubAssign for Qty {
+    fn sub_assign(&mut self, rhs: Self) {
+        *self = *self - rhs;
+    }
+}
+
+impl std::ops::Neg for Qty {
+    type Output = Self;
+
+    fn neg(self) -> Self::Output {
+        Self {
+            units: self.units.checked_neg().expect("Qty neg overflowed"),
+        }
+    }
+}
+
+impl std::iter::Sum for Qty {
+    fn sum<I: Iterator<Item = Self>>(iter: I) -> Self {
+        iter.fold(Self::ZERO, |acc, q| acc + q)
+    }
+}
+
 #[cfg(test)]
 mod manual_printouts {
     use super::*;
 
+    #[test]
+    fn rounds_to_non_power_of_ten_ticks() {
+        let quarter = MinTicksize::from(0.25);
+        assert_eq!(quarter.decimals(), 2);
+        assert_eq!(
+            Price::from_f32(100.13)
+                .round_to_min_tick(quarter)
+                .to_string(quarter),
+            "100.25"
+        );
+        assert_eq!(Price::from_f32(100.12).to_string(quarter), "100.00");
+
+        let five = MinTicksize::from(5.0);
+        assert_eq!(five.decimals(), 0);
+        assert_eq!(Price::from_f32(4312.0).to_string(five), "4310");
+        assert_eq!(Price::from_f32(-7.6).to_string(five), "-10");
+
+        let tiny = MinTicksize::from(0.00000001);
+        assert_eq!(tiny.decimals(), 8);
+    }
+
+    #[test]
+    fn formats_sub_satoshi_prices() {
+        let tick = MinTicksize::from(1e-12);
+        assert_eq!(tick.units, 1);
+        assert_eq!(tick.decimals(), 12);
+
+        let pepe = Price::from_f32(0.000_001_234_5).round_to_min_tick(MinTicksize::from(1e-11));
+        assert_eq!(pepe.to_string(MinTicksize::from(1e-11)), "0.00000123450");
+        assert_eq!(pepe.to_string(MinTicksize::from(1e-8)), "0.00000123");
+
+        let step = MinTicksize::from(1e-10).as_step();
+        let low = Price::from_f32(0.000_000_5).round_to_step(step);
+        let high = low.add_steps(3, step);
+        assert_ne!(low, high);
+        assert_eq!(high.to_string(MinTicksize::from(1e-10)), "0.0000005003");
+        assert_eq!(Price::steps_between_inclusive(low, high, step), Some(4));
+        assert_eq!(
+            low.add_steps(-1, step).to_string(MinTicksize::from(1e-10)),
+            "0.0000004999"
+        );
+    }
+
+    #[test]
+    fn keeps_decimal_ticks_exact() {
+        for (tick, price) in [(0.1, "100.1"), (0.01, "16590.21"), (0.001, "0.123")] {
+            let tick = MinTicksize::from(tick);
+            assert_eq!(price.parse::<Price>().unwrap().to_string(tick), price);
+        }
+        assert_eq!(MinTicksize::from(0.01).units, 10_000_000_000);
+        assert_eq!(
+            Price::from_f32(16590.21).to_string(MinTicksize::from(0.01)),
+            "16590.21"
+        );
+
+        let tick = MinTicksize::from(0.1);
+        let json = serde_json::to_string(&tick).unwrap();
+        assert_eq!(json, r#""0.1""#);
+        assert_eq!(serde_json::from_str::<MinTicksize>(&json).unwrap(), tick);
+        // caches written before ticks were strings
+        assert_eq!(serde_json::from_str::<MinTicksize>("0.1").unwrap(), tick);
+    }
+
+    #[test]
+    fn handles_prices_in_the_millions() {
+        let tick = MinTicksize::from(1.0);
+        let bid: Price = "95000000".parse().unwrap();
+        let ask = bid.add_steps(2, tick.as_step());
+        assert_eq!(ask.to_string(tick), "95000002");
+        assert_eq!((bid + ask).to_string(tick), "190000002");
+
+        let depth = crate::depth::Depth {
+            bids: [(bid, Qty::from_f32(1.0))].into(),
+            asks: [(ask, Qty::from_f32(1.0))].into(),
+        };
+        assert_eq!(depth.mid_price().unwrap().to_string(tick), "95000001");
+    }
+
+    #[test]
+    fn parses_exact_quantities() {
+        let qty: Qty = "0.100000".parse().unwrap();
+        assert_eq!(qty.units, 100_000);
+        assert_eq!(qty.to_string(), "0.1");
+
+        let sum = (0..10).map(|_| qty).sum::<Qty>();
+        assert_eq!(sum, Qty::from_units(1_000_000));
+        assert_eq!(sum.to_string(), "1");
+
+        let tiny: Qty = "0.0000015".parse().unwrap();
+        assert_eq!(tiny.units, 2);
+        assert_eq!("-2.5".parse::<Qty>().unwrap().to_string(), "-2.5");
+
+        let notional = Qty::from_units(1_500_000).to_quote(Price::from_f32(100.25));
+        assert_eq!(notional.to_string(), "150.375");
+    }
+
+    #[test]
+    fn sums_past_what_i64_units_hold() {
+        // 1e12 whole units per bar, a day of 1m bars of a low priced coin's base volume
+        let bar: Qty = "1000000000000.5".parse().unwrap();
+        let total = (0..1440).map(|_| bar).sum::<Qty>();
+
+        assert!(total.units > i128::from(i64::MAX));
+        assert_eq!(total.to_string(), "1440000000000720");
+        let all_but_one = (0..1439).map(|_| bar).sum::<Qty>();
+        assert_eq!((total - all_but_one).to_string(), "1000000000000.5");
+    }
+
     #[test]
     fn show_min_tick_rounding() {
         let orig: f32 = 0.000051;
@@ -313,7 +792,7 @@ mod manual_printouts {
         let back = p.to_f32_lossy();
 
         let scale = 10f32.powi(Price::PRICE_SCALE);
-        let expected_units = (orig * scale).round() as i64;
+        let expected_units = (orig * scale).round() as i128;
         let expected_back = (expected_units as f32) / scale;
 
         println!("orig (f32)        = {:0.9}", orig);
@@ -38,6 +38,8 @@ pub enum Interaction {
     Ruler {
         start: Option<Point>,
     },
+    /// Waiting for a click on the bar to anchor on
+    Anchoring,
 }
 
 #[derive(Debug, Clone, Copy)]
@@ -57,6 +59,8 @@ pub enum Message {
     BoundsChanged(Rectangle),
     SplitDragged(usize, f32),
     DoubleClick(AxisScaleClicked),
+    /// Clicked while anchoring, at the interval of the bar under the cursor
+    AnchorPlaced(u64),
 }
 
 pub trait Chart: PlotConstants + canvas::Program<Message> {
@@ -123,6 +127,7 @@ fn canvas_interaction<T: Chart>(
                     if let mouse::Button::Left = button {
                         match interaction {
                             Interaction::None
+                            | Interaction::Anchoring
                             | Interaction::Panning { .. }
                             | Interaction::Zoomin { .. } => {
                                 *interaction = Interaction::Panning {
@@ -150,7 +155,7 @@ fn canvas_interaction<T: Chart>(
                         );
                         Some(canvas::Action::publish(msg).and_capture())
                     }
-                    Interaction::None | Interaction::Ruler { .. } => {
+                    Interaction::None | Interaction::Ruler { .. } | Interaction::Anchoring => {
                         Some(canvas::Action::publish(Message::CrosshairMoved))
                     }
                     _ => None,
@@ -408,7 +413,7 @@ pub fn update<T: Chart>(chart: &mut T, message: &Message) {
 
                         state.interval_to_x(cursor_time)
                     }
-                    Basis::Tick(_) => {
+                    _ => {
                         let tick_index = cursor_chart_x / state.cell_width;
                         state.cell_width = new_width;
 
@@ -486,6 +491,8 @@ pub fn update<T: Chart>(chart: &mut T, message: &Message) {
             }
         }
         Message::CrosshairMoved => return chart.invalidate_crosshair(),
+        // placing anchors is up to the pane, which keeps them
+        Message::AnchorPlaced(_) => return,
     }
     chart.invalidate_all();
 }
@@ -690,8 +697,8 @@ impl ViewState {
     }
 
     #[inline]
-    fn price_unit() -> i64 {
-        10i64.pow(Price::PRICE_SCALE as u32)
+    fn price_unit() -> i128 {
+        10i128.pow(Price::PRICE_SCALE as u32)
     }
 
     fn visible_region(&self, size: Size) -> Rectangle {
@@ -714,10 +721,6 @@ impl ViewState {
 
     fn interval_range(&self, region: &Rectangle) -> (u64, u64) {
         match self.basis {
-            Basis::Tick(_) => (
-                self.x_to_interval(region.x + region.width),
-                self.x_to_interval(region.x),
-            ),
             Basis::Time(timeframe) => {
                 let interval = timeframe.to_milliseconds();
                 (
@@ -726,6 +729,10 @@ impl ViewState {
                         .saturating_add(interval / 2),
                 )
             }
+            _ => (
+                self.x_to_interval(region.x + region.width),
+                self.x_to_interval(region.x),
+            ),
         }
     }
 
@@ -745,7 +752,7 @@ impl ViewState {
                 let diff = value as f64 - self.latest_x as f64;
                 (diff / interval * cell_width) as f32
             }
-            Basis::Tick(_) => -((value as f32) * self.cell_width),
+            _ => -((value as f32) * self.cell_width),
         }
     }
 
@@ -762,7 +769,7 @@ impl ViewState {
                     self.latest_x.saturating_add(diff)
                 }
             }
-            Basis::Tick(_) => {
+            _ => {
                 let tick = -(x / self.cell_width);
                 tick.round() as u64
             }
@@ -784,12 +791,12 @@ impl ViewState {
     fn y_to_price(&self, y: f32) -> Price {
         if self.tick_size.units == 0 {
             let one = Self::price_unit() as f32;
-            let delta_units = ((y / self.cell_height) * one).round() as i64;
+            let delta_units = ((y / self.cell_height) * one).round() as i128;
             return Price::from_units(self.base_price_y.units - delta_units);
         }
 
         let ticks: f32 = y / self.cell_height;
-        let delta_units = (ticks * self.tick_size.units as f32).round() as i64;
+        let delta_units = (ticks * self.tick_size.units as f32).round() as i128;
         Price::from_units(self.base_price_y.units - delta_units)
     }
 
@@ -867,6 +874,7 @@ impl ViewState {
                     let tick_diff = tick1.abs_diff(tick2);
                     format!("{} ticks", tick_diff)
                 }
+                basis => basis.to_string(),
             };
 
             let rect_x = snapped_p1_x.min(snapped_p2_x);
@@ -918,12 +926,12 @@ impl ViewState {
                     let datapoints = (diff_ms / interval_ms).max(1);
                     format!("{} bars", datapoints)
                 }
-                Basis::Tick(aggregation) => {
+                basis => {
                     let (tick1, _) = self.snap_x_to_index(p1.x, bounds, region);
                     let (tick2, _) = self.snap_x_to_index(p2.x, bounds, region);
 
                     let tick_diff = tick1.abs_diff(tick2);
-                    let datapoints = (tick_diff / u64::from(aggregation.0)).max(1);
+                    let datapoints = (tick_diff / basis.index_units_per_bar()).max(1);
                     format!("{} bars", datapoints)
                 }
             };
@@ -1013,7 +1021,7 @@ impl ViewState {
                 );
                 (rounded_price, rounded_timestamp)
             }
-            Basis::Tick(aggregation) => {
+            basis => {
                 let (chart_x_min, chart_x_max) = (region.x, region.x + region.width);
                 let crosshair_pos = chart_x_min + (cursor_position.x / bounds.width) * region.width;
 
@@ -1022,7 +1030,7 @@ impl ViewState {
                 let snapped_crosshair = cell_index * self.cell_width;
                 let snap_ratio = (snapped_crosshair - chart_x_min) / (chart_x_max - chart_x_min);
 
-                let rounded_tick = (-cell_index as u64) * (u64::from(aggregation.0));
+                let rounded_tick = (-cell_index as u64) * basis.index_units_per_bar();
 
                 frame.stroke(
                     &Path::line(
@@ -1096,7 +1104,7 @@ impl ViewState {
 
                 let millis_at_x = earliest + f64::from(x_ratio) * (latest - earliest);
 
-                let rounded_timestamp = (millis_at_x / (interval as f64)).round() as u64 * interval;
+                let rounded_timestamp = timeframe.bucket_start(millis_at_x as u64 + interval / 2);
 
                 let snap_ratio = if latest - earliest > 0.0 {
                     ((rounded_timestamp as f64 - earliest) / (latest - earliest)) as f32
@@ -1106,7 +1114,7 @@ impl ViewState {
 
                 (rounded_timestamp, snap_ratio)
             }
-            Basis::Tick(aggregation) => {
+            basis => {
                 let (chart_x_min, chart_x_max) = (region.x, region.x + region.width);
                 let chart_x = chart_x_min + x_ratio * (chart_x_max - chart_x_min);
 
@@ -1119,7 +1127,7 @@ impl ViewState {
                     0.5
                 };
 
-                let rounded_tick = (-cell_index as u64) * u64::from(aggregation.0);
+                let rounded_tick = (-cell_index as u64) * basis.index_units_per_bar();
 
                 (rounded_tick, snap_ratio)
             }
@@ -1129,16 +1137,18 @@ impl ViewState {
 
 fn request_fetch(handler: &mut RequestHandler, range: FetchRange) -> Option<Action> {
     match handler.add_request(range) {
-        Ok(Some(req_id)) => {
-            let fetch_spec = FetchSpec {
-                req_id,
-                fetch: range,
-                stream: None,
-            };
-            let fetch = FetchRequests::from([fetch_spec]);
+        Ok(missing) if !missing.is_empty() => {
+            let fetch = missing
+                .into_iter()
+                .map(|(req_id, fetch)| FetchSpec {
+                    req_id,
+                    fetch,
+                    stream: None,
+                })
+                .collect::<FetchRequests>();
             Some(Action::RequestFetch(fetch))
         }
-        Ok(None) => None,
+        Ok(_) => None,
         Err(reason) => {
             log::error!("Failed to request {:?}: {}", range, reason);
             // TODO: handle this more explicitly, maybe by returning Action::ErrorOccurred
@@ -45,7 +45,9 @@ impl ComparisonChart {
     pub fn new(basis: Basis, tickers: &[TickerInfo], config: Option<Config>) -> Self {
         let timeframe = match basis {
             Basis::Time(tf) => tf,
-            Basis::Tick(_) => todo!("WIP: ComparisonChart does not support tick basis"),
+            Basis::Tick(_) | Basis::Volume(_) | Basis::Range(_) | Basis::Renko(_) => {
+                todo!("WIP: ComparisonChart only supports time basis")
+            }
         };
 
         let cfg = config.unwrap_or_default();
@@ -141,8 +143,7 @@ impl ComparisonChart {
         let idx = self.get_or_create_series_idx(&ticker_info);
         let dst = &mut self.series[idx].points;
 
-        let dt = self.timeframe.to_milliseconds().max(1);
-        let align = |t: u64| (t / dt) * dt;
+        let align = |t: u64| self.timeframe.bucket_start(t);
 
         let mut incoming: Vec<(u64, f32)> = klines
             .iter()
@@ -208,9 +209,8 @@ impl ComparisonChart {
         let idx = self.get_or_create_series_idx(ticker_info);
         let series = &mut self.series[idx];
 
-        // Align to timeframe grid
-        let dt = self.timeframe.to_milliseconds().max(1);
-        let t = (kline.time / dt) * dt;
+        // Align to timeframe grid, klines may come from a lower timeframe it is resampled from
+        let t = self.timeframe.bucket_start(kline.time);
         let new_point = (t, kline.close.to_f32());
 
         if let Some((last_x, last_y)) = series.points.last_mut() {
@@ -289,15 +289,17 @@ impl ComparisonChart {
         out: &mut Vec<(uuid::Uuid, FetchRange, Option<StreamKind>)>,
     ) {
         let handler = self.request_handler.entry(ticker).or_default();
-        if let Ok(Some(req_id)) = handler.add_request(range) {
-            out.push((
-                req_id,
-                range,
-                Some(StreamKind::Kline {
-                    ticker_info: ticker,
-                    timeframe: self.timeframe,
-                }),
-            ));
+        if let Ok(missing) = handler.add_request(range) {
+            for (req_id, range) in missing {
+                out.push((
+                    req_id,
+                    range,
+                    Some(StreamKind::Kline {
+                        ticker_info: ticker,
+                        timeframe: self.timeframe,
+                    }),
+                ));
+            }
         }
     }
 
@@ -379,7 +381,9 @@ impl ComparisonChart {
                 let reqs = self.collect_fetch_reqs(self.desired_fetch_batches(self.pan));
                 self.fetch_action(reqs)
             }
-            Basis::Tick(_) => unimplemented!(),
+            Basis::Tick(_) | Basis::Volume(_) | Basis::Range(_) | Basis::Renko(_) => {
+                unimplemented!()
+            }
         }
     }
 
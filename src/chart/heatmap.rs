@@ -8,8 +8,8 @@ use crate::{
 use data::chart::{
     Basis, ViewConfig,
     heatmap::{
-        CLEANUP_THRESHOLD, Config, HeatmapDataPoint, HeatmapStudy, HistoricalDepth, ProfileKind,
-        QtyScale,
+        CLEANUP_THRESHOLD, Config, HeatmapDataPoint, HeatmapStudy, HistoricalDepth, OrderRun,
+        ProfileKind, QtyScale,
     },
     indicator::HeatmapIndicator,
 };
@@ -17,12 +17,12 @@ use data::util::{abbr_large_numbers, count_decimals};
 use data::{
     aggr::time::{DataPoint, TimeSeries},
     chart::Autoscale,
+    export::{self, Scope},
 };
 use exchange::{
-    TickerInfo, Trade,
+    SizeUnit, TickerInfo, Trade,
     depth::Depth,
-    util::{Price, PriceStep},
-    volume_size_unit,
+    util::{Price, PriceStep, Qty},
 };
 
 use iced::widget::canvas::{self, Event, Geometry, Path};
@@ -265,7 +265,7 @@ impl HeatmapChart {
 
         let aggregate_time: u64 = match chart.basis {
             Basis::Time(interval) => interval.into(),
-            Basis::Tick(_) => todo!(),
+            Basis::Tick(_) | Basis::Volume(_) | Basis::Range(_) | Basis::Renko(_) => todo!(),
         };
 
         let rounded_depth_update = (depth_update / aggregate_time) * aggregate_time;
@@ -277,7 +277,7 @@ impl HeatmapChart {
                 .entry(rounded_depth_update)
                 .or_insert_with(|| HeatmapDataPoint {
                     grouped_trades: Box::new([]),
-                    buy_sell: (0.0, 0.0),
+                    buy_sell: (Qty::ZERO, Qty::ZERO),
                 });
 
             for trade in trades_buffer {
@@ -356,7 +356,7 @@ impl HeatmapChart {
     pub fn basis_interval(&self) -> Option<u64> {
         match self.chart.basis {
             Basis::Time(interval) => Some(interval.into()),
-            Basis::Tick(_) => None,
+            Basis::Tick(_) | Basis::Volume(_) | Basis::Range(_) | Basis::Renko(_) => None,
         }
     }
 
@@ -382,6 +382,15 @@ impl HeatmapChart {
         self.chart.tick_size.to_f32_lossy()
     }
 
+    pub fn export_order_runs(&self, scope: Scope) -> export::Table {
+        let (earliest, latest) = match scope {
+            Scope::Visible => self.visible_timerange().unwrap_or((0, u64::MAX)),
+            Scope::Full => (0, u64::MAX),
+        };
+
+        export::order_runs(&self.heatmap, earliest, latest)
+    }
+
     pub fn toggle_indicator(&mut self, indicator: HeatmapIndicator) {
         if self.indicators[indicator].is_some() {
             self.indicators[indicator] = None;
@@ -438,9 +447,9 @@ impl HeatmapChart {
         );
 
         QtyScale {
-            max_trade_qty,
-            max_aggr_volume,
-            max_depth_qty,
+            max_trade_qty: max_trade_qty.to_f32(),
+            max_aggr_volume: max_aggr_volume.to_f32(),
+            max_depth_qty: max_depth_qty.to_f32(),
         }
     }
 }
@@ -500,7 +509,7 @@ impl canvas::Program<Message> for HeatmapChart {
             let (max_aggr_volume, max_trade_qty) =
                 (qty_scales.max_aggr_volume, qty_scales.max_trade_qty);
 
-            let size_in_quote_ccy = volume_size_unit() == exchange::SizeUnit::Quote;
+            let size_unit = self.visual_config().size_unit;
 
             let volume_indicator = self.indicators[HeatmapIndicator::Volume].is_some();
 
@@ -531,7 +540,7 @@ impl canvas::Program<Message> for HeatmapChart {
                     let width = end_x - start_x;
 
                     if width > 0.001 {
-                        let color_alpha = (visual_run.qty() / max_depth_qty).min(1.0);
+                        let color_alpha = (visual_run.qty().to_f32() / max_depth_qty).min(1.0);
 
                         frame.fill_rectangle(
                             Point::new(start_x, y_position - (cell_height / 2.0)),
@@ -548,12 +557,8 @@ impl canvas::Program<Message> for HeatmapChart {
 
                         runs.iter()
                             .filter(|run| {
-                                let order_size = market_type.qty_in_quote_value(
-                                    run.qty(),
-                                    *price,
-                                    size_in_quote_ccy,
-                                );
-                                order_size > self.visual_config.order_size_filter
+                                let order_size = market_type.qty_in_quote_value(run.qty(), *price);
+                                order_size.to_f32() > self.visual_config.order_size_filter
                             })
                             .for_each(|run| {
                                 let start_x = chart.interval_to_x(run.start_time.max(earliest));
@@ -562,7 +567,7 @@ impl canvas::Program<Message> for HeatmapChart {
 
                                 let width = end_x - start_x;
 
-                                let color_alpha = (run.qty() / max_depth_qty).min(1.0);
+                                let color_alpha = (run.qty().to_f32() / max_depth_qty).min(1.0);
 
                                 frame.fill_rectangle(
                                     Point::new(start_x, y_position - (cell_height / 2.0)),
@@ -574,10 +579,16 @@ impl canvas::Program<Message> for HeatmapChart {
             }
 
             if let Some(latest_timestamp) = self.trades.latest_timestamp() {
+                let shown_qty = |price: Price, run: &OrderRun| {
+                    market_type
+                        .qty_in_size_unit(run.qty(), price, size_unit)
+                        .to_f32()
+                };
+
                 let max_qty = self
                     .heatmap
                     .latest_order_runs(highest, lowest, latest_timestamp)
-                    .map(|(_, run)| run.qty())
+                    .map(|(price, run)| shown_qty(*price, run))
                     .fold(f32::MIN, f32::max)
                     .ceil()
                     * 5.0
@@ -588,7 +599,7 @@ impl canvas::Program<Message> for HeatmapChart {
                         .latest_order_runs(highest, lowest, latest_timestamp)
                         .for_each(|(price, run)| {
                             let y_position = chart.price_to_y(*price);
-                            let bar_width = (run.qty() / max_qty) * 50.0;
+                            let bar_width = (shown_qty(*price, run) / max_qty) * 50.0;
 
                             frame.fill_rectangle(
                                 Point::new(0.0, y_position - (cell_height / 2.0)),
@@ -622,13 +633,9 @@ impl canvas::Program<Message> for HeatmapChart {
                     dp.grouped_trades.iter().for_each(|trade| {
                         let y_position = chart.price_to_y(trade.price);
 
-                        let trade_size = market_type.qty_in_quote_value(
-                            trade.qty,
-                            trade.price,
-                            size_in_quote_ccy,
-                        );
+                        let trade_size = market_type.qty_in_quote_value(trade.qty, trade.price);
 
-                        if trade_size > self.visual_config.trade_size_filter {
+                        if trade_size.to_f32() > self.visual_config.trade_size_filter {
                             let color = if trade.is_sell {
                                 palette.danger.base.color
                             } else {
@@ -639,7 +646,7 @@ impl canvas::Program<Message> for HeatmapChart {
                                 if let Some(trade_size_scale) = self.visual_config.trade_size_scale
                                 {
                                     let scale_factor = (trade_size_scale as f32) / 100.0;
-                                    1.0 + (trade.qty / max_trade_qty)
+                                    1.0 + (trade.qty.to_f32() / max_trade_qty)
                                         * (MAX_CIRCLE_RADIUS - 1.0)
                                         * scale_factor
                                 } else {
@@ -664,8 +671,8 @@ impl canvas::Program<Message> for HeatmapChart {
                             frame,
                             x_position,
                             (region.y + region.height) - area_height,
-                            buy_volume,
-                            sell_volume,
+                            buy_volume.to_f32(),
+                            sell_volume.to_f32(),
                             max_aggr_volume,
                             area_height,
                             bar_width,
@@ -732,6 +739,7 @@ impl canvas::Program<Message> for HeatmapChart {
                     chart,
                     &self.trades,
                     area_width,
+                    self.visual_config.size_unit,
                 );
             }
 
@@ -783,14 +791,13 @@ impl canvas::Program<Message> for HeatmapChart {
 
                     let aggr_time: u64 = match chart.basis {
                         Basis::Time(interval) => interval.into(),
-                        Basis::Tick(_) => return,
+                        Basis::Tick(_) | Basis::Volume(_) | Basis::Range(_) | Basis::Renko(_) => {
+                            return;
+                        }
                     };
-                    let tick_size = chart.tick_size.to_f32_lossy();
                     let step = chart.tick_size;
 
-                    let base_data_price = Price::from_f32(cursor_at_price)
-                        .round_to_step(step)
-                        .to_f32();
+                    let base_data_price = Price::from_f32(cursor_at_price).round_to_step(step);
                     let base_data_time = (cursor_at_time / aggr_time) * aggr_time;
 
                     let price_tick_offsets = [1i64, 0, -1];
@@ -798,14 +805,14 @@ impl canvas::Program<Message> for HeatmapChart {
 
                     let prices_for_display_lookup: [f32; 3] = std::array::from_fn(|i| {
                         let offset = price_tick_offsets[i];
-                        base_data_price + (offset as f32 * tick_size)
+                        base_data_price.add_steps(offset, step).to_f32()
                     });
                     let times_for_display_lookup: [u64; 4] = std::array::from_fn(|i| {
                         let offset = time_interval_offsets[i];
                         base_data_time.saturating_add_signed(offset * aggr_time as i64)
                     });
 
-                    let display_grid_qtys: FxHashMap<(u64, Price), (f32, bool)> =
+                    let display_grid_qtys: FxHashMap<(u64, Price), (Qty, bool)> =
                         self.heatmap.query_grid_qtys(
                             base_data_time,
                             base_data_price,
@@ -820,6 +827,8 @@ impl canvas::Program<Message> for HeatmapChart {
                         return;
                     }
 
+                    let size_unit = self.visual_config.size_unit;
+
                     let should_draw_below = cursor_position.y < TOOLTIP_HEIGHT + TOOLTIP_PADDING;
                     let should_draw_left =
                         cursor_position.x > bounds.width - (TOOLTIP_WIDTH + TOOLTIP_PADDING);
@@ -860,7 +869,11 @@ impl canvas::Program<Message> for HeatmapChart {
                             if let Some((qty, is_bid)) =
                                 display_grid_qtys.get(&(data_time_val, data_price_key))
                             {
-                                let text_content = abbr_large_numbers(*qty);
+                                let text_content = abbr_large_numbers(
+                                    market_type
+                                        .qty_in_size_unit(*qty, data_price_key, size_unit)
+                                        .to_f32(),
+                                );
                                 let color = if *is_bid {
                                     palette.success.strong.color
                                 } else {
@@ -905,7 +918,7 @@ impl canvas::Program<Message> for HeatmapChart {
         match interaction {
             Interaction::Panning { .. } => mouse::Interaction::Grabbing,
             Interaction::Zoomin { .. } => mouse::Interaction::ZoomIn,
-            Interaction::None | Interaction::Ruler { .. } => {
+            Interaction::None | Interaction::Ruler { .. } | Interaction::Anchoring => {
                 if cursor.is_over(bounds) {
                     return mouse::Interaction::Crosshair;
                 }
@@ -931,8 +944,10 @@ fn draw_volume_profile(
     chart: &ViewState,
     timeseries: &TimeSeries<HeatmapDataPoint>,
     area_width: f32,
+    size_unit: SizeUnit,
 ) {
     let (highest, lowest) = chart.price_range(region);
+    let market_type = chart.ticker_info.market_type();
 
     let time_range = match kind {
         ProfileKind::VisibleRange => {
@@ -943,7 +958,7 @@ fn draw_volume_profile(
         ProfileKind::FixedWindow(datapoints) => {
             let basis_interval: u64 = match chart.basis {
                 Basis::Time(interval) => interval.into(),
-                Basis::Tick(_) => return,
+                Basis::Tick(_) | Basis::Volume(_) | Basis::Range(_) | Basis::Renko(_) => return,
             };
 
             let latest = chart
@@ -986,10 +1001,13 @@ fn draw_volume_profile(
                 let index = ((grouped_price.units - first_tick.units) / step.units) as usize;
 
                 if let Some(entry) = profile.get_mut(index) {
+                    let qty = market_type
+                        .qty_in_size_unit(trade.qty, trade.price, size_unit)
+                        .to_f32();
                     if trade.is_sell {
-                        entry.1 += trade.qty;
+                        entry.1 += qty;
                     } else {
-                        entry.0 += trade.qty;
+                        entry.0 += qty;
                     }
                     max_aggr_volume = max_aggr_volume.max(entry.0 + entry.1);
                 }
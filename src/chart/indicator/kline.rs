@@ -1,13 +1,38 @@
-use crate::chart::{Message, ViewState};
+use crate::chart::{
+    Message, ViewState,
+    indicator::plot::{AnySeries, Series},
+};
 
 use data::chart::PlotData;
 use data::chart::indicator::KlineIndicator;
-use data::chart::kline::KlineDataPoint;
+use data::chart::kline::{KlineDataPoint, KlineTrades};
+use exchange::adapter::MarketKind;
 use exchange::fetcher::FetchRange;
-use exchange::{Kline, Timeframe, Trade};
+use exchange::util::Price;
+use exchange::{Kline, SizeUnit, Trade};
 
+use iced::theme::palette::Extended;
+use iced::widget::canvas;
+use iced::{Point, widget::row};
+use std::collections::BTreeMap;
+use std::ops::RangeInclusive;
+
+pub mod atr;
+pub mod bands;
+pub mod bar_delta;
+pub mod cumulative_delta;
+pub mod macd;
+pub mod moving_average;
 pub mod open_interest;
+pub mod realized_volatility;
+pub mod rsi;
+pub mod script;
+pub mod stochastic;
+pub mod trade_count;
+pub mod trade_size;
 pub mod volume;
+pub mod volume_z_score;
+pub mod vwap;
 
 pub trait KlineIndicatorImpl {
     /// Clear all caches for a full redraw
@@ -17,11 +42,25 @@ pub trait KlineIndicatorImpl {
     /// e.g. tooltips and scale labels for a partial redraw
     fn clear_crosshair_caches(&mut self);
 
+    /// Panel of the indicator, overlays have none
     fn element<'a>(
         &'a self,
-        chart: &'a ViewState,
-        visible_range: std::ops::RangeInclusive<u64>,
-    ) -> iced::Element<'a, Message>;
+        _chart: &'a ViewState,
+        _visible_range: RangeInclusive<u64>,
+    ) -> iced::Element<'a, Message> {
+        row![].into()
+    }
+
+    /// Draws onto the price chart, for overlays only; `frame` is already
+    /// translated and scaled to the chart's coordinates
+    fn draw_overlay(
+        &self,
+        _frame: &mut canvas::Frame,
+        _chart: &ViewState,
+        _visible_range: RangeInclusive<u64>,
+        _palette: &Extended,
+    ) {
+    }
 
     /// If the indicator needs data fetching, return the required range
     fn fetch_range(&mut self, _ctx: &FetchCtx) -> Option<FetchRange> {
@@ -43,15 +82,28 @@ pub trait KlineIndicatorImpl {
 
     fn on_ticksize_change(&mut self, _source: &PlotData<KlineDataPoint>) {}
 
+    /// Display unit for quantity values, applied on the next rebuild
+    fn set_size_unit(&mut self, _market: MarketKind, _unit: SizeUnit) {}
+
+    /// Parameters of the instance were edited, applied on the next rebuild
+    fn set_config(&mut self, _config: KlineIndicator) {}
+
     /// Timeframe/tick interval has changed
     fn on_basis_change(&mut self, _source: &PlotData<KlineDataPoint>) {}
 
     fn on_open_interest(&mut self, _pairs: &[exchange::OpenInterest]) {}
+
+    /// Open times of the bars the user anchored on, replacing any set before
+    fn set_anchors(&mut self, _anchors: &[u64]) {}
+
+    /// Time-keyed values of the indicator, if it holds any that are worth exporting
+    fn series(&self) -> Option<&std::collections::BTreeMap<u64, f32>> {
+        None
+    }
 }
 
 pub struct FetchCtx<'a> {
     pub main_chart: &'a ViewState,
-    pub timeframe: Timeframe,
     pub visible_earliest: u64,
     pub kline_latest: u64,
     pub prefetch_earliest: u64,
@@ -59,9 +111,132 @@ pub struct FetchCtx<'a> {
 
 pub fn make_empty(which: KlineIndicator) -> Box<dyn KlineIndicatorImpl> {
     match which {
-        KlineIndicator::Volume => Box::new(super::kline::volume::VolumeIndicator::new()),
-        KlineIndicator::OpenInterest => {
-            Box::new(super::kline::open_interest::OpenInterestIndicator::new())
+        KlineIndicator::Volume { delta } => {
+            Box::new(super::kline::volume::VolumeIndicator::new(delta))
+        }
+        KlineIndicator::OpenInterest { change } => Box::new(
+            super::kline::open_interest::OpenInterestIndicator::new(change),
+        ),
+        KlineIndicator::MovingAverage {
+            kind,
+            period,
+            source,
+        } => Box::new(super::kline::moving_average::MovingAverageIndicator::new(
+            kind, period, source,
+        )),
+        KlineIndicator::Bands {
+            kind,
+            period,
+            width,
+        } => Box::new(super::kline::bands::BandsIndicator::new(
+            kind, period, width,
+        )),
+        KlineIndicator::CumulativeDelta { style, reset } => {
+            Box::new(super::kline::cumulative_delta::CumulativeDeltaIndicator::new(style, reset))
+        }
+        KlineIndicator::Vwap { anchor, bands } => {
+            Box::new(super::kline::vwap::VwapIndicator::new(anchor, bands))
+        }
+        KlineIndicator::Rsi { period, overbought } => {
+            Box::new(super::kline::rsi::RsiIndicator::new(period, overbought))
+        }
+        KlineIndicator::Macd { fast, slow, signal } => {
+            Box::new(super::kline::macd::MacdIndicator::new(fast, slow, signal))
+        }
+        KlineIndicator::Stochastic {
+            period,
+            smooth_k,
+            smooth_d,
+        } => Box::new(super::kline::stochastic::StochasticIndicator::new(
+            period, smooth_k, smooth_d,
+        )),
+        KlineIndicator::Atr { period } => Box::new(super::kline::atr::AtrIndicator::new(period)),
+        KlineIndicator::RealizedVolatility { estimator, period } => Box::new(
+            super::kline::realized_volatility::RealizedVolatilityIndicator::new(estimator, period),
+        ),
+        KlineIndicator::VolumeZScore { period, threshold } => Box::new(
+            super::kline::volume_z_score::VolumeZScoreIndicator::new(period, threshold),
+        ),
+        KlineIndicator::BarDelta => Box::new(super::kline::bar_delta::BarDeltaIndicator::new()),
+        KlineIndicator::TradeCount => {
+            Box::new(super::kline::trade_count::TradeCountIndicator::new())
         }
+        KlineIndicator::AverageTradeSize => {
+            Box::new(super::kline::trade_size::TradeSizeIndicator::new())
+        }
+        KlineIndicator::Script { name } => {
+            Box::new(super::kline::script::ScriptIndicator::new(name))
+        }
+    }
+}
+
+/// Every bar of the source keyed by open time or by tick index, mapped through `bar`
+fn source_bars<'a, T: 'a>(
+    source: &'a PlotData<KlineDataPoint>,
+    bar: impl Fn(&'a Kline, &'a KlineTrades) -> T + 'a,
+) -> Box<dyn Iterator<Item = (u64, T)> + 'a> {
+    match source {
+        PlotData::TimeBased(timeseries) => Box::new(
+            timeseries
+                .datapoints
+                .iter()
+                .map(move |(time, dp)| (*time, bar(&dp.kline, &dp.footprint))),
+        ),
+        PlotData::TickBased(tickseries) => Box::new(
+            tickseries
+                .datapoints
+                .iter()
+                .enumerate()
+                .map(move |(idx, dp)| (idx as u64, bar(&dp.kline, &dp.footprint))),
+        ),
     }
 }
+
+/// Tick bars touched by trades inserted when the source had `old_dp_len` bars,
+/// `None` for time based sources whose bars arrive as klines
+fn updated_tick_bars<'a, T: 'a>(
+    source: &'a PlotData<KlineDataPoint>,
+    old_dp_len: usize,
+    bar: impl Fn(&'a Kline, &'a KlineTrades) -> T + 'a,
+) -> Option<impl Iterator<Item = (u64, T)> + 'a> {
+    let PlotData::TickBased(tickseries) = source else {
+        return None;
+    };
+
+    Some(
+        tickseries
+            .datapoints
+            .iter()
+            .enumerate()
+            .skip(old_dp_len.saturating_sub(1))
+            .map(move |(idx, dp)| (idx as u64, bar(&dp.kline, &dp.footprint))),
+    )
+}
+
+/// Points of an overlay line over the visible range, values keyed like the chart's datapoints
+fn overlay_points<Y>(
+    chart: &ViewState,
+    data: &BTreeMap<u64, Y>,
+    visible_range: RangeInclusive<u64>,
+    value: impl Fn(&Y) -> f32,
+) -> Vec<Point> {
+    let mut points = vec![];
+
+    AnySeries::for_basis(chart.basis, data).for_each_in(visible_range, |x, y| {
+        let price = Price::from_f32_lossy(value(y));
+        points.push(Point::new(chart.interval_to_x(x), chart.price_to_y(price)));
+    });
+
+    points
+}
+
+fn polyline(points: &[Point]) -> Option<canvas::Path> {
+    let (first, rest) = points.split_first()?;
+
+    Some(canvas::Path::new(|builder| {
+        builder.move_to(*first);
+        for point in rest {
+            builder.line_to(*point);
+        }
+    }))
+}
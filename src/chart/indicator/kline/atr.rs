@@ -0,0 +1,112 @@
+use crate::chart::{
+    Caches, Message, ViewState,
+    indicator::{
+        indicator_row,
+        kline::{KlineIndicatorImpl, source_bars, updated_tick_bars},
+        plot::{PlotTooltip, line::LinePlot},
+    },
+};
+
+use data::chart::{
+    PlotData,
+    indicator::{KlineIndicator, oscillator::AtrSeries},
+    kline::KlineDataPoint,
+};
+use data::util::count_decimals;
+use exchange::{Kline, Trade};
+
+use std::ops::RangeInclusive;
+
+pub struct AtrIndicator {
+    cache: Caches,
+    series: AtrSeries,
+}
+
+impl AtrIndicator {
+    pub fn new(period: u16) -> Self {
+        Self {
+            cache: Caches::default(),
+            series: AtrSeries::new(period as usize),
+        }
+    }
+
+    fn indicator_elem<'a>(
+        &'a self,
+        main_chart: &'a ViewState,
+        visible_range: RangeInclusive<u64>,
+    ) -> iced::Element<'a, Message> {
+        // a digit finer than the tick size, ranges are averages of whole ticks
+        let decimals = count_decimals(main_chart.tick_size.to_f32_lossy()) + 1;
+
+        let plot = LinePlot::new(|atr: &f32| *atr)
+            .stroke_width(1.0)
+            .show_points(false)
+            .with_tooltip(move |atr: &f32, _next: Option<&f32>| {
+                PlotTooltip::new(format!("ATR: {atr:.decimals$}"))
+            });
+
+        indicator_row(
+            main_chart,
+            &self.cache,
+            plot,
+            self.series.values(),
+            visible_range,
+        )
+    }
+}
+
+impl KlineIndicatorImpl for AtrIndicator {
+    fn clear_all_caches(&mut self) {
+        self.cache.clear_all();
+    }
+
+    fn clear_crosshair_caches(&mut self) {
+        self.cache.clear_crosshair();
+    }
+
+    fn element<'a>(
+        &'a self,
+        chart: &'a ViewState,
+        visible_range: RangeInclusive<u64>,
+    ) -> iced::Element<'a, Message> {
+        self.indicator_elem(chart, visible_range)
+    }
+
+    fn rebuild_from_source(&mut self, source: &PlotData<KlineDataPoint>) {
+        self.series.clear();
+        self.series.insert(source_bars(source, |kline, _| kline));
+        self.clear_all_caches();
+    }
+
+    fn on_insert_klines(&mut self, klines: &[Kline]) {
+        self.series
+            .insert(klines.iter().map(|kline| (kline.time, kline)));
+        self.clear_all_caches();
+    }
+
+    fn on_insert_trades(
+        &mut self,
+        _trades: &[Trade],
+        old_dp_len: usize,
+        source: &PlotData<KlineDataPoint>,
+    ) {
+        if let Some(bars) = updated_tick_bars(source, old_dp_len, |kline, _| kline) {
+            self.series.insert(bars);
+            self.clear_all_caches();
+        }
+    }
+
+    fn on_ticksize_change(&mut self, source: &PlotData<KlineDataPoint>) {
+        self.rebuild_from_source(source);
+    }
+
+    fn on_basis_change(&mut self, source: &PlotData<KlineDataPoint>) {
+        self.rebuild_from_source(source);
+    }
+
+    fn set_config(&mut self, config: KlineIndicator) {
+        if let KlineIndicator::Atr { period } = config {
+            *self = Self::new(period);
+        }
+    }
+}
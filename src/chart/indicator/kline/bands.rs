@@ -0,0 +1,156 @@
+use crate::chart::{
+    ViewState,
+    indicator::kline::{KlineIndicatorImpl, overlay_points, polyline},
+};
+
+use data::chart::{
+    PlotData,
+    indicator::{
+        KlineIndicator,
+        bands::{BandKind, BandSeries},
+    },
+    kline::KlineDataPoint,
+};
+use exchange::{Kline, Trade};
+
+use iced::theme::palette::Extended;
+use iced::widget::canvas::{self, LineDash, Path, Stroke};
+use std::ops::RangeInclusive;
+
+pub struct BandsIndicator {
+    series: BandSeries,
+}
+
+impl BandsIndicator {
+    pub fn new(kind: BandKind, period: u16, width: f32) -> Self {
+        Self {
+            series: BandSeries::new(kind, period as usize, width),
+        }
+    }
+}
+
+impl KlineIndicatorImpl for BandsIndicator {
+    // drawn within the main chart's cache, nothing of its own to clear
+    fn clear_all_caches(&mut self) {}
+
+    fn clear_crosshair_caches(&mut self) {}
+
+    fn draw_overlay(
+        &self,
+        frame: &mut canvas::Frame,
+        chart: &ViewState,
+        visible_range: RangeInclusive<u64>,
+        palette: &Extended,
+    ) {
+        let values = self.series.values();
+
+        let upper = overlay_points(chart, values, visible_range.clone(), |b| b.upper);
+        let middle = overlay_points(chart, values, visible_range.clone(), |b| b.middle);
+        let lower = overlay_points(chart, values, visible_range, |b| b.lower);
+
+        let color = palette.primary.base.color;
+
+        // area between the outer lines, along the upper and back along the lower
+        let area = Path::new(|builder| {
+            let mut outline = upper.iter().chain(lower.iter().rev());
+            if let Some(first) = outline.next() {
+                builder.move_to(*first);
+                outline.for_each(|point| builder.line_to(*point));
+                builder.close();
+            }
+        });
+        frame.fill(&area, color.scale_alpha(0.06));
+
+        let outer_stroke = Stroke::with_color(
+            Stroke {
+                width: 1.0,
+                ..Stroke::default()
+            },
+            color.scale_alpha(0.8),
+        );
+        for line in [&upper, &lower].into_iter().filter_map(|p| polyline(p)) {
+            frame.stroke(&line, outer_stroke);
+        }
+
+        if let Some(line) = polyline(&middle) {
+            frame.stroke(
+                &line,
+                Stroke::with_color(
+                    Stroke {
+                        width: 1.0,
+                        line_dash: LineDash {
+                            segments: &[4.0, 2.0],
+                            offset: 0,
+                        },
+                        ..Stroke::default()
+                    },
+                    color.scale_alpha(0.6),
+                ),
+            );
+        }
+    }
+
+    fn rebuild_from_source(&mut self, source: &PlotData<KlineDataPoint>) {
+        self.series.clear();
+
+        match source {
+            PlotData::TimeBased(timeseries) => self.series.insert(
+                timeseries
+                    .datapoints
+                    .iter()
+                    .map(|(time, dp)| (*time, &dp.kline)),
+            ),
+            PlotData::TickBased(tickseries) => self.series.insert(
+                tickseries
+                    .datapoints
+                    .iter()
+                    .enumerate()
+                    .map(|(idx, dp)| (idx as u64, &dp.kline)),
+            ),
+        }
+    }
+
+    fn on_insert_klines(&mut self, klines: &[Kline]) {
+        self.series
+            .insert(klines.iter().map(|kline| (kline.time, kline)));
+    }
+
+    fn on_insert_trades(
+        &mut self,
+        _trades: &[Trade],
+        old_dp_len: usize,
+        source: &PlotData<KlineDataPoint>,
+    ) {
+        if let PlotData::TickBased(tickseries) = source {
+            let start_idx = old_dp_len.saturating_sub(1);
+
+            self.series.insert(
+                tickseries
+                    .datapoints
+                    .iter()
+                    .enumerate()
+                    .skip(start_idx)
+                    .map(|(idx, dp)| (idx as u64, &dp.kline)),
+            );
+        }
+    }
+
+    fn on_ticksize_change(&mut self, source: &PlotData<KlineDataPoint>) {
+        self.rebuild_from_source(source);
+    }
+
+    fn on_basis_change(&mut self, source: &PlotData<KlineDataPoint>) {
+        self.rebuild_from_source(source);
+    }
+
+    fn set_config(&mut self, config: KlineIndicator) {
+        if let KlineIndicator::Bands {
+            kind,
+            period,
+            width,
+        } = config
+        {
+            *self = Self::new(kind, period, width);
+        }
+    }
+}
@@ -0,0 +1,182 @@
+use crate::chart::{
+    Caches, Message, ViewState,
+    indicator::{
+        indicator_row,
+        kline::KlineIndicatorImpl,
+        plot::{
+            PlotTooltip,
+            candle::{CandlePlot, Ohlc},
+        },
+    },
+};
+
+use data::chart::{
+    PlotData,
+    indicator::bar_trades::DeltaBar,
+    kline::{KlineDataPoint, KlineTrades},
+};
+use data::util::format_with_commas;
+use exchange::{Kline, Trade};
+
+use std::collections::BTreeMap;
+use std::ops::RangeInclusive;
+
+/// Each bar's delta drawn as a candle opening at zero, wicks reaching
+/// the lowest and highest the delta went while the bar formed
+pub struct BarDeltaIndicator {
+    cache: Caches,
+    data: BTreeMap<u64, DeltaBar>,
+}
+
+impl BarDeltaIndicator {
+    pub fn new() -> Self {
+        Self {
+            cache: Caches::default(),
+            data: BTreeMap::new(),
+        }
+    }
+
+    fn insert<'a>(&mut self, bars: impl Iterator<Item = (u64, &'a Kline, &'a KlineTrades)>) {
+        for (key, kline, footprint) in bars {
+            match DeltaBar::new(kline, footprint) {
+                Some(bar) => self.data.insert(key, bar),
+                None => self.data.remove(&key),
+            };
+        }
+    }
+
+    fn indicator_elem<'a>(
+        &'a self,
+        main_chart: &'a ViewState,
+        visible_range: RangeInclusive<u64>,
+    ) -> iced::Element<'a, Message> {
+        let plot = CandlePlot::new(|bar: &DeltaBar| Ohlc {
+            open: 0.0,
+            high: bar.highest,
+            low: bar.lowest,
+            close: bar.delta,
+        })
+        .with_tooltip(|bar: &DeltaBar, _next: Option<&DeltaBar>| {
+            let delta = format!("Delta: {}", format_with_commas(bar.delta));
+
+            if bar.has_extremes {
+                PlotTooltip::new(format!(
+                    "{delta}\nMax: {}\nMin: {}",
+                    format_with_commas(bar.highest),
+                    format_with_commas(bar.lowest)
+                ))
+            } else {
+                PlotTooltip::new(delta)
+            }
+        });
+
+        indicator_row(main_chart, &self.cache, plot, &self.data, visible_range)
+    }
+}
+
+impl KlineIndicatorImpl for BarDeltaIndicator {
+    fn clear_all_caches(&mut self) {
+        self.cache.clear_all();
+    }
+
+    fn clear_crosshair_caches(&mut self) {
+        self.cache.clear_crosshair();
+    }
+
+    fn element<'a>(
+        &'a self,
+        chart: &'a ViewState,
+        visible_range: RangeInclusive<u64>,
+    ) -> iced::Element<'a, Message> {
+        self.indicator_elem(chart, visible_range)
+    }
+
+    fn rebuild_from_source(&mut self, source: &PlotData<KlineDataPoint>) {
+        self.data.clear();
+
+        match source {
+            PlotData::TimeBased(timeseries) => self.insert(
+                timeseries
+                    .datapoints
+                    .iter()
+                    .map(|(time, dp)| (*time, &dp.kline, &dp.footprint)),
+            ),
+            PlotData::TickBased(tickseries) => self.insert(
+                tickseries
+                    .datapoints
+                    .iter()
+                    .enumerate()
+                    .map(|(idx, dp)| (idx as u64, &dp.kline, &dp.footprint)),
+            ),
+        }
+        self.clear_all_caches();
+    }
+
+    fn on_insert_klines(&mut self, klines: &[Kline]) {
+        let no_trades = KlineTrades::new();
+
+        for kline in klines {
+            let Some(mut bar) = DeltaBar::new(kline, &no_trades) else {
+                continue;
+            };
+
+            // extremes followed trade by trade are kept, only the close moves
+            if let Some(prev) = self.data.get(&kline.time)
+                && prev.has_extremes
+            {
+                bar = DeltaBar {
+                    lowest: prev.lowest.min(bar.delta),
+                    highest: prev.highest.max(bar.delta),
+                    has_extremes: true,
+                    ..bar
+                };
+            }
+            self.data.insert(kline.time, bar);
+        }
+        self.clear_all_caches();
+    }
+
+    fn on_insert_trades(
+        &mut self,
+        trades: &[Trade],
+        old_dp_len: usize,
+        source: &PlotData<KlineDataPoint>,
+    ) {
+        match source {
+            PlotData::TimeBased(timeseries) => {
+                let Some(earliest) = trades.iter().map(|trade| trade.time).min() else {
+                    return;
+                };
+                let from = timeseries.interval.bucket_start(earliest);
+
+                self.insert(
+                    timeseries
+                        .datapoints
+                        .range(from..)
+                        .map(|(time, dp)| (*time, &dp.kline, &dp.footprint)),
+                );
+            }
+            PlotData::TickBased(tickseries) => {
+                let start_idx = old_dp_len.saturating_sub(1);
+
+                self.insert(
+                    tickseries
+                        .datapoints
+                        .iter()
+                        .enumerate()
+                        .skip(start_idx)
+                        .map(|(idx, dp)| (idx as u64, &dp.kline, &dp.footprint)),
+                );
+            }
+        }
+        self.clear_all_caches();
+    }
+
+    fn on_ticksize_change(&mut self, source: &PlotData<KlineDataPoint>) {
+        self.rebuild_from_source(source);
+    }
+
+    fn on_basis_change(&mut self, source: &PlotData<KlineDataPoint>) {
+        self.rebuild_from_source(source);
+    }
+}
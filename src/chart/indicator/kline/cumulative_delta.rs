@@ -0,0 +1,208 @@
+use crate::chart::{
+    Basis, Caches, Message, ViewState,
+    indicator::{
+        indicator_row,
+        kline::KlineIndicatorImpl,
+        plot::{
+            PlotTooltip,
+            candle::{CandlePlot, Ohlc},
+            line::LinePlot,
+        },
+    },
+};
+
+use data::chart::{
+    PlotData,
+    indicator::{
+        KlineIndicator,
+        cvd::{CvdBar, CvdReset, CvdSeries, CvdStyle},
+    },
+    kline::KlineDataPoint,
+};
+use data::util::format_with_commas;
+use exchange::{Kline, Trade};
+
+use iced::widget::{center, text};
+use std::ops::RangeInclusive;
+
+pub struct CumulativeDeltaIndicator {
+    cache: Caches,
+    series: CvdSeries,
+    style: CvdStyle,
+    reset: CvdReset,
+}
+
+impl CumulativeDeltaIndicator {
+    pub fn new(style: CvdStyle, reset: CvdReset) -> Self {
+        Self {
+            cache: Caches::default(),
+            series: CvdSeries::new(reset),
+            style,
+            reset,
+        }
+    }
+
+    /// Cumulative delta up to the earliest visible bar, subtracted when resetting on the visible range
+    fn visible_offset(&self, basis: Basis, visible_range: &RangeInclusive<u64>) -> f32 {
+        if self.reset != CvdReset::VisibleRange {
+            return 0.0;
+        }
+
+        let bars = self.series.bars();
+        let earliest_key = match basis {
+            Basis::Time(_) => *visible_range.start(),
+            // tick bars are keyed oldest first, the range counts back from the latest
+            _ => bars
+                .last_key_value()
+                .map_or(0, |(last, _)| last.saturating_sub(*visible_range.end())),
+        };
+
+        bars.range(..earliest_key)
+            .next_back()
+            .map_or(0.0, |(_, bar)| bar.close)
+    }
+
+    fn indicator_elem<'a>(
+        &'a self,
+        main_chart: &'a ViewState,
+        visible_range: RangeInclusive<u64>,
+    ) -> iced::Element<'a, Message> {
+        if !self.series.bars().is_empty() && !self.series.has_split_volume() {
+            let exchange = main_chart.ticker_info.exchange();
+            return center(text(format!(
+                "CVD needs buy/sell volume, {exchange} reports only the total"
+            )))
+            .into();
+        }
+
+        let offset = self.visible_offset(main_chart.basis, &visible_range);
+
+        let tooltip = move |bar: &CvdBar, _next: Option<&CvdBar>| {
+            PlotTooltip::new(format!(
+                "CVD: {}\nBar delta: {}",
+                format_with_commas(bar.close - offset),
+                format_with_commas(bar.delta())
+            ))
+        };
+
+        match self.style {
+            CvdStyle::Candles => {
+                let plot = CandlePlot::new(move |bar: &CvdBar| Ohlc {
+                    open: bar.open - offset,
+                    high: bar.high - offset,
+                    low: bar.low - offset,
+                    close: bar.close - offset,
+                })
+                .with_tooltip(tooltip);
+
+                indicator_row(
+                    main_chart,
+                    &self.cache,
+                    plot,
+                    self.series.bars(),
+                    visible_range,
+                )
+            }
+            CvdStyle::Line => {
+                let plot = LinePlot::new(move |bar: &CvdBar| bar.close - offset)
+                    .stroke_width(1.0)
+                    .show_points(false)
+                    .with_tooltip(tooltip);
+
+                indicator_row(
+                    main_chart,
+                    &self.cache,
+                    plot,
+                    self.series.bars(),
+                    visible_range,
+                )
+            }
+        }
+    }
+}
+
+impl KlineIndicatorImpl for CumulativeDeltaIndicator {
+    fn clear_all_caches(&mut self) {
+        self.cache.clear_all();
+    }
+
+    fn clear_crosshair_caches(&mut self) {
+        self.cache.clear_crosshair();
+    }
+
+    fn element<'a>(
+        &'a self,
+        chart: &'a ViewState,
+        visible_range: RangeInclusive<u64>,
+    ) -> iced::Element<'a, Message> {
+        self.indicator_elem(chart, visible_range)
+    }
+
+    fn rebuild_from_source(&mut self, source: &PlotData<KlineDataPoint>) {
+        self.series.clear();
+
+        match source {
+            PlotData::TimeBased(timeseries) => self.series.insert(
+                timeseries
+                    .datapoints
+                    .iter()
+                    .map(|(time, dp)| (*time, &dp.kline)),
+            ),
+            PlotData::TickBased(tickseries) => self.series.insert(
+                tickseries
+                    .datapoints
+                    .iter()
+                    .enumerate()
+                    .map(|(idx, dp)| (idx as u64, &dp.kline)),
+            ),
+        }
+        self.clear_all_caches();
+    }
+
+    fn on_insert_klines(&mut self, klines: &[Kline]) {
+        self.series
+            .insert(klines.iter().map(|kline| (kline.time, kline)));
+        self.clear_all_caches();
+    }
+
+    fn on_insert_trades(
+        &mut self,
+        _trades: &[Trade],
+        old_dp_len: usize,
+        source: &PlotData<KlineDataPoint>,
+    ) {
+        match source {
+            PlotData::TimeBased(_) => return,
+            PlotData::TickBased(tickseries) => {
+                let start_idx = old_dp_len.saturating_sub(1);
+                self.series.insert(
+                    tickseries
+                        .datapoints
+                        .iter()
+                        .enumerate()
+                        .skip(start_idx)
+                        .map(|(idx, dp)| (idx as u64, &dp.kline)),
+                );
+            }
+        }
+        self.clear_all_caches();
+    }
+
+    fn on_ticksize_change(&mut self, source: &PlotData<KlineDataPoint>) {
+        self.rebuild_from_source(source);
+    }
+
+    fn on_basis_change(&mut self, source: &PlotData<KlineDataPoint>) {
+        self.rebuild_from_source(source);
+    }
+
+    fn set_config(&mut self, config: KlineIndicator) {
+        if let KlineIndicator::CumulativeDelta { style, reset } = config {
+            self.style = style;
+            if reset != self.reset {
+                self.reset = reset;
+                self.series = CvdSeries::new(reset);
+            }
+        }
+    }
+}
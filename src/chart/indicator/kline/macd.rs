@@ -0,0 +1,129 @@
+use crate::chart::{
+    Caches, Message, ViewState,
+    indicator::{
+        indicator_row,
+        kline::{KlineIndicatorImpl, source_bars, updated_tick_bars},
+        plot::{
+            PlotTooltip,
+            bar::{BarClass, BarPlot, Baseline},
+            line::LinePlot,
+        },
+    },
+};
+
+use data::chart::{
+    PlotData,
+    indicator::{
+        KlineIndicator,
+        oscillator::{MacdPoint, MacdSeries},
+    },
+    kline::KlineDataPoint,
+};
+use exchange::{Kline, Trade};
+
+use std::ops::RangeInclusive;
+
+pub struct MacdIndicator {
+    cache: Caches,
+    series: MacdSeries,
+}
+
+impl MacdIndicator {
+    pub fn new(fast: u16, slow: u16, signal: u16) -> Self {
+        Self {
+            cache: Caches::default(),
+            series: MacdSeries::new(fast as usize, slow as usize, signal as usize),
+        }
+    }
+
+    fn indicator_elem<'a>(
+        &'a self,
+        main_chart: &'a ViewState,
+        visible_range: RangeInclusive<u64>,
+    ) -> iced::Element<'a, Message> {
+        let histogram = BarPlot::new(MacdPoint::histogram, |_: &MacdPoint| BarClass::Directional)
+            .baseline(Baseline::Signed)
+            .bar_width_factor(0.6)
+            .padding(0.08)
+            .with_tooltip(|point: &MacdPoint, _next: Option<&MacdPoint>| {
+                PlotTooltip::new(format!(
+                    "MACD: {:.4}\nSignal: {:.4}\nHistogram: {:.4}",
+                    point.macd,
+                    point.signal,
+                    point.histogram()
+                ))
+            });
+
+        let macd = LinePlot::new(|point: &MacdPoint| point.macd)
+            .stroke_width(1.0)
+            .show_points(false)
+            .color(|palette| palette.primary.strong.color);
+        let signal = LinePlot::new(|point: &MacdPoint| point.signal)
+            .stroke_width(1.0)
+            .show_points(false);
+
+        indicator_row(
+            main_chart,
+            &self.cache,
+            (histogram, (macd, signal)),
+            self.series.values(),
+            visible_range,
+        )
+    }
+}
+
+impl KlineIndicatorImpl for MacdIndicator {
+    fn clear_all_caches(&mut self) {
+        self.cache.clear_all();
+    }
+
+    fn clear_crosshair_caches(&mut self) {
+        self.cache.clear_crosshair();
+    }
+
+    fn element<'a>(
+        &'a self,
+        chart: &'a ViewState,
+        visible_range: RangeInclusive<u64>,
+    ) -> iced::Element<'a, Message> {
+        self.indicator_elem(chart, visible_range)
+    }
+
+    fn rebuild_from_source(&mut self, source: &PlotData<KlineDataPoint>) {
+        self.series.clear();
+        self.series.insert(source_bars(source, |kline, _| kline));
+        self.clear_all_caches();
+    }
+
+    fn on_insert_klines(&mut self, klines: &[Kline]) {
+        self.series
+            .insert(klines.iter().map(|kline| (kline.time, kline)));
+        self.clear_all_caches();
+    }
+
+    fn on_insert_trades(
+        &mut self,
+        _trades: &[Trade],
+        old_dp_len: usize,
+        source: &PlotData<KlineDataPoint>,
+    ) {
+        if let Some(bars) = updated_tick_bars(source, old_dp_len, |kline, _| kline) {
+            self.series.insert(bars);
+            self.clear_all_caches();
+        }
+    }
+
+    fn on_ticksize_change(&mut self, source: &PlotData<KlineDataPoint>) {
+        self.rebuild_from_source(source);
+    }
+
+    fn on_basis_change(&mut self, source: &PlotData<KlineDataPoint>) {
+        self.rebuild_from_source(source);
+    }
+
+    fn set_config(&mut self, config: KlineIndicator) {
+        if let KlineIndicator::Macd { fast, slow, signal } = config {
+            *self = Self::new(fast, slow, signal);
+        }
+    }
+}
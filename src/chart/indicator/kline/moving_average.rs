@@ -0,0 +1,139 @@
+use crate::chart::{
+    ViewState,
+    indicator::kline::{KlineIndicatorImpl, overlay_points, polyline},
+};
+
+use data::chart::{
+    PlotData,
+    indicator::{
+        KlineIndicator,
+        average::{AverageSeries, MaKind, PriceSource},
+    },
+    kline::KlineDataPoint,
+};
+use exchange::{Kline, Trade};
+
+use iced::theme::palette::Extended;
+use iced::widget::canvas::{self, Stroke};
+use std::ops::RangeInclusive;
+
+pub struct MovingAverageIndicator {
+    series: AverageSeries,
+    kind: MaKind,
+    source: PriceSource,
+}
+
+impl MovingAverageIndicator {
+    pub fn new(kind: MaKind, period: u16, source: PriceSource) -> Self {
+        Self {
+            series: AverageSeries::new(kind, period as usize),
+            kind,
+            source,
+        }
+    }
+
+    fn line_color(&self, palette: &Extended) -> iced::Color {
+        match self.kind {
+            MaKind::Sma => palette.primary.base.color,
+            MaKind::Ema => palette.primary.strong.color,
+            MaKind::Wma => palette.secondary.strong.color,
+            MaKind::Hma => palette.secondary.base.color,
+        }
+    }
+}
+
+impl KlineIndicatorImpl for MovingAverageIndicator {
+    // drawn within the main chart's cache, nothing of its own to clear
+    fn clear_all_caches(&mut self) {}
+
+    fn clear_crosshair_caches(&mut self) {}
+
+    fn draw_overlay(
+        &self,
+        frame: &mut canvas::Frame,
+        chart: &ViewState,
+        visible_range: RangeInclusive<u64>,
+        palette: &Extended,
+    ) {
+        let points = overlay_points(chart, self.series.values(), visible_range, |v| *v);
+
+        if let Some(line) = polyline(&points) {
+            frame.stroke(
+                &line,
+                Stroke::with_color(
+                    Stroke {
+                        width: 1.5,
+                        ..Stroke::default()
+                    },
+                    self.line_color(palette),
+                ),
+            );
+        }
+    }
+
+    fn rebuild_from_source(&mut self, source: &PlotData<KlineDataPoint>) {
+        self.series.clear();
+
+        match source {
+            PlotData::TimeBased(timeseries) => self.series.insert(
+                timeseries
+                    .datapoints
+                    .iter()
+                    .map(|(time, dp)| (*time, self.source.value(&dp.kline))),
+            ),
+            PlotData::TickBased(tickseries) => self.series.insert(
+                tickseries
+                    .datapoints
+                    .iter()
+                    .enumerate()
+                    .map(|(idx, dp)| (idx as u64, self.source.value(&dp.kline))),
+            ),
+        }
+    }
+
+    fn on_insert_klines(&mut self, klines: &[Kline]) {
+        let source = self.source;
+        self.series
+            .insert(klines.iter().map(|kline| (kline.time, source.value(kline))));
+    }
+
+    fn on_insert_trades(
+        &mut self,
+        _trades: &[Trade],
+        old_dp_len: usize,
+        source: &PlotData<KlineDataPoint>,
+    ) {
+        if let PlotData::TickBased(tickseries) = source {
+            let start_idx = old_dp_len.saturating_sub(1);
+            let price_source = self.source;
+
+            self.series.insert(
+                tickseries
+                    .datapoints
+                    .iter()
+                    .enumerate()
+                    .skip(start_idx)
+                    .map(|(idx, dp)| (idx as u64, price_source.value(&dp.kline))),
+            );
+        }
+    }
+
+    fn on_ticksize_change(&mut self, source: &PlotData<KlineDataPoint>) {
+        self.rebuild_from_source(source);
+    }
+
+    fn on_basis_change(&mut self, source: &PlotData<KlineDataPoint>) {
+        self.rebuild_from_source(source);
+    }
+
+    fn set_config(&mut self, config: KlineIndicator) {
+        if let KlineIndicator::MovingAverage {
+            kind,
+            period,
+            source,
+        } = config
+        {
+            *self = Self::new(kind, period, source);
+        }
+    }
+}
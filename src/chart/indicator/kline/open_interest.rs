@@ -1,30 +1,43 @@
 use crate::chart::{
-    Basis, Caches, Message, ViewState,
+    Caches, Message, ViewState,
     indicator::{
         indicator_row,
         kline::{FetchCtx, KlineIndicatorImpl},
-        plot::{PlotTooltip, line::LinePlot},
+        plot::{
+            PlotTooltip,
+            bar::{BarClass, BarPlot, Baseline},
+            line::LinePlot,
+        },
     },
 };
 
-use data::chart::{PlotData, kline::KlineDataPoint};
+use data::chart::{
+    PlotData,
+    indicator::{
+        KlineIndicator,
+        open_interest::{BarOi, OpenInterestSeries},
+    },
+    kline::KlineDataPoint,
+};
 use data::util::format_with_commas;
 use exchange::{Kline, Timeframe, Trade};
-use exchange::{adapter::Exchange, fetcher::FetchRange};
+use exchange::{adapter, fetcher::FetchRange};
 
 use iced::widget::{center, row, text};
 use std::{collections::BTreeMap, ops::RangeInclusive};
 
 pub struct OpenInterestIndicator {
     cache: Caches,
-    pub data: BTreeMap<u64, f32>,
+    series: OpenInterestSeries,
+    change: bool,
 }
 
 impl OpenInterestIndicator {
-    pub fn new() -> Self {
+    pub fn new(change: bool) -> Self {
         Self {
             cache: Caches::default(),
-            data: BTreeMap::new(),
+            series: OpenInterestSeries::default(),
+            change,
         }
     }
 
@@ -33,75 +46,67 @@ impl OpenInterestIndicator {
         main_chart: &'a ViewState,
         visible_range: RangeInclusive<u64>,
     ) -> iced::Element<'a, Message> {
-        match main_chart.basis {
-            Basis::Time(timeframe) => {
-                let exchange = main_chart.ticker_info.exchange();
-                if !Self::is_supported_exchange(exchange) {
-                    return center(text(format!(
-                        "WIP: Open Interest is not available for {exchange}"
-                    )))
-                    .into();
-                }
-
-                if !Self::is_supported_timeframe(timeframe) {
-                    return center(text(format!(
-                        "WIP: Open Interest is not available on {timeframe} timeframe"
-                    )))
-                    .into();
-                }
+        if !main_chart.ticker_info.is_perps() {
+            return center(text("Open Interest is only available for perpetuals")).into();
+        }
+        if self.series.samples().is_empty()
+            && !adapter::has_oi_history(main_chart.ticker_info.exchange())
+        {
+            return center(text(
+                "Recording Open Interest, first samples come in a minute",
+            ))
+            .into();
+        }
 
-                let (earliest, latest) = visible_range.clone().into_inner();
-                if latest < earliest {
-                    return row![].into();
-                }
-            }
-            Basis::Tick(_) => {
-                return center(text("WIP: Open Interest is not available for tick charts.")).into();
-            }
+        let (earliest, latest) = visible_range.clone().into_inner();
+        if latest < earliest {
+            return row![].into();
         }
 
-        let tooltip = |value: &f32, next: Option<&f32>| {
-            let value_text = format!("Open Interest: {}", format_with_commas(*value));
-            let change_text = if let Some(next_value) = next {
-                let delta = next_value - *value;
-                let sign = if delta >= 0.0 { "+" } else { "" };
-                format!("Change: {}{}", sign, format_with_commas(delta))
-            } else {
-                "Change: N/A".to_string()
+        let tooltip = |oi: &BarOi, _next: Option<&BarOi>| {
+            let value_text = format!("Open Interest: {}", format_with_commas(oi.value));
+            let change_text = match oi.change {
+                Some(delta) => {
+                    let sign = if delta >= 0.0 { "+" } else { "" };
+                    format!("Change: {}{}", sign, format_with_commas(delta))
+                }
+                None => "Change: N/A".to_string(),
             };
             PlotTooltip::new(format!("{value_text}\n{change_text}"))
         };
 
-        let value_fn = |v: &f32| *v;
+        if self.change {
+            let plot = BarPlot::new(
+                |oi: &BarOi| oi.change.unwrap_or(0.0),
+                |_: &BarOi| BarClass::Directional,
+            )
+            .baseline(Baseline::Signed)
+            .bar_width_factor(0.9)
+            .with_tooltip(tooltip);
+
+            return indicator_row(
+                main_chart,
+                &self.cache,
+                plot,
+                self.series.values(),
+                visible_range,
+            );
+        }
 
-        let plot = LinePlot::new(value_fn)
+        let plot = LinePlot::new(|oi: &BarOi| oi.value)
             .stroke_width(1.0)
             .show_points(true)
             .point_radius_factor(0.2)
             .padding(0.08)
             .with_tooltip(tooltip);
 
-        indicator_row(main_chart, &self.cache, plot, &self.data, visible_range)
-    }
-
-    // helper to compute (earliest, latest) present OI keys
-    fn oi_timerange(&self, latest_kline: u64) -> (u64, u64) {
-        let mut from_time = latest_kline;
-        let mut to_time = u64::MIN;
-
-        self.data.iter().for_each(|(time, _)| {
-            from_time = from_time.min(*time);
-            to_time = to_time.max(*time);
-        });
-        (from_time, to_time)
-    }
-
-    pub fn is_supported_exchange(exchange: Exchange) -> bool {
-        exchange.is_perps() && exchange != Exchange::HyperliquidLinear
-    }
-
-    pub fn is_supported_timeframe(timeframe: Timeframe) -> bool {
-        timeframe >= Timeframe::M5 && timeframe <= Timeframe::H4 && timeframe != Timeframe::H2
+        indicator_row(
+            main_chart,
+            &self.cache,
+            plot,
+            self.series.values(),
+            visible_range,
+        )
     }
 }
 
@@ -123,15 +128,11 @@ impl KlineIndicatorImpl for OpenInterestIndicator {
     }
 
     fn fetch_range(&mut self, ctx: &FetchCtx) -> Option<FetchRange> {
-        let exchange = ctx.main_chart.ticker_info.exchange();
-        let is_supported =
-            Self::is_supported_exchange(exchange) && Self::is_supported_timeframe(ctx.timeframe);
-
-        if !is_supported {
+        if !ctx.main_chart.ticker_info.is_perps() {
             return None;
         }
 
-        let (oi_earliest, oi_latest) = self.oi_timerange(ctx.kline_latest);
+        let (oi_earliest, oi_latest) = self.series.timerange(ctx.kline_latest);
 
         if ctx.visible_earliest < oi_earliest {
             return Some(FetchRange::OpenInterest(ctx.prefetch_earliest, oi_earliest));
@@ -147,27 +148,91 @@ impl KlineIndicatorImpl for OpenInterestIndicator {
         None
     }
 
-    fn rebuild_from_source(&mut self, _source: &PlotData<KlineDataPoint>) {
-        // OI comes from network via external fetches(trade-fetch alike)
+    fn rebuild_from_source(&mut self, source: &PlotData<KlineDataPoint>) {
+        // OI comes from network via external fetches(trade-fetch alike), only the
+        // bars it's mapped onto come from the source
+        match source {
+            PlotData::TimeBased(timeseries) => {
+                self.series
+                    .clear_bars(timeseries.interval.open_interest_period());
+                self.series
+                    .insert_bars(timeseries.datapoints.keys().map(|time| (*time, *time)));
+            }
+            PlotData::TickBased(tickseries) => {
+                self.series.clear_bars(Timeframe::M5);
+                self.series.insert_bars(
+                    tickseries
+                        .datapoints
+                        .iter()
+                        .enumerate()
+                        .map(|(idx, dp)| (idx as u64, dp.kline.time)),
+                );
+            }
+        }
         self.clear_all_caches();
     }
 
-    fn on_insert_klines(&mut self, _klines: &[Kline]) {}
+    fn on_insert_klines(&mut self, klines: &[Kline]) {
+        self.series
+            .insert_bars(klines.iter().map(|kline| (kline.time, kline.time)));
+        self.clear_all_caches();
+    }
 
     fn on_insert_trades(
         &mut self,
-        _trades: &[Trade],
-        _old_dp_len: usize,
-        _source: &PlotData<KlineDataPoint>,
+        trades: &[Trade],
+        old_dp_len: usize,
+        source: &PlotData<KlineDataPoint>,
     ) {
+        match source {
+            PlotData::TimeBased(timeseries) => {
+                let Some(earliest) = trades.iter().map(|trade| trade.time).min() else {
+                    return;
+                };
+                let from = timeseries.interval.bucket_start(earliest);
+
+                self.series.insert_bars(
+                    timeseries
+                        .datapoints
+                        .range(from..)
+                        .map(|(time, _)| (*time, *time)),
+                );
+            }
+            PlotData::TickBased(tickseries) => {
+                let start_idx = old_dp_len.saturating_sub(1);
+
+                self.series.insert_bars(
+                    tickseries
+                        .datapoints
+                        .iter()
+                        .enumerate()
+                        .skip(start_idx)
+                        .map(|(idx, dp)| (idx as u64, dp.kline.time)),
+                );
+            }
+        }
+        self.clear_all_caches();
     }
 
     fn on_ticksize_change(&mut self, _source: &PlotData<KlineDataPoint>) {}
 
-    fn on_basis_change(&mut self, _source: &PlotData<KlineDataPoint>) {}
+    fn on_basis_change(&mut self, source: &PlotData<KlineDataPoint>) {
+        self.rebuild_from_source(source);
+    }
+
+    fn set_config(&mut self, config: KlineIndicator) {
+        if let KlineIndicator::OpenInterest { change } = config {
+            self.change = change;
+        }
+    }
 
     fn on_open_interest(&mut self, data: &[exchange::OpenInterest]) {
-        self.data.extend(data.iter().map(|oi| (oi.time, oi.value)));
+        self.series
+            .insert_samples(data.iter().map(|oi| (oi.time, oi.value)));
         self.clear_all_caches();
     }
+
+    fn series(&self) -> Option<&BTreeMap<u64, f32>> {
+        Some(self.series.samples())
+    }
 }
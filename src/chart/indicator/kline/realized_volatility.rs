@@ -0,0 +1,115 @@
+use crate::chart::{
+    Caches, Message, ViewState,
+    indicator::{
+        indicator_row,
+        kline::{KlineIndicatorImpl, source_bars, updated_tick_bars},
+        plot::{PlotTooltip, line::LinePlot},
+    },
+};
+
+use data::chart::{
+    PlotData,
+    indicator::{
+        KlineIndicator,
+        rolling::{RealizedVolSeries, VolEstimator},
+    },
+    kline::KlineDataPoint,
+};
+use exchange::{Kline, Trade};
+
+use std::ops::RangeInclusive;
+
+pub struct RealizedVolatilityIndicator {
+    cache: Caches,
+    series: RealizedVolSeries,
+}
+
+impl RealizedVolatilityIndicator {
+    pub fn new(estimator: VolEstimator, period: u16) -> Self {
+        Self {
+            cache: Caches::default(),
+            series: RealizedVolSeries::new(estimator, period as usize),
+        }
+    }
+
+    fn indicator_elem<'a>(
+        &'a self,
+        main_chart: &'a ViewState,
+        visible_range: RangeInclusive<u64>,
+    ) -> iced::Element<'a, Message> {
+        let plot = LinePlot::new(|vol: &f32| *vol)
+            .stroke_width(1.0)
+            .show_points(false)
+            .with_tooltip(|vol: &f32, _next: Option<&f32>| {
+                PlotTooltip::new(format!("Realized Vol: {vol:.2}%"))
+            });
+
+        indicator_row(
+            main_chart,
+            &self.cache,
+            plot,
+            self.series.values(),
+            visible_range,
+        )
+    }
+}
+
+impl KlineIndicatorImpl for RealizedVolatilityIndicator {
+    fn clear_all_caches(&mut self) {
+        self.cache.clear_all();
+    }
+
+    fn clear_crosshair_caches(&mut self) {
+        self.cache.clear_crosshair();
+    }
+
+    fn element<'a>(
+        &'a self,
+        chart: &'a ViewState,
+        visible_range: RangeInclusive<u64>,
+    ) -> iced::Element<'a, Message> {
+        self.indicator_elem(chart, visible_range)
+    }
+
+    fn rebuild_from_source(&mut self, source: &PlotData<KlineDataPoint>) {
+        let interval = match source {
+            PlotData::TimeBased(timeseries) => Some(timeseries.interval.to_milliseconds()),
+            PlotData::TickBased(_) => None,
+        };
+        self.series.clear(interval);
+        self.series.insert(source_bars(source, |kline, _| kline));
+        self.clear_all_caches();
+    }
+
+    fn on_insert_klines(&mut self, klines: &[Kline]) {
+        self.series
+            .insert(klines.iter().map(|kline| (kline.time, kline)));
+        self.clear_all_caches();
+    }
+
+    fn on_insert_trades(
+        &mut self,
+        _trades: &[Trade],
+        old_dp_len: usize,
+        source: &PlotData<KlineDataPoint>,
+    ) {
+        if let Some(bars) = updated_tick_bars(source, old_dp_len, |kline, _| kline) {
+            self.series.insert(bars);
+            self.clear_all_caches();
+        }
+    }
+
+    fn on_ticksize_change(&mut self, source: &PlotData<KlineDataPoint>) {
+        self.rebuild_from_source(source);
+    }
+
+    fn on_basis_change(&mut self, source: &PlotData<KlineDataPoint>) {
+        self.rebuild_from_source(source);
+    }
+
+    fn set_config(&mut self, config: KlineIndicator) {
+        if let KlineIndicator::RealizedVolatility { estimator, period } = config {
+            *self = Self::new(estimator, period);
+        }
+    }
+}
@@ -0,0 +1,114 @@
+use crate::chart::{
+    Caches, Message, ViewState,
+    indicator::{
+        indicator_row,
+        kline::{KlineIndicatorImpl, source_bars, updated_tick_bars},
+        plot::{PlotTooltip, guide::GuidePlot, line::LinePlot},
+    },
+};
+
+use data::chart::{
+    PlotData,
+    indicator::{KlineIndicator, oscillator::RsiSeries},
+    kline::KlineDataPoint,
+};
+use exchange::{Kline, Trade};
+
+use std::ops::RangeInclusive;
+
+pub struct RsiIndicator {
+    cache: Caches,
+    series: RsiSeries,
+    overbought: u8,
+}
+
+impl RsiIndicator {
+    pub fn new(period: u16, overbought: u8) -> Self {
+        Self {
+            cache: Caches::default(),
+            series: RsiSeries::new(period as usize),
+            overbought,
+        }
+    }
+
+    fn indicator_elem<'a>(
+        &'a self,
+        main_chart: &'a ViewState,
+        visible_range: RangeInclusive<u64>,
+    ) -> iced::Element<'a, Message> {
+        let overbought = f32::from(self.overbought);
+
+        let line = LinePlot::new(|rsi: &f32| *rsi)
+            .stroke_width(1.0)
+            .show_points(false)
+            .padding(0.0)
+            .with_tooltip(|rsi: &f32, _next: Option<&f32>| {
+                PlotTooltip::new(format!("RSI: {rsi:.2}"))
+            });
+        let guides = GuidePlot::new([100.0 - overbought, 50.0, overbought]).within(0.0, 100.0);
+
+        indicator_row(
+            main_chart,
+            &self.cache,
+            (guides, line),
+            self.series.values(),
+            visible_range,
+        )
+    }
+}
+
+impl KlineIndicatorImpl for RsiIndicator {
+    fn clear_all_caches(&mut self) {
+        self.cache.clear_all();
+    }
+
+    fn clear_crosshair_caches(&mut self) {
+        self.cache.clear_crosshair();
+    }
+
+    fn element<'a>(
+        &'a self,
+        chart: &'a ViewState,
+        visible_range: RangeInclusive<u64>,
+    ) -> iced::Element<'a, Message> {
+        self.indicator_elem(chart, visible_range)
+    }
+
+    fn rebuild_from_source(&mut self, source: &PlotData<KlineDataPoint>) {
+        self.series.clear();
+        self.series.insert(source_bars(source, |kline, _| kline));
+        self.clear_all_caches();
+    }
+
+    fn on_insert_klines(&mut self, klines: &[Kline]) {
+        self.series
+            .insert(klines.iter().map(|kline| (kline.time, kline)));
+        self.clear_all_caches();
+    }
+
+    fn on_insert_trades(
+        &mut self,
+        _trades: &[Trade],
+        old_dp_len: usize,
+        source: &PlotData<KlineDataPoint>,
+    ) {
+        if let Some(bars) = updated_tick_bars(source, old_dp_len, |kline, _| kline) {
+            self.series.insert(bars);
+            self.clear_all_caches();
+        }
+    }
+
+    fn on_ticksize_change(&mut self, source: &PlotData<KlineDataPoint>) {
+        self.rebuild_from_source(source);
+    }
+
+    fn on_basis_change(&mut self, source: &PlotData<KlineDataPoint>) {
+        self.rebuild_from_source(source);
+    }
+
+    fn set_config(&mut self, config: KlineIndicator) {
+        if let KlineIndicator::Rsi { period, overbought } = config {
+            *self = Self::new(period, overbought);
+        }
+    }
+}
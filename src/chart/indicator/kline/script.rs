@@ -0,0 +1,210 @@
+use crate::chart::{
+    Caches, Message, ViewState,
+    indicator::{
+        indicator_row,
+        kline::KlineIndicatorImpl,
+        plot::{
+            PlotTooltip,
+            bar::{BarClass, BarPlot, Baseline},
+            line::LinePlot,
+            marker::MarkerPlot,
+        },
+    },
+};
+
+use data::chart::{
+    PlotData,
+    indicator::script::{BarInputs, Script, ScriptError, ScriptName, ScriptPlot, ScriptSeries},
+    kline::{KlineDataPoint, KlineTrades},
+};
+use data::util::format_with_commas;
+use exchange::{Kline, Trade};
+
+use iced::widget::{center, text};
+use std::collections::BTreeMap;
+use std::ops::RangeInclusive;
+
+pub struct ScriptIndicator {
+    cache: Caches,
+    name: ScriptName,
+    series: Result<ScriptSeries, ScriptError>,
+}
+
+impl ScriptIndicator {
+    pub fn new(name: ScriptName) -> Self {
+        Self {
+            cache: Caches::default(),
+            name,
+            series: Script::load(name).map(ScriptSeries::new),
+        }
+    }
+
+    /// Reads the script again, so edits show up whenever the chart is rebuilt
+    fn reload(&mut self) {
+        match (Script::load(self.name), &mut self.series) {
+            (Ok(script), Ok(series)) => series.reload(script),
+            (Ok(script), Err(_)) => self.series = Ok(ScriptSeries::new(script)),
+            (Err(error), _) => {
+                log::warn!("Couldn't load indicator script {}: {error}", self.name);
+                self.series = Err(error);
+            }
+        }
+    }
+
+    fn insert<'a>(&mut self, bars: impl Iterator<Item = (u64, &'a Kline, &'a KlineTrades)>) {
+        if let Ok(series) = &mut self.series {
+            series.insert(
+                bars.map(|(key, kline, footprint)| (key, BarInputs::new(kline, footprint))),
+            );
+        }
+    }
+
+    fn indicator_elem<'a>(
+        &'a self,
+        main_chart: &'a ViewState,
+        visible_range: RangeInclusive<u64>,
+    ) -> iced::Element<'a, Message> {
+        let series = match &self.series {
+            Ok(series) => series,
+            Err(error) => return center(text(format!("{}: {error}", self.name))).into(),
+        };
+        if let Some(error) = series.error() {
+            return center(text(format!("{}: {error}", self.name))).into();
+        }
+
+        let name = self.name;
+        let tooltip = move |value: &f32, _next: Option<&f32>| {
+            PlotTooltip::new(format!("{name}: {}", format_with_commas(*value)))
+        };
+        let values = series.values();
+
+        match series.plot() {
+            ScriptPlot::Line => {
+                let plot = LinePlot::new(|value: &f32| *value)
+                    .stroke_width(1.0)
+                    .show_points(true)
+                    .point_radius_factor(0.2)
+                    .padding(0.08)
+                    .with_tooltip(tooltip);
+
+                indicator_row(main_chart, &self.cache, plot, values, visible_range)
+            }
+            ScriptPlot::Histogram => {
+                let plot = BarPlot::new(|value: &f32| *value, |_: &f32| BarClass::Directional)
+                    .baseline(Baseline::Signed)
+                    .bar_width_factor(0.9)
+                    .with_tooltip(tooltip);
+
+                indicator_row(main_chart, &self.cache, plot, values, visible_range)
+            }
+            ScriptPlot::Markers => {
+                let plot = MarkerPlot::new(|value: &f32| *value).with_tooltip(tooltip);
+
+                indicator_row(main_chart, &self.cache, plot, values, visible_range)
+            }
+        }
+    }
+}
+
+impl KlineIndicatorImpl for ScriptIndicator {
+    fn clear_all_caches(&mut self) {
+        self.cache.clear_all();
+    }
+
+    fn clear_crosshair_caches(&mut self) {
+        self.cache.clear_crosshair();
+    }
+
+    fn element<'a>(
+        &'a self,
+        chart: &'a ViewState,
+        visible_range: RangeInclusive<u64>,
+    ) -> iced::Element<'a, Message> {
+        self.indicator_elem(chart, visible_range)
+    }
+
+    fn rebuild_from_source(&mut self, source: &PlotData<KlineDataPoint>) {
+        self.reload();
+
+        match source {
+            PlotData::TimeBased(timeseries) => {
+                self.insert(
+                    timeseries
+                        .datapoints
+                        .iter()
+                        .map(|(time, dp)| (*time, &dp.kline, &dp.footprint)),
+                );
+            }
+            PlotData::TickBased(tickseries) => self.insert(
+                tickseries
+                    .datapoints
+                    .iter()
+                    .enumerate()
+                    .map(|(idx, dp)| (idx as u64, &dp.kline, &dp.footprint)),
+            ),
+        }
+        self.clear_all_caches();
+    }
+
+    fn on_insert_klines(&mut self, klines: &[Kline]) {
+        if let Ok(series) = &mut self.series {
+            series.insert_klines(klines);
+        }
+        self.clear_all_caches();
+    }
+
+    fn on_insert_trades(
+        &mut self,
+        trades: &[Trade],
+        old_dp_len: usize,
+        source: &PlotData<KlineDataPoint>,
+    ) {
+        match source {
+            PlotData::TimeBased(timeseries) => {
+                let Some(earliest) = trades.iter().map(|trade| trade.time).min() else {
+                    return;
+                };
+                let from = timeseries.interval.bucket_start(earliest);
+
+                self.insert(
+                    timeseries
+                        .datapoints
+                        .range(from..)
+                        .map(|(time, dp)| (*time, &dp.kline, &dp.footprint)),
+                );
+            }
+            PlotData::TickBased(tickseries) => {
+                let start_idx = old_dp_len.saturating_sub(1);
+
+                self.insert(
+                    tickseries
+                        .datapoints
+                        .iter()
+                        .enumerate()
+                        .skip(start_idx)
+                        .map(|(idx, dp)| (idx as u64, &dp.kline, &dp.footprint)),
+                );
+            }
+        }
+        self.clear_all_caches();
+    }
+
+    fn on_ticksize_change(&mut self, source: &PlotData<KlineDataPoint>) {
+        self.rebuild_from_source(source);
+    }
+
+    fn on_basis_change(&mut self, source: &PlotData<KlineDataPoint>) {
+        self.rebuild_from_source(source);
+    }
+
+    fn on_open_interest(&mut self, data: &[exchange::OpenInterest]) {
+        if let Ok(series) = &mut self.series {
+            series.insert_open_interest(data.iter().map(|oi| (oi.time, oi.value)));
+        }
+        self.clear_all_caches();
+    }
+
+    fn series(&self) -> Option<&BTreeMap<u64, f32>> {
+        self.series.as_ref().ok().map(ScriptSeries::values)
+    }
+}
@@ -0,0 +1,122 @@
+use crate::chart::{
+    Caches, Message, ViewState,
+    indicator::{
+        indicator_row,
+        kline::{KlineIndicatorImpl, source_bars, updated_tick_bars},
+        plot::{PlotTooltip, guide::GuidePlot, line::LinePlot},
+    },
+};
+
+use data::chart::{
+    PlotData,
+    indicator::{
+        KlineIndicator,
+        oscillator::{StochasticPoint, StochasticSeries},
+    },
+    kline::KlineDataPoint,
+};
+use exchange::{Kline, Trade};
+
+use std::ops::RangeInclusive;
+
+pub struct StochasticIndicator {
+    cache: Caches,
+    series: StochasticSeries,
+}
+
+impl StochasticIndicator {
+    pub fn new(period: u16, smooth_k: u16, smooth_d: u16) -> Self {
+        Self {
+            cache: Caches::default(),
+            series: StochasticSeries::new(period as usize, smooth_k as usize, smooth_d as usize),
+        }
+    }
+
+    fn indicator_elem<'a>(
+        &'a self,
+        main_chart: &'a ViewState,
+        visible_range: RangeInclusive<u64>,
+    ) -> iced::Element<'a, Message> {
+        let k = LinePlot::new(|point: &StochasticPoint| point.k)
+            .stroke_width(1.0)
+            .show_points(false)
+            .padding(0.0)
+            .color(|palette| palette.primary.strong.color)
+            .with_tooltip(|point: &StochasticPoint, _next: Option<&StochasticPoint>| {
+                PlotTooltip::new(format!("%K: {:.2}\n%D: {:.2}", point.k, point.d))
+            });
+        let d = LinePlot::new(|point: &StochasticPoint| point.d)
+            .stroke_width(1.0)
+            .show_points(false);
+        let guides = GuidePlot::new([20.0, 80.0]).within(0.0, 100.0);
+
+        indicator_row(
+            main_chart,
+            &self.cache,
+            (guides, (k, d)),
+            self.series.values(),
+            visible_range,
+        )
+    }
+}
+
+impl KlineIndicatorImpl for StochasticIndicator {
+    fn clear_all_caches(&mut self) {
+        self.cache.clear_all();
+    }
+
+    fn clear_crosshair_caches(&mut self) {
+        self.cache.clear_crosshair();
+    }
+
+    fn element<'a>(
+        &'a self,
+        chart: &'a ViewState,
+        visible_range: RangeInclusive<u64>,
+    ) -> iced::Element<'a, Message> {
+        self.indicator_elem(chart, visible_range)
+    }
+
+    fn rebuild_from_source(&mut self, source: &PlotData<KlineDataPoint>) {
+        self.series.clear();
+        self.series.insert(source_bars(source, |kline, _| kline));
+        self.clear_all_caches();
+    }
+
+    fn on_insert_klines(&mut self, klines: &[Kline]) {
+        self.series
+            .insert(klines.iter().map(|kline| (kline.time, kline)));
+        self.clear_all_caches();
+    }
+
+    fn on_insert_trades(
+        &mut self,
+        _trades: &[Trade],
+        old_dp_len: usize,
+        source: &PlotData<KlineDataPoint>,
+    ) {
+        if let Some(bars) = updated_tick_bars(source, old_dp_len, |kline, _| kline) {
+            self.series.insert(bars);
+            self.clear_all_caches();
+        }
+    }
+
+    fn on_ticksize_change(&mut self, source: &PlotData<KlineDataPoint>) {
+        self.rebuild_from_source(source);
+    }
+
+    fn on_basis_change(&mut self, source: &PlotData<KlineDataPoint>) {
+        self.rebuild_from_source(source);
+    }
+
+    fn set_config(&mut self, config: KlineIndicator) {
+        if let KlineIndicator::Stochastic {
+            period,
+            smooth_k,
+            smooth_d,
+        } = config
+        {
+            *self = Self::new(period, smooth_k, smooth_d);
+        }
+    }
+}
@@ -0,0 +1,130 @@
+use crate::chart::{
+    Caches, Message, ViewState,
+    indicator::{
+        indicator_row,
+        kline::{KlineIndicatorImpl, source_bars, updated_tick_bars},
+        plot::{
+            PlotTooltip,
+            bar::{BarClass, BarPlot},
+        },
+    },
+};
+
+use data::chart::{
+    PlotData,
+    indicator::bar_trades::BarTrades,
+    kline::{KlineDataPoint, KlineTrades},
+};
+use data::util::format_with_commas;
+use exchange::Trade;
+
+use iced::widget::{center, text};
+use std::collections::BTreeMap;
+use std::ops::RangeInclusive;
+
+pub struct TradeCountIndicator {
+    cache: Caches,
+    data: BTreeMap<u64, BarTrades>,
+}
+
+impl TradeCountIndicator {
+    pub fn new() -> Self {
+        Self {
+            cache: Caches::default(),
+            data: BTreeMap::new(),
+        }
+    }
+
+    fn insert<'a>(&mut self, bars: impl Iterator<Item = (u64, &'a KlineTrades)>) {
+        for (key, footprint) in bars {
+            match BarTrades::of(footprint) {
+                Some(trades) => self.data.insert(key, trades),
+                None => self.data.remove(&key),
+            };
+        }
+    }
+
+    fn indicator_elem<'a>(
+        &'a self,
+        main_chart: &'a ViewState,
+        visible_range: RangeInclusive<u64>,
+    ) -> iced::Element<'a, Message> {
+        if self.data.is_empty() {
+            return center(text("Counts trades as they are loaded, none yet")).into();
+        }
+
+        let plot = BarPlot::new(
+            |trades: &BarTrades| trades.count() as f32,
+            |trades: &BarTrades| BarClass::Overlay {
+                overlay: trades.buy_count as f32 - trades.sell_count as f32,
+            },
+        )
+        .bar_width_factor(0.9)
+        .with_tooltip(|trades: &BarTrades, _next: Option<&BarTrades>| {
+            PlotTooltip::new(format!(
+                "Trades: {}\nBuys: {}\nSells: {}",
+                format_with_commas(trades.count() as f32),
+                format_with_commas(trades.buy_count as f32),
+                format_with_commas(trades.sell_count as f32)
+            ))
+        });
+
+        indicator_row(main_chart, &self.cache, plot, &self.data, visible_range)
+    }
+}
+
+impl KlineIndicatorImpl for TradeCountIndicator {
+    fn clear_all_caches(&mut self) {
+        self.cache.clear_all();
+    }
+
+    fn clear_crosshair_caches(&mut self) {
+        self.cache.clear_crosshair();
+    }
+
+    fn element<'a>(
+        &'a self,
+        chart: &'a ViewState,
+        visible_range: RangeInclusive<u64>,
+    ) -> iced::Element<'a, Message> {
+        self.indicator_elem(chart, visible_range)
+    }
+
+    fn rebuild_from_source(&mut self, source: &PlotData<KlineDataPoint>) {
+        self.data.clear();
+        self.insert(source_bars(source, |_, footprint| footprint));
+        self.clear_all_caches();
+    }
+
+    fn on_insert_trades(
+        &mut self,
+        trades: &[Trade],
+        old_dp_len: usize,
+        source: &PlotData<KlineDataPoint>,
+    ) {
+        if let PlotData::TimeBased(timeseries) = source {
+            let Some(earliest) = trades.iter().map(|trade| trade.time).min() else {
+                return;
+            };
+            let from = timeseries.interval.bucket_start(earliest);
+
+            self.insert(
+                timeseries
+                    .datapoints
+                    .range(from..)
+                    .map(|(time, dp)| (*time, &dp.footprint)),
+            );
+        } else if let Some(bars) = updated_tick_bars(source, old_dp_len, |_, footprint| footprint) {
+            self.insert(bars);
+        }
+        self.clear_all_caches();
+    }
+
+    fn on_ticksize_change(&mut self, source: &PlotData<KlineDataPoint>) {
+        self.rebuild_from_source(source);
+    }
+
+    fn on_basis_change(&mut self, source: &PlotData<KlineDataPoint>) {
+        self.rebuild_from_source(source);
+    }
+}
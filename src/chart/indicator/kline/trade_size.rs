@@ -0,0 +1,199 @@
+use crate::chart::{
+    Caches, Message, ViewState,
+    indicator::{
+        indicator_row,
+        kline::KlineIndicatorImpl,
+        plot::{
+            PlotTooltip,
+            bar::{BarClass, BarPlot},
+        },
+    },
+};
+
+use data::chart::{
+    PlotData,
+    indicator::bar_trades::BarTrades,
+    kline::{KlineDataPoint, KlineTrades},
+};
+use data::util::abbr_large_numbers;
+use exchange::{
+    Kline, SizeUnit, Trade,
+    adapter::MarketKind,
+    util::{Price, Qty},
+};
+
+use iced::widget::{center, text};
+use std::collections::BTreeMap;
+use std::ops::RangeInclusive;
+
+/// Average size of a bar's trades, in the pane's size unit
+#[derive(Clone, Copy)]
+struct TradeSize {
+    average: f32,
+    buy: Option<f32>,
+    sell: Option<f32>,
+}
+
+pub struct TradeSizeIndicator {
+    cache: Caches,
+    data: BTreeMap<u64, TradeSize>,
+    market: MarketKind,
+    size_unit: SizeUnit,
+}
+
+impl TradeSizeIndicator {
+    pub fn new() -> Self {
+        Self {
+            cache: Caches::default(),
+            data: BTreeMap::new(),
+            market: MarketKind::Spot,
+            size_unit: SizeUnit::Base,
+        }
+    }
+
+    /// Trades are valued at the bar's close when shown in quote units
+    fn trade_size(&self, trades: &BarTrades, close: Price) -> TradeSize {
+        let average = |qty: Qty, count: usize| {
+            (count > 0).then(|| {
+                let qty = self.market.qty_in_size_unit(qty, close, self.size_unit);
+                qty.to_f32() / count as f32
+            })
+        };
+
+        TradeSize {
+            average: average(trades.buy_qty + trades.sell_qty, trades.count()).unwrap_or(0.0),
+            buy: average(trades.buy_qty, trades.buy_count),
+            sell: average(trades.sell_qty, trades.sell_count),
+        }
+    }
+
+    fn insert<'a>(&mut self, bars: impl Iterator<Item = (u64, &'a Kline, &'a KlineTrades)>) {
+        for (key, kline, footprint) in bars {
+            match BarTrades::of(footprint) {
+                Some(trades) => {
+                    let size = self.trade_size(&trades, kline.close);
+                    self.data.insert(key, size);
+                }
+                None => {
+                    self.data.remove(&key);
+                }
+            }
+        }
+    }
+
+    fn indicator_elem<'a>(
+        &'a self,
+        main_chart: &'a ViewState,
+        visible_range: RangeInclusive<u64>,
+    ) -> iced::Element<'a, Message> {
+        if self.data.is_empty() {
+            return center(text("Averages trades as they are loaded, none yet")).into();
+        }
+
+        let side = |size: Option<f32>| size.map_or("-".to_string(), abbr_large_numbers);
+
+        let plot = BarPlot::new(
+            |size: &TradeSize| size.average,
+            |_: &TradeSize| BarClass::Single,
+        )
+        .bar_width_factor(0.9)
+        .with_tooltip(move |size: &TradeSize, _next: Option<&TradeSize>| {
+            PlotTooltip::new(format!(
+                "Avg size: {}\nAvg buy: {}\nAvg sell: {}",
+                abbr_large_numbers(size.average),
+                side(size.buy),
+                side(size.sell)
+            ))
+        });
+
+        indicator_row(main_chart, &self.cache, plot, &self.data, visible_range)
+    }
+}
+
+impl KlineIndicatorImpl for TradeSizeIndicator {
+    fn clear_all_caches(&mut self) {
+        self.cache.clear_all();
+    }
+
+    fn clear_crosshair_caches(&mut self) {
+        self.cache.clear_crosshair();
+    }
+
+    fn element<'a>(
+        &'a self,
+        chart: &'a ViewState,
+        visible_range: RangeInclusive<u64>,
+    ) -> iced::Element<'a, Message> {
+        self.indicator_elem(chart, visible_range)
+    }
+
+    fn rebuild_from_source(&mut self, source: &PlotData<KlineDataPoint>) {
+        self.data.clear();
+
+        match source {
+            PlotData::TimeBased(timeseries) => self.insert(
+                timeseries
+                    .datapoints
+                    .iter()
+                    .map(|(time, dp)| (*time, &dp.kline, &dp.footprint)),
+            ),
+            PlotData::TickBased(tickseries) => self.insert(
+                tickseries
+                    .datapoints
+                    .iter()
+                    .enumerate()
+                    .map(|(idx, dp)| (idx as u64, &dp.kline, &dp.footprint)),
+            ),
+        }
+        self.clear_all_caches();
+    }
+
+    fn on_insert_trades(
+        &mut self,
+        trades: &[Trade],
+        old_dp_len: usize,
+        source: &PlotData<KlineDataPoint>,
+    ) {
+        match source {
+            PlotData::TimeBased(timeseries) => {
+                let Some(earliest) = trades.iter().map(|trade| trade.time).min() else {
+                    return;
+                };
+                let from = timeseries.interval.bucket_start(earliest);
+
+                self.insert(
+                    timeseries
+                        .datapoints
+                        .range(from..)
+                        .map(|(time, dp)| (*time, &dp.kline, &dp.footprint)),
+                );
+            }
+            PlotData::TickBased(tickseries) => {
+                let start_idx = old_dp_len.saturating_sub(1);
+
+                self.insert(
+                    tickseries
+                        .datapoints
+                        .iter()
+                        .enumerate()
+                        .skip(start_idx)
+                        .map(|(idx, dp)| (idx as u64, &dp.kline, &dp.footprint)),
+                );
+            }
+        }
+        self.clear_all_caches();
+    }
+
+    fn on_ticksize_change(&mut self, source: &PlotData<KlineDataPoint>) {
+        self.rebuild_from_source(source);
+    }
+
+    fn on_basis_change(&mut self, source: &PlotData<KlineDataPoint>) {
+        self.rebuild_from_source(source);
+    }
+
+    fn set_size_unit(&mut self, market: MarketKind, unit: SizeUnit) {
+        self.market = market;
+        self.size_unit = unit;
+    }
+}
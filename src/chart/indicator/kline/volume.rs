@@ -10,23 +10,44 @@ use crate::chart::{
     },
 };
 
-use data::chart::{PlotData, kline::KlineDataPoint};
+use data::chart::{PlotData, indicator::KlineIndicator, kline::KlineDataPoint};
 use data::util::format_with_commas;
-use exchange::{Kline, Trade};
+use exchange::{
+    Kline, SizeUnit, Trade,
+    adapter::MarketKind,
+    util::{Price, Qty},
+};
 
 use std::collections::BTreeMap;
 use std::ops::RangeInclusive;
 
 pub struct VolumeIndicator {
     cache: Caches,
-    data: BTreeMap<u64, (f32, f32)>,
+    data: BTreeMap<u64, (Qty, Qty)>,
+    market: MarketKind,
+    size_unit: SizeUnit,
+    delta: bool,
 }
 
 impl VolumeIndicator {
-    pub fn new() -> Self {
+    pub fn new(delta: bool) -> Self {
         Self {
             cache: Caches::default(),
             data: BTreeMap::new(),
+            market: MarketKind::Spot,
+            size_unit: SizeUnit::Base,
+            delta,
+        }
+    }
+
+    /// Bar volumes are valued at the bar's close when shown in quote units
+    fn shown_volume(&self, (buy, sell): (Qty, Qty), close: Price) -> (Qty, Qty) {
+        let convert = |qty| self.market.qty_in_size_unit(qty, close, self.size_unit);
+
+        if buy == Kline::TOTAL_ONLY {
+            (buy, convert(sell))
+        } else {
+            (convert(buy), convert(sell))
         }
     }
 
@@ -35,28 +56,39 @@ impl VolumeIndicator {
         main_chart: &'a ViewState,
         visible_range: RangeInclusive<u64>,
     ) -> iced::Element<'a, Message> {
-        let tooltip = |&(buy, sell): &(f32, f32), _next: Option<&(f32, f32)>| {
-            if buy == -1.0 {
-                PlotTooltip::new(format!("Volume: {}", format_with_commas(sell)))
+        let delta = self.delta;
+
+        let tooltip = move |&(buy, sell): &(Qty, Qty), _next: Option<&(Qty, Qty)>| {
+            if buy == Kline::TOTAL_ONLY {
+                PlotTooltip::new(format!("Volume: {}", format_with_commas(sell.to_f32())))
+            } else if !delta {
+                PlotTooltip::new(format!(
+                    "Volume: {}",
+                    format_with_commas((buy + sell).to_f32())
+                ))
             } else {
-                let buy_t = format!("Buy Volume: {}", format_with_commas(buy));
-                let sell_t = format!("Sell Volume: {}", format_with_commas(sell));
+                let buy_t = format!("Buy Volume: {}", format_with_commas(buy.to_f32()));
+                let sell_t = format!("Sell Volume: {}", format_with_commas(sell.to_f32()));
                 PlotTooltip::new(format!("{buy_t}\n{sell_t}"))
             }
         };
 
-        let bar_kind = |&(buy, sell): &(f32, f32)| {
-            if buy == -1.0 {
-                BarClass::Single // bybit workaround: single bar
+        let bar_kind = move |&(buy, sell): &(Qty, Qty)| {
+            if buy == Kline::TOTAL_ONLY || !delta {
+                BarClass::Single // bybit workaround or split hidden: single bar
             } else {
                 BarClass::Overlay {
-                    overlay: buy - sell,
+                    overlay: (buy - sell).to_f32(),
                 } // use the overlay for volume delta, sign determines up/down color
             }
         };
 
-        let value_fn = |&(buy, sell): &(f32, f32)| {
-            if buy == -1.0 { sell } else { buy + sell }
+        let value_fn = |&(buy, sell): &(Qty, Qty)| {
+            if buy == Kline::TOTAL_ONLY {
+                sell.to_f32()
+            } else {
+                (buy + sell).to_f32()
+            }
         };
 
         let plot = BarPlot::new(value_fn, bar_kind)
@@ -85,21 +117,29 @@ impl KlineIndicatorImpl for VolumeIndicator {
     }
 
     fn rebuild_from_source(&mut self, source: &PlotData<KlineDataPoint>) {
-        match source {
-            PlotData::TimeBased(timeseries) => {
-                self.data = timeseries.volume_data();
-            }
-            PlotData::TickBased(tickseries) => {
-                self.data = tickseries.volume_data();
-            }
-        }
+        self.data = match source {
+            PlotData::TimeBased(timeseries) => timeseries
+                .datapoints
+                .iter()
+                .map(|(time, dp)| (*time, self.shown_volume(dp.kline.volume, dp.kline.close)))
+                .collect(),
+            PlotData::TickBased(tickseries) => tickseries
+                .datapoints
+                .iter()
+                .enumerate()
+                .map(|(idx, dp)| {
+                    let volume = self.shown_volume(dp.kline.volume, dp.kline.close);
+                    (idx as u64, volume)
+                })
+                .collect(),
+        };
         self.clear_all_caches();
     }
 
     fn on_insert_klines(&mut self, klines: &[Kline]) {
         for kline in klines {
-            self.data
-                .insert(kline.time, (kline.volume.0, kline.volume.1));
+            let volume = self.shown_volume(kline.volume, kline.close);
+            self.data.insert(kline.time, volume);
         }
         self.clear_all_caches();
     }
@@ -115,8 +155,8 @@ impl KlineIndicatorImpl for VolumeIndicator {
             PlotData::TickBased(tickseries) => {
                 let start_idx = old_dp_len.saturating_sub(1);
                 for (idx, dp) in tickseries.datapoints.iter().enumerate().skip(start_idx) {
-                    self.data
-                        .insert(idx as u64, (dp.kline.volume.0, dp.kline.volume.1));
+                    let volume = self.shown_volume(dp.kline.volume, dp.kline.close);
+                    self.data.insert(idx as u64, volume);
                 }
             }
         }
@@ -130,4 +170,15 @@ impl KlineIndicatorImpl for VolumeIndicator {
     fn on_basis_change(&mut self, source: &PlotData<KlineDataPoint>) {
         self.rebuild_from_source(source);
     }
+
+    fn set_size_unit(&mut self, market: MarketKind, unit: SizeUnit) {
+        self.market = market;
+        self.size_unit = unit;
+    }
+
+    fn set_config(&mut self, config: KlineIndicator) {
+        if let KlineIndicator::Volume { delta } = config {
+            self.delta = delta;
+        }
+    }
 }
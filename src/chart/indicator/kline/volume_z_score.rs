@@ -0,0 +1,135 @@
+use crate::chart::{
+    Caches, Message, ViewState,
+    indicator::{
+        indicator_row,
+        kline::{KlineIndicatorImpl, source_bars, updated_tick_bars},
+        plot::{
+            PlotTooltip,
+            bar::{BarClass, BarPlot, Baseline},
+            guide::GuidePlot,
+        },
+    },
+};
+
+use data::chart::{
+    PlotData,
+    indicator::{
+        KlineIndicator,
+        rolling::{VolumeZScore, VolumeZScoreSeries},
+    },
+    kline::KlineDataPoint,
+};
+use data::util::format_with_commas;
+use exchange::{Kline, Trade};
+
+use std::ops::RangeInclusive;
+
+pub struct VolumeZScoreIndicator {
+    cache: Caches,
+    series: VolumeZScoreSeries,
+    threshold: f32,
+}
+
+impl VolumeZScoreIndicator {
+    pub fn new(period: u16, threshold: f32) -> Self {
+        Self {
+            cache: Caches::default(),
+            series: VolumeZScoreSeries::new(period as usize),
+            threshold,
+        }
+    }
+
+    fn indicator_elem<'a>(
+        &'a self,
+        main_chart: &'a ViewState,
+        visible_range: RangeInclusive<u64>,
+    ) -> iced::Element<'a, Message> {
+        let threshold = self.threshold;
+
+        // spikes stand out in the success color, the rest stay neutral
+        let bars = BarPlot::new(
+            |point: &VolumeZScore| point.z,
+            move |point: &VolumeZScore| {
+                if point.z >= threshold {
+                    BarClass::Directional
+                } else {
+                    BarClass::Single
+                }
+            },
+        )
+        .baseline(Baseline::Signed)
+        .bar_width_factor(0.9)
+        .with_tooltip(|point: &VolumeZScore, _next: Option<&VolumeZScore>| {
+            PlotTooltip::new(format!(
+                "Volume: {}\nZ-score: {:.2}",
+                format_with_commas(point.volume),
+                point.z
+            ))
+        });
+        let guides = GuidePlot::new([threshold]);
+
+        indicator_row(
+            main_chart,
+            &self.cache,
+            (guides, bars),
+            self.series.values(),
+            visible_range,
+        )
+    }
+}
+
+impl KlineIndicatorImpl for VolumeZScoreIndicator {
+    fn clear_all_caches(&mut self) {
+        self.cache.clear_all();
+    }
+
+    fn clear_crosshair_caches(&mut self) {
+        self.cache.clear_crosshair();
+    }
+
+    fn element<'a>(
+        &'a self,
+        chart: &'a ViewState,
+        visible_range: RangeInclusive<u64>,
+    ) -> iced::Element<'a, Message> {
+        self.indicator_elem(chart, visible_range)
+    }
+
+    fn rebuild_from_source(&mut self, source: &PlotData<KlineDataPoint>) {
+        self.series.clear();
+        self.series.insert(source_bars(source, |kline, _| kline));
+        self.clear_all_caches();
+    }
+
+    fn on_insert_klines(&mut self, klines: &[Kline]) {
+        self.series
+            .insert(klines.iter().map(|kline| (kline.time, kline)));
+        self.clear_all_caches();
+    }
+
+    fn on_insert_trades(
+        &mut self,
+        _trades: &[Trade],
+        old_dp_len: usize,
+        source: &PlotData<KlineDataPoint>,
+    ) {
+        if let Some(bars) = updated_tick_bars(source, old_dp_len, |kline, _| kline) {
+            self.series.insert(bars);
+            self.clear_all_caches();
+        }
+    }
+
+    fn on_ticksize_change(&mut self, source: &PlotData<KlineDataPoint>) {
+        self.rebuild_from_source(source);
+    }
+
+    fn on_basis_change(&mut self, source: &PlotData<KlineDataPoint>) {
+        self.rebuild_from_source(source);
+    }
+
+    fn set_config(&mut self, config: KlineIndicator) {
+        if let KlineIndicator::VolumeZScore { period, threshold } = config {
+            *self = Self::new(period, threshold);
+        }
+    }
+}
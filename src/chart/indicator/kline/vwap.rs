@@ -0,0 +1,213 @@
+use crate::chart::{
+    ViewState,
+    indicator::{
+        kline::{KlineIndicatorImpl, polyline},
+        plot::{AnySeries, Series},
+    },
+};
+
+use data::chart::{
+    PlotData,
+    indicator::{
+        KlineIndicator,
+        vwap::{VwapAnchor, VwapPoint, VwapSeries},
+    },
+    kline::KlineDataPoint,
+};
+use exchange::{Kline, Trade, util::Price};
+
+use iced::Point;
+use iced::theme::palette::Extended;
+use iced::widget::canvas::{self, LineDash, Path, Stroke};
+use std::ops::RangeInclusive;
+
+pub struct VwapIndicator {
+    series: VwapSeries,
+    anchor: VwapAnchor,
+    bands: u8,
+    anchors: Vec<u64>,
+}
+
+impl VwapIndicator {
+    pub fn new(anchor: VwapAnchor, bands: u8) -> Self {
+        Self {
+            series: VwapSeries::new(anchor),
+            anchor,
+            bands: bands.min(3),
+            anchors: vec![],
+        }
+    }
+
+    /// Lines of `offset` standard deviations from the VWAP, split where sessions start
+    fn session_lines(
+        &self,
+        chart: &ViewState,
+        visible_range: RangeInclusive<u64>,
+        offset: f32,
+    ) -> Vec<Vec<Point>> {
+        let mut lines: Vec<Vec<Point>> = vec![];
+        let mut session = None;
+
+        AnySeries::for_basis(chart.basis, self.series.values()).for_each_in(
+            visible_range,
+            |x, point: &VwapPoint| {
+                if session != Some(point.session) {
+                    session = Some(point.session);
+                    lines.push(vec![]);
+                }
+
+                let price = Price::from_f32_lossy(point.vwap + offset * point.std_dev);
+                if let Some(line) = lines.last_mut() {
+                    line.push(Point::new(chart.interval_to_x(x), chart.price_to_y(price)));
+                }
+            },
+        );
+
+        lines
+    }
+}
+
+impl KlineIndicatorImpl for VwapIndicator {
+    // drawn within the main chart's cache, nothing of its own to clear
+    fn clear_all_caches(&mut self) {}
+
+    fn clear_crosshair_caches(&mut self) {}
+
+    fn draw_overlay(
+        &self,
+        frame: &mut canvas::Frame,
+        chart: &ViewState,
+        visible_range: RangeInclusive<u64>,
+        palette: &Extended,
+    ) {
+        let color = palette.secondary.strong.color;
+
+        for sigma in 1..=self.bands {
+            let stroke = Stroke::with_color(
+                Stroke {
+                    width: 1.0,
+                    line_dash: LineDash {
+                        segments: &[3.0, 3.0],
+                        offset: 0,
+                    },
+                    ..Stroke::default()
+                },
+                color.scale_alpha(0.7 - 0.15 * f32::from(sigma)),
+            );
+
+            for offset in [f32::from(sigma), -f32::from(sigma)] {
+                for line in self.session_lines(chart, visible_range.clone(), offset) {
+                    if let Some(path) = polyline(&line) {
+                        frame.stroke(&path, stroke);
+                    }
+                }
+            }
+        }
+
+        let stroke = Stroke::with_color(
+            Stroke {
+                width: 1.5,
+                ..Stroke::default()
+            },
+            color,
+        );
+
+        for line in self.session_lines(chart, visible_range, 0.0) {
+            if let Some(path) = polyline(&line) {
+                frame.stroke(&path, stroke);
+            }
+
+            // marks where each anchored session starts, the earliest point of its line
+            if self.anchor == VwapAnchor::Custom {
+                let start = match chart.basis {
+                    data::chart::Basis::Time(_) => line.first(),
+                    _ => line.last(),
+                };
+                if let Some(start) = start {
+                    frame.fill(&Path::circle(*start, 3.0), color);
+                }
+            }
+        }
+    }
+
+    fn rebuild_from_source(&mut self, source: &PlotData<KlineDataPoint>) {
+        self.series.clear();
+
+        match source {
+            PlotData::TimeBased(timeseries) => self.series.insert_datapoints(
+                timeseries
+                    .datapoints
+                    .iter()
+                    .map(|(time, dp)| (*time, &dp.kline, &dp.footprint)),
+            ),
+            PlotData::TickBased(tickseries) => self.series.insert_datapoints(
+                tickseries
+                    .datapoints
+                    .iter()
+                    .enumerate()
+                    .map(|(idx, dp)| (idx as u64, &dp.kline, &dp.footprint)),
+            ),
+        }
+    }
+
+    fn on_insert_klines(&mut self, klines: &[Kline]) {
+        self.series
+            .insert_klines(klines.iter().map(|kline| (kline.time, kline)));
+    }
+
+    fn on_insert_trades(
+        &mut self,
+        trades: &[Trade],
+        old_dp_len: usize,
+        source: &PlotData<KlineDataPoint>,
+    ) {
+        match source {
+            PlotData::TimeBased(timeseries) => {
+                let Some(earliest) = trades.iter().map(|trade| trade.time).min() else {
+                    return;
+                };
+                let from = timeseries.interval.bucket_start(earliest);
+
+                self.series.insert_datapoints(
+                    timeseries
+                        .datapoints
+                        .range(from..)
+                        .map(|(time, dp)| (*time, &dp.kline, &dp.footprint)),
+                );
+            }
+            PlotData::TickBased(tickseries) => {
+                let start_idx = old_dp_len.saturating_sub(1);
+
+                self.series.insert_datapoints(
+                    tickseries
+                        .datapoints
+                        .iter()
+                        .enumerate()
+                        .skip(start_idx)
+                        .map(|(idx, dp)| (idx as u64, &dp.kline, &dp.footprint)),
+                );
+            }
+        }
+    }
+
+    fn on_ticksize_change(&mut self, source: &PlotData<KlineDataPoint>) {
+        self.rebuild_from_source(source);
+    }
+
+    fn on_basis_change(&mut self, source: &PlotData<KlineDataPoint>) {
+        self.rebuild_from_source(source);
+    }
+
+    fn set_config(&mut self, config: KlineIndicator) {
+        if let KlineIndicator::Vwap { anchor, bands } = config {
+            let anchors = std::mem::take(&mut self.anchors);
+            *self = Self::new(anchor, bands);
+            self.set_anchors(&anchors);
+        }
+    }
+
+    fn set_anchors(&mut self, anchors: &[u64]) {
+        self.anchors = anchors.to_vec();
+        self.series.set_anchors(anchors);
+    }
+}
@@ -9,7 +9,10 @@ use std::collections::BTreeMap;
 use std::ops::RangeInclusive;
 
 pub mod bar;
+pub mod candle;
+pub mod guide;
 pub mod line;
+pub mod marker;
 
 pub trait Series {
     type Y;
@@ -93,8 +96,8 @@ pub enum AnySeries<'a, Y> {
 impl<'a, Y> AnySeries<'a, Y> {
     pub fn for_basis(basis: Basis, data: &'a BTreeMap<u64, Y>) -> Self {
         match basis {
-            Basis::Tick(_) => Self::Reversed(ReversedBTreeSeries::new(data)),
             Basis::Time(_) => Self::Forward(data),
+            _ => Self::Reversed(ReversedBTreeSeries::new(data)),
         }
     }
 }
@@ -171,6 +174,49 @@ pub trait Plot<S: Series> {
     }
 }
 
+/// Two plots of the same series drawn over each other, the first one below.
+/// Extents span both, padded as the first plot pads them; tooltips come from
+/// the first plot that has any
+impl<S, A, B> Plot<S> for (A, B)
+where
+    S: Series,
+    A: Plot<S>,
+    B: Plot<S>,
+{
+    fn y_extents(&self, s: &S, range: RangeInclusive<u64>) -> Option<(f32, f32)> {
+        match (
+            self.0.y_extents(s, range.clone()),
+            self.1.y_extents(s, range),
+        ) {
+            (Some((min_a, max_a)), Some((min_b, max_b))) => {
+                Some((min_a.min(min_b), max_a.max(max_b)))
+            }
+            (extents, None) | (None, extents) => extents,
+        }
+    }
+
+    fn adjust_extents(&self, min: f32, max: f32) -> (f32, f32) {
+        self.0.adjust_extents(min, max)
+    }
+
+    fn draw<'a>(
+        &'a self,
+        frame: &'a mut canvas::Frame,
+        ctx: &'a ViewState,
+        theme: &Theme,
+        s: &S,
+        range: RangeInclusive<u64>,
+        scale: &YScale,
+    ) {
+        self.0.draw(frame, ctx, theme, s, range.clone(), scale);
+        self.1.draw(frame, ctx, theme, s, range, scale);
+    }
+
+    fn tooltip_fn(&self) -> Option<&TooltipFn<S::Y>> {
+        self.0.tooltip_fn().or_else(|| self.1.tooltip_fn())
+    }
+}
+
 pub struct ChartCanvas<'a, P, S>
 where
     P: Plot<S>,
@@ -288,7 +334,7 @@ where
                         };
                         (rx, sr)
                     }
-                    Basis::Tick(_) => {
+                    _ => {
                         let world_x = region.x + (cursor_position.x / bounds.width) * region.width;
                         let snapped_world_x = (world_x / ctx.cell_width).round() * ctx.cell_width;
 
@@ -17,6 +17,8 @@ pub enum Baseline {
     Min,
     /// Use a fixed numeric baseline.
     Fixed(f32),
+    /// Use zero as baseline, negative values extend below it. Extents: [min(0), max(0)].
+    Signed,
 }
 
 #[derive(Clone, Copy)]
@@ -27,6 +29,8 @@ pub enum BarClass {
     Single,
     /// draw two bars, a success/danger colored (alpha) and an overlay using full color.
     Overlay { overlay: f32 }, // signed; sign decides color
+    /// draw a single bar, success colored above the baseline and danger below
+    Directional,
 }
 
 pub struct BarPlot<V, CL, T> {
@@ -108,6 +112,11 @@ where
             Baseline::Zero => 0.0,
             Baseline::Min => min_v,
             Baseline::Fixed(v) => v,
+            Baseline::Signed => {
+                let (lowest, highest) = (min_v.min(0.0), max_v.max(0.0));
+                let pad = (highest - lowest) * self.padding;
+                return Some((lowest - pad, highest + pad));
+            }
         };
 
         let lowest = min_ext;
@@ -132,7 +141,7 @@ where
         let bar_width = ctx.cell_width * self.bar_width_factor;
 
         let baseline_value = match self.baseline {
-            Baseline::Zero => 0.0,
+            Baseline::Zero | Baseline::Signed => 0.0,
             Baseline::Min => scale.min, // extents min
             Baseline::Fixed(v) => v,
         };
@@ -149,6 +158,9 @@ where
                 let y_total = scale.to_y(total);
                 let h = (y_base - y_total).max(0.0);
                 (y_total, h)
+            } else if rel < 0.0 && matches!(self.baseline, Baseline::Signed) {
+                let y_total = scale.to_y(total);
+                (y_base, (y_total - y_base).max(0.0))
             } else {
                 (y_base, 0.0)
             };
@@ -164,6 +176,19 @@ where
                         palette.secondary.strong.color,
                     );
                 }
+                BarClass::Directional => {
+                    let color = if rel >= 0.0 {
+                        palette.success.base.color
+                    } else {
+                        palette.danger.base.color
+                    };
+
+                    frame.fill_rectangle(
+                        Point::new(left, top_y),
+                        Size::new(bar_width, h_total),
+                        color.scale_alpha(0.6),
+                    );
+                }
                 BarClass::Overlay { overlay } => {
                     let base_color = if overlay >= 0.0 {
                         palette.success.base.color
@@ -0,0 +1,120 @@
+use std::ops::RangeInclusive;
+
+use iced::{Point, Size, Theme, widget::canvas};
+
+use crate::chart::{
+    ViewState,
+    indicator::plot::{Plot, PlotTooltip, Series, TooltipFn, YScale},
+};
+
+#[derive(Clone, Copy)]
+pub struct Ohlc {
+    pub open: f32,
+    pub high: f32,
+    pub low: f32,
+    pub close: f32,
+}
+
+pub struct CandlePlot<V, T> {
+    /// Maps a datapoint to the candle it is drawn as
+    pub value: V,
+    pub candle_width_factor: f32,
+    // padding in percentage of the value range, applies both top and bottom
+    pub padding: f32,
+    pub tooltip: Option<TooltipFn<T>>,
+    _phantom: std::marker::PhantomData<T>,
+}
+
+impl<V, T> CandlePlot<V, T> {
+    pub fn new(value: V) -> Self {
+        Self {
+            value,
+            candle_width_factor: 0.8,
+            padding: 0.08,
+            tooltip: None,
+            _phantom: std::marker::PhantomData,
+        }
+    }
+
+    pub fn with_tooltip<F>(mut self, tooltip: F) -> Self
+    where
+        F: Fn(&T, Option<&T>) -> PlotTooltip + 'static,
+    {
+        self.tooltip = Some(Box::new(tooltip));
+        self
+    }
+}
+
+impl<S, V> Plot<S> for CandlePlot<V, S::Y>
+where
+    S: Series,
+    V: Fn(&S::Y) -> Ohlc,
+{
+    fn y_extents(&self, datapoints: &S, range: RangeInclusive<u64>) -> Option<(f32, f32)> {
+        let mut min_v = f32::MAX;
+        let mut max_v = f32::MIN;
+
+        datapoints.for_each_in(range, |_, y| {
+            let candle = (self.value)(y);
+            min_v = min_v.min(candle.low);
+            max_v = max_v.max(candle.high);
+        });
+
+        if min_v == f32::MAX {
+            None
+        } else {
+            Some((min_v, max_v))
+        }
+    }
+
+    fn adjust_extents(&self, min: f32, max: f32) -> (f32, f32) {
+        if self.padding > 0.0 && max > min {
+            let pad = (max - min) * self.padding;
+            (min - pad, max + pad)
+        } else {
+            (min, max)
+        }
+    }
+
+    fn draw(
+        &self,
+        frame: &mut canvas::Frame,
+        ctx: &ViewState,
+        theme: &Theme,
+        datapoints: &S,
+        range: RangeInclusive<u64>,
+        scale: &YScale,
+    ) {
+        let palette = theme.extended_palette();
+        let candle_width = ctx.cell_width * self.candle_width_factor;
+
+        datapoints.for_each_in(range, |x, y| {
+            let candle = (self.value)(y);
+            let center_x = ctx.interval_to_x(x);
+
+            let color = if candle.close >= candle.open {
+                palette.success.base.color
+            } else {
+                palette.danger.base.color
+            };
+
+            let (y_open, y_close) = (scale.to_y(candle.open), scale.to_y(candle.close));
+            let (y_high, y_low) = (scale.to_y(candle.high), scale.to_y(candle.low));
+
+            frame.fill_rectangle(
+                Point::new(center_x - (candle_width / 2.0), y_open.min(y_close)),
+                Size::new(candle_width, (y_open - y_close).abs().max(1.0)),
+                color,
+            );
+            frame.fill_rectangle(
+                Point::new(center_x - (candle_width / 8.0), y_high),
+                Size::new(candle_width / 4.0, (y_low - y_high).abs()),
+                color,
+            );
+        });
+    }
+
+    fn tooltip_fn(&self) -> Option<&TooltipFn<S::Y>> {
+        self.tooltip.as_ref()
+    }
+}
@@ -0,0 +1,85 @@
+use std::ops::RangeInclusive;
+
+use iced::{
+    Point, Theme,
+    widget::canvas::{self, LineDash, Path, Stroke},
+};
+
+use crate::chart::{
+    ViewState,
+    indicator::plot::{Plot, Series, TooltipFn, YScale},
+};
+
+/// Dashed horizontal lines at fixed values, e.g. overbought/oversold levels.
+/// Meant to be layered with the plot of the values they are guides for
+pub struct GuidePlot {
+    pub levels: Vec<f32>,
+    /// Value range always kept in view, otherwise only the levels are
+    pub extents: Option<(f32, f32)>,
+}
+
+impl GuidePlot {
+    pub fn new(levels: impl Into<Vec<f32>>) -> Self {
+        Self {
+            levels: levels.into(),
+            extents: None,
+        }
+    }
+
+    pub fn within(mut self, min: f32, max: f32) -> Self {
+        self.extents = Some((min, max));
+        self
+    }
+}
+
+impl<S: Series> Plot<S> for GuidePlot {
+    fn y_extents(&self, _datapoints: &S, _range: RangeInclusive<u64>) -> Option<(f32, f32)> {
+        self.extents.or_else(|| {
+            let min = self.levels.iter().copied().reduce(f32::min)?;
+            let max = self.levels.iter().copied().reduce(f32::max)?;
+            Some((min, max))
+        })
+    }
+
+    fn draw(
+        &self,
+        frame: &mut canvas::Frame,
+        ctx: &ViewState,
+        theme: &Theme,
+        _datapoints: &S,
+        range: RangeInclusive<u64>,
+        scale: &YScale,
+    ) {
+        let palette = theme.extended_palette();
+
+        let stroke = Stroke::with_color(
+            Stroke {
+                width: 1.0,
+                line_dash: LineDash {
+                    segments: &[2.0, 4.0],
+                    offset: 0,
+                },
+                ..Stroke::default()
+            },
+            palette.background.strong.color,
+        );
+
+        let (x1, x2) = (
+            ctx.interval_to_x(*range.start()),
+            ctx.interval_to_x(*range.end()),
+        );
+        let (left, right) = (x1.min(x2) - ctx.cell_width, x1.max(x2) + ctx.cell_width);
+
+        for level in &self.levels {
+            let y = scale.to_y(*level);
+            frame.stroke(
+                &Path::line(Point::new(left, y), Point::new(right, y)),
+                stroke,
+            );
+        }
+    }
+
+    fn tooltip_fn(&self) -> Option<&TooltipFn<S::Y>> {
+        None
+    }
+}
@@ -1,7 +1,8 @@
 use std::ops::RangeInclusive;
 
 use iced::{
-    Theme,
+    Color, Theme,
+    theme::palette::Extended,
     widget::canvas::{self, Path, Stroke},
 };
 
@@ -18,6 +19,8 @@ pub struct LinePlot<V, T> {
     pub stroke_width: f32,
     pub show_points: bool,
     pub point_radius_factor: f32,
+    /// Picks the line's color from the theme, secondary strong unless set
+    pub color: fn(&Extended) -> Color,
     _phantom: std::marker::PhantomData<T>,
 }
 
@@ -32,6 +35,7 @@ impl<V, T> LinePlot<V, T> {
             stroke_width: 1.0,
             show_points: true,
             point_radius_factor: 0.2,
+            color: |palette| palette.secondary.strong.color,
             _phantom: std::marker::PhantomData,
         }
     }
@@ -59,6 +63,11 @@ impl<V, T> LinePlot<V, T> {
         self
     }
 
+    pub fn color(mut self, color: fn(&Extended) -> Color) -> Self {
+        self.color = color;
+        self
+    }
+
     pub fn with_tooltip<F>(mut self, tooltip: F) -> Self
     where
         F: Fn(&T, Option<&T>) -> PlotTooltip + 'static,
@@ -113,8 +122,7 @@ where
         range: RangeInclusive<u64>,
         scale: &YScale,
     ) {
-        let palette = theme.extended_palette();
-        let color = palette.secondary.strong.color;
+        let color = (self.color)(theme.extended_palette());
 
         let stroke = Stroke::with_color(
             Stroke {
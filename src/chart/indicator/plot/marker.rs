@@ -0,0 +1,94 @@
+use std::ops::RangeInclusive;
+
+use iced::{
+    Color, Theme,
+    theme::palette::Extended,
+    widget::canvas::{self, Path},
+};
+
+use crate::chart::{
+    ViewState,
+    indicator::plot::{Plot, PlotTooltip, Series, TooltipFn, YScale},
+};
+
+/// A dot at each value, unconnected, for series that only have values on some bars
+pub struct MarkerPlot<V, T> {
+    pub value: V,
+    pub tooltip: Option<TooltipFn<T>>,
+    // padding in percentage of the value range, applies both top and bottom
+    pub padding: f32,
+    /// circle radius as a factor of cell width, capped at 5px
+    pub radius_factor: f32,
+    pub color: fn(&Extended) -> Color,
+    _phantom: std::marker::PhantomData<T>,
+}
+
+impl<V, T> MarkerPlot<V, T> {
+    pub fn new(value: V) -> Self {
+        Self {
+            value,
+            tooltip: None,
+            padding: 0.08,
+            radius_factor: 0.3,
+            color: |palette| palette.secondary.strong.color,
+            _phantom: std::marker::PhantomData,
+        }
+    }
+
+    pub fn with_tooltip<F>(mut self, tooltip: F) -> Self
+    where
+        F: Fn(&T, Option<&T>) -> PlotTooltip + 'static,
+    {
+        self.tooltip = Some(Box::new(tooltip));
+        self
+    }
+}
+
+impl<S, V> Plot<S> for MarkerPlot<V, S::Y>
+where
+    S: Series,
+    V: Fn(&S::Y) -> f32,
+{
+    fn y_extents(&self, datapoints: &S, range: RangeInclusive<u64>) -> Option<(f32, f32)> {
+        let mut extents: Option<(f32, f32)> = None;
+
+        datapoints.for_each_in(range, |_, y| {
+            let v = (self.value)(y);
+            extents = Some(extents.map_or((v, v), |(min, max)| (min.min(v), max.max(v))));
+        });
+
+        extents
+    }
+
+    fn adjust_extents(&self, min: f32, max: f32) -> (f32, f32) {
+        if self.padding > 0.0 && max > min {
+            let pad = (max - min) * self.padding;
+            (min - pad, max + pad)
+        } else {
+            (min, max)
+        }
+    }
+
+    fn draw(
+        &self,
+        frame: &mut canvas::Frame,
+        ctx: &ViewState,
+        theme: &Theme,
+        datapoints: &S,
+        range: RangeInclusive<u64>,
+        scale: &YScale,
+    ) {
+        let color = (self.color)(theme.extended_palette());
+        let radius = (ctx.cell_width * self.radius_factor).clamp(1.5, 5.0);
+
+        datapoints.for_each_in(range, |x, y| {
+            let sx = ctx.interval_to_x(x) - (ctx.cell_width / 2.0);
+            let sy = scale.to_y((self.value)(y));
+            frame.fill(&Path::circle(iced::Point::new(sx, sy), radius), color);
+        });
+    }
+
+    fn tooltip_fn(&self) -> Option<&TooltipFn<S::Y>> {
+        self.tooltip.as_ref()
+    }
+}
@@ -10,14 +10,16 @@ use data::chart::Autoscale;
 use data::chart::kline::ClusterScaling;
 use data::chart::{
     KlineChartKind, ViewConfig,
-    indicator::{Indicator, KlineIndicator},
+    indicator::{KlineIndicator, vwap::VwapAnchor},
     kline::{ClusterKind, FootprintStudy, KlineDataPoint, KlineTrades, NPoc, PointOfControl},
 };
+use data::export::{self, Dataset, Scope};
 use data::util::{abbr_large_numbers, count_decimals};
-use exchange::util::{Price, PriceStep};
+use exchange::util::{Price, PriceStep, Qty};
 use exchange::{
-    Kline, OpenInterest as OIData, TickerInfo, Trade,
-    fetcher::{FetchRange, RequestHandler},
+    Kline, OpenInterest as OIData, TickerInfo, Timeframe, Trade,
+    adapter::{Exchange, local},
+    fetcher::{FetchKind, FetchRange, RequestHandler},
 };
 
 use iced::task::Handle;
@@ -25,7 +27,6 @@ use iced::theme::palette::Extended;
 use iced::widget::canvas::{self, Event, Geometry, Path, Stroke};
 use iced::{Alignment, Element, Point, Rectangle, Renderer, Size, Theme, Vector, mouse};
 
-use enum_map::EnumMap;
 use std::time::Instant;
 
 impl Chart for KlineChart {
@@ -42,9 +43,8 @@ impl Chart for KlineChart {
     fn invalidate_crosshair(&mut self) {
         self.chart.cache.clear_crosshair();
         self.indicators
-            .values_mut()
-            .filter_map(Option::as_mut)
-            .for_each(|indi| indi.clear_crosshair_caches());
+            .iter_mut()
+            .for_each(|(_, indi)| indi.clear_crosshair_caches());
     }
 
     fn invalidate_all(&mut self) {
@@ -63,10 +63,14 @@ impl Chart for KlineChart {
         let mut elements = vec![];
 
         for selected_indicator in enabled {
-            if !KlineIndicator::for_market(market).contains(selected_indicator) {
+            if !selected_indicator.is_available_for(market) || selected_indicator.is_overlay() {
                 continue;
             }
-            if let Some(indi) = self.indicators[*selected_indicator].as_ref() {
+            if let Some((_, indi)) = self
+                .indicators
+                .iter()
+                .find(|(config, _)| config == selected_indicator)
+            {
                 elements.push(indi.element(chart_state, earliest..=latest));
             }
         }
@@ -92,8 +96,20 @@ impl Chart for KlineChart {
 
                 Some((earliest, latest))
             }
-            Basis::Tick(_) => {
-                unimplemented!()
+            _ => {
+                let PlotData::TickBased(tick_aggr) = &self.data_source else {
+                    return None;
+                };
+                let last_idx = tick_aggr.datapoints.len().checked_sub(1)?;
+
+                // tick keys count back from the latest bar
+                let (latest, earliest) = chart.interval_range(&region);
+                let time_at = |back: u64| {
+                    let idx = last_idx.saturating_sub(back as usize);
+                    tick_aggr.datapoints[idx].kline.time
+                };
+
+                Some((time_at(earliest), time_at(latest)))
             }
         }
     }
@@ -171,12 +187,21 @@ pub struct KlineChart {
     chart: ViewState,
     data_source: PlotData<KlineDataPoint>,
     raw_trades: Vec<Trade>,
-    indicators: EnumMap<KlineIndicator, Option<Box<dyn KlineIndicatorImpl>>>,
-    fetching_trades: (bool, Option<Handle>),
+    indicators: Vec<(KlineIndicator, Box<dyn KlineIndicatorImpl>)>,
+    fetching_trades: (bool, Vec<Handle>),
+    /// Time of the first trade the bars were built from when trades before it were
+    /// requested, fetched batches can run past it into trades already counted
+    trade_backfill_end: Option<u64>,
+    /// Earliest visible time when a trade fetch was cancelled, cancelled ranges
+    /// stay blocked until the view reaches back past it
+    trades_cancelled_from: Option<u64>,
     pub(crate) kind: KlineChartKind,
     request_handler: RequestHandler,
     study_configurator: study::Configurator<FootprintStudy>,
+    visual_config: data::chart::kline::Config,
     last_tick: Instant,
+    /// Open times of the bars the user anchored VWAPs on
+    vwap_anchors: Vec<u64>,
 }
 
 impl KlineChart {
@@ -189,13 +214,14 @@ impl KlineChart {
         enabled_indicators: &[KlineIndicator],
         ticker_info: TickerInfo,
         kind: &KlineChartKind,
+        visual_config: data::chart::kline::Config,
     ) -> Self {
         match basis {
             Basis::Time(interval) => {
                 let step = PriceStep::from_f32(tick_size);
 
                 let timeseries = TimeSeries::<KlineDataPoint>::new(interval, step, klines_raw)
-                    .with_trades(&raw_trades);
+                    .with_trades(&raw_trades, klines_from_trades(&ticker_info, interval));
 
                 let base_price_y = timeseries.base_price();
                 let latest_x = timeseries.latest_timestamp().unwrap_or(0);
@@ -252,11 +278,12 @@ impl KlineChart {
 
                 let data_source = PlotData::TimeBased(timeseries);
 
-                let mut indicators = EnumMap::default();
+                let mut indicators = Vec::with_capacity(enabled_indicators.len());
                 for &i in enabled_indicators {
                     let mut indi = indicator::kline::make_empty(i);
+                    indi.set_size_unit(ticker_info.market_type(), visual_config.size_unit);
                     indi.rebuild_from_source(&data_source);
-                    indicators[i] = Some(indi);
+                    indicators.push((i, indi));
                 }
 
                 KlineChart {
@@ -264,14 +291,21 @@ impl KlineChart {
                     data_source,
                     raw_trades,
                     indicators,
-                    fetching_trades: (false, None),
+                    fetching_trades: (false, vec![]),
+                    trade_backfill_end: None,
+                    trades_cancelled_from: None,
                     request_handler: RequestHandler::new(),
                     kind: kind.clone(),
                     study_configurator: study::Configurator::new(),
+                    visual_config,
                     last_tick: Instant::now(),
+                    vwap_anchors: vec![],
                 }
             }
-            Basis::Tick(interval) => {
+            _ => {
+                let rule = basis
+                    .bar_rule()
+                    .expect("non-time basis groups trades into bars");
                 let step = PriceStep::from_f32(tick_size);
 
                 let cell_width = match kind {
@@ -308,13 +342,14 @@ impl KlineChart {
                 };
                 chart.translation.x = x_translation;
 
-                let data_source = PlotData::TickBased(TickAggr::new(interval, step, &raw_trades));
+                let data_source = PlotData::TickBased(TickAggr::new(rule, step, &raw_trades));
 
-                let mut indicators = EnumMap::default();
+                let mut indicators = Vec::with_capacity(enabled_indicators.len());
                 for &i in enabled_indicators {
                     let mut indi = indicator::kline::make_empty(i);
+                    indi.set_size_unit(ticker_info.market_type(), visual_config.size_unit);
                     indi.rebuild_from_source(&data_source);
-                    indicators[i] = Some(indi);
+                    indicators.push((i, indi));
                 }
 
                 KlineChart {
@@ -322,25 +357,34 @@ impl KlineChart {
                     data_source,
                     raw_trades,
                     indicators,
-                    fetching_trades: (false, None),
+                    fetching_trades: (false, vec![]),
+                    trade_backfill_end: None,
+                    trades_cancelled_from: None,
                     request_handler: RequestHandler::new(),
                     kind: kind.clone(),
                     study_configurator: study::Configurator::new(),
+                    visual_config,
                     last_tick: Instant::now(),
+                    vwap_anchors: vec![],
                 }
             }
         }
     }
 
-    pub fn update_latest_kline(&mut self, kline: &Kline) {
+    /// `timeframe` is the one of the stream, lower than the chart's when it is resampled
+    pub fn update_latest_kline(&mut self, kline: &Kline, timeframe: Timeframe) {
         match self.data_source {
             PlotData::TimeBased(ref mut timeseries) => {
-                timeseries.insert_klines(&[*kline]);
+                let kline = if timeframe == timeseries.interval {
+                    timeseries.insert_klines(&[*kline]);
+                    *kline
+                } else {
+                    timeseries.insert_base_kline(kline)
+                };
 
                 self.indicators
-                    .values_mut()
-                    .filter_map(Option::as_mut)
-                    .for_each(|indi| indi.on_insert_klines(&[*kline]));
+                    .iter_mut()
+                    .for_each(|(_, indi)| indi.on_insert_klines(&[kline]));
 
                 let chart = self.mut_state();
 
@@ -359,7 +403,46 @@ impl KlineChart {
     }
 
     fn missing_data_task(&mut self) -> Option<Action> {
+        if let Some(cancelled_from) = self.trades_cancelled_from
+            && self
+                .visible_timerange()
+                .is_some_and(|(earliest, _)| earliest < cancelled_from)
+        {
+            self.request_handler.clear_cancelled(FetchKind::Trades);
+            self.trades_cancelled_from = None;
+        }
+
+        let from_trades = self.builds_klines_from_trades();
+
         match &self.data_source {
+            PlotData::TimeBased(timeseries) if from_trades => {
+                // no klines to fetch, earlier bars come from trade backfill where available
+                if timeseries.datapoints.is_empty()
+                    || self.fetching_trades.0
+                    || !exchange::fetcher::is_trade_fetch_enabled()
+                {
+                    return None;
+                }
+
+                let (visible_earliest, _) = self.visible_timerange()?;
+                let (kline_earliest, _) = timeseries.timerange();
+                // the first bar may have started forming before the stream did
+                let first_trade = timeseries
+                    .datapoints
+                    .values()
+                    .next()
+                    .and_then(|dp| dp.first_trade_time())
+                    .unwrap_or(kline_earliest);
+
+                if visible_earliest < kline_earliest {
+                    let range = FetchRange::Trades(visible_earliest, first_trade);
+                    if let Some(action) = request_fetch(&mut self.request_handler, range) {
+                        self.fetching_trades = (true, vec![]);
+                        self.trade_backfill_end = Some(first_trade);
+                        return Some(action);
+                    }
+                }
+            }
             PlotData::TimeBased(timeseries) => {
                 let timeframe_ms = timeseries.interval.to_milliseconds();
 
@@ -388,13 +471,13 @@ impl KlineChart {
 
                 // priority 2, trades fetch
                 if !self.fetching_trades.0
-                    && exchange::fetcher::is_trade_fetch_enabled()
+                    && self.fetches_trades()
                     && let Some((fetch_from, fetch_to)) =
                         timeseries.suggest_trade_fetch_range(visible_earliest, visible_latest)
                 {
                     let range = FetchRange::Trades(fetch_from, fetch_to);
                     if let Some(action) = request_fetch(&mut self.request_handler, range) {
-                        self.fetching_trades = (true, None);
+                        self.fetching_trades = (true, vec![]);
                         return Some(action);
                     }
                 }
@@ -402,12 +485,11 @@ impl KlineChart {
                 // priority 3, Open Interest data
                 let ctx = indicator::kline::FetchCtx {
                     main_chart: &self.chart,
-                    timeframe: timeseries.interval,
                     visible_earliest,
                     kline_latest,
                     prefetch_earliest: earliest,
                 };
-                for indi in self.indicators.values_mut().filter_map(Option::as_mut) {
+                for (_, indi) in &mut self.indicators {
                     if let Some(range) = indi.fetch_range(&ctx)
                         && let Some(action) = request_fetch(&mut self.request_handler, range)
                     {
@@ -417,7 +499,7 @@ impl KlineChart {
 
                 // priority 4, missing klines & integrity check
                 if let Some(missing_keys) =
-                    timeseries.check_kline_integrity(kline_earliest, kline_latest, timeframe_ms)
+                    timeseries.check_kline_integrity(kline_earliest, kline_latest)
                 {
                     let latest =
                         missing_keys.iter().max().unwrap_or(&visible_latest) + timeframe_ms;
@@ -430,25 +512,221 @@ impl KlineChart {
                     }
                 }
             }
-            PlotData::TickBased(_) => {
-                // TODO: implement trade fetch
+            PlotData::TickBased(tick_aggr) => {
+                // imported trades are loaded at once, live venues are still TODO
+                if tick_aggr.datapoints.is_empty()
+                    && !self.fetching_trades.0
+                    && self.is_local_with_trades()
+                {
+                    let range = FetchRange::Trades(0, u64::MAX);
+                    if let Some(action) = request_fetch(&mut self.request_handler, range) {
+                        self.fetching_trades = (true, vec![]);
+                        return Some(action);
+                    }
+                }
+
+                // Open Interest data, matched to bars by the time they opened at
+                let kline_latest = tick_aggr.datapoints.last()?.kline.time;
+                let (visible_earliest, visible_latest) = self.visible_timerange()?;
+
+                let ctx = indicator::kline::FetchCtx {
+                    main_chart: &self.chart,
+                    visible_earliest,
+                    kline_latest,
+                    prefetch_earliest: visible_earliest
+                        .saturating_sub(visible_latest - visible_earliest),
+                };
+                for (_, indi) in &mut self.indicators {
+                    if let Some(range) = indi.fetch_range(&ctx)
+                        && let Some(action) = request_fetch(&mut self.request_handler, range)
+                    {
+                        return Some(action);
+                    }
+                }
             }
         }
 
         None
     }
 
+    /// Bars of timeframes the venue has no klines for are built from the trade stream
+    fn builds_klines_from_trades(&self) -> bool {
+        match self.chart.basis {
+            Basis::Time(timeframe) => klines_from_trades(&self.chart.ticker_info, timeframe),
+            _ => false,
+        }
+    }
+
+    /// Series of a higher timeframe built from the klines and trades already loaded, so
+    /// switching to it only fetches what they don't cover. Skipped when the live stream
+    /// of `basis` is neither of its own timeframe nor the loaded one, as it couldn't
+    /// carry on the open bucket
+    fn resampled_series(&self, basis: Basis) -> Option<TimeSeries<KlineDataPoint>> {
+        let Basis::Time(timeframe) = basis else {
+            return None;
+        };
+        let PlotData::TimeBased(timeseries) = &self.data_source else {
+            return None;
+        };
+
+        let ticker_info = &self.chart.ticker_info;
+        if self.builds_klines_from_trades() || klines_from_trades(ticker_info, timeframe) {
+            return None;
+        }
+
+        let source = ticker_info.exchange().kline_source(timeframe)?;
+        if source != timeframe && source != timeseries.interval {
+            return None;
+        }
+
+        let mut resampled = timeseries.resample(timeframe)?;
+        resampled.insert_trades_existing_buckets(&self.raw_trades);
+        Some(resampled)
+    }
+
+    /// Adds trades to bars built from them, passing the touched klines on to indicators
+    fn insert_trades_as_klines(&mut self, trades: &[Trade]) {
+        let PlotData::TimeBased(ref mut timeseries) = self.data_source else {
+            return;
+        };
+        let Some(earliest) = trades.iter().map(|t| t.time).min() else {
+            return;
+        };
+
+        let old_dp_len = timeseries.datapoints.len();
+        timeseries.insert_trades_or_create_bucket(trades, true);
+
+        let klines: Vec<Kline> = timeseries
+            .datapoints
+            .range(timeseries.interval.bucket_start(earliest)..)
+            .map(|(_, dp)| dp.kline)
+            .collect();
+
+        let latest = timeseries.datapoints.values().last().map(|dp| dp.kline);
+
+        // the buckets hold the trades' footprint too, for indicators reading it
+        self.indicators.iter_mut().for_each(|(_, indi)| {
+            indi.on_insert_klines(&klines);
+            indi.on_insert_trades(trades, old_dp_len, &self.data_source);
+        });
+
+        if let Some(latest) = latest {
+            let chart = self.mut_state();
+
+            if latest.time > chart.latest_x {
+                chart.latest_x = latest.time;
+            }
+            chart.last_price = Some(PriceInfoLabel::new(latest.close, latest.open));
+        }
+    }
+
+    fn is_local_with_trades(&self) -> bool {
+        let ticker = self.chart.ticker_info.ticker;
+        ticker.exchange == Exchange::Local && local::has_trades(&ticker)
+    }
+
+    /// Imported trades don't hit the network, so footprints of local tickers always load them
+    fn fetches_trades(&self) -> bool {
+        if self.chart.ticker_info.exchange() == Exchange::Local {
+            matches!(self.kind, KlineChartKind::Footprint { .. }) && self.is_local_with_trades()
+        } else {
+            exchange::fetcher::is_trade_fetch_enabled()
+        }
+    }
+
     pub fn reset_request_handler(&mut self) {
         self.request_handler = RequestHandler::new();
-        self.fetching_trades = (false, None);
+        self.fetching_trades = (false, vec![]);
+        self.trades_cancelled_from = None;
+    }
+
+    /// Aborts running trade fetches, their ranges aren't requested again until the
+    /// view reaches back past what was visible or the basis changes
+    pub fn cancel_trade_fetch(&mut self) {
+        self.request_handler.cancel_pending(FetchKind::Trades);
+        self.fetching_trades = (false, vec![]);
+        self.trades_cancelled_from = self.visible_timerange().map(|(earliest, _)| earliest);
     }
 
     pub fn raw_trades(&self) -> Vec<Trade> {
         self.raw_trades.clone()
     }
 
+    /// Datasets this chart can export, in the order they're offered
+    pub fn export_datasets(&self) -> Vec<Dataset> {
+        let mut datasets = vec![Dataset::Klines];
+        if matches!(self.kind, KlineChartKind::Footprint { .. }) {
+            datasets.push(Dataset::Footprint);
+        }
+        if !self.raw_trades.is_empty() {
+            datasets.push(Dataset::Trades);
+        }
+        datasets
+    }
+
+    pub fn export_table(&self, dataset: Dataset, scope: Scope) -> export::Table {
+        let open_interest = self
+            .indicators
+            .iter()
+            .find(|(config, _)| matches!(config, KlineIndicator::OpenInterest { .. }))
+            .and_then(|(_, indi)| indi.series())
+            .filter(|series| !series.is_empty());
+
+        let (earliest, latest) = match scope {
+            Scope::Visible => {
+                let region = self.chart.visible_region(self.chart.bounds.size());
+                self.chart.interval_range(&region)
+            }
+            Scope::Full => (0, u64::MAX),
+        };
+
+        let trades_within = |from: u64, to: u64| {
+            export::trades(
+                self.raw_trades
+                    .iter()
+                    .filter(|t| t.time >= from && t.time <= to)
+                    .map(|t| (t.time, t.price, t.qty, t.is_sell)),
+            )
+        };
+
+        match &self.data_source {
+            PlotData::TimeBased(series) => match dataset {
+                Dataset::Klines => export::klines(
+                    export::time_series_klines(series, earliest, latest),
+                    open_interest,
+                ),
+                Dataset::Footprint => export::footprint(
+                    series
+                        .datapoints
+                        .range(earliest..=latest)
+                        .map(|(time, dp)| (*time, &dp.footprint)),
+                ),
+                Dataset::Trades => trades_within(earliest, latest),
+                Dataset::OrderRuns => export::Table::default(),
+            },
+            PlotData::TickBased(tick_aggr) => {
+                let bars = || export::tick_aggr_bars(tick_aggr, earliest as usize, latest as usize);
+
+                match dataset {
+                    Dataset::Klines => export::klines(bars().map(|bar| &bar.kline), open_interest),
+                    Dataset::Footprint => {
+                        export::footprint(bars().map(|bar| (bar.kline.time, &bar.footprint)))
+                    }
+                    Dataset::Trades => {
+                        let from = bars().next().map_or(0, |bar| bar.kline.time);
+                        let to = bars().last().map_or(0, |bar| {
+                            bar.footprint.last_trade_t().unwrap_or(bar.kline.time)
+                        });
+                        trades_within(from, to)
+                    }
+                    Dataset::OrderRuns => export::Table::default(),
+                }
+            }
+        }
+    }
+
     pub fn set_handle(&mut self, handle: Handle) {
-        self.fetching_trades.1 = Some(handle);
+        self.fetching_trades.1.push(handle);
     }
 
     pub fn tick_size(&self) -> f32 {
@@ -493,6 +771,22 @@ impl KlineChart {
         self.chart.layout()
     }
 
+    pub fn visual_config(&self) -> data::chart::kline::Config {
+        self.visual_config
+    }
+
+    pub fn set_visual_config(&mut self, visual_config: data::chart::kline::Config) {
+        self.visual_config = visual_config;
+
+        let market = self.chart.ticker_info.market_type();
+        for (_, indi) in &mut self.indicators {
+            indi.set_size_unit(market, visual_config.size_unit);
+            indi.rebuild_from_source(&self.data_source);
+        }
+
+        self.invalidate(None);
+    }
+
     pub fn set_cluster_kind(&mut self, new_kind: ClusterKind) {
         if let KlineChartKind::Footprint {
             ref mut clusters, ..
@@ -519,6 +813,10 @@ impl KlineChart {
         self.chart.basis
     }
 
+    pub fn ticker_info(&self) -> TickerInfo {
+        self.chart.ticker_info
+    }
+
     pub fn change_tick_size(&mut self, new_tick_size: f32) {
         let chart = self.mut_state();
 
@@ -537,34 +835,54 @@ impl KlineChart {
         }
 
         self.indicators
-            .values_mut()
-            .filter_map(Option::as_mut)
-            .for_each(|indi| indi.on_ticksize_change(&self.data_source));
+            .iter_mut()
+            .for_each(|(_, indi)| indi.on_ticksize_change(&self.data_source));
 
         self.invalidate(None);
     }
 
     pub fn set_basis(&mut self, new_basis: Basis) -> Option<Action> {
+        let resampled = self.resampled_series(new_basis);
+
         self.chart.last_price = None;
         self.chart.basis = new_basis;
 
         match new_basis {
             Basis::Time(interval) => {
                 let step = self.chart.tick_size;
-                let timeseries = TimeSeries::<KlineDataPoint>::new(interval, step, &[]);
-                self.data_source = PlotData::TimeBased(timeseries);
+
+                if let Some(timeseries) = resampled {
+                    if let Some(latest) = timeseries.latest_kline() {
+                        self.chart.latest_x = latest.time;
+                        self.chart.last_price =
+                            Some(PriceInfoLabel::new(latest.close, latest.open));
+                    }
+                    self.data_source = PlotData::TimeBased(timeseries);
+                } else {
+                    let mut timeseries = TimeSeries::<KlineDataPoint>::new(interval, step, &[]);
+
+                    if self.builds_klines_from_trades() {
+                        timeseries = timeseries.with_trades(&self.raw_trades, true);
+                        if let Some(latest) = timeseries.latest_timestamp() {
+                            self.chart.latest_x = latest;
+                        }
+                    }
+                    self.data_source = PlotData::TimeBased(timeseries);
+                }
             }
-            Basis::Tick(tick_count) => {
+            _ => {
+                let rule = new_basis
+                    .bar_rule()
+                    .expect("non-time basis groups trades into bars");
                 let step = self.chart.tick_size;
-                let tick_aggr = TickAggr::new(tick_count, step, &self.raw_trades);
+                let tick_aggr = TickAggr::new(rule, step, &self.raw_trades);
                 self.data_source = PlotData::TickBased(tick_aggr);
             }
         }
 
         self.indicators
-            .values_mut()
-            .filter_map(Option::as_mut)
-            .for_each(|indi| indi.on_basis_change(&self.data_source));
+            .iter_mut()
+            .for_each(|(_, indi)| indi.on_basis_change(&self.data_source));
 
         self.reset_request_handler();
         self.invalidate(Some(Instant::now()))
@@ -591,6 +909,11 @@ impl KlineChart {
     pub fn insert_trades_buffer(&mut self, trades_buffer: &[Trade]) {
         self.raw_trades.extend_from_slice(trades_buffer);
 
+        if self.builds_klines_from_trades() {
+            self.insert_trades_as_klines(trades_buffer);
+            return;
+        }
+
         match self.data_source {
             PlotData::TickBased(ref mut tick_aggr) => {
                 let old_dp_len = tick_aggr.datapoints.len();
@@ -603,35 +926,66 @@ impl KlineChart {
                     self.chart.last_price = None;
                 }
 
-                self.indicators
-                    .values_mut()
-                    .filter_map(Option::as_mut)
-                    .for_each(|indi| {
-                        indi.on_insert_trades(trades_buffer, old_dp_len, &self.data_source)
-                    });
+                self.indicators.iter_mut().for_each(|(_, indi)| {
+                    indi.on_insert_trades(trades_buffer, old_dp_len, &self.data_source)
+                });
 
                 self.invalidate(None);
             }
             PlotData::TimeBased(ref mut timeseries) => {
+                let old_dp_len = timeseries.datapoints.len();
                 timeseries.insert_trades_existing_buckets(trades_buffer);
+
+                self.indicators.iter_mut().for_each(|(_, indi)| {
+                    indi.on_insert_trades(trades_buffer, old_dp_len, &self.data_source)
+                });
             }
         }
     }
 
-    pub fn insert_raw_trades(&mut self, raw_trades: Vec<Trade>, is_batches_done: bool) {
+    pub fn insert_raw_trades(
+        &mut self,
+        req_id: Option<uuid::Uuid>,
+        mut raw_trades: Vec<Trade>,
+        is_batches_done: bool,
+    ) {
+        if self.builds_klines_from_trades()
+            && let Some(end) = self.trade_backfill_end
+        {
+            raw_trades.retain(|trade| trade.time < end);
+        }
+
         match self.data_source {
             PlotData::TickBased(ref mut tick_aggr) => {
                 tick_aggr.insert_trades(&raw_trades);
             }
+            PlotData::TimeBased(_) if self.builds_klines_from_trades() => {
+                self.insert_trades_as_klines(&raw_trades);
+            }
             PlotData::TimeBased(ref mut timeseries) => {
+                let old_dp_len = timeseries.datapoints.len();
                 timeseries.insert_trades_existing_buckets(&raw_trades);
+
+                self.indicators.iter_mut().for_each(|(_, indi)| {
+                    indi.on_insert_trades(&raw_trades, old_dp_len, &self.data_source)
+                });
             }
         }
 
         self.raw_trades.extend(raw_trades);
 
         if is_batches_done {
-            self.fetching_trades = (false, None);
+            if let Some(req_id) = req_id {
+                self.request_handler.mark_completed(req_id);
+            }
+
+            let still_fetching = self
+                .request_handler
+                .coverage(FetchKind::Trades)
+                .is_some_and(|coverage| coverage.has_pending());
+            if !still_fetching {
+                self.fetching_trades = (false, vec![]);
+            }
         }
     }
 
@@ -642,9 +996,8 @@ impl KlineChart {
                 timeseries.insert_trades_existing_buckets(&self.raw_trades);
 
                 self.indicators
-                    .values_mut()
-                    .filter_map(Option::as_mut)
-                    .for_each(|indi| indi.on_insert_klines(klines_raw));
+                    .iter_mut()
+                    .for_each(|(_, indi)| indi.on_insert_klines(klines_raw));
 
                 if klines_raw.is_empty() {
                     self.request_handler
@@ -660,7 +1013,9 @@ impl KlineChart {
 
     pub fn insert_open_interest(&mut self, req_id: Option<uuid::Uuid>, oi_data: &[OIData]) {
         if let Some(req_id) = req_id {
-            if oi_data.is_empty() {
+            // venues without history only have what was recorded, which may be nothing yet
+            let exchange = self.ticker_info().exchange();
+            if oi_data.is_empty() && exchange::adapter::has_oi_history(exchange) {
                 self.request_handler
                     .mark_failed(req_id, "No data received".to_string());
             } else {
@@ -668,9 +1023,9 @@ impl KlineChart {
             }
         }
 
-        if let Some(indi) = self.indicators[KlineIndicator::OpenInterest].as_mut() {
-            indi.on_open_interest(oi_data);
-        }
+        self.indicators
+            .iter_mut()
+            .for_each(|(_, indi)| indi.on_open_interest(oi_data));
     }
 
     fn calc_qty_scales(
@@ -687,24 +1042,28 @@ impl KlineChart {
         let rounded_lowest = lowest.round_to_side_step(true, step).add_steps(-1, step);
 
         match &self.data_source {
-            PlotData::TimeBased(timeseries) => timeseries.max_qty_ts_range(
-                cluster_kind,
-                earliest,
-                latest,
-                rounded_highest,
-                rounded_lowest,
-            ),
-            PlotData::TickBased(tick_aggr) => {
-                let earliest = earliest as usize;
-                let latest = latest as usize;
-
-                tick_aggr.max_qty_idx_range(
+            PlotData::TimeBased(timeseries) => timeseries
+                .max_qty_ts_range(
                     cluster_kind,
                     earliest,
                     latest,
                     rounded_highest,
                     rounded_lowest,
                 )
+                .to_f32(),
+            PlotData::TickBased(tick_aggr) => {
+                let earliest = earliest as usize;
+                let latest = latest as usize;
+
+                tick_aggr
+                    .max_qty_idx_range(
+                        cluster_kind,
+                        earliest,
+                        latest,
+                        rounded_highest,
+                        rounded_lowest,
+                    )
+                    .to_f32()
             }
         }
     }
@@ -787,7 +1146,7 @@ impl KlineChart {
         }
 
         chart.cache.clear_all();
-        for indi in self.indicators.values_mut().filter_map(Option::as_mut) {
+        for (_, indi) in &mut self.indicators {
             indi.clear_all_caches();
         }
 
@@ -799,26 +1158,126 @@ impl KlineChart {
         }
     }
 
+    /// Indicators drawn in a panel below the chart, overlays excluded
+    fn panel_indicator_count(&self) -> usize {
+        self.indicators
+            .iter()
+            .filter(|(config, _)| !config.is_overlay())
+            .count()
+    }
+
     pub fn toggle_indicator(&mut self, indicator: KlineIndicator) {
-        let prev_indi_count = self.indicators.values().filter(|v| v.is_some()).count();
+        let prev_indi_count = self.panel_indicator_count();
 
-        if self.indicators[indicator].is_some() {
-            self.indicators[indicator] = None;
+        if let Some(idx) = self.indicators.iter().position(|(i, _)| *i == indicator) {
+            self.indicators.remove(idx);
         } else {
             let mut box_indi = indicator::kline::make_empty(indicator);
+            box_indi.set_size_unit(
+                self.chart.ticker_info.market_type(),
+                self.visual_config.size_unit,
+            );
+            box_indi.set_anchors(&self.vwap_anchors);
             box_indi.rebuild_from_source(&self.data_source);
-            self.indicators[indicator] = Some(box_indi);
+            self.indicators.push((indicator, box_indi));
         }
 
         if let Some(main_split) = self.chart.layout.splits.first() {
-            let current_indi_count = self.indicators.values().filter(|v| v.is_some()).count();
             self.chart.layout.splits = data::util::calc_panel_splits(
                 *main_split,
-                current_indi_count,
+                self.panel_indicator_count(),
                 Some(prev_indi_count),
             );
         }
     }
+
+    pub fn set_vwap_anchors(&mut self, anchors: &[u64]) {
+        self.vwap_anchors = anchors.to_vec();
+
+        for (_, indi) in &mut self.indicators {
+            indi.set_anchors(anchors);
+        }
+        self.invalidate(None);
+    }
+
+    /// Whether clicking a bar should anchor a VWAP on it
+    fn places_anchors(&self) -> bool {
+        self.indicators.iter().any(|(config, _)| {
+            matches!(
+                config,
+                KlineIndicator::Vwap {
+                    anchor: VwapAnchor::Custom,
+                    ..
+                }
+            )
+        })
+    }
+
+    /// Open time of the bar at `interval`, as published with [`Message::AnchorPlaced`]
+    pub fn bar_time_at(&self, interval: u64) -> Option<u64> {
+        match &self.data_source {
+            PlotData::TimeBased(timeseries) => timeseries
+                .datapoints
+                .contains_key(&interval)
+                .then_some(interval),
+            PlotData::TickBased(tick_aggr) => {
+                let idx = tick_aggr
+                    .datapoints
+                    .len()
+                    .checked_sub(1)?
+                    .checked_sub(interval as usize)?;
+                tick_aggr.datapoints.get(idx).map(|dp| dp.kline.time)
+            }
+        }
+    }
+
+    /// Pressing `A` over the chart arms anchoring, the next click anchors on the bar under the cursor
+    fn anchor_interaction(
+        &self,
+        interaction: &mut Interaction,
+        event: &Event,
+        bounds: Rectangle,
+        cursor: mouse::Cursor,
+    ) -> Option<canvas::Action<Message>> {
+        let cursor_position = cursor.position_in(bounds)?;
+
+        match event {
+            Event::Keyboard(iced::keyboard::Event::KeyPressed { key, .. })
+                if key.as_ref() == iced::keyboard::Key::Character("a") =>
+            {
+                *interaction = Interaction::Anchoring;
+                Some(canvas::Action::request_redraw().and_capture())
+            }
+            Event::Mouse(mouse::Event::ButtonPressed(mouse::Button::Left))
+                if matches!(interaction, Interaction::Anchoring) =>
+            {
+                *interaction = Interaction::None;
+
+                let chart = self.state();
+                let region = chart.visible_region(bounds.size());
+                let interval = match chart.basis {
+                    Basis::Time(_) => {
+                        chart
+                            .snap_x_to_index(cursor_position.x, bounds.size(), region)
+                            .0
+                    }
+                    _ => chart.x_to_interval(region.x + cursor_position.x / chart.scaling),
+                };
+
+                Some(canvas::Action::publish(Message::AnchorPlaced(interval)).and_capture())
+            }
+            _ => None,
+        }
+    }
+
+    /// Applies edited parameters to the instance configured as `old`
+    pub fn configure_indicator(&mut self, old: KlineIndicator, new: KlineIndicator) {
+        if let Some((config, indi)) = self.indicators.iter_mut().find(|(i, _)| *i == old) {
+            *config = new;
+            indi.set_config(new);
+            indi.rebuild_from_source(&self.data_source);
+        }
+    }
 }
 
 impl canvas::Program<Message> for KlineChart {
@@ -831,6 +1290,12 @@ impl canvas::Program<Message> for KlineChart {
         bounds: Rectangle,
         cursor: mouse::Cursor,
     ) -> Option<canvas::Action<Message>> {
+        if self.places_anchors()
+            && let Some(action) = self.anchor_interaction(interaction, event, bounds, cursor)
+        {
+            return Some(action);
+        }
+
         super::canvas_interaction(self, interaction, event, bounds, cursor)
     }
 
@@ -881,6 +1346,12 @@ impl canvas::Program<Message> for KlineChart {
                         *clusters,
                     );
 
+                    let market = chart.ticker_info.market_type();
+                    let size_unit = self.visual_config.size_unit;
+                    let shown_qty = |qty: Qty, price: Price| {
+                        market.qty_in_size_unit(qty, price, size_unit).to_f32()
+                    };
+
                     let cell_height_unscaled = chart.cell_height * chart.scaling;
                     let cell_width_unscaled = chart.cell_width * chart.scaling;
 
@@ -952,14 +1423,14 @@ impl canvas::Program<Message> for KlineChart {
                                 candle_width,
                                 cluster_scaling,
                                 palette,
-                                text_size,
+                                show_text.then_some(text_size),
                                 self.tick_size(),
-                                show_text,
                                 imbalance,
                                 kline,
                                 trades,
                                 *clusters,
                                 content_spacing,
+                                shown_qty,
                             );
                         },
                     );
@@ -987,6 +1458,11 @@ impl canvas::Program<Message> for KlineChart {
                 }
             }
 
+            self.indicators
+                .iter()
+                .filter(|(config, _)| config.is_overlay())
+                .for_each(|(_, indi)| indi.draw_overlay(frame, chart, earliest..=latest, palette));
+
             chart.draw_last_price_line(frame, palette, region);
         });
 
@@ -1017,7 +1493,8 @@ impl canvas::Program<Message> for KlineChart {
         match interaction {
             Interaction::Panning { .. } => mouse::Interaction::Grabbing,
             Interaction::Zoomin { .. } => mouse::Interaction::ZoomIn,
-            Interaction::None | Interaction::Ruler { .. } => {
+            Interaction::Anchoring if cursor.is_over(bounds) => mouse::Interaction::Pointer,
+            Interaction::None | Interaction::Ruler { .. } | Interaction::Anchoring => {
                 if cursor.is_over(bounds) {
                     mouse::Interaction::Crosshair
                 } else {
@@ -1313,17 +1790,17 @@ fn effective_cluster_qty(
         ClusterKind::BidAsk => footprint
             .trades
             .values()
-            .map(|group| group.buy_qty.max(group.sell_qty))
+            .map(|group| group.buy_qty.max(group.sell_qty).to_f32())
             .fold(0.0_f32, f32::max),
         ClusterKind::DeltaProfile => footprint
             .trades
             .values()
-            .map(|group| (group.buy_qty - group.sell_qty).abs())
+            .map(|group| group.delta_qty().abs().to_f32())
             .fold(0.0_f32, f32::max),
         ClusterKind::VolumeProfile => footprint
             .trades
             .values()
-            .map(|group| group.buy_qty + group.sell_qty)
+            .map(|group| group.total_qty().to_f32())
             .fold(0.0_f32, f32::max),
     };
 
@@ -1348,14 +1825,14 @@ fn draw_clusters(
     candle_width: f32,
     max_cluster_qty: f32,
     palette: &Extended,
-    text_size: f32,
+    text_size: Option<f32>,
     tick_size: f32,
-    show_text: bool,
     imbalance: Option<(usize, Option<usize>, bool)>,
     kline: &Kline,
     footprint: &KlineTrades,
     cluster_kind: ClusterKind,
     spacing: ContentGaps,
+    shown_qty: impl Fn(Qty, Price) -> f32,
 ) {
     let text_color = palette.background.weakest.text;
 
@@ -1375,7 +1852,7 @@ fn draw_clusters(
                 spacing,
                 imbalance.is_some(),
             );
-            let bar_alpha = if show_text { 0.25 } else { 1.0 };
+            let bar_alpha = if text_size.is_some() { 0.25 } else { 1.0 };
 
             for (price, group) in &footprint.trades {
                 let y = price_to_y(*price);
@@ -1386,8 +1863,8 @@ fn draw_clusters(
                             frame,
                             area.bars_left,
                             y,
-                            group.buy_qty,
-                            group.sell_qty,
+                            group.buy_qty.to_f32(),
+                            group.sell_qty.to_f32(),
                             max_cluster_qty,
                             area.bars_width,
                             cell_height,
@@ -1397,10 +1874,10 @@ fn draw_clusters(
                             true,
                         );
 
-                        if show_text {
+                        if let Some(text_size) = text_size {
                             draw_cluster_text(
                                 frame,
-                                &abbr_large_numbers(group.total_qty()),
+                                &abbr_large_numbers(shown_qty(group.total_qty(), *price)),
                                 Point::new(area.bars_left, y),
                                 text_size,
                                 text_color,
@@ -1410,11 +1887,11 @@ fn draw_clusters(
                         }
                     }
                     ClusterKind::DeltaProfile => {
-                        let delta = group.delta_qty();
-                        if show_text {
+                        let delta = group.delta_qty().to_f32();
+                        if let Some(text_size) = text_size {
                             draw_cluster_text(
                                 frame,
-                                &abbr_large_numbers(delta),
+                                &abbr_large_numbers(shown_qty(group.delta_qty(), *price)),
                                 Point::new(area.bars_left, y),
                                 text_size,
                                 text_color,
@@ -1442,8 +1919,7 @@ fn draw_clusters(
 
                 if let Some((threshold, color_scale, ignore_zeros)) = imbalance {
                     let step = PriceStep::from_f32(tick_size);
-                    let higher_price =
-                        Price::from_f32(price.to_f32() + tick_size).round_to_step(step);
+                    let higher_price = price.add_steps(1, step);
 
                     let rect_w = ((area.imb_marker_width - 1.0) / 2.0).max(1.0);
                     let buyside_x = area.imb_marker_left + area.imb_marker_width - rect_w;
@@ -1455,7 +1931,7 @@ fn draw_clusters(
                         &price_to_y,
                         footprint,
                         *price,
-                        group.sell_qty,
+                        group.sell_qty.to_f32(),
                         higher_price,
                         threshold,
                         color_scale,
@@ -1487,7 +1963,7 @@ fn draw_clusters(
                 spacing,
             );
 
-            let bar_alpha = if show_text { 0.25 } else { 1.0 };
+            let bar_alpha = if text_size.is_some() { 0.25 } else { 1.0 };
 
             let imb_marker_reserve = if imbalance.is_some() {
                 ((area.imb_marker_width - 1.0) / 2.0).max(1.0)
@@ -1506,11 +1982,11 @@ fn draw_clusters(
             for (price, group) in &footprint.trades {
                 let y = price_to_y(*price);
 
-                if group.buy_qty > 0.0 && right_area_width > 0.0 {
-                    if show_text {
+                if !group.buy_qty.is_zero() && right_area_width > 0.0 {
+                    if let Some(text_size) = text_size {
                         draw_cluster_text(
                             frame,
-                            &abbr_large_numbers(group.buy_qty),
+                            &abbr_large_numbers(shown_qty(group.buy_qty, *price)),
                             Point::new(area.bid_area_left, y),
                             text_size,
                             text_color,
@@ -1519,7 +1995,7 @@ fn draw_clusters(
                         );
                     }
 
-                    let bar_width = (group.buy_qty / max_cluster_qty) * right_area_width;
+                    let bar_width = (group.buy_qty.to_f32() / max_cluster_qty) * right_area_width;
                     if bar_width > 0.0 {
                         frame.fill_rectangle(
                             Point::new(area.bid_area_left, y - (cell_height / 2.0)),
@@ -1528,11 +2004,11 @@ fn draw_clusters(
                         );
                     }
                 }
-                if group.sell_qty > 0.0 && left_area_width > 0.0 {
-                    if show_text {
+                if !group.sell_qty.is_zero() && left_area_width > 0.0 {
+                    if let Some(text_size) = text_size {
                         draw_cluster_text(
                             frame,
-                            &abbr_large_numbers(group.sell_qty),
+                            &abbr_large_numbers(shown_qty(group.sell_qty, *price)),
                             Point::new(area.ask_area_right, y),
                             text_size,
                             text_color,
@@ -1541,7 +2017,7 @@ fn draw_clusters(
                         );
                     }
 
-                    let bar_width = (group.sell_qty / max_cluster_qty) * left_area_width;
+                    let bar_width = (group.sell_qty.to_f32() / max_cluster_qty) * left_area_width;
                     if bar_width > 0.0 {
                         frame.fill_rectangle(
                             Point::new(area.ask_area_right, y - (cell_height / 2.0)),
@@ -1555,8 +2031,7 @@ fn draw_clusters(
                     && area.imb_marker_width > 0.0
                 {
                     let step = PriceStep::from_f32(tick_size);
-                    let higher_price =
-                        Price::from_f32(price.to_f32() + tick_size).round_to_step(step);
+                    let higher_price = price.add_steps(1, step);
 
                     let rect_width = ((area.imb_marker_width - 1.0) / 2.0).max(1.0);
 
@@ -1568,7 +2043,7 @@ fn draw_clusters(
                         &price_to_y,
                         footprint,
                         *price,
-                        group.sell_qty,
+                        group.sell_qty.to_f32(),
                         higher_price,
                         threshold,
                         color_scale,
@@ -1615,7 +2090,7 @@ fn draw_imbalance_markers(
     }
 
     if let Some(group) = footprint.trades.get(&higher_price) {
-        let diagonal_buy_qty = group.buy_qty;
+        let diagonal_buy_qty = group.buy_qty.to_f32();
 
         if ignore_zeros && diagonal_buy_qty <= 0.0 {
             return;
@@ -1731,7 +2206,7 @@ fn draw_crosshair_tooltip(
                 }
             }),
         PlotData::TickBased(tick_aggr) => {
-            let index = (at_interval / u64::from(tick_aggr.interval.0)) as usize;
+            let index = (at_interval / tick_aggr.interval.index_units_per_bar()) as usize;
             if index < tick_aggr.datapoints.len() {
                 Some(&tick_aggr.datapoints[tick_aggr.datapoints.len() - 1 - index].kline)
             } else {
@@ -1877,3 +2352,8 @@ impl BidAskArea {
 fn should_show_text(cell_height_unscaled: f32, cell_width_unscaled: f32, min_w: f32) -> bool {
     cell_height_unscaled > 8.0 && cell_width_unscaled > min_w
 }
+
+/// Whether klines of `timeframe` have to be built from trades, no venue kline stream exists for it
+fn klines_from_trades(ticker_info: &TickerInfo, timeframe: Timeframe) -> bool {
+    ticker_info.exchange().kline_source(timeframe).is_none()
+}
@@ -247,7 +247,43 @@ impl AxisLabelsX<'_> {
         palette: &Extended,
     ) -> Option<AxisLabel> {
         match self.basis {
-            Basis::Tick(interval) => {
+            Basis::Time(timeframe) => {
+                let (_, crosshair_ratio, _) = self.calc_crosshair_pos(cursor_pos, region);
+
+                let x_min = self.x_to_interval(region.x);
+                let x_max = self.x_to_interval(region.x + region.width);
+
+                let crosshair_millis =
+                    x_min as f64 + f64::from(crosshair_ratio) * (x_max as f64 - x_min as f64);
+
+                let interval = timeframe.to_milliseconds();
+
+                let crosshair_time =
+                    chrono::DateTime::from_timestamp_millis(crosshair_millis as i64)?;
+                let rounded_timestamp =
+                    timeframe.bucket_start(crosshair_time.timestamp_millis() as u64 + interval / 2);
+
+                let snap_ratio =
+                    (rounded_timestamp as f64 - x_min as f64) / (x_max as f64 - x_min as f64);
+
+                let snap_x = snap_ratio * f64::from(bounds.width);
+                if snap_x.is_nan() || snap_x < 0.0 || snap_x > f64::from(bounds.width) {
+                    return None;
+                }
+
+                let text_content = self
+                    .timezone
+                    .format_crosshair_timestamp(rounded_timestamp as i64, interval);
+
+                return Some(AxisLabel::new_x(
+                    snap_x as f32,
+                    text_content,
+                    bounds,
+                    true,
+                    palette,
+                ));
+            }
+            basis => {
                 let Some(interval_keys) = &self.interval_keys else {
                     return None;
                 };
@@ -276,7 +312,7 @@ impl AxisLabelsX<'_> {
                 if let Some(timestamp) = interval_keys.get(array_index) {
                     let text_content = self
                         .timezone
-                        .format_crosshair_timestamp(*timestamp as i64, interval.0.into());
+                        .format_crosshair_timestamp(*timestamp as i64, basis.index_units_per_bar());
 
                     return Some(AxisLabel::new_x(
                         snap_x,
@@ -287,43 +323,6 @@ impl AxisLabelsX<'_> {
                     ));
                 }
             }
-            Basis::Time(timeframe) => {
-                let (_, crosshair_ratio, _) = self.calc_crosshair_pos(cursor_pos, region);
-
-                let x_min = self.x_to_interval(region.x);
-                let x_max = self.x_to_interval(region.x + region.width);
-
-                let crosshair_millis =
-                    x_min as f64 + f64::from(crosshair_ratio) * (x_max as f64 - x_min as f64);
-
-                let interval = timeframe.to_milliseconds();
-
-                let crosshair_time =
-                    chrono::DateTime::from_timestamp_millis(crosshair_millis as i64)?;
-                let rounded_timestamp =
-                    (crosshair_time.timestamp_millis() as f64 / (interval as f64)).round() as u64
-                        * interval;
-
-                let snap_ratio =
-                    (rounded_timestamp as f64 - x_min as f64) / (x_max as f64 - x_min as f64);
-
-                let snap_x = snap_ratio * f64::from(bounds.width);
-                if snap_x.is_nan() || snap_x < 0.0 || snap_x > f64::from(bounds.width) {
-                    return None;
-                }
-
-                let text_content = self
-                    .timezone
-                    .format_crosshair_timestamp(rounded_timestamp as i64, interval);
-
-                return Some(AxisLabel::new_x(
-                    snap_x as f32,
-                    text_content,
-                    bounds,
-                    true,
-                    palette,
-                ));
-            }
         }
 
         None
@@ -354,7 +353,7 @@ impl AxisLabelsX<'_> {
                     self.max.saturating_add(diff)
                 }
             }
-            Basis::Tick(_) => {
+            _ => {
                 let tick = -(x / self.cell_width);
                 tick.round() as u64
             }
@@ -454,7 +453,23 @@ impl canvas::Program<Message> for AxisLabelsX<'_> {
             let mut labels: Vec<AxisLabel> = Vec::with_capacity(label_count + 1); // +1 for crosshair
 
             match self.basis {
-                Basis::Tick(_) => {
+                Basis::Time(timeframe) => {
+                    let x_min_region = self.x_to_interval(region.x);
+                    let x_max_region = self.x_to_interval(region.x + region.width);
+
+                    let generated_labels = timeseries::generate_time_labels(
+                        timeframe,
+                        self.timezone,
+                        bounds,
+                        x_min_region,
+                        x_max_region,
+                        label_count as i32,
+                        palette,
+                    );
+
+                    labels.extend(generated_labels);
+                }
+                _ => {
                     if let Some(interval_keys) = &self.interval_keys {
                         let last_idx = interval_keys.len() - 1;
                         let mut last_x: Option<f32> = None;
@@ -484,22 +499,6 @@ impl canvas::Program<Message> for AxisLabelsX<'_> {
                         }
                     }
                 }
-                Basis::Time(timeframe) => {
-                    let x_min_region = self.x_to_interval(region.x);
-                    let x_max_region = self.x_to_interval(region.x + region.width);
-
-                    let generated_labels = timeseries::generate_time_labels(
-                        timeframe,
-                        self.timezone,
-                        bounds,
-                        x_min_region,
-                        x_max_region,
-                        label_count as i32,
-                        palette,
-                    );
-
-                    labels.extend(generated_labels);
-                }
             }
 
             if let Some(cursor_pos) = cursor.position_in(self.chart_bounds)
@@ -658,10 +657,8 @@ impl canvas::Program<Message> for AxisLabelsY<'_> {
             if let Some(label) = self.last_price {
                 let candle_close_label = match self.basis {
                     Basis::Time(timeframe) => {
-                        let interval = timeframe.to_milliseconds();
-
                         let current_time = chrono::Utc::now().timestamp_millis() as u64;
-                        let next_kline_open = (current_time / interval + 1) * interval;
+                        let next_kline_open = timeframe.next_bucket_start(current_time);
 
                         let remaining_seconds = (next_kline_open - current_time) / 1000;
 
@@ -690,7 +687,7 @@ impl canvas::Program<Message> for AxisLabelsY<'_> {
                             None
                         }
                     }
-                    Basis::Tick(_) => None,
+                    _ => None,
                 };
 
                 let (price, color) = label.get_with_color(palette);
@@ -147,18 +147,35 @@ pub fn generate_time_labels(
             return vec![];
         };
 
-        daily_labels_gen(
-            timezone,
-            &mut labels,
-            axis_bounds,
-            x_min,
-            x_max,
-            start_utc_dt,
-            end_utc_dt,
-            calc_x_pos,
-            is_drawable,
-            palette,
-        );
+        // bars of weekly and monthly timeframes don't open on every day
+        if timeframe.is_weekly() {
+            weekly_labels_gen(
+                timezone,
+                &mut labels,
+                axis_bounds,
+                x_min,
+                x_max,
+                x_labels_can_fit,
+                timeframe,
+                end_utc_dt,
+                calc_x_pos,
+                is_drawable,
+                palette,
+            );
+        } else if timeframe != exchange::Timeframe::MN1 {
+            daily_labels_gen(
+                timezone,
+                &mut labels,
+                axis_bounds,
+                x_min,
+                x_max,
+                start_utc_dt,
+                end_utc_dt,
+                calc_x_pos,
+                is_drawable,
+                palette,
+            );
+        }
 
         monthly_labels_gen(
             timezone,
@@ -286,6 +303,45 @@ fn daily_labels_gen(
     );
 }
 
+fn weekly_labels_gen(
+    timezone: UserTimezone,
+    all_labels: &mut Vec<AxisLabel>,
+    axis_bounds: Rectangle,
+    x_min: u64,
+    x_max: u64,
+    labels_can_fit: i32,
+    timeframe: exchange::Timeframe,
+    end_utc_dt: DateTime<chrono::Utc>,
+    calc_x_pos: impl Fn(u64, u64, u64, f32) -> f64,
+    is_drawable: impl Fn(f64, f32) -> bool,
+    palette: &Extended,
+) {
+    // first bar open at or before the visible range, Mondays for weekly buckets
+    let Some(current) = DateTime::from_timestamp_millis(timeframe.bucket_start(x_min) as i64)
+    else {
+        return;
+    };
+
+    let interval = timeframe.to_milliseconds();
+    let bars_per_label = ((x_max - x_min) / interval / labels_can_fit.max(1) as u64).max(1);
+    let step = chrono::Duration::milliseconds((bars_per_label * interval) as i64);
+
+    above_daily_labels_gen(
+        current,
+        &end_utc_dt,
+        x_min,
+        x_max,
+        axis_bounds,
+        all_labels,
+        &calc_x_pos,
+        &is_drawable,
+        move |dt| dt.checked_add_signed(step),
+        with_user_timezone(timezone, |dt| dt.format("%d").to_string()),
+        |_dt| false,
+        palette,
+    );
+}
+
 fn monthly_labels_gen(
     timezone: UserTimezone,
     all_labels: &mut Vec<AxisLabel>,
@@ -29,7 +29,6 @@ pub struct SavedState {
     pub theme: data::Theme,
     pub custom_theme: Option<data::Theme>,
     pub audio_cfg: data::AudioStream,
-    pub volume_size_unit: exchange::SizeUnit,
 }
 
 impl SavedState {
@@ -57,7 +56,6 @@ impl Default for SavedState {
             theme: data::Theme::default(),
             custom_theme: None,
             audio_cfg: data::AudioStream::default(),
-            volume_size_unit: exchange::SizeUnit::Base,
         }
     }
 }
@@ -328,7 +326,6 @@ pub fn load_saved_state() -> SavedState {
             };
 
             exchange::fetcher::toggle_trade_fetch(state.trade_fetch_enabled);
-            exchange::set_preferred_currency(state.size_in_quote_ccy);
 
             SavedState {
                 theme: state.selected_theme,
@@ -339,7 +336,6 @@ pub fn load_saved_state() -> SavedState {
                 sidebar: state.sidebar,
                 scale_factor: state.scale_factor,
                 audio_cfg: state.audio_cfg,
-                volume_size_unit: state.size_in_quote_ccy,
             }
         }
         Err(e) => {
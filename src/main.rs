@@ -13,7 +13,7 @@ mod window;
 use data::config::theme::default_theme;
 use data::{layout::WindowSpec, sidebar};
 use layout::{LayoutId, configuration};
-use modal::{LayoutManager, ThemeEditor, audio::AudioStream};
+use modal::{ImportDialog, LayoutManager, ThemeEditor, audio::AudioStream};
 use modal::{dashboard_modal, main_dialog_modal};
 use screen::dashboard::{self, Dashboard};
 use widget::{
@@ -59,12 +59,13 @@ struct Flowsurface {
     layout_manager: LayoutManager,
     theme_editor: ThemeEditor,
     audio_stream: AudioStream,
+    import_dialog: ImportDialog,
     confirm_dialog: Option<screen::ConfirmDialog<Message>>,
-    volume_size_unit: exchange::SizeUnit,
     ui_scale_factor: data::ScaleFactor,
     timezone: data::UserTimezone,
     theme: data::Theme,
     notifications: Vec<Toast>,
+    market_data_cache_size: Option<u64>,
 }
 
 #[derive(Debug, Clone)]
@@ -79,19 +80,20 @@ enum Message {
     Tick(std::time::Instant),
     WindowEvent(window::Event),
     ExitRequested(HashMap<window::Id, WindowSpec>),
-    RestartRequested(HashMap<window::Id, WindowSpec>),
     GoBack,
     DataFolderRequested,
+    MarketDataCacheSize(u64),
+    PruneMarketData,
     ThemeSelected(data::Theme),
     ScaleFactorChanged(data::ScaleFactor),
     SetTimezone(data::UserTimezone),
     ToggleTradeFetch(bool),
-    ApplyVolumeSizeUnit(exchange::SizeUnit),
     RemoveNotification(usize),
     ToggleDialogModal(Option<screen::ConfirmDialog<Message>>),
     ThemeEditor(modal::theme_editor::Message),
     Layouts(modal::layout_manager::Message),
     AudioStream(modal::audio::Message),
+    Import(modal::import::Message),
 }
 
 impl Flowsurface {
@@ -109,6 +111,8 @@ impl Flowsurface {
             window::open(config)
         };
 
+        exchange::adapter::local::set_storage_dir(data::data_path(None).join("imports"));
+
         let (sidebar, launch_sidebar) = dashboard::Sidebar::new(&saved_state);
 
         let (audio_stream, audio_init_err) = AudioStream::new(saved_state.audio_cfg);
@@ -118,13 +122,14 @@ impl Flowsurface {
             layout_manager: saved_state.layout_manager,
             theme_editor: ThemeEditor::new(saved_state.custom_theme),
             audio_stream,
+            import_dialog: ImportDialog::new(),
             sidebar,
             confirm_dialog: None,
             timezone: saved_state.timezone,
             ui_scale_factor: saved_state.scale_factor,
-            volume_size_unit: saved_state.volume_size_unit,
             theme: saved_state.theme,
             notifications: vec![],
+            market_data_cache_size: None,
         };
 
         if let Some(err) = audio_init_err {
@@ -236,10 +241,6 @@ impl Flowsurface {
                 self.save_state_to_disk(&windows);
                 return iced::exit();
             }
-            Message::RestartRequested(windows) => {
-                self.save_state_to_disk(&windows);
-                return self.restart();
-            }
             Message::GoBack => {
                 let main_window = self.main_window.id;
 
@@ -342,6 +343,16 @@ impl Flowsurface {
                                 }
                             }
                         }
+                        Some(dashboard::Event::SwitchTickersInGroup(ticker_info)) => dashboard
+                            .switch_tickers_in_group(
+                                main_window.id,
+                                ticker_info,
+                                self.sidebar.tickers_info(),
+                            )
+                            .map(move |msg| Message::Dashboard {
+                                layout_id: Some(layout_id),
+                                event: msg,
+                            }),
                         None => Task::none(),
                     };
 
@@ -468,12 +479,68 @@ impl Flowsurface {
                     }
                 }
             }
+            Message::Import(message) => {
+                let (task, action) = self.import_dialog.update(message);
+                let task = task.map(Message::Import);
+
+                match action {
+                    Some(modal::import::Action::DatasetsChanged(imported)) => {
+                        if let Some(ticker_info) = imported {
+                            self.notifications
+                                .push(Toast::new(toast::Notification::Info(format!(
+                                    "Imported {}",
+                                    ticker_info.ticker
+                                ))));
+                        }
+
+                        let exchange = exchange::adapter::Exchange::Local;
+                        // listing local datasets can't fail, it only reads the in-memory registry
+                        let refresh_table = Task::perform(
+                            async move {
+                                exchange::adapter::fetch_ticker_info(exchange)
+                                    .await
+                                    .unwrap_or_default()
+                            },
+                            move |info| {
+                                Message::Sidebar(dashboard::sidebar::Message::TickersTable(
+                                    dashboard::tickers_table::Message::UpdateTickersInfo(
+                                        exchange, info,
+                                    ),
+                                ))
+                            },
+                        );
+
+                        return task.chain(refresh_table);
+                    }
+                    Some(modal::import::Action::Error(err)) => {
+                        self.notifications.push(Toast::error(err));
+                    }
+                    None => {}
+                }
+
+                return task;
+            }
             Message::DataFolderRequested => {
                 if let Err(err) = data::open_data_folder() {
                     self.notifications
                         .push(Toast::error(format!("Failed to open data folder: {err}")));
                 }
             }
+            Message::MarketDataCacheSize(size) => {
+                self.market_data_cache_size = Some(size);
+            }
+            Message::PruneMarketData => {
+                self.confirm_dialog = None;
+                self.market_data_cache_size = None;
+
+                return Task::perform(
+                    async {
+                        data::prune_market_data();
+                        data::market_data_cache_size()
+                    },
+                    Message::MarketDataCacheSize,
+                );
+            }
             Message::ThemeEditor(msg) => {
                 let action = self.theme_editor.update(msg, &self.theme.clone().into());
 
@@ -493,7 +560,26 @@ impl Flowsurface {
                 }
             }
             Message::Sidebar(message) => {
+                let fetch_cache_size = matches!(
+                    message,
+                    dashboard::sidebar::Message::ToggleSidebarMenu(Some(sidebar::Menu::Settings))
+                );
+                if matches!(
+                    message,
+                    dashboard::sidebar::Message::ToggleSidebarMenu(Some(sidebar::Menu::Import))
+                ) {
+                    self.import_dialog.refresh();
+                }
+
                 let (task, action) = self.sidebar.update(message);
+                let task = if fetch_cache_size {
+                    task.map(Message::Sidebar).chain(Task::perform(
+                        async { data::market_data_cache_size() },
+                        Message::MarketDataCacheSize,
+                    ))
+                } else {
+                    task.map(Message::Sidebar)
+                };
 
                 match action {
                     Some(dashboard::sidebar::Action::TickerSelected(ticker_info, content)) => {
@@ -507,8 +593,23 @@ impl Flowsurface {
                                     kind,
                                 )
                             } else {
-                                self.active_dashboard_mut()
-                                    .switch_tickers_in_group(main_window_id, ticker_info)
+                                // borrows the dashboard and the sidebar's tickers side by side
+                                let active_layout = self
+                                    .layout_manager
+                                    .active_layout_id()
+                                    .expect("No active layout")
+                                    .unique;
+                                let dashboard = self
+                                    .layout_manager
+                                    .get_mut(active_layout)
+                                    .map(|layout| &mut layout.dashboard)
+                                    .expect("No active dashboard");
+
+                                dashboard.switch_tickers_in_group(
+                                    main_window_id,
+                                    ticker_info,
+                                    self.sidebar.tickers_info(),
+                                )
                             }
                         };
 
@@ -523,17 +624,7 @@ impl Flowsurface {
                     None => {}
                 }
 
-                return task.map(Message::Sidebar);
-            }
-            Message::ApplyVolumeSizeUnit(pref) => {
-                self.volume_size_unit = pref;
-                self.confirm_dialog = None;
-
-                let mut active_windows: Vec<window::Id> =
-                    self.active_dashboard().popout.keys().copied().collect();
-                active_windows.push(self.main_window.id);
-
-                return window::collect_window_specs(active_windows, Message::RestartRequested);
+                return task;
             }
         }
         Task::none()
@@ -744,40 +835,6 @@ impl Flowsurface {
                         Message::SetTimezone,
                     );
 
-                    let size_in_quote_currency_checkbox = {
-                        let is_active = match self.volume_size_unit {
-                            exchange::SizeUnit::Quote => true,
-                            exchange::SizeUnit::Base => false,
-                        };
-
-                        let checkbox = iced::widget::checkbox(is_active)
-                            .label("Size in quote currency")
-                            .on_toggle(|checked| {
-                                let on_dialog_confirm = Message::ApplyVolumeSizeUnit(if checked {
-                                    exchange::SizeUnit::Quote
-                                } else {
-                                    exchange::SizeUnit::Base
-                                });
-
-                                let confirm_dialog = screen::ConfirmDialog::new(
-                                    "Changing size display currency requires application restart"
-                                        .to_string(),
-                                    Box::new(on_dialog_confirm.clone()),
-                                )
-                                .with_confirm_btn_text("Restart now".to_string());
-
-                                Message::ToggleDialogModal(Some(confirm_dialog))
-                            });
-
-                        tooltip(
-                            checkbox,
-                            Some(
-                                "Display sizes/volumes in quote currency (USD)\nHas no effect on inverse perps or open interest",
-                            ),
-                            TooltipPosition::Top,
-                        )
-                    };
-
                     let sidebar_pos = pick_list(
                         [sidebar::Position::Left, sidebar::Position::Right],
                         Some(sidebar_pos),
@@ -852,11 +909,42 @@ impl Flowsurface {
                         )
                     };
 
+                    let market_data_cache = {
+                        let size_text = match self.market_data_cache_size {
+                            Some(size) => data::util::format_bytes(size),
+                            None => "...".to_string(),
+                        };
+
+                        let prune_btn = button(text("Prune")).on_press_maybe(
+                            self.market_data_cache_size
+                                .filter(|&size| size > 0)
+                                .map(|_| {
+                                    let confirm_dialog = screen::ConfirmDialog::new(
+                                        "Delete all downloaded trade archives?".to_string(),
+                                        Box::new(Message::PruneMarketData),
+                                    );
+                                    Message::ToggleDialogModal(Some(confirm_dialog))
+                                }),
+                        );
+
+                        tooltip(
+                            row![text(format!("Cache: {size_text}")), prune_btn]
+                                .align_y(Alignment::Center)
+                                .spacing(8),
+                            Some("Downloaded trade archives stored in the data folder"),
+                            TooltipPosition::Top,
+                        )
+                    };
+
                     let column_content = split_column![
                         column![open_data_folder,].spacing(8),
                         column![text("Sidebar position").size(14), sidebar_pos,].spacing(12),
                         column![text("Time zone").size(14), timezone_picklist,].spacing(12),
-                        column![text("Market data").size(14), size_in_quote_currency_checkbox,].spacing(12),
+                        column![
+                            text("Market data").size(14),
+                            market_data_cache,
+                        ]
+                        .spacing(12),
                         column![text("Theme").size(14), theme_picklist,].spacing(12),
                         column![text("Interface scale").size(14), scale_factor,].spacing(12),
                         column![
@@ -1038,6 +1126,21 @@ impl Flowsurface {
                     align_x,
                 )
             }
+            sidebar::Menu::Import => {
+                let (align_x, padding) = match sidebar_pos {
+                    sidebar::Position::Left => (Alignment::Start, padding::left(44).top(108)),
+                    sidebar::Position::Right => (Alignment::End, padding::right(44).top(108)),
+                };
+
+                dashboard_modal(
+                    base,
+                    self.import_dialog.view().map(Message::Import),
+                    Message::Sidebar(dashboard::sidebar::Message::ToggleSidebarMenu(None)),
+                    padding,
+                    Alignment::Start,
+                    align_x,
+                )
+            }
             sidebar::Menu::ThemeEditor => {
                 let (align_x, padding) = match sidebar_pos {
                     sidebar::Position::Left => (Alignment::Start, padding::left(44).bottom(4)),
@@ -1106,7 +1209,6 @@ impl Flowsurface {
             self.sidebar.state.clone(),
             self.ui_scale_factor,
             audio_cfg,
-            self.volume_size_unit,
         );
 
         match serde_json::to_string(&state) {
@@ -1121,22 +1223,4 @@ impl Flowsurface {
             Err(e) => log::error!("Failed to serialize layout: {}", e),
         }
     }
-
-    fn restart(&mut self) -> Task<Message> {
-        let mut windows_to_close: Vec<window::Id> =
-            self.active_dashboard().popout.keys().copied().collect();
-        windows_to_close.push(self.main_window.id);
-
-        let close_windows = Task::batch(
-            windows_to_close
-                .into_iter()
-                .map(window::close)
-                .collect::<Vec<_>>(),
-        );
-
-        let (new_state, init_task) = Flowsurface::new();
-        *self = new_state;
-
-        close_windows.chain(init_task)
-    }
 }
@@ -0,0 +1,369 @@
+use crate::style::{self, Icon, icon_text};
+
+use exchange::{
+    Ticker, TickerInfo,
+    adapter::local::{self, CsvMapping, DataKind, Field, Imported, Preview},
+};
+use iced::widget::{
+    button, checkbox, column, container, pick_list, row, scrollable, space, text, text_input,
+};
+use iced::{Alignment, Element, Length, Task};
+use std::path::PathBuf;
+
+const PREVIEW_CELL_WIDTH: f32 = 72.0;
+
+#[derive(Debug, Clone)]
+pub enum Message {
+    PathChanged(String),
+    LoadPreview,
+    PreviewLoaded(Result<Preview, String>),
+    KindSelected(DataKind),
+    ColumnSelected(Field, Column),
+    HeaderToggled(bool),
+    SymbolChanged(String),
+    Import,
+    Imported(Result<TickerInfo, String>),
+    Remove(Ticker),
+    Removed(Result<(), String>),
+}
+
+pub enum Action {
+    /// Imported datasets changed, `Some` when a new one was just added
+    DatasetsChanged(Option<TickerInfo>),
+    Error(String),
+}
+
+/// A column choice in the mapping pick lists, `None` leaves the field unmapped
+#[derive(Debug, Clone, PartialEq)]
+pub struct Column {
+    index: Option<usize>,
+    label: String,
+}
+
+impl std::fmt::Display for Column {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.label)
+    }
+}
+
+pub struct ImportDialog {
+    path: String,
+    symbol: String,
+    preview: Option<Preview>,
+    is_busy: bool,
+    error: Option<String>,
+    imported: Vec<Imported>,
+}
+
+impl ImportDialog {
+    pub fn new() -> Self {
+        Self {
+            path: String::new(),
+            symbol: String::new(),
+            preview: None,
+            is_busy: false,
+            error: None,
+            imported: vec![],
+        }
+    }
+
+    /// Re-reads the imported datasets, e.g. when the dialog gets opened
+    pub fn refresh(&mut self) {
+        self.imported = local::imported();
+    }
+
+    pub fn update(&mut self, message: Message) -> (Task<Message>, Option<Action>) {
+        match message {
+            Message::PathChanged(path) => {
+                self.path = path;
+            }
+            Message::LoadPreview => {
+                let path = PathBuf::from(self.path.trim());
+                if self.symbol.is_empty() {
+                    self.symbol = suggest_symbol(&path);
+                }
+
+                self.is_busy = true;
+                self.error = None;
+
+                return (
+                    Task::perform(
+                        async move { local::preview(&path).map_err(|e| e.to_string()) },
+                        Message::PreviewLoaded,
+                    ),
+                    None,
+                );
+            }
+            Message::PreviewLoaded(result) => {
+                self.is_busy = false;
+                match result {
+                    Ok(preview) => self.preview = Some(preview),
+                    Err(err) => {
+                        self.preview = None;
+                        self.error = Some(err);
+                    }
+                }
+            }
+            Message::KindSelected(kind) => {
+                if let Some(preview) = &mut self.preview {
+                    preview.mapping.kind = kind;
+                }
+            }
+            Message::ColumnSelected(field, column) => {
+                if let Some(preview) = &mut self.preview {
+                    preview.mapping.columns[field] = column.index;
+                }
+            }
+            Message::HeaderToggled(has_header) => {
+                if let Some(preview) = &mut self.preview {
+                    preview.mapping.has_header = has_header;
+                }
+            }
+            Message::SymbolChanged(symbol) => {
+                self.symbol = symbol.to_uppercase();
+            }
+            Message::Import => {
+                let Some(preview) = &self.preview else {
+                    return (Task::none(), None);
+                };
+
+                let path = PathBuf::from(self.path.trim());
+                let symbol = self.symbol.clone();
+                let mapping: CsvMapping = preview.mapping.clone();
+
+                self.is_busy = true;
+                self.error = None;
+
+                return (
+                    Task::perform(
+                        async move {
+                            local::import(&path, &symbol, &mapping).map_err(|e| e.to_string())
+                        },
+                        Message::Imported,
+                    ),
+                    None,
+                );
+            }
+            Message::Imported(result) => {
+                self.is_busy = false;
+                match result {
+                    Ok(ticker_info) => {
+                        self.path.clear();
+                        self.symbol.clear();
+                        self.preview = None;
+                        self.refresh();
+
+                        return (
+                            Task::none(),
+                            Some(Action::DatasetsChanged(Some(ticker_info))),
+                        );
+                    }
+                    Err(err) => self.error = Some(err),
+                }
+            }
+            Message::Remove(ticker) => {
+                return (
+                    Task::perform(
+                        async move { local::remove(&ticker).map_err(|e| e.to_string()) },
+                        Message::Removed,
+                    ),
+                    None,
+                );
+            }
+            Message::Removed(result) => {
+                self.refresh();
+
+                return match result {
+                    Ok(()) => (Task::none(), Some(Action::DatasetsChanged(None))),
+                    Err(err) => (Task::none(), Some(Action::Error(err))),
+                };
+            }
+        }
+
+        (Task::none(), None)
+    }
+
+    pub fn view(&self) -> Element<'_, Message> {
+        let path_row = row![
+            text_input("Path to a .csv or .zip file", &self.path)
+                .on_input(Message::PathChanged)
+                .on_submit(Message::LoadPreview),
+            button(text("Load")).on_press_maybe(
+                (!self.is_busy && !self.path.trim().is_empty()).then_some(Message::LoadPreview)
+            ),
+        ]
+        .spacing(4)
+        .align_y(Alignment::Center);
+
+        let mut content = column![text("Import data").size(14), path_row].spacing(12);
+
+        if let Some(preview) = &self.preview {
+            content = content.push(self.mapping_view(preview));
+        }
+
+        if let Some(err) = &self.error {
+            content = content.push(text(err).style(|theme: &iced::Theme| text::Style {
+                color: Some(theme.extended_palette().danger.base.color),
+            }));
+        }
+
+        content = content.push(self.imported_view());
+
+        container(content)
+            .max_width(420)
+            .padding(24)
+            .style(style::dashboard_modal)
+            .into()
+    }
+
+    fn mapping_view<'a>(&'a self, preview: &'a Preview) -> Element<'a, Message> {
+        let mapping = &preview.mapping;
+
+        let cell = |value: &str| {
+            container(text(value.to_string()).size(11))
+                .width(PREVIEW_CELL_WIDTH)
+                .clip(true)
+        };
+
+        let mut table =
+            column![row(preview.headers.iter().map(|h| cell(h).into())).spacing(4)].spacing(2);
+        for values in &preview.rows {
+            table = table.push(row(values.iter().map(|v| cell(v).into())).spacing(4));
+        }
+
+        let table = scrollable(table)
+            .direction(scrollable::Direction::Horizontal(
+                scrollable::Scrollbar::new().width(4).scroller_width(4),
+            ))
+            .style(style::scroll_bar);
+
+        let columns = std::iter::once(Column {
+            index: None,
+            label: "None".to_string(),
+        })
+        .chain(
+            preview
+                .headers
+                .iter()
+                .enumerate()
+                .map(|(index, header)| Column {
+                    index: Some(index),
+                    label: header.clone(),
+                }),
+        )
+        .collect::<Vec<_>>();
+
+        let labeled = |label: String, picker: Element<'a, Message>| {
+            row![text(label).width(Length::Fill), picker]
+                .spacing(8)
+                .align_y(Alignment::Center)
+        };
+
+        let mut fields = column![
+            labeled(
+                "Type".to_string(),
+                pick_list(DataKind::ALL, Some(mapping.kind), Message::KindSelected).into()
+            ),
+            checkbox(mapping.has_header)
+                .label("First row is a header")
+                .on_toggle(Message::HeaderToggled),
+        ]
+        .spacing(6);
+
+        for &field in mapping.kind.fields() {
+            let selected = columns
+                .iter()
+                .find(|c| c.index == mapping.columns[field])
+                .cloned();
+            let label = if field.is_required() {
+                field.to_string()
+            } else {
+                format!("{field} (optional)")
+            };
+
+            fields = fields.push(labeled(
+                label,
+                pick_list(columns.clone(), selected, move |column| {
+                    Message::ColumnSelected(field, column)
+                })
+                .width(140)
+                .into(),
+            ));
+        }
+
+        let import_row = row![
+            text_input("Symbol", &self.symbol).on_input(Message::SymbolChanged),
+            button(text("Import"))
+                .on_press_maybe(
+                    (!self.is_busy && !self.symbol.is_empty()).then_some(Message::Import)
+                )
+                .style(|theme, status| style::button::confirm(theme, status, true)),
+        ]
+        .spacing(4)
+        .align_y(Alignment::Center);
+
+        column![table, fields, import_row].spacing(12).into()
+    }
+
+    fn imported_view(&self) -> Element<'_, Message> {
+        if self.imported.is_empty() {
+            return text("No imported datasets").size(11).into();
+        }
+
+        let mut list = column![text("Imported").size(14)].spacing(4);
+
+        for imported in &self.imported {
+            let ticker = imported.ticker_info.ticker;
+            let (symbol, _) = ticker.to_full_symbol_and_type();
+
+            let (from, to) = imported.range;
+            let range = match (format_date(from), format_date(to)) {
+                (Some(from), Some(to)) => format!("{from} → {to}"),
+                _ => String::new(),
+            };
+
+            list = list.push(
+                container(
+                    row![
+                        column![
+                            text(symbol),
+                            text(format!("{} {}, {range}", imported.rows, imported.kind)).size(11),
+                        ]
+                        .spacing(2),
+                        space::horizontal(),
+                        button(icon_text(Icon::TrashBin, 12))
+                            .on_press(Message::Remove(ticker))
+                            .style(|theme, status| style::button::transparent(
+                                theme, status, false
+                            )),
+                    ]
+                    .align_y(Alignment::Center),
+                )
+                .padding(8)
+                .style(style::modal_container),
+            );
+        }
+
+        list.into()
+    }
+}
+
+impl Default for ImportDialog {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+fn format_date(ms: u64) -> Option<String> {
+    chrono::DateTime::from_timestamp_millis(ms as i64)
+        .map(|dt| dt.format("%Y-%m-%d %H:%M").to_string())
+}
+
+/// File name without extension and archive period suffixes, e.g. `BTCUSDT-aggTrades-2024-01`
+fn suggest_symbol(path: &std::path::Path) -> String {
+    path.file_stem()
+        .and_then(|s| s.to_str())
+        .and_then(|s| s.split(['-', '_', '.', ' ']).next())
+        .map(|s| s.to_uppercase())
+        .unwrap_or_default()
+}
@@ -3,6 +3,7 @@ use iced::{
     widget::{container, mouse_area, opaque},
 };
 
+pub mod export;
 pub mod indicators;
 pub mod mini_tickers_list;
 pub mod settings;
@@ -13,9 +14,15 @@ pub enum Modal {
     StreamModifier(super::stream::Modifier),
     MiniTickersList(mini_tickers_list::MiniPanel),
     Settings,
-    Indicators,
+    /// With the index of the selected indicator whose settings are expanded, and the
+    /// user scripts found when the modal was opened
+    Indicators {
+        expanded: Option<usize>,
+        scripts: Vec<data::chart::indicator::script::ScriptName>,
+    },
     LinkGroup,
     Controls,
+    Export(export::Export),
 }
 
 pub fn stack_modal<'a, Message>(
@@ -0,0 +1,114 @@
+use crate::style;
+
+use data::export::{Dataset, Format, Scope};
+use iced::{
+    Alignment, Element, Length,
+    widget::{button, column, container, pick_list, row, text},
+};
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Export {
+    datasets: Vec<Dataset>,
+    dataset: Dataset,
+    format: Format,
+    scope: Scope,
+    has_scope: bool,
+}
+
+#[derive(Debug, Clone)]
+pub enum Message {
+    DatasetSelected(Dataset),
+    FormatSelected(Format),
+    ScopeSelected(Scope),
+    Export,
+}
+
+pub enum Action {
+    Export {
+        dataset: Dataset,
+        format: Format,
+        scope: Scope,
+    },
+}
+
+impl Export {
+    /// `has_scope` is false for panels that have no notion of a visible range
+    pub fn new(datasets: Vec<Dataset>, has_scope: bool) -> Self {
+        let dataset = datasets.first().copied().unwrap_or(Dataset::Klines);
+
+        Self {
+            datasets,
+            dataset,
+            format: Format::default(),
+            scope: Scope::default(),
+            has_scope,
+        }
+    }
+
+    pub fn update(&mut self, message: Message) -> Option<Action> {
+        match message {
+            Message::DatasetSelected(dataset) => self.dataset = dataset,
+            Message::FormatSelected(format) => self.format = format,
+            Message::ScopeSelected(scope) => self.scope = scope,
+            Message::Export => {
+                return Some(Action::Export {
+                    dataset: self.dataset,
+                    format: self.format,
+                    scope: if self.has_scope {
+                        self.scope
+                    } else {
+                        Scope::Full
+                    },
+                });
+            }
+        }
+        None
+    }
+
+    pub fn view(&self) -> Element<'_, Message> {
+        let labeled = |label: &'static str, picker: Element<'static, Message>| {
+            row![text(label).width(Length::Fill), picker]
+                .spacing(8)
+                .align_y(Alignment::Center)
+        };
+
+        let mut content = column![text("Export data").size(14)].spacing(12);
+
+        if self.datasets.len() > 1 {
+            content = content.push(labeled(
+                "Data",
+                pick_list(
+                    self.datasets.clone(),
+                    Some(self.dataset),
+                    Message::DatasetSelected,
+                )
+                .into(),
+            ));
+        }
+
+        content = content.push(labeled(
+            "Format",
+            pick_list(Format::ALL, Some(self.format), Message::FormatSelected).into(),
+        ));
+
+        if self.has_scope {
+            content = content.push(labeled(
+                "Range",
+                pick_list(Scope::ALL, Some(self.scope), Message::ScopeSelected).into(),
+            ));
+        }
+
+        content = content.push(
+            button(text("Export").align_x(Alignment::Center))
+                .width(Length::Fill)
+                .on_press(Message::Export)
+                .style(|theme, status| style::button::confirm(theme, status, true)),
+        );
+
+        container(content)
+            .width(260)
+            .padding(16)
+            .style(style::chart_modal)
+            .into()
+    }
+}
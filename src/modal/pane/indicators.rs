@@ -1,25 +1,459 @@
 use crate::screen::dashboard::pane::{self, Message};
 use crate::style::{self, Icon, icon_text};
-use crate::widget::{column_drag, dragger_row};
+use crate::widget::{column_drag, dragger_row, labeled_slider};
 
-use data::chart::indicator::{Indicator, UiIndicator};
+use data::chart::indicator::{
+    HeatmapIndicator, Indicator, KlineIndicator, UiIndicator,
+    average::{MaKind, PriceSource},
+    bands::BandKind,
+    cvd::{CvdReset, CvdStyle},
+    rolling::VolEstimator,
+    script::ScriptName,
+    vwap::VwapAnchor,
+};
 use iced::{
     Element, Length, padding,
-    widget::{button, column, container, pane_grid, row, space, text},
+    widget::{button, checkbox, column, container, pane_grid, pick_list, row, space, text},
 };
 
+/// Settings form of a single indicator instance
+pub trait IndicatorConfig: Indicator + Copy + Into<UiIndicator> {
+    /// `None` when the indicator has no parameters to set
+    fn view_config<'a>(
+        &self,
+        on_change: impl Fn(Self) -> Message + Clone + 'a,
+    ) -> Option<Element<'a, Message>>;
+
+    /// Offered next to the built-in ones, whatever the market
+    fn user_defined(_scripts: &[ScriptName]) -> Vec<Self> {
+        vec![]
+    }
+}
+
+impl IndicatorConfig for KlineIndicator {
+    fn user_defined(scripts: &[ScriptName]) -> Vec<Self> {
+        scripts
+            .iter()
+            .map(|&name| KlineIndicator::Script { name })
+            .collect()
+    }
+
+    fn view_config<'a>(
+        &self,
+        on_change: impl Fn(Self) -> Message + Clone + 'a,
+    ) -> Option<Element<'a, Message>> {
+        match *self {
+            KlineIndicator::Volume { delta } => {
+                let delta_checkbox =
+                    checkbox(delta)
+                        .label("Buy/sell split")
+                        .on_toggle(move |is_checked| {
+                            on_change(KlineIndicator::Volume { delta: is_checked })
+                        });
+
+                Some(column![delta_checkbox].padding(8).spacing(4).into())
+            }
+            KlineIndicator::OpenInterest { change } => {
+                let change_checkbox =
+                    checkbox(change)
+                        .label("Per-bar change")
+                        .on_toggle(move |is_checked| {
+                            on_change(KlineIndicator::OpenInterest { change: is_checked })
+                        });
+
+                Some(column![change_checkbox].padding(8).spacing(4).into())
+            }
+            KlineIndicator::BarDelta
+            | KlineIndicator::TradeCount
+            | KlineIndicator::AverageTradeSize
+            | KlineIndicator::Script { .. } => None,
+            KlineIndicator::MovingAverage {
+                kind,
+                period,
+                source,
+            } => {
+                let with = move |kind, period, source| KlineIndicator::MovingAverage {
+                    kind,
+                    period,
+                    source,
+                };
+
+                let kind_picker = {
+                    let on_change = on_change.clone();
+                    pick_list(MaKind::ALL, Some(kind), move |kind| {
+                        on_change(with(kind, period, source))
+                    })
+                };
+                let source_picker = {
+                    let on_change = on_change.clone();
+                    pick_list(PriceSource::ALL, Some(source), move |source| {
+                        on_change(with(kind, period, source))
+                    })
+                };
+                let period_slider = labeled_slider(
+                    "Period",
+                    2..=200,
+                    period,
+                    move |period| on_change(with(kind, period, source)),
+                    |period| period.to_string(),
+                    Some(1),
+                );
+
+                Some(
+                    column![row![kind_picker, source_picker].spacing(4), period_slider]
+                        .padding(8)
+                        .spacing(4)
+                        .into(),
+                )
+            }
+            KlineIndicator::Bands {
+                kind,
+                period,
+                width,
+            } => {
+                let with = move |kind, period, width| KlineIndicator::Bands {
+                    kind,
+                    period,
+                    width,
+                };
+
+                let kind_picker = {
+                    let on_change = on_change.clone();
+                    pick_list(BandKind::ALL, Some(kind), move |kind| {
+                        on_change(with(kind, period, width))
+                    })
+                };
+                let period_slider = {
+                    let on_change = on_change.clone();
+                    labeled_slider(
+                        "Period",
+                        2..=200,
+                        period,
+                        move |period| on_change(with(kind, period, width)),
+                        |period| period.to_string(),
+                        Some(1),
+                    )
+                };
+                // kept to one decimal, the value is shown in the indicator's name
+                let width_slider = labeled_slider(
+                    "Width",
+                    0.5..=4.0,
+                    width,
+                    move |width: f32| on_change(with(kind, period, (width * 10.0).round() / 10.0)),
+                    |width| format!("{width:.1}"),
+                    Some(0.1),
+                );
+
+                Some(
+                    column![kind_picker, period_slider, width_slider]
+                        .padding(8)
+                        .spacing(4)
+                        .into(),
+                )
+            }
+            KlineIndicator::CumulativeDelta { style, reset } => {
+                let style_picker = {
+                    let on_change = on_change.clone();
+                    pick_list(CvdStyle::ALL, Some(style), move |style| {
+                        on_change(KlineIndicator::CumulativeDelta { style, reset })
+                    })
+                };
+
+                // the session on offer keeps its start time when switching away and back
+                let session = match reset {
+                    CvdReset::Session { .. } => reset,
+                    _ => CvdReset::DEFAULT_SESSION,
+                };
+                let reset_picker = {
+                    let on_change = on_change.clone();
+                    pick_list(
+                        [
+                            CvdReset::None,
+                            CvdReset::DailyUtc,
+                            session,
+                            CvdReset::VisibleRange,
+                        ],
+                        Some(reset),
+                        move |reset| on_change(KlineIndicator::CumulativeDelta { style, reset }),
+                    )
+                };
+
+                let mut content = column![row![style_picker, reset_picker].spacing(4)]
+                    .padding(8)
+                    .spacing(4);
+
+                if let CvdReset::Session { start_minute } = reset {
+                    content = content.push(labeled_slider(
+                        "Start (UTC)",
+                        0..=1425,
+                        start_minute,
+                        move |start_minute| {
+                            on_change(KlineIndicator::CumulativeDelta {
+                                style,
+                                reset: CvdReset::Session { start_minute },
+                            })
+                        },
+                        |minute| format!("{:02}:{:02}", minute / 60, minute % 60),
+                        Some(15),
+                    ));
+                }
+
+                Some(content.into())
+            }
+            KlineIndicator::Vwap { anchor, bands } => {
+                let anchor_picker = {
+                    let on_change = on_change.clone();
+                    pick_list(VwapAnchor::ALL, Some(anchor), move |anchor| {
+                        on_change(KlineIndicator::Vwap { anchor, bands })
+                    })
+                };
+                let bands_slider = labeled_slider(
+                    "Bands",
+                    0..=3,
+                    bands,
+                    move |bands| on_change(KlineIndicator::Vwap { anchor, bands }),
+                    |bands| format!("{bands} SD"),
+                    Some(1),
+                );
+
+                let mut content = column![anchor_picker, bands_slider].padding(8).spacing(4);
+
+                if anchor == VwapAnchor::Custom {
+                    content = content.push(
+                        text("Press A over the chart, then click a bar to anchor on it or remove its anchor")
+                            .size(11),
+                    );
+                }
+
+                Some(content.into())
+            }
+            KlineIndicator::Rsi { period, overbought } => {
+                let period_slider = {
+                    let on_change = on_change.clone();
+                    labeled_slider(
+                        "Period",
+                        2..=100,
+                        period,
+                        move |period| on_change(KlineIndicator::Rsi { period, overbought }),
+                        |period| period.to_string(),
+                        Some(1),
+                    )
+                };
+                let levels_slider = labeled_slider(
+                    "Levels",
+                    55..=95,
+                    overbought,
+                    move |overbought| on_change(KlineIndicator::Rsi { period, overbought }),
+                    |overbought| format!("{}/{overbought}", 100 - overbought),
+                    Some(5),
+                );
+
+                Some(
+                    column![period_slider, levels_slider]
+                        .padding(8)
+                        .spacing(4)
+                        .into(),
+                )
+            }
+            KlineIndicator::Macd { fast, slow, signal } => {
+                let with = move |fast, slow, signal| KlineIndicator::Macd { fast, slow, signal };
+
+                // the fast average is kept shorter than the slow one
+                let fast_slider = {
+                    let on_change = on_change.clone();
+                    labeled_slider(
+                        "Fast",
+                        2..=slow.saturating_sub(1).max(2),
+                        fast,
+                        move |fast| on_change(with(fast, slow, signal)),
+                        |fast| fast.to_string(),
+                        Some(1),
+                    )
+                };
+                let slow_slider = {
+                    let on_change = on_change.clone();
+                    labeled_slider(
+                        "Slow",
+                        (fast + 1)..=200,
+                        slow,
+                        move |slow| on_change(with(fast, slow, signal)),
+                        |slow| slow.to_string(),
+                        Some(1),
+                    )
+                };
+                let signal_slider = labeled_slider(
+                    "Signal",
+                    2..=50,
+                    signal,
+                    move |signal| on_change(with(fast, slow, signal)),
+                    |signal| signal.to_string(),
+                    Some(1),
+                );
+
+                Some(
+                    column![fast_slider, slow_slider, signal_slider]
+                        .padding(8)
+                        .spacing(4)
+                        .into(),
+                )
+            }
+            KlineIndicator::Stochastic {
+                period,
+                smooth_k,
+                smooth_d,
+            } => {
+                let with = move |period, smooth_k, smooth_d| KlineIndicator::Stochastic {
+                    period,
+                    smooth_k,
+                    smooth_d,
+                };
+
+                let period_slider = {
+                    let on_change = on_change.clone();
+                    labeled_slider(
+                        "Period",
+                        2..=100,
+                        period,
+                        move |period| on_change(with(period, smooth_k, smooth_d)),
+                        |period| period.to_string(),
+                        Some(1),
+                    )
+                };
+                let smooth_k_slider = {
+                    let on_change = on_change.clone();
+                    labeled_slider(
+                        "%K smoothing",
+                        1..=10,
+                        smooth_k,
+                        move |smooth_k| on_change(with(period, smooth_k, smooth_d)),
+                        |smooth_k| smooth_k.to_string(),
+                        Some(1),
+                    )
+                };
+                let smooth_d_slider = labeled_slider(
+                    "%D smoothing",
+                    1..=10,
+                    smooth_d,
+                    move |smooth_d| on_change(with(period, smooth_k, smooth_d)),
+                    |smooth_d| smooth_d.to_string(),
+                    Some(1),
+                );
+
+                Some(
+                    column![period_slider, smooth_k_slider, smooth_d_slider]
+                        .padding(8)
+                        .spacing(4)
+                        .into(),
+                )
+            }
+            KlineIndicator::Atr { period } => {
+                let period_slider = labeled_slider(
+                    "Period",
+                    2..=100,
+                    period,
+                    move |period| on_change(KlineIndicator::Atr { period }),
+                    |period| period.to_string(),
+                    Some(1),
+                );
+
+                Some(column![period_slider].padding(8).spacing(4).into())
+            }
+            KlineIndicator::RealizedVolatility { estimator, period } => {
+                let estimator_picker = {
+                    let on_change = on_change.clone();
+                    pick_list(VolEstimator::ALL, Some(estimator), move |estimator| {
+                        on_change(KlineIndicator::RealizedVolatility { estimator, period })
+                    })
+                };
+                let period_slider = labeled_slider(
+                    "Period",
+                    2..=200,
+                    period,
+                    move |period| {
+                        on_change(KlineIndicator::RealizedVolatility { estimator, period })
+                    },
+                    |period| period.to_string(),
+                    Some(1),
+                );
+
+                Some(
+                    column![estimator_picker, period_slider]
+                        .padding(8)
+                        .spacing(4)
+                        .into(),
+                )
+            }
+            KlineIndicator::VolumeZScore { period, threshold } => {
+                let period_slider = {
+                    let on_change = on_change.clone();
+                    labeled_slider(
+                        "Period",
+                        5..=200,
+                        period,
+                        move |period| on_change(KlineIndicator::VolumeZScore { period, threshold }),
+                        |period| period.to_string(),
+                        Some(1),
+                    )
+                };
+                // kept to one decimal, the value is shown in the indicator's name
+                let threshold_slider = labeled_slider(
+                    "Threshold",
+                    1.0..=5.0,
+                    threshold,
+                    move |threshold: f32| {
+                        on_change(KlineIndicator::VolumeZScore {
+                            period,
+                            threshold: (threshold * 10.0).round() / 10.0,
+                        })
+                    },
+                    |threshold| format!("{threshold:.1} SD"),
+                    Some(0.1),
+                );
+
+                Some(
+                    column![period_slider, threshold_slider]
+                        .padding(8)
+                        .spacing(4)
+                        .into(),
+                )
+            }
+        }
+    }
+}
+
+impl IndicatorConfig for HeatmapIndicator {
+    fn view_config<'a>(
+        &self,
+        _on_change: impl Fn(Self) -> Message + Clone + 'a,
+    ) -> Option<Element<'a, Message>> {
+        match self {
+            HeatmapIndicator::Volume => None,
+        }
+    }
+}
+
+/// `expanded` is the index of the selected indicator whose settings are shown
 pub fn view<'a, I>(
     pane: pane_grid::Pane,
     state: &'a pane::State,
     selected: &[I],
+    scripts: &[ScriptName],
     market_type: Option<exchange::adapter::MarketKind>,
+    expanded: Option<usize>,
 ) -> Element<'a, Message>
 where
-    I: Indicator + Copy + Into<UiIndicator>,
+    I: IndicatorConfig,
 {
     let content_allows_dragging = matches!(state.content, pane::Content::Kline { .. });
     let content_row = if let Some(market) = market_type {
-        content_row(pane, selected, market, content_allows_dragging)
+        content_row(
+            pane,
+            selected,
+            scripts,
+            market,
+            content_allows_dragging,
+            expanded,
+        )
     } else {
         column![].spacing(4).into()
     };
@@ -37,7 +471,7 @@ fn build_indicator_row<'a, I>(
     is_selected: bool,
 ) -> Element<'a, Message>
 where
-    I: Indicator + Copy + Into<UiIndicator>,
+    I: IndicatorConfig,
 {
     let content = if is_selected {
         row![
@@ -60,18 +494,58 @@ where
         .into()
 }
 
+/// Selected indicator with a toggle for its settings, and the settings themselves when expanded
+fn build_selected_row<'a, I>(
+    pane: pane_grid::Pane,
+    index: usize,
+    indicator: &I,
+    is_expanded: bool,
+) -> Element<'a, Message>
+where
+    I: IndicatorConfig,
+{
+    let toggle_row = build_indicator_row(pane, indicator, true);
+
+    let Some(config) = indicator.view_config(move |new: I| {
+        Message::PaneEvent(pane, pane::Event::ConfigureIndicator(index, new.into()))
+    }) else {
+        return toggle_row;
+    };
+
+    let settings_btn = button(icon_text(Icon::Cog, 12))
+        .on_press(Message::PaneEvent(
+            pane,
+            pane::Event::IndicatorSettingsToggled(index),
+        ))
+        .style(move |theme, status| style::button::transparent(theme, status, is_expanded));
+
+    let mut content = column![
+        row![toggle_row, settings_btn]
+            .align_y(iced::Alignment::Center)
+            .spacing(4)
+    ];
+
+    if is_expanded {
+        content = content.push(container(config).style(style::modal_container));
+    }
+
+    content.spacing(4).into()
+}
+
 fn selected_list<'a, I>(
     pane: pane_grid::Pane,
     selected: &[I],
     reorderable: bool,
+    expanded: Option<usize>,
 ) -> Element<'a, Message>
 where
-    I: Indicator + Copy + Into<UiIndicator>,
+    I: IndicatorConfig,
 {
     let elements: Vec<Element<_>> = selected
         .iter()
-        .map(|indicator| {
-            let base = build_indicator_row(pane, indicator, true);
+        .enumerate()
+        .map(|(index, indicator)| {
+            let base = build_selected_row(pane, index, indicator, expanded == Some(index));
             dragger_row(base, reorderable)
         })
         .collect();
@@ -93,7 +567,7 @@ where
 
 fn available_list<'a, I>(pane: pane_grid::Pane, available: &[I]) -> Element<'a, Message>
 where
-    I: Indicator + Copy + Into<UiIndicator>,
+    I: IndicatorConfig,
 {
     let elements: Vec<Element<_>> = available
         .iter()
@@ -111,24 +585,29 @@ where
 fn content_row<'a, I>(
     pane: pane_grid::Pane,
     selected: &[I],
+    scripts: &[ScriptName],
     market: exchange::adapter::MarketKind,
     allows_drag: bool,
+    expanded: Option<usize>,
 ) -> Element<'a, Message>
 where
-    I: Indicator + Copy + Into<UiIndicator>,
+    I: IndicatorConfig,
 {
     let reorderable = allows_drag && selected.len() >= 2;
 
     let selected_list = if !selected.is_empty() {
-        Some(selected_list(pane, selected, reorderable))
+        Some(selected_list(pane, selected, reorderable, expanded))
     } else {
         None
     };
 
+    // defaults stay on offer while their instance is configured differently,
+    // adding another instance of the same indicator
     let available: Vec<I> = I::for_market(market)
         .iter()
-        .filter(|indicator| !selected.contains(indicator))
         .cloned()
+        .chain(I::user_defined(scripts))
+        .filter(|indicator| !selected.contains(indicator))
         .collect();
     let available_list = if !available.is_empty() {
         Some(available_list(pane, &available))
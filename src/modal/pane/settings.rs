@@ -16,6 +16,7 @@ use data::layout::pane::VisualConfig;
 use data::panel::ladder;
 use data::panel::timeandsales::{StackedBar, StackedBarRatio};
 use data::util::format_with_commas;
+use exchange::SizeUnit;
 
 use iced::widget::{checkbox, space};
 use iced::{
@@ -255,8 +256,20 @@ pub fn heatmap_cfg_view<'a>(
         )
     });
 
+    let size_unit_column = size_unit_column(cfg.size_unit, move |unit| {
+        Message::VisualConfigChanged(
+            pane,
+            VisualConfig::Heatmap(heatmap::Config {
+                size_unit: unit,
+                ..cfg
+            }),
+            false,
+        )
+    });
+
     let content = split_column![
         size_filters_column,
+        size_unit_column,
         noise_filters_column,
         trade_viz_column,
         column![text("Studies").size(14), study_cfg].spacing(8),
@@ -428,8 +441,20 @@ pub fn timesales_cfg_view<'a>(
             .into()
     };
 
+    let size_unit_column = size_unit_column(cfg.size_unit, move |unit| {
+        Message::VisualConfigChanged(
+            pane,
+            VisualConfig::TimeAndSales(timeandsales::Config {
+                size_unit: unit,
+                ..cfg
+            }),
+            false,
+        )
+    });
+
     let content = split_column![
         trade_size_column,
+        size_unit_column,
         history_column,
         stacked_bar,
         row![space::horizontal(), sync_all_button(pane, VisualConfig::TimeAndSales(cfg))],
@@ -463,10 +488,23 @@ pub fn kline_cfg_view<'a>(
     pane: pane_grid::Pane,
     basis: data::chart::Basis,
 ) -> Element<'a, Message> {
+    let size_unit_column = size_unit_column(cfg.size_unit, move |unit| {
+        Message::VisualConfigChanged(
+            pane,
+            VisualConfig::Kline(data::chart::kline::Config { size_unit: unit }),
+            false,
+        )
+    });
+
     let content = match kind {
-        KlineChartKind::Candles => column![text(
-            "This chart type doesn't have any configurations, WIP..."
-        )],
+        KlineChartKind::Candles => split_column![
+            size_unit_column,
+            row![
+                space::horizontal(),
+                sync_all_button(pane, VisualConfig::Kline(cfg))
+            ],
+            ; spacing = 12, align_x = Alignment::Start
+        ],
         KlineChartKind::Footprint {
             clusters,
             scaling,
@@ -518,6 +556,7 @@ pub fn kline_cfg_view<'a>(
             split_column![
                 column![text("Cluster type").size(14), cluster_picklist].spacing(8),
                 column![text("Cluster scaling").size(14), scaling].spacing(8),
+                size_unit_column,
                 column![text("Studies").size(14), study_cfg].spacing(8),
                 row![
                     space::horizontal(),
@@ -604,8 +643,20 @@ pub fn ladder_cfg_view<'a>(cfg: ladder::Config, pane: pane_grid::Pane) -> Elemen
 
     let history_column = column![text("History").size(14), retention_slider].spacing(8);
 
+    let size_unit_column = size_unit_column(cfg.size_unit, move |unit| {
+        Message::VisualConfigChanged(
+            pane,
+            VisualConfig::Ladder(ladder::Config {
+                size_unit: unit,
+                ..cfg
+            }),
+            false,
+        )
+    });
+
     let content = split_column![
         display_options,
+        size_unit_column,
         history_column,
         row![
             space::horizontal(),
@@ -617,6 +668,27 @@ pub fn ladder_cfg_view<'a>(cfg: ladder::Config, pane: pane_grid::Pane) -> Elemen
     cfg_view_container(320, content)
 }
 
+fn size_unit_column<'a>(
+    unit: SizeUnit,
+    on_select: impl Fn(SizeUnit) -> Message + 'a,
+) -> Element<'a, Message> {
+    column![
+        row![
+            text("Size unit").size(14),
+            tooltip(
+                button("i").style(style::button::info),
+                Some("Show sizes in base asset or quote notional\nInverse contracts are always in quote"),
+                TooltipPosition::Top,
+            )
+        ]
+        .spacing(4)
+        .align_y(Alignment::Center),
+        pick_list(SizeUnit::ALL, Some(unit), on_select),
+    ]
+    .spacing(8)
+    .into()
+}
+
 fn sync_all_button<'a>(pane: pane_grid::Pane, config: VisualConfig) -> Element<'a, Message> {
     tooltip(
         button("Sync all").on_press(Message::VisualConfigChanged(pane, config, true)),
@@ -780,7 +852,7 @@ pub mod study {
         ) -> Element<'a, Message<Self>> {
             let interval_ms = match basis {
                 data::chart::Basis::Time(interval) => interval.to_milliseconds(),
-                data::chart::Basis::Tick(_) => {
+                _ => {
                     return iced::widget::center(text(
                         "Heatmap studies are not supported for tick-based charts",
                     ))
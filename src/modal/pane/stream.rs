@@ -3,10 +3,12 @@ use crate::{
     widget::numeric_input_box,
 };
 
+use data::aggr::{TickCount, range::RangeTicks, renko::RenkoBox, volume::VolumeSize};
 use data::chart::Basis;
 use exchange::{
     TickMultiplier, TickerInfo, Timeframe,
     adapter::{Exchange, hyperliquid::allowed_multipliers_for_base_tick},
+    util::Qty,
 };
 use iced::{
     Element, Length,
@@ -16,7 +18,7 @@ use iced::{
 };
 use serde::{Deserialize, Serialize};
 
-const NUMERIC_INPUT_BUF_SIZE: usize = 5; // Max 5 digits for u16 (65535)
+const NUMERIC_INPUT_BUF_SIZE: usize = 8; // Fits u16 (65535) and decimal volume sizes
 
 const TICK_COUNT_MIN: u16 = 4;
 const TICK_COUNT_MAX: u16 = 1000;
@@ -24,6 +26,13 @@ const TICK_COUNT_MAX: u16 = 1000;
 const TICK_MULTIPLIER_MIN: u16 = 1;
 const TICK_MULTIPLIER_MAX: u16 = 2000;
 
+const RANGE_TICKS_MIN: u16 = 2;
+const RANGE_TICKS_MAX: u16 = 2000;
+
+const RENKO_BOX_MIN: u16 = 1;
+const RENKO_BOX_MAX: u16 = 2000;
+const RENKO_REVERSALS: [u16; 3] = [1, 2, 3];
+
 #[derive(Debug, Clone, Copy, PartialEq, Deserialize, Serialize)]
 pub enum ModifierKind {
     Candlestick(Basis),
@@ -62,7 +71,7 @@ impl NumericInput {
         Self::from_str(&tm.0.to_string())
     }
 
-    pub fn from_tick_count(tc: data::aggr::TickCount) -> Self {
+    pub fn from_tick_count(tc: TickCount) -> Self {
         Self::from_str(&tc.0.to_string())
     }
 
@@ -87,14 +96,41 @@ impl NumericInput {
             .map(TickMultiplier)
     }
 
-    pub fn parse_tick_count(self) -> Option<data::aggr::TickCount> {
+    pub fn parse_timeframe(self) -> Option<Timeframe> {
+        if self.len == 0 {
+            return None;
+        }
+        std::str::from_utf8(&self.buffer[..self.len as usize])
+            .ok()
+            .and_then(|s| s.parse::<Timeframe>().ok())
+    }
+
+    pub fn parse_tick_count(self) -> Option<TickCount> {
+        self.parse_u16().map(TickCount)
+    }
+
+    pub fn parse_range_ticks(self) -> Option<RangeTicks> {
+        self.parse_u16().map(RangeTicks)
+    }
+
+    pub fn parse_volume_size(self) -> Option<VolumeSize> {
+        if self.len == 0 {
+            return None;
+        }
+        std::str::from_utf8(&self.buffer[..self.len as usize])
+            .ok()
+            .and_then(|s| s.parse::<Qty>().ok())
+            .filter(|qty| *qty > Qty::ZERO)
+            .map(VolumeSize)
+    }
+
+    fn parse_u16(self) -> Option<u16> {
         if self.len == 0 {
             return None;
         }
         std::str::from_utf8(&self.buffer[..self.len as usize])
             .ok()
             .and_then(|s| s.parse::<u16>().ok())
-            .map(data::aggr::TickCount)
     }
 }
 
@@ -116,12 +152,98 @@ pub enum ViewMode {
 
 #[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq)]
 pub enum SelectedTab {
-    Timeframe,
+    Timeframe {
+        raw_input_buf: NumericInput,
+        parsed_input: Option<Timeframe>,
+        is_input_valid: bool,
+    },
     TickCount {
         raw_input_buf: NumericInput,
-        parsed_input: Option<data::aggr::TickCount>,
+        parsed_input: Option<TickCount>,
+        is_input_valid: bool,
+    },
+    Volume {
+        raw_input_buf: NumericInput,
+        parsed_input: Option<VolumeSize>,
+        is_input_valid: bool,
+    },
+    Range {
+        raw_input_buf: NumericInput,
+        parsed_input: Option<RangeTicks>,
         is_input_valid: bool,
     },
+    Renko {
+        raw_input_buf: NumericInput,
+        parsed_input: Option<u16>,
+        is_input_valid: bool,
+    },
+}
+
+impl SelectedTab {
+    /// Tab that holds `basis`, with its custom input pre-filled when it isn't a preset
+    fn for_basis(basis: Basis) -> Self {
+        match basis {
+            Basis::Time(tf) => {
+                let is_custom = matches!(tf, Timeframe::Custom(_));
+                SelectedTab::Timeframe {
+                    raw_input_buf: if is_custom {
+                        NumericInput::from_str(&tf.to_string())
+                    } else {
+                        NumericInput::default()
+                    },
+                    parsed_input: is_custom.then_some(tf),
+                    is_input_valid: true,
+                }
+            }
+            Basis::Tick(tc) => SelectedTab::TickCount {
+                raw_input_buf: if tc.is_custom() {
+                    NumericInput::from_tick_count(tc)
+                } else {
+                    NumericInput::default()
+                },
+                parsed_input: if tc.is_custom() { Some(tc) } else { None },
+                is_input_valid: true,
+            },
+            Basis::Volume(size) => SelectedTab::Volume {
+                raw_input_buf: NumericInput::from_str(&size.0.to_string()),
+                parsed_input: Some(size),
+                is_input_valid: true,
+            },
+            Basis::Range(range) => SelectedTab::Range {
+                raw_input_buf: if range.is_custom() {
+                    NumericInput::from_str(&range.0.to_string())
+                } else {
+                    NumericInput::default()
+                },
+                parsed_input: range.is_custom().then_some(range),
+                is_input_valid: true,
+            },
+            Basis::Renko(renko) => {
+                let is_custom = renko.is_custom_size();
+                SelectedTab::Renko {
+                    raw_input_buf: if is_custom {
+                        NumericInput::from_str(&renko.size.to_string())
+                    } else {
+                        NumericInput::default()
+                    },
+                    parsed_input: is_custom.then_some(renko.size),
+                    is_input_valid: true,
+                }
+            }
+        }
+    }
+
+    /// Whether `basis` is one this tab picks from
+    fn holds(&self, basis: Basis) -> bool {
+        matches!(
+            (self, basis),
+            (SelectedTab::Timeframe { .. }, Basis::Time(_))
+                | (SelectedTab::TickCount { .. }, Basis::Tick(_))
+                | (SelectedTab::Volume { .. }, Basis::Volume(_))
+                | (SelectedTab::Range { .. }, Basis::Range(_))
+                | (SelectedTab::Renko { .. }, Basis::Renko(_))
+        )
+    }
 }
 
 pub enum Action {
@@ -137,6 +259,10 @@ pub enum Message {
     TicksizeInputChanged(String),
     TicksizeSelected(TickMultiplier),
     TickCountInputChanged(String),
+    TimeframeInputChanged(String),
+    VolumeInputChanged(String),
+    RangeInputChanged(String),
+    RenkoInputChanged(String),
 }
 
 #[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq)]
@@ -225,7 +351,21 @@ impl Modifier {
         match message {
             Message::TabSelected(tab) => Some(Action::TabSelected(tab)),
             Message::BasisSelected(basis) => match basis {
-                Basis::Time(_) => Some(Action::BasisSelected(basis)),
+                Basis::Time(new_tf) => {
+                    if let SelectedTab::Timeframe {
+                        raw_input_buf,
+                        parsed_input,
+                        is_input_valid,
+                    } = &mut self.tab
+                        && *parsed_input != Some(new_tf)
+                    {
+                        *raw_input_buf = NumericInput::default();
+                        *parsed_input = None;
+                        *is_input_valid = true;
+                    }
+
+                    Some(Action::BasisSelected(basis))
+                }
                 Basis::Tick(new_tc) => {
                     if let SelectedTab::TickCount {
                         raw_input_buf,
@@ -246,6 +386,50 @@ impl Modifier {
                         None
                     }
                 }
+                Basis::Volume(new_size) => {
+                    if let SelectedTab::Volume {
+                        raw_input_buf,
+                        parsed_input,
+                        is_input_valid,
+                    } = &mut self.tab
+                    {
+                        *raw_input_buf = NumericInput::from_str(&new_size.0.to_string());
+                        *parsed_input = Some(new_size);
+                        *is_input_valid = true;
+                    }
+
+                    Some(Action::BasisSelected(basis))
+                }
+                Basis::Range(new_range) => {
+                    if let SelectedTab::Range {
+                        raw_input_buf,
+                        parsed_input,
+                        is_input_valid,
+                    } = &mut self.tab
+                        && *parsed_input != Some(new_range)
+                    {
+                        *raw_input_buf = NumericInput::default();
+                        *parsed_input = None;
+                        *is_input_valid = true;
+                    }
+
+                    Some(Action::BasisSelected(basis))
+                }
+                Basis::Renko(new_renko) => {
+                    if let SelectedTab::Renko {
+                        raw_input_buf,
+                        parsed_input,
+                        is_input_valid,
+                    } = &mut self.tab
+                        && *parsed_input != Some(new_renko.size)
+                    {
+                        *raw_input_buf = NumericInput::default();
+                        *parsed_input = None;
+                        *is_input_valid = true;
+                    }
+
+                    Some(Action::BasisSelected(basis))
+                }
             },
             Message::TicksizeSelected(new_ticksize) => {
                 if let ViewMode::TicksizeSelection {
@@ -293,6 +477,24 @@ impl Modifier {
                 }
                 None
             }
+            Message::TimeframeInputChanged(value_str) => {
+                if let SelectedTab::Timeframe {
+                    ref mut raw_input_buf,
+                    ref mut parsed_input,
+                    ref mut is_input_valid,
+                } = self.tab
+                {
+                    let timeframe_str: String = value_str
+                        .chars()
+                        .filter(char::is_ascii_alphanumeric)
+                        .collect();
+
+                    *raw_input_buf = NumericInput::from_str(&timeframe_str);
+                    *parsed_input = raw_input_buf.parse_timeframe();
+                    *is_input_valid = raw_input_buf.is_empty() || parsed_input.is_some();
+                }
+                None
+            }
             Message::TickCountInputChanged(value_str) => {
                 if let SelectedTab::TickCount {
                     ref mut raw_input_buf,
@@ -321,6 +523,60 @@ impl Modifier {
                 }
                 None
             }
+            Message::VolumeInputChanged(value_str) => {
+                if let SelectedTab::Volume {
+                    ref mut raw_input_buf,
+                    ref mut parsed_input,
+                    ref mut is_input_valid,
+                } = self.tab
+                {
+                    let decimal_value_str: String = value_str
+                        .chars()
+                        .filter(|c| c.is_ascii_digit() || *c == '.')
+                        .collect();
+
+                    *raw_input_buf = NumericInput::from_str(&decimal_value_str);
+                    *parsed_input = raw_input_buf.parse_volume_size();
+                    *is_input_valid = raw_input_buf.is_empty() || parsed_input.is_some();
+                }
+                None
+            }
+            Message::RangeInputChanged(value_str) => {
+                if let SelectedTab::Range {
+                    ref mut raw_input_buf,
+                    ref mut parsed_input,
+                    ref mut is_input_valid,
+                } = self.tab
+                {
+                    let numeric_value_str: String =
+                        value_str.chars().filter(char::is_ascii_digit).collect();
+
+                    *raw_input_buf = NumericInput::from_str(&numeric_value_str);
+                    *parsed_input = raw_input_buf.parse_range_ticks();
+                    *is_input_valid = raw_input_buf.is_empty()
+                        || parsed_input
+                            .is_some_and(|r| r.0 >= RANGE_TICKS_MIN && r.0 <= RANGE_TICKS_MAX);
+                }
+                None
+            }
+            Message::RenkoInputChanged(value_str) => {
+                if let SelectedTab::Renko {
+                    ref mut raw_input_buf,
+                    ref mut parsed_input,
+                    ref mut is_input_valid,
+                } = self.tab
+                {
+                    let numeric_value_str: String =
+                        value_str.chars().filter(char::is_ascii_digit).collect();
+
+                    *raw_input_buf = NumericInput::from_str(&numeric_value_str);
+                    *parsed_input = raw_input_buf.parse_u16();
+                    *is_input_valid = raw_input_buf.is_empty()
+                        || parsed_input
+                            .is_some_and(|size| (RENKO_BOX_MIN..=RENKO_BOX_MAX).contains(&size));
+                }
+                None
+            }
         }
     }
 
@@ -363,16 +619,8 @@ impl Modifier {
                 };
 
                 if selected_basis.is_some() {
-                    let (timeframe_tab_is_selected, tick_count_tab_is_selected) = match self.tab {
-                        SelectedTab::Timeframe => (true, false),
-                        SelectedTab::TickCount { .. } => (false, true),
-                    };
-
-                    let tabs_row = {
+                    let tabs_row: Element<'a, Message> = {
                         if allows_tick_basis {
-                            let is_timeframe_selected =
-                                matches!(selected_basis, Some(Basis::Time(_)));
-
                             let tab_button =
                                 |content: iced::widget::text::Text<'a>,
                                  msg: Option<Message>,
@@ -400,51 +648,85 @@ impl Modifier {
                                     }
                                 };
 
-                            row![
-                                tab_button(
-                                    text("Timeframe"),
-                                    if timeframe_tab_is_selected {
-                                        None
-                                    } else {
-                                        Some(Message::TabSelected(SelectedTab::Timeframe))
-                                    },
-                                    !timeframe_tab_is_selected,
-                                    is_timeframe_selected,
-                                ),
-                                tab_button(
-                                    text("Ticks"),
-                                    if tick_count_tab_is_selected {
-                                        None
-                                    } else {
-                                        let tick_count_tab = match self.tab {
-                                            SelectedTab::TickCount {
-                                                raw_input_buf,
-                                                parsed_input,
-                                                is_input_valid,
-                                            } => SelectedTab::TickCount {
-                                                raw_input_buf,
-                                                parsed_input,
-                                                is_input_valid,
-                                            },
-                                            _ => SelectedTab::TickCount {
-                                                raw_input_buf: NumericInput::default(),
-                                                parsed_input: None,
-                                                is_input_valid: true,
-                                            },
-                                        };
-                                        Some(Message::TabSelected(tick_count_tab))
-                                    },
-                                    !tick_count_tab_is_selected,
-                                    !is_timeframe_selected,
-                                ),
+                            let basis_tab = |label: &'static str, blank_tab: SelectedTab| {
+                                let is_tab_selected = std::mem::discriminant(&self.tab)
+                                    == std::mem::discriminant(&blank_tab);
+                                let holds_selected_basis =
+                                    selected_basis.is_some_and(|basis| blank_tab.holds(basis));
+
+                                let msg = if is_tab_selected {
+                                    None
+                                } else {
+                                    let tab = match selected_basis {
+                                        Some(basis) if holds_selected_basis => {
+                                            SelectedTab::for_basis(basis)
+                                        }
+                                        _ => blank_tab,
+                                    };
+                                    Some(Message::TabSelected(tab))
+                                };
+
+                                tab_button(text(label), msg, !is_tab_selected, holds_selected_basis)
+                            };
+
+                            let blank_input = NumericInput::default();
+
+                            column![
+                                row![
+                                    basis_tab(
+                                        "Timeframe",
+                                        SelectedTab::Timeframe {
+                                            raw_input_buf: blank_input,
+                                            parsed_input: None,
+                                            is_input_valid: true,
+                                        },
+                                    ),
+                                    basis_tab(
+                                        "Ticks",
+                                        SelectedTab::TickCount {
+                                            raw_input_buf: blank_input,
+                                            parsed_input: None,
+                                            is_input_valid: true,
+                                        },
+                                    ),
+                                ]
+                                .spacing(4),
+                                row![
+                                    basis_tab(
+                                        "Volume",
+                                        SelectedTab::Volume {
+                                            raw_input_buf: blank_input,
+                                            parsed_input: None,
+                                            is_input_valid: true,
+                                        },
+                                    ),
+                                    basis_tab(
+                                        "Range",
+                                        SelectedTab::Range {
+                                            raw_input_buf: blank_input,
+                                            parsed_input: None,
+                                            is_input_valid: true,
+                                        },
+                                    ),
+                                    basis_tab(
+                                        "Renko",
+                                        SelectedTab::Renko {
+                                            raw_input_buf: blank_input,
+                                            parsed_input: None,
+                                            is_input_valid: true,
+                                        },
+                                    ),
+                                ]
+                                .spacing(4),
                             ]
                             .spacing(4)
+                            .into()
                         } else {
                             let text_content = match kind {
                                 ModifierKind::Comparison(_) => "Timeframe",
                                 _ => "Aggregation",
                             };
-                            row![text(text_content).size(13)]
+                            row![text(text_content).size(13)].into()
                         }
                     };
 
@@ -454,22 +736,40 @@ impl Modifier {
                 }
 
                 match self.tab {
-                    SelectedTab::Timeframe => {
+                    SelectedTab::Timeframe {
+                        raw_input_buf,
+                        parsed_input,
+                        is_input_valid,
+                    } => {
                         let selected_tf = match selected_basis {
                             Some(Basis::Time(tf)) => Some(tf),
                             _ => None,
                         };
 
+                        let custom_input = numeric_input_box::<_, Message>(
+                            "Custom: ",
+                            "e.g. 10m, 6h, 3d",
+                            &raw_input_buf.to_display_string(),
+                            is_input_valid,
+                            Message::TimeframeInputChanged,
+                            parsed_input.map(|tf| Message::BasisSelected(tf.into())),
+                        );
+
                         if allows_tick_basis {
+                            let timeframes: Vec<Timeframe> = Timeframe::SUB_MINUTE
+                                .into_iter()
+                                .chain(Timeframe::KLINE)
+                                .collect();
                             let kline_timeframe_grid = modifiers_grid(
-                                &Timeframe::KLINE,
+                                &timeframes,
                                 selected_tf,
                                 |tf| Message::BasisSelected(tf.into()),
                                 &create_button,
-                                3,
+                                4,
                             );
-                            basis_selection_column =
-                                basis_selection_column.push(kline_timeframe_grid);
+                            basis_selection_column = basis_selection_column
+                                .push(custom_input)
+                                .push(kline_timeframe_grid);
                         } else if let Some(info) = ticker_info {
                             match kind {
                                 ModifierKind::Comparison(_) => {
@@ -480,8 +780,9 @@ impl Modifier {
                                         &create_button,
                                         3,
                                     );
-                                    basis_selection_column =
-                                        basis_selection_column.push(kline_timeframe_grid);
+                                    basis_selection_column = basis_selection_column
+                                        .push(custom_input)
+                                        .push(kline_timeframe_grid);
                                 }
                                 ModifierKind::Heatmap(_, _) => {
                                     let heatmap_timeframes: Vec<Timeframe> = Timeframe::HEATMAP
@@ -516,7 +817,7 @@ impl Modifier {
                         };
 
                         let tick_count_grid = modifiers_grid(
-                            &data::aggr::TickCount::ALL,
+                            &TickCount::ALL,
                             selected_tick_count,
                             |tc| Message::BasisSelected(Basis::Tick(tc)),
                             &create_button,
@@ -540,6 +841,120 @@ impl Modifier {
                         basis_selection_column = basis_selection_column.push(custom_input);
                         basis_selection_column = basis_selection_column.push(tick_count_grid);
                     }
+                    SelectedTab::Volume {
+                        raw_input_buf,
+                        parsed_input,
+                        is_input_valid,
+                    } => {
+                        let size_input = numeric_input_box::<_, Message>(
+                            "Size: ",
+                            "e.g. 10, 0.5",
+                            &raw_input_buf.to_display_string(),
+                            is_input_valid,
+                            Message::VolumeInputChanged,
+                            parsed_input.map(|size| Message::BasisSelected(Basis::Volume(size))),
+                        );
+
+                        basis_selection_column = basis_selection_column
+                            .push(size_input)
+                            .push(text("Traded quantity per bar, in base units").size(11));
+                    }
+                    SelectedTab::Range {
+                        raw_input_buf,
+                        parsed_input,
+                        is_input_valid,
+                    } => {
+                        let selected_range = match selected_basis {
+                            Some(Basis::Range(range)) => Some(range),
+                            _ => None,
+                        };
+
+                        let range_grid = modifiers_grid(
+                            &RangeTicks::ALL,
+                            selected_range,
+                            |range| Message::BasisSelected(Basis::Range(range)),
+                            &create_button,
+                            3,
+                        );
+
+                        let custom_input = {
+                            let range_to_submit = parsed_input
+                                .filter(|r| r.0 >= RANGE_TICKS_MIN && r.0 <= RANGE_TICKS_MAX);
+
+                            numeric_input_box::<_, Message>(
+                                "Custom: ",
+                                &format!("{}-{}", RANGE_TICKS_MIN, RANGE_TICKS_MAX),
+                                &raw_input_buf.to_display_string(),
+                                is_input_valid,
+                                Message::RangeInputChanged,
+                                range_to_submit
+                                    .map(|range| Message::BasisSelected(Basis::Range(range))),
+                            )
+                        };
+
+                        basis_selection_column = basis_selection_column
+                            .push(custom_input)
+                            .push(range_grid)
+                            .push(text("High-low span per bar, in ticks").size(11));
+                    }
+                    SelectedTab::Renko {
+                        raw_input_buf,
+                        parsed_input,
+                        is_input_valid,
+                    } => {
+                        let selected_renko = match selected_basis {
+                            Some(Basis::Renko(renko)) => Some(renko),
+                            _ => None,
+                        };
+                        let reversal = selected_renko
+                            .map_or(RenkoBox::DEFAULT_REVERSAL, |renko| renko.reversal);
+                        let size = selected_renko.map_or(RenkoBox::SIZES[1], |renko| renko.size);
+
+                        let size_grid = modifiers_grid(
+                            &RenkoBox::SIZES,
+                            selected_renko.map(|renko| renko.size),
+                            |size| {
+                                Message::BasisSelected(Basis::Renko(RenkoBox { size, reversal }))
+                            },
+                            &create_button,
+                            3,
+                        );
+
+                        let reversal_grid = modifiers_grid(
+                            &RENKO_REVERSALS,
+                            selected_renko.map(|renko| renko.reversal),
+                            |reversal| {
+                                Message::BasisSelected(Basis::Renko(RenkoBox { size, reversal }))
+                            },
+                            &create_button,
+                            3,
+                        );
+
+                        let custom_input = {
+                            let size_to_submit = parsed_input
+                                .filter(|size| *size >= RENKO_BOX_MIN && *size <= RENKO_BOX_MAX);
+
+                            numeric_input_box::<_, Message>(
+                                "Box: ",
+                                &format!("{}-{}", RENKO_BOX_MIN, RENKO_BOX_MAX),
+                                &raw_input_buf.to_display_string(),
+                                is_input_valid,
+                                Message::RenkoInputChanged,
+                                size_to_submit.map(|size| {
+                                    Message::BasisSelected(Basis::Renko(RenkoBox {
+                                        size,
+                                        reversal,
+                                    }))
+                                }),
+                            )
+                        };
+
+                        basis_selection_column = basis_selection_column
+                            .push(custom_input)
+                            .push(size_grid)
+                            .push(text("Box size in ticks, reversal in boxes").size(11))
+                            .push(reversal_grid);
+                    }
                 }
 
                 container(scrollable::Scrollable::with_direction(
@@ -723,18 +1138,7 @@ impl From<&ModifierKind> for SelectedTab {
             | ModifierKind::Footprint(basis, _)
             | ModifierKind::Heatmap(basis, _)
             | ModifierKind::Orderbook(basis, _)
-            | ModifierKind::Comparison(basis) => match basis {
-                Basis::Time(_) => SelectedTab::Timeframe,
-                Basis::Tick(tc) => SelectedTab::TickCount {
-                    raw_input_buf: if tc.is_custom() {
-                        NumericInput::from_tick_count(*tc)
-                    } else {
-                        NumericInput::default()
-                    },
-                    parsed_input: if tc.is_custom() { Some(*tc) } else { None },
-                    is_input_valid: true,
-                },
-            },
+            | ModifierKind::Comparison(basis) => SelectedTab::for_basis(*basis),
         }
     }
 }
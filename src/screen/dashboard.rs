@@ -10,7 +10,7 @@ use crate::{
     chart,
     screen::dashboard::tickers_table::TickersTable,
     style,
-    widget::toast::Toast,
+    widget::toast::{self, Toast},
     window::{self, Window},
 };
 use data::{
@@ -18,13 +18,16 @@ use data::{
     layout::{WindowSpec, pane::ContentKind},
 };
 use exchange::{
-    Kline, PushFrequency, StreamPairKind, TickMultiplier, TickerInfo, Timeframe, Trade,
+    Kline, OpenInterest, PushFrequency, StreamPairKind, TickMultiplier, Ticker, TickerInfo,
+    Timeframe, Trade,
     adapter::{
         self, AdapterError, Exchange, PersistStreamKind, ResolvedStream, StreamConfig, StreamKind,
-        StreamTicksize, UniqueStreams, binance, bybit, hyperliquid, okex,
+        StreamTicksize, UniqueStreams,
+        binance::{self, archive::DownloadProgress},
+        bybit, hyperliquid, local, okex,
     },
     depth::Depth,
-    fetcher::{FetchRange, FetchedData},
+    fetcher::{FetchRange, FetchedData, InfoKind},
 };
 
 use iced::{
@@ -35,8 +38,17 @@ use iced::{
         pane_grid::{self, Configuration},
     },
 };
-use iced_futures::futures::TryFutureExt;
-use std::{collections::HashMap, path::PathBuf, time::Instant, vec};
+use iced_futures::futures::{StreamExt, TryFutureExt, channel::mpsc, future, stream};
+use rustc_hash::FxHashMap;
+use std::{
+    collections::HashMap,
+    path::PathBuf,
+    time::{Duration, Instant},
+    vec,
+};
+
+/// How often the open interest of charted perps is recorded
+const OI_POLL_INTERVAL: Duration = Duration::from_secs(60);
 
 #[derive(Debug, Clone)]
 pub enum Message {
@@ -60,6 +72,7 @@ pub struct Dashboard {
     pub popout: HashMap<window::Id, (pane_grid::State<pane::State>, WindowSpec)>,
     pub streams: UniqueStreams,
     layout_id: uuid::Uuid,
+    last_oi_poll: Option<Instant>,
 }
 
 impl Default for Dashboard {
@@ -70,6 +83,7 @@ impl Default for Dashboard {
             streams: UniqueStreams::default(),
             popout: HashMap::new(),
             layout_id: uuid::Uuid::new_v4(),
+            last_oi_poll: None,
         }
     }
 }
@@ -87,6 +101,9 @@ pub enum Event {
         pane_id: uuid::Uuid,
         streams: Vec<PersistStreamKind>,
     },
+    /// Linked panes on other venues switch to their own listing of the market,
+    /// which needs the tickers table to resolve
+    SwitchTickersInGroup(TickerInfo),
 }
 
 impl Dashboard {
@@ -136,6 +153,7 @@ impl Dashboard {
             streams: UniqueStreams::default(),
             popout,
             layout_id,
+            last_oi_poll: None,
         }
     }
 
@@ -273,6 +291,9 @@ impl Dashboard {
                                             ) | (
                                                 data::layout::pane::VisualConfig::TimeAndSales(_),
                                                 pane::Content::TimeAndSales(_)
+                                            ) | (
+                                                data::layout::pane::VisualConfig::Ladder(_),
+                                                pane::Content::Ladder(_)
                                             ) | (
                                                 data::layout::pane::VisualConfig::Comparison(_),
                                                 pane::Content::Comparison(_)
@@ -326,8 +347,12 @@ impl Dashboard {
                         state.link_group = group;
                         state.modal = None;
 
+                        // a pane already on the same market elsewhere stays on its venue
                         if let Some(ticker_info) = maybe_ticker_info
                             && state.stream_pair() != Some(ticker_info)
+                            && !state
+                                .stream_pair()
+                                .is_some_and(|current| same_underlying(&current, &ticker_info))
                         {
                             let pane_id = state.unique_id();
                             let content_kind = state.content.kind();
@@ -368,11 +393,30 @@ impl Dashboard {
                             )
                             .chain(self.refresh_streams(main_window.id)),
                             pane::Effect::SwitchTickersInGroup(ticker_info) => {
-                                self.switch_tickers_in_group(main_window.id, ticker_info)
+                                return (
+                                    Task::none(),
+                                    Some(Event::SwitchTickersInGroup(ticker_info)),
+                                );
                             }
                             pane::Effect::FocusWidget(id) => {
                                 return (iced::widget::operation::focus(id), None);
                             }
+                            pane::Effect::ExportData {
+                                table,
+                                format,
+                                path,
+                            } => Task::perform(
+                                async move { table.write(format, &path).map(|()| path) },
+                                |result| {
+                                    Message::Notification(match result {
+                                        Ok(path) => Toast::new(toast::Notification::Info(format!(
+                                            "Exported to {}",
+                                            path.display()
+                                        ))),
+                                        Err(err) => Toast::error(format!("Export failed: {err}")),
+                                    })
+                                },
+                            ),
                         };
                         return (task, None);
                     }
@@ -737,10 +781,13 @@ impl Dashboard {
         )))
     }
 
+    /// Linked panes on the focused pane's venue switch to `ticker_info`, the ones on other
+    /// venues to their own listing of the same market when `tickers_info` has one
     pub fn switch_tickers_in_group(
         &mut self,
         main_window: window::Id,
         ticker_info: TickerInfo,
+        tickers_info: &FxHashMap<Ticker, Option<TickerInfo>>,
     ) -> Task<Message> {
         if self.focus.is_none()
             && self.panes.len() == 1
@@ -755,21 +802,34 @@ impl Dashboard {
         });
 
         if let Some(group) = link_group {
-            let pane_infos: Vec<(window::Id, pane_grid::Pane, ContentKind)> = self
+            let focused_exchange = self
+                .focus
+                .and_then(|(window, pane)| self.get_pane(main_window, window, pane))
+                .and_then(|state| state.stream_pair())
+                .map(|info| info.exchange());
+
+            let pane_infos: Vec<(window::Id, pane_grid::Pane, ContentKind, TickerInfo)> = self
                 .iter_all_panes_mut(main_window)
                 .filter_map(|(window, pane, state)| {
-                    if state.link_group == Some(group) {
-                        Some((window, pane, state.content.kind()))
-                    } else {
-                        None
+                    if state.link_group != Some(group) {
+                        return None;
                     }
+
+                    let target = match state.stream_pair().map(|info| info.exchange()) {
+                        Some(exchange) if Some(exchange) != focused_exchange => ticker_info
+                            .equivalent_on(exchange, tickers_info.values().flatten())
+                            .unwrap_or(ticker_info),
+                        _ => ticker_info,
+                    };
+
+                    Some((window, pane, state.content.kind(), target))
                 })
                 .collect();
 
             let tasks: Vec<Task<Message>> = pane_infos
                 .iter()
-                .map(|(window, pane, content_kind)| {
-                    self.init_pane(main_window, *window, *pane, ticker_info, *content_kind)
+                .map(|(window, pane, content_kind, target)| {
+                    self.init_pane(main_window, *window, *pane, *target, *content_kind)
                 })
                 .collect();
 
@@ -816,12 +876,16 @@ impl Dashboard {
         stream_type: StreamKind,
     ) -> Task<Message> {
         match data {
-            FetchedData::Trades { batch, until_time } => {
+            FetchedData::Trades {
+                batch,
+                until_time,
+                req_id,
+            } => {
                 let last_trade_time = batch.last().map_or(0, |trade| trade.time);
 
                 if last_trade_time < until_time {
                     if let Err(reason) =
-                        self.insert_fetched_trades(main_window, pane_id, &batch, false)
+                        self.insert_fetched_trades(main_window, pane_id, req_id, &batch, false)
                     {
                         return self.handle_error(Some(pane_id), &reason, main_window);
                     }
@@ -832,9 +896,13 @@ impl Dashboard {
                         .copied()
                         .collect::<Vec<_>>();
 
-                    if let Err(reason) =
-                        self.insert_fetched_trades(main_window, pane_id, &filtered_batch, true)
-                    {
+                    if let Err(reason) = self.insert_fetched_trades(
+                        main_window,
+                        pane_id,
+                        req_id,
+                        &filtered_batch,
+                        true,
+                    ) {
                         return self.handle_error(Some(pane_id), &reason, main_window);
                     }
                 }
@@ -854,11 +922,11 @@ impl Dashboard {
             }
             FetchedData::OI { data, req_id } => {
                 if let Some(pane_state) = self.get_mut_pane_state_by_uuid(main_window, pane_id) {
-                    pane_state.status = pane::Status::Ready;
-
-                    if let StreamKind::Kline { .. } = stream_type {
-                        pane_state.insert_hist_oi(req_id, &data);
+                    // polled samples arrive without a request, nothing was loading
+                    if req_id.is_some() {
+                        pane_state.status = pane::Status::Ready;
                     }
+                    pane_state.insert_hist_oi(req_id, &data);
                 }
             }
         }
@@ -870,6 +938,7 @@ impl Dashboard {
         &mut self,
         main_window: window::Id,
         pane_id: uuid::Uuid,
+        req_id: Option<uuid::Uuid>,
         trades: &[Trade],
         is_batches_done: bool,
     ) -> Result<(), DashboardError> {
@@ -895,7 +964,7 @@ impl Dashboard {
         match &mut pane_state.content {
             pane::Content::Kline { chart, .. } => {
                 if let Some(c) = chart {
-                    c.insert_raw_trades(trades.to_owned(), is_batches_done);
+                    c.insert_raw_trades(req_id, trades.to_owned(), is_batches_done);
 
                     if is_batches_done {
                         pane_state.status = pane::Status::Ready;
@@ -907,6 +976,14 @@ impl Dashboard {
                     ))
                 }
             }
+            pane::Content::TimeAndSales(Some(panel)) => {
+                panel.insert_buffer(trades);
+
+                if is_batches_done {
+                    pane_state.status = pane::Status::Ready;
+                }
+                Ok(())
+            }
             _ => Err(DashboardError::Unknown(
                 "No matching chart found for fetched trades".to_string(),
             )),
@@ -926,7 +1003,9 @@ impl Dashboard {
                 if pane_state.matches_stream(stream) {
                     match &mut pane_state.content {
                         pane::Content::Kline { chart: Some(c), .. } => {
-                            c.update_latest_kline(kline);
+                            if let Some((_, timeframe)) = stream.as_kline_stream() {
+                                c.update_latest_kline(kline, timeframe);
+                            }
                         }
                         pane::Content::Comparison(Some(c)) => {
                             c.update_latest_kline(&stream.ticker_info(), kline);
@@ -1021,7 +1100,15 @@ impl Dashboard {
                         ));
                     }
                 },
-                Some(pane::Action::Panel(_action)) => {}
+                Some(pane::Action::Panel(action)) => match action {
+                    panel::Action::RequestFetch(reqs) => {
+                        tasks.push(request_fetch_many(
+                            state,
+                            layout_id,
+                            reqs.into_iter().map(|r| (r.req_id, r.fetch, r.stream)),
+                        ));
+                    }
+                },
                 Some(pane::Action::ResolveStreams(streams)) => {
                     tasks.push(Task::done(Message::ResolveStreams(
                         state.unique_id(),
@@ -1040,6 +1127,54 @@ impl Dashboard {
                 None => {}
             });
 
+        if self
+            .last_oi_poll
+            .is_none_or(|last| now.duration_since(last) >= OI_POLL_INTERVAL)
+        {
+            self.last_oi_poll = Some(now);
+            tasks.push(self.poll_open_interest(main_window));
+        }
+
+        Task::batch(tasks)
+    }
+
+    /// Records the current open interest of every ticker a chart shows it for,
+    /// once per ticker however many panes show it
+    fn poll_open_interest(&mut self, main_window: window::Id) -> Task<Message> {
+        let layout_id = self.layout_id;
+        let mut panes_by_ticker: FxHashMap<Ticker, Vec<(uuid::Uuid, StreamKind)>> =
+            FxHashMap::default();
+
+        self.iter_all_panes_mut(main_window)
+            .for_each(|(_window_id, _pane, state)| {
+                if let Some(stream) = state.open_interest_stream() {
+                    panes_by_ticker
+                        .entry(stream.ticker_info().ticker)
+                        .or_default()
+                        .push((state.unique_id(), stream));
+                }
+            });
+
+        let tasks = panes_by_ticker.into_iter().map(|(ticker, panes)| {
+            Task::future(poll_oi(ticker)).then(move |result| match result {
+                Ok(oi) => Task::batch(panes.iter().map(|(pane_id, stream)| {
+                    Task::done(Message::DistributeFetchedData {
+                        layout_id,
+                        pane_id: *pane_id,
+                        stream: *stream,
+                        data: FetchedData::OI {
+                            data: vec![oi],
+                            req_id: None,
+                        },
+                    })
+                })),
+                Err(err) => {
+                    log::warn!("Failed to poll open interest of {ticker}: {err}");
+                    Task::none()
+                }
+            })
+        });
+
         Task::batch(tasks)
     }
 
@@ -1143,22 +1278,22 @@ fn request_fetch(
             }
         }
         FetchRange::OpenInterest(from, to) => {
-            let kline_stream = {
-                if let Some(s) = stream {
-                    Some((s, pane_id))
-                } else {
-                    state.streams.find_ready_map(|stream| {
-                        if let StreamKind::Kline { .. } = stream {
-                            Some((*stream, pane_id))
-                        } else {
-                            None
-                        }
+            // tick charts have no kline stream, their trade stream names the ticker
+            let oi_stream = stream.or_else(|| {
+                state
+                    .streams
+                    .find_ready_map(|stream| {
+                        matches!(stream, StreamKind::Kline { .. }).then_some(*stream)
                     })
-                }
-            };
+                    .or_else(|| {
+                        state.streams.find_ready_map(|stream| {
+                            matches!(stream, StreamKind::DepthAndTrades { .. }).then_some(*stream)
+                        })
+                    })
+            });
 
-            if let Some((stream, pane_uid)) = kline_stream {
-                return oi_fetch_task(layout_id, pane_uid, stream, Some(req_id), Some((from, to)));
+            if let Some(stream) = oi_stream {
+                return oi_fetch_task(layout_id, pane_id, stream, Some(req_id), Some((from, to)));
             }
         }
         FetchRange::Trades(from_time, to_time) => {
@@ -1171,6 +1306,16 @@ fn request_fetch(
             });
 
             if let Some((ticker_info, pane_id, stream)) = trade_info {
+                if ticker_info.exchange() == Exchange::Local {
+                    return local_trades_fetch_task(
+                        layout_id,
+                        pane_id,
+                        stream,
+                        req_id,
+                        (from_time, to_time),
+                    );
+                }
+
                 let is_binance = matches!(
                     ticker_info.exchange(),
                     Exchange::BinanceSpot | Exchange::BinanceLinear | Exchange::BinanceInverse
@@ -1181,17 +1326,27 @@ fn request_fetch(
 
                     let (task, handle) = Task::sip(
                         fetch_trades_batched(ticker_info, from_time, to_time, data_path),
-                        move |batch| {
-                            let data = FetchedData::Trades {
-                                batch,
-                                until_time: to_time,
-                            };
-                            Message::DistributeFetchedData {
-                                layout_id,
-                                pane_id,
-                                data,
-                                stream,
+                        move |progress| match progress {
+                            TradeFetchProgress::Batch(batch) => {
+                                let data = FetchedData::Trades {
+                                    batch,
+                                    until_time: to_time,
+                                    req_id: Some(req_id),
+                                };
+                                Message::DistributeFetchedData {
+                                    layout_id,
+                                    pane_id,
+                                    data,
+                                    stream,
+                                }
                             }
+                            TradeFetchProgress::Download(progress) => Message::ChangePaneStatus(
+                                pane_id,
+                                pane::Status::Loading(InfoKind::DownloadingArchive {
+                                    downloaded: progress.downloaded,
+                                    total: progress.total,
+                                }),
+                            ),
                         },
                         move |result| match result {
                             Ok(()) => Message::ChangePaneStatus(pane_id, pane::Status::Ready),
@@ -1242,29 +1397,121 @@ fn oi_fetch_task(
         pane::Status::Loading(exchange::fetcher::InfoKind::FetchingOI),
     ));
 
-    let fetch_task = match stream {
-        StreamKind::Kline {
-            ticker_info,
-            timeframe,
-        } => Task::perform(
-            adapter::fetch_open_interest(ticker_info.ticker, timeframe, range)
-                .map_err(|err| format!("{err}")),
-            move |result| match result {
-                Ok(oi) => {
-                    let data = FetchedData::OI { data: oi, req_id };
-                    Message::DistributeFetchedData {
-                        layout_id,
-                        pane_id,
-                        data,
-                        stream,
-                    }
-                }
-                Err(err) => Message::ErrorOccurred(Some(pane_id), DashboardError::Fetch(err)),
-            },
-        ),
-        _ => Task::none(),
+    // venues only report OI at a few periods, bars get the samples closest to them
+    let period = match stream {
+        StreamKind::Kline { timeframe, .. } => timeframe.open_interest_period(),
+        StreamKind::DepthAndTrades { .. } => Timeframe::M5,
     };
 
+    let fetch_task = Task::perform(
+        fetch_oi_with_recorded(stream.ticker_info().ticker, period, range),
+        move |result| match result {
+            Ok(oi) => {
+                let data = FetchedData::OI { data: oi, req_id };
+                Message::DistributeFetchedData {
+                    layout_id,
+                    pane_id,
+                    data,
+                    stream,
+                }
+            }
+            Err(err) => Message::ErrorOccurred(Some(pane_id), DashboardError::Fetch(err)),
+        },
+    );
+
+    update_status.chain(fetch_task)
+}
+
+/// History merged with the samples recorded locally, which are all there is for venues
+/// without history and for ranges past their retention. History gets recorded as well
+async fn fetch_oi_with_recorded(
+    ticker: Ticker,
+    period: Timeframe,
+    range: Option<(u64, u64)>,
+) -> Result<Vec<OpenInterest>, String> {
+    let recorded = blocking(move || data::open_interest::load(ticker, range))
+        .await
+        .unwrap_or_else(|err| {
+            log::warn!("Failed to read recorded open interest of {ticker}: {err}");
+            vec![]
+        });
+
+    if !adapter::has_oi_history(ticker.exchange) {
+        return Ok(recorded);
+    }
+
+    match adapter::fetch_open_interest(ticker, period, range).await {
+        Ok(history) => {
+            let to_record = history.clone();
+            if let Err(err) =
+                blocking(move || data::open_interest::record(ticker, &to_record)).await
+            {
+                log::warn!("Failed to record open interest of {ticker}: {err}");
+            }
+
+            Ok(data::open_interest::merge(&recorded, &history))
+        }
+        Err(err) if !recorded.is_empty() => {
+            log::warn!("Showing recorded open interest of {ticker} only: {err}");
+            Ok(recorded)
+        }
+        Err(err) => Err(format!("{err}")),
+    }
+}
+
+async fn poll_oi(ticker: Ticker) -> Result<OpenInterest, AdapterError> {
+    let oi = adapter::fetch_current_oi(ticker).await?;
+
+    if let Err(err) = blocking(move || data::open_interest::record(ticker, &[oi])).await {
+        log::warn!("Failed to record open interest of {ticker}: {err}");
+    }
+    Ok(oi)
+}
+
+/// Runs file work on the blocking pool, off the async executor
+async fn blocking<T: Send + 'static>(
+    work: impl FnOnce() -> std::io::Result<T> + Send + 'static,
+) -> std::io::Result<T> {
+    tokio::task::spawn_blocking(work)
+        .await
+        .map_err(std::io::Error::other)?
+}
+
+/// Imported trades are served in a single batch, so it's always the last one
+fn local_trades_fetch_task(
+    layout_id: uuid::Uuid,
+    pane_id: uuid::Uuid,
+    stream: StreamKind,
+    req_id: uuid::Uuid,
+    (from, to): (u64, u64),
+) -> Task<Message> {
+    let update_status = Task::done(Message::ChangePaneStatus(
+        pane_id,
+        pane::Status::Loading(InfoKind::FetchingTrades(0)),
+    ));
+
+    let fetch_task = Task::perform(
+        local::fetch_trades(stream.ticker_info(), from, to).map_err(|err| err.to_user_message()),
+        move |result| match result {
+            Ok(batch) => {
+                let data = FetchedData::Trades {
+                    until_time: batch.last().map_or(0, |trade| trade.time),
+                    batch,
+                    req_id: Some(req_id),
+                };
+                Message::DistributeFetchedData {
+                    layout_id,
+                    pane_id,
+                    data,
+                    stream,
+                }
+            }
+            Err(err) => {
+                Message::ErrorOccurred(Some(pane_id), DashboardError::Fetch(err.to_string()))
+            }
+        },
+    );
+
     update_status.chain(fetch_task)
 }
 
@@ -1311,28 +1558,52 @@ fn kline_fetch_task(
     update_status.chain(fetch_task)
 }
 
+#[derive(Debug, Clone)]
+pub enum TradeFetchProgress {
+    Download(DownloadProgress),
+    Batch(Vec<Trade>),
+}
+
 pub fn fetch_trades_batched(
     ticker_info: TickerInfo,
     from_time: u64,
     to_time: u64,
     data_path: PathBuf,
-) -> impl Straw<(), Vec<Trade>, AdapterError> {
+) -> impl Straw<(), TradeFetchProgress, AdapterError> {
     sipper(async move |mut progress| {
         let mut latest_trade_t = from_time;
 
         while latest_trade_t < to_time {
-            match binance::fetch_trades(ticker_info, latest_trade_t, data_path.clone()).await {
-                Ok(batch) => {
-                    if batch.is_empty() {
-                        break;
-                    }
+            let (download_tx, download_rx) = mpsc::channel(16);
+            let (batch_tx, mut batch_rx) = tokio::sync::mpsc::channel(2);
+
+            let fetch = binance::fetch_trades(
+                ticker_info,
+                latest_trade_t,
+                to_time,
+                data_path.clone(),
+                Some(download_tx),
+                batch_tx,
+            );
 
-                    latest_trade_t = batch.last().map_or(latest_trade_t, |trade| trade.time);
+            let forward = async {
+                let batches =
+                    stream::poll_fn(|cx| batch_rx.poll_recv(cx)).map(TradeFetchProgress::Batch);
+                let mut updates =
+                    stream::select(download_rx.map(TradeFetchProgress::Download), batches);
 
-                    let () = progress.send(batch).await;
+                while let Some(update) = updates.next().await {
+                    progress.send(update).await;
                 }
-                Err(err) => return Err(err),
+            };
+
+            let (result, ()) = future::join(fetch, forward).await;
+            let complete_until = result?;
+
+            if complete_until < latest_trade_t {
+                break;
             }
+            latest_trade_t = complete_until + 1;
         }
 
         Ok(())
@@ -1372,6 +1643,8 @@ pub fn depth_subscription(
                 |cfg: &StreamConfig<TickerInfo>| okex::connect_market_stream(cfg.id, cfg.push_freq);
             Subscription::run_with(config, builder)
         }
+        // imported datasets have no live feed
+        Exchange::Local => Subscription::none(),
     }
 }
 
@@ -1401,9 +1674,16 @@ pub fn kline_subscription(
         }
         Exchange::OkexLinear | Exchange::OkexInverse | Exchange::OkexSpot => {
             let builder = |cfg: &StreamConfig<Vec<(TickerInfo, Timeframe)>>| {
-                okex::connect_kline_stream(cfg.id.clone(), cfg.market_type)
+                okex::connect_kline_stream(cfg.id.clone())
             };
             Subscription::run_with(config, builder)
         }
+        Exchange::Local => Subscription::none(),
     }
 }
+
+fn same_underlying(a: &TickerInfo, b: &TickerInfo) -> bool {
+    a.instrument()
+        .zip(b.instrument())
+        .is_some_and(|(a, b)| a.same_underlying(&b))
+}
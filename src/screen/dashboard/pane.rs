@@ -21,7 +21,7 @@ use data::{
     UserTimezone,
     chart::{
         Basis, ViewConfig,
-        indicator::{HeatmapIndicator, Indicator, KlineIndicator, UiIndicator},
+        indicator::{HeatmapIndicator, KlineIndicator, UiIndicator},
     },
     layout::pane::{ContentKind, LinkGroup, PaneSetup, Settings, VisualConfig},
 };
@@ -44,6 +44,11 @@ pub enum Effect {
     RequestFetch(FetchRequests),
     SwitchTickersInGroup(TickerInfo),
     FocusWidget(iced::widget::Id),
+    ExportData {
+        table: data::export::Table,
+        format: data::export::Format,
+        path: std::path::PathBuf,
+    },
 }
 
 #[derive(Debug, Default, Clone, PartialEq)]
@@ -86,6 +91,8 @@ pub enum Event {
     ChartInteraction(super::chart::Message),
     PanelInteraction(super::panel::Message),
     ToggleIndicator(UiIndicator),
+    IndicatorSettingsToggled(usize),
+    ConfigureIndicator(usize, UiIndicator),
     DeleteNotification(usize),
     ReorderIndicator(column_drag::DragEvent),
     ClusterKindSelected(data::chart::kline::ClusterKind),
@@ -94,6 +101,8 @@ pub enum Event {
     StreamModifierChanged(modal::stream::Message),
     ComparisonChartInteraction(super::chart::comparison::Message),
     MiniTickersListInteraction(modal::pane::mini_tickers_list::Message),
+    CancelTradeFetch,
+    ExportModal(modal::pane::export::Message),
 }
 
 pub struct State {
@@ -213,10 +222,17 @@ impl State {
                         derived_plan.basis,
                         Timeframe::M5,
                         |tf| {
-                            vec![
-                                depth_stream(&derived_plan),
-                                kline_stream(derived_plan.ticker_info, tf),
-                            ]
+                            let mut streams = vec![depth_stream(&derived_plan)];
+                            // sub-minute bars are built from the trades alone
+                            if derived_plan
+                                .ticker_info
+                                .exchange()
+                                .kline_source(tf)
+                                .is_some()
+                            {
+                                streams.push(kline_stream(derived_plan.ticker_info, tf));
+                            }
+                            streams
                         },
                         || vec![depth_stream(&derived_plan)],
                     );
@@ -235,21 +251,35 @@ impl State {
                         )
                     };
 
+                    let trades_stream = || {
+                        let depth_aggr = derived_plan
+                            .ticker_info
+                            .exchange()
+                            .stream_ticksize(None, TickMultiplier(50));
+                        let temp = PaneSetup {
+                            depth_aggr,
+                            ..derived_plan
+                        };
+                        vec![depth_stream(&temp)]
+                    };
+
                     let streams = by_basis_default(
                         derived_plan.basis,
                         Timeframe::M15,
-                        |tf| vec![kline_stream(derived_plan.ticker_info, tf)],
-                        || {
-                            let depth_aggr = derived_plan
+                        |tf| {
+                            // sub-minute bars are built from the trades alone
+                            if derived_plan
                                 .ticker_info
                                 .exchange()
-                                .stream_ticksize(None, TickMultiplier(50));
-                            let temp = PaneSetup {
-                                depth_aggr,
-                                ..derived_plan
-                            };
-                            vec![depth_stream(&temp)]
+                                .kline_source(tf)
+                                .is_some()
+                            {
+                                vec![kline_stream(derived_plan.ticker_info, tf)]
+                            } else {
+                                trades_stream()
+                            }
                         },
+                        trades_stream,
                     );
 
                     (content, streams)
@@ -321,6 +351,22 @@ impl State {
         streams
     }
 
+    /// Stream of the perp ticker whose open interest the pane's chart shows, if it shows any
+    pub fn open_interest_stream(&self) -> Option<StreamKind> {
+        let Content::Kline { indicators, .. } = &self.content else {
+            return None;
+        };
+        if !indicators
+            .iter()
+            .any(|indicator| matches!(indicator, KlineIndicator::OpenInterest { .. }))
+        {
+            return None;
+        }
+
+        self.streams
+            .find_ready_map(|stream| stream.ticker_info().is_perps().then_some(*stream))
+    }
+
     pub fn insert_hist_oi(&mut self, req_id: Option<uuid::Uuid>, oi: &[OpenInterest]) {
         match &mut self.content {
             Content::Kline { chart, .. } => {
@@ -373,7 +419,11 @@ impl State {
                         indicators,
                         ticker_info,
                         chart.kind(),
+                        chart.visual_config(),
                     );
+                    if let Some(anchors) = self.settings.vwap_anchors.get(&ticker_info.ticker) {
+                        chart.set_vwap_anchors(anchors);
+                    }
                 }
             }
             Content::Comparison(chart) => {
@@ -724,16 +774,19 @@ impl State {
                         )
                     };
 
-                    let indicator_modal = if self.modal == Some(Modal::Indicators) {
-                        Some(modal::indicators::view(
-                            id,
-                            self,
-                            indicators,
-                            self.stream_pair().map(|i| i.ticker.market_type()),
-                        ))
-                    } else {
-                        None
-                    };
+                    let indicator_modal =
+                        if let Some(Modal::Indicators { expanded, scripts }) = &self.modal {
+                            Some(modal::indicators::view(
+                                id,
+                                self,
+                                indicators,
+                                scripts,
+                                self.stream_pair().map(|i| i.ticker.market_type()),
+                                *expanded,
+                            ))
+                        } else {
+                            None
+                        };
 
                     self.compose_stack_view(
                         base,
@@ -813,23 +866,26 @@ impl State {
                     let settings_modal = || {
                         kline_cfg_view(
                             chart.study_configurator(),
-                            data::chart::kline::Config {},
+                            chart.visual_config(),
                             chart_kind,
                             id,
                             chart.basis(),
                         )
                     };
 
-                    let indicator_modal = if self.modal == Some(Modal::Indicators) {
-                        Some(modal::indicators::view(
-                            id,
-                            self,
-                            indicators,
-                            self.stream_pair().map(|i| i.ticker.market_type()),
-                        ))
-                    } else {
-                        None
-                    };
+                    let indicator_modal =
+                        if let Some(Modal::Indicators { expanded, scripts }) = &self.modal {
+                            Some(modal::indicators::view(
+                                id,
+                                self,
+                                indicators,
+                                scripts,
+                                self.stream_pair().map(|i| i.ticker.market_type()),
+                                *expanded,
+                            ))
+                        } else {
+                            None
+                        };
 
                     self.compose_stack_view(
                         base,
@@ -872,12 +928,39 @@ impl State {
             Status::Loading(exchange::fetcher::InfoKind::FetchingOI) => {
                 stream_info_element = stream_info_element.push(text("Fetching Open Interest..."));
             }
+            Status::Loading(exchange::fetcher::InfoKind::DownloadingArchive {
+                downloaded,
+                total,
+            }) => {
+                let progress = match total {
+                    Some(total) => format!(
+                        "{} / {}",
+                        data::util::format_bytes(*downloaded),
+                        data::util::format_bytes(*total)
+                    ),
+                    None => data::util::format_bytes(*downloaded),
+                };
+                stream_info_element =
+                    stream_info_element.push(text(format!("Downloading trades... {progress}")));
+            }
             Status::Stale(msg) => {
                 stream_info_element = stream_info_element.push(text(msg));
             }
             Status::Ready => {}
         }
 
+        if let Status::Loading(
+            exchange::fetcher::InfoKind::FetchingTrades(_)
+            | exchange::fetcher::InfoKind::DownloadingArchive { .. },
+        ) = &self.status
+        {
+            stream_info_element = stream_info_element.push(
+                button(text("Cancel").size(11))
+                    .on_press(Message::PaneEvent(id, Event::CancelTradeFetch))
+                    .style(|theme, status| style::button::transparent(theme, status, false)),
+            );
+        }
+
         let content = pane_grid::Content::new(body)
             .style(move |theme| style::pane_background(theme, is_focused));
 
@@ -950,6 +1033,21 @@ impl State {
                     super::chart::update(c, &msg);
                 }
                 Content::Kline { chart: Some(c), .. } => {
+                    if let super::chart::Message::AnchorPlaced(interval) = msg
+                        && let Some(time) = c.bar_time_at(interval)
+                    {
+                        let ticker = c.ticker_info().ticker;
+                        let anchors = self.settings.vwap_anchors.entry(ticker).or_default();
+
+                        // clicking an anchored bar again removes its anchor
+                        if let Some(idx) = anchors.iter().position(|t| *t == time) {
+                            anchors.remove(idx);
+                        } else {
+                            anchors.push(time);
+                            anchors.sort_unstable();
+                        }
+                        c.set_vwap_anchors(anchors);
+                    }
                     super::chart::update(c, &msg);
                 }
                 _ => {}
@@ -961,6 +1059,15 @@ impl State {
             },
             Event::ToggleIndicator(ind) => {
                 self.content.toggle_indicator(ind);
+                self.collapse_indicator_settings();
+            }
+            Event::IndicatorSettingsToggled(idx) => {
+                if let Some(Modal::Indicators { expanded, .. }) = &mut self.modal {
+                    *expanded = (*expanded != Some(idx)).then_some(idx);
+                }
+            }
+            Event::ConfigureIndicator(idx, ind) => {
+                self.content.configure_indicator(idx, ind);
             }
             Event::DeleteNotification(idx) => {
                 if idx < self.notifications.len() {
@@ -969,6 +1076,27 @@ impl State {
             }
             Event::ReorderIndicator(e) => {
                 self.content.reorder_indicators(&e);
+                self.collapse_indicator_settings();
+            }
+            Event::CancelTradeFetch => {
+                if let Content::Kline { chart: Some(c), .. } = &mut self.content {
+                    c.cancel_trade_fetch();
+                }
+                self.status = Status::Ready;
+            }
+            Event::ExportModal(message) => {
+                if let Some(Modal::Export(export)) = &mut self.modal
+                    && let Some(action) = export.update(message)
+                {
+                    let modal::pane::export::Action::Export {
+                        dataset,
+                        format,
+                        scope,
+                    } = action;
+
+                    self.modal = None;
+                    return self.export_data(dataset, format, scope);
+                }
             }
             Event::ClusterKindSelected(kind) => {
                 if let Content::Kline {
@@ -1059,6 +1187,12 @@ impl State {
                                     effect = Some(Effect::RefreshStreams);
                                 }
                             }
+                            modal::stream::Action::BasisSelected(new_basis)
+                                if !new_basis.is_time()
+                                    && !matches!(self.content, Content::Kline { .. }) =>
+                            {
+                                log::warn!("{new_basis} basis picked for a time only pane");
+                            }
                             modal::stream::Action::BasisSelected(new_basis) => {
                                 modifier.update_kind_with_basis(new_basis);
                                 self.settings.selected_basis = Some(new_basis);
@@ -1086,7 +1220,7 @@ impl State {
                                                 Basis::Time(tf) => {
                                                     *push_freq = exchange::PushFrequency::Custom(tf)
                                                 }
-                                                Basis::Tick(_) => {
+                                                _ => {
                                                     *push_freq =
                                                         exchange::PushFrequency::ServerDefault
                                                 }
@@ -1099,16 +1233,26 @@ impl State {
                                         if let Some(base_ticker) = base_ticker {
                                             match new_basis {
                                                 Basis::Time(tf) => {
-                                                    let kline_stream = StreamKind::Kline {
-                                                        ticker_info: base_ticker,
-                                                        timeframe: tf,
-                                                    };
-                                                    let mut streams = vec![kline_stream];
+                                                    let has_klines = base_ticker
+                                                        .exchange()
+                                                        .kline_source(tf)
+                                                        .is_some();
+
+                                                    let mut streams = vec![];
+                                                    if has_klines {
+                                                        streams.push(StreamKind::Kline {
+                                                            ticker_info: base_ticker,
+                                                            timeframe: tf,
+                                                        });
+                                                    }
 
-                                                    if matches!(
-                                                        c.kind,
-                                                        data::chart::KlineChartKind::Footprint { .. }
-                                                    ) {
+                                                    // sub-minute bars are built from the trades alone
+                                                    if !has_klines
+                                                        || matches!(
+                                                            c.kind,
+                                                            data::chart::KlineChartKind::Footprint { .. }
+                                                        )
+                                                    {
                                                         let depth_aggr = if base_ticker
                                                             .exchange()
                                                             .is_depth_client_aggr()
@@ -1131,14 +1275,14 @@ impl State {
                                                     self.streams = ResolvedStream::Ready(streams);
                                                     let action = c.set_basis(new_basis);
 
-                                                    if let Some(chart::Action::RequestFetch(
-                                                        fetch,
-                                                    )) = action
-                                                    {
-                                                        effect = Some(Effect::RequestFetch(fetch));
-                                                    }
+                                                    effect = Some(match action {
+                                                        Some(chart::Action::RequestFetch(
+                                                            fetch,
+                                                        )) => Effect::RequestFetch(fetch),
+                                                        _ => Effect::RefreshStreams,
+                                                    });
                                                 }
-                                                Basis::Tick(_) => {
+                                                _ => {
                                                     let depth_aggr = if base_ticker
                                                         .exchange()
                                                         .is_depth_client_aggr()
@@ -1304,10 +1448,27 @@ impl State {
         {
             buttons = buttons.push(button_with_tooltip(
                 icon_text(Icon::ChartOutline, 12),
-                show_modal(Modal::Indicators),
+                show_modal(Modal::Indicators {
+                    expanded: None,
+                    scripts: vec![],
+                }),
                 Some("Indicators"),
                 tooltip_pos,
-                modal_btn_style(Modal::Indicators),
+                control_btn_style(matches!(self.modal, Some(Modal::Indicators { .. }))),
+            ));
+        }
+
+        if !treat_as_starter && let Some((datasets, has_scope)) = self.export_datasets() {
+            let is_active = matches!(self.modal, Some(Modal::Export(_)));
+
+            buttons = buttons.push(button_with_tooltip(
+                icon_text(Icon::ExternalLink, 12),
+                show_modal(Modal::Export(modal::pane::export::Export::new(
+                    datasets, has_scope,
+                ))),
+                Some("Export data"),
+                tooltip_pos,
+                control_btn_style(is_active),
             ));
         }
 
@@ -1430,13 +1591,22 @@ impl State {
                 padding::right(12).left(12),
                 Alignment::End,
             ),
-            Some(Modal::Indicators) => stack_modal(
+            Some(Modal::Indicators { .. }) => stack_modal(
                 base,
                 indicator_modal.unwrap_or_else(|| column![].into()),
                 on_blur,
                 padding::right(12).left(12),
                 Alignment::End,
             ),
+            Some(Modal::Export(export)) => stack_modal(
+                base,
+                export
+                    .view()
+                    .map(move |message| Message::PaneEvent(pane, Event::ExportModal(message))),
+                on_blur,
+                padding::right(12).left(12),
+                Alignment::End,
+            ),
             Some(Modal::Controls) => stack_modal(
                 base,
                 if let Some(controls) = compact_controls {
@@ -1456,7 +1626,63 @@ impl State {
         self.streams.matches_stream(stream)
     }
 
-    fn show_modal_with_focus(&mut self, requested_modal: Modal) -> Option<Effect> {
+    fn export_datasets(&self) -> Option<(Vec<data::export::Dataset>, bool)> {
+        match &self.content {
+            Content::Kline { chart: Some(c), .. } => Some((c.export_datasets(), true)),
+            Content::Heatmap { chart: Some(_), .. } => {
+                Some((vec![data::export::Dataset::OrderRuns], true))
+            }
+            Content::TimeAndSales(Some(_)) => Some((vec![data::export::Dataset::Trades], false)),
+            _ => None,
+        }
+    }
+
+    fn export_data(
+        &mut self,
+        dataset: data::export::Dataset,
+        format: data::export::Format,
+        scope: data::export::Scope,
+    ) -> Option<Effect> {
+        let table = match &self.content {
+            Content::Kline { chart: Some(c), .. } => c.export_table(dataset, scope),
+            Content::Heatmap { chart: Some(c), .. } => c.export_order_runs(scope),
+            Content::TimeAndSales(Some(p)) => p.export_trades(),
+            _ => return None,
+        };
+
+        if table.is_empty() {
+            self.notifications
+                .push(Toast::warn(data::export::ExportError::Empty.to_string()));
+            return None;
+        }
+
+        let name = self.stream_pair().map_or_else(
+            || "export".to_string(),
+            |ti| {
+                format!(
+                    "{}_{}",
+                    ti.exchange(),
+                    ti.ticker.to_full_symbol_and_type().0
+                )
+            },
+        );
+        let now_ms = chrono::Utc::now().timestamp_millis() as u64;
+
+        Some(Effect::ExportData {
+            table,
+            format,
+            path: data::export::export_path(&name, dataset, format, now_ms),
+        })
+    }
+
+    /// Indices of the selected indicators shift on removal and reorder
+    fn collapse_indicator_settings(&mut self) {
+        if let Some(Modal::Indicators { expanded, .. }) = &mut self.modal {
+            *expanded = None;
+        }
+    }
+
+    fn show_modal_with_focus(&mut self, mut requested_modal: Modal) -> Option<Effect> {
         let should_toggle_close = match (&self.modal, &requested_modal) {
             (Some(Modal::StreamModifier(open)), Modal::StreamModifier(req)) => {
                 open.view_mode == req.view_mode
@@ -1470,6 +1696,11 @@ impl State {
             return None;
         }
 
+        // scripts are listed once on open, not on every redraw of the modal
+        if let Modal::Indicators { scripts, .. } = &mut requested_modal {
+            *scripts = data::chart::indicator::script::available();
+        }
+
         let focus_widget_id = match &requested_modal {
             Modal::MiniTickersList(m) => Some(m.search_box_id.clone()),
             _ => None,
@@ -1691,13 +1922,13 @@ impl Content {
         let basis = settings.selected_basis.unwrap_or(Basis::Time(default_tf));
 
         let enabled_indicators = {
-            let available = KlineIndicator::for_market(ticker_info.market_type());
+            let market = ticker_info.market_type();
             prev_indis.map_or_else(
-                || vec![KlineIndicator::Volume],
+                || vec![KlineIndicator::VOLUME],
                 |indis| {
                     indis
                         .into_iter()
-                        .filter(|i| available.contains(i))
+                        .filter(|i| i.is_available_for(market))
                         .collect()
                 },
             )
@@ -1707,19 +1938,21 @@ impl Content {
             let main_chart_split: f32 = 0.8;
             let mut splits_vec = vec![main_chart_split];
 
-            if !enabled_indicators.is_empty() {
-                let num_indicators = enabled_indicators.len();
-
-                if num_indicators > 0 {
-                    let indicator_total_height_ratio = 1.0 - main_chart_split;
-                    let height_per_indicator_pane =
-                        indicator_total_height_ratio / num_indicators as f32;
-
-                    let mut current_split_pos = main_chart_split;
-                    for _ in 0..(num_indicators - 1) {
-                        current_split_pos += height_per_indicator_pane;
-                        splits_vec.push(current_split_pos);
-                    }
+            // overlays draw on the main chart, only the rest get a panel
+            let num_indicators = enabled_indicators
+                .iter()
+                .filter(|indicator| !indicator.is_overlay())
+                .count();
+
+            if num_indicators > 0 {
+                let indicator_total_height_ratio = 1.0 - main_chart_split;
+                let height_per_indicator_pane =
+                    indicator_total_height_ratio / num_indicators as f32;
+
+                let mut current_split_pos = main_chart_split;
+                for _ in 0..(num_indicators - 1) {
+                    current_split_pos += height_per_indicator_pane;
+                    splits_vec.push(current_split_pos);
                 }
             }
             splits_vec
@@ -1732,7 +1965,13 @@ impl Content {
                 autoscale: Some(data::chart::Autoscale::FitToVisible),
             });
 
-        let chart = KlineChart::new(
+        let config = settings
+            .visual_config
+            .clone()
+            .and_then(|cfg| cfg.kline())
+            .unwrap_or_default();
+
+        let mut chart = KlineChart::new(
             layout.clone(),
             basis,
             tick_size,
@@ -1741,7 +1980,11 @@ impl Content {
             &enabled_indicators,
             ticker_info,
             &determined_chart_kind,
+            config,
         );
+        if let Some(anchors) = settings.vwap_anchors.get(&ticker_info.ticker) {
+            chart.set_vwap_anchors(anchors);
+        }
 
         Content::Kline {
             chart: Some(chart),
@@ -1756,7 +1999,7 @@ impl Content {
             ContentKind::Starter => Content::Starter,
             ContentKind::CandlestickChart => Content::Kline {
                 chart: None,
-                indicators: vec![KlineIndicator::Volume],
+                indicators: vec![KlineIndicator::VOLUME],
                 kind: data::chart::KlineChartKind::Candles,
                 layout: ViewConfig {
                     splits: vec![],
@@ -1765,7 +2008,7 @@ impl Content {
             },
             ContentKind::FootprintChart => Content::Kline {
                 chart: None,
-                indicators: vec![KlineIndicator::Volume],
+                indicators: vec![KlineIndicator::VOLUME],
                 kind: data::chart::KlineChartKind::Footprint {
                     clusters: data::chart::kline::ClusterKind::default(),
                     scaling: data::chart::kline::ClusterScaling::default(),
@@ -1849,6 +2092,40 @@ impl Content {
         }
     }
 
+    /// Replaces the parameters of the selected indicator at `idx`, unless another
+    /// selected instance already has them
+    pub fn configure_indicator(&mut self, idx: usize, indicator: UiIndicator) {
+        match (self, indicator) {
+            (Content::Heatmap { indicators, .. }, UiIndicator::Heatmap(ind)) => {
+                if !indicators.contains(&ind)
+                    && let Some(slot) = indicators.get_mut(idx)
+                {
+                    *slot = ind;
+                }
+            }
+            (
+                Content::Kline {
+                    chart, indicators, ..
+                },
+                UiIndicator::Kline(ind),
+            ) => {
+                let Some(chart) = chart else {
+                    return;
+                };
+
+                if !indicators.contains(&ind)
+                    && let Some(slot) = indicators.get_mut(idx)
+                {
+                    chart.configure_indicator(*slot, ind);
+                    *slot = ind;
+                }
+            }
+            (content, indicator) => {
+                log::warn!("Can't configure {indicator:?} on a {content} pane");
+            }
+        }
+    }
+
     pub fn reorder_indicators(&mut self, event: &column_drag::DragEvent) {
         match self {
             Content::Heatmap { indicators, .. } => column_drag::reorder_vec(indicators, event),
@@ -1867,8 +2144,11 @@ impl Content {
             (Content::Heatmap { chart: Some(c), .. }, VisualConfig::Heatmap(cfg)) => {
                 c.set_visual_config(cfg);
             }
+            (Content::Kline { chart: Some(c), .. }, VisualConfig::Kline(cfg)) => {
+                c.set_visual_config(cfg);
+            }
             (Content::TimeAndSales(Some(panel)), VisualConfig::TimeAndSales(cfg)) => {
-                panel.config = cfg;
+                panel.set_config(cfg);
             }
             (Content::Ladder(Some(panel)), VisualConfig::Ladder(cfg)) => {
                 panel.config = cfg;
@@ -2068,6 +2348,6 @@ fn by_basis_default<T>(
 ) -> T {
     match basis.unwrap_or(Basis::Time(default_tf)) {
         Basis::Time(tf) => on_time(tf),
-        Basis::Tick(_) => on_tick(),
+        _ => on_tick(),
     }
 }
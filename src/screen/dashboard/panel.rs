@@ -14,7 +14,9 @@ pub enum Message {
     Invalidate(Option<Instant>),
 }
 
-pub enum Action {}
+pub enum Action {
+    RequestFetch(exchange::fetcher::FetchRequests),
+}
 
 pub trait Panel: canvas::Program<Message> {
     fn scroll(&mut self, scroll: f32);
@@ -2,7 +2,7 @@ use super::Message;
 use crate::style;
 use data::panel::ladder::{ChaseTracker, Config, GroupedDepth, Side, TradeStore};
 use exchange::Trade;
-use exchange::util::{Price, PriceStep};
+use exchange::util::{Price, PriceStep, Qty};
 use exchange::{TickerInfo, depth::Depth};
 
 use iced::widget::canvas::{self, Path, Stroke, Text};
@@ -123,19 +123,28 @@ impl Ladder {
         }
     }
 
+    /// Traded quantities at `price`, in the pane's size unit
     fn trade_qty_at(&self, price: Price) -> (f32, f32) {
-        self.trades.trade_qty_at(price)
+        let (buy, sell) = self.trades.trade_qty_at(price);
+        (self.shown_qty(buy, price), self.shown_qty(sell, price))
+    }
+
+    fn shown_qty(&self, qty: Qty, price: Price) -> f32 {
+        self.ticker_info
+            .market_type()
+            .qty_in_size_unit(qty, price, self.config.size_unit)
+            .to_f32()
     }
 
     pub fn last_update(&self) -> Instant {
         self.last_tick
     }
 
-    fn grouped_asks(&self) -> &BTreeMap<Price, f32> {
+    fn grouped_asks(&self) -> &BTreeMap<Price, Qty> {
         &self.orderbook[Side::Ask.idx()].orders
     }
 
-    fn grouped_bids(&self) -> &BTreeMap<Price, f32> {
+    fn grouped_bids(&self) -> &BTreeMap<Price, Qty> {
         &self.orderbook[Side::Bid.idx()].orders
     }
 
@@ -822,10 +831,11 @@ impl Ladder {
 
             let is_bid = idx > 0;
             let order_qty = if is_bid {
-                bids_grouped.get(&price).copied().unwrap_or(0.0)
+                bids_grouped.get(&price).copied().unwrap_or_default()
             } else {
-                asks_grouped.get(&price).copied().unwrap_or(0.0)
+                asks_grouped.get(&price).copied().unwrap_or_default()
             };
+            let order_qty = self.shown_qty(order_qty, price);
 
             let top_y_screen = mid_screen_y + PriceGrid::top_y(idx) - scroll;
             if top_y_screen >= bounds.height || top_y_screen + ROW_HEIGHT <= 0.0 {
@@ -3,7 +3,11 @@ use crate::style;
 use data::config::theme::{darken, lighten};
 pub use data::panel::timeandsales::Config;
 use data::panel::timeandsales::{HistAgg, StackedBar, StackedBarRatio, TradeDisplay, TradeEntry};
-use exchange::{TickerInfo, Trade, volume_size_unit};
+use exchange::{
+    TickerInfo, Trade,
+    adapter::{Exchange, local},
+    fetcher::{FetchRange, FetchSpec},
+};
 
 use iced::widget::canvas::{self, Text};
 use iced::{Alignment, Event, Point, Rectangle, Renderer, Size, Theme, mouse};
@@ -80,6 +84,7 @@ pub struct TimeAndSales {
     cache: canvas::Cache,
     last_tick: Instant,
     scroll_offset: f32,
+    backfill_requested: bool,
 }
 
 impl TimeAndSales {
@@ -87,7 +92,7 @@ impl TimeAndSales {
         Self {
             recent_trades: VecDeque::new(),
             paused_trades_buffer: VecDeque::new(),
-            hist_agg: HistAgg::default(),
+            hist_agg: HistAgg::new(ticker_info.market_type()),
             is_paused: false,
             config: config.unwrap_or_default(),
             max_filtered_qty: 0.0,
@@ -95,6 +100,7 @@ impl TimeAndSales {
             cache: canvas::Cache::default(),
             last_tick: Instant::now(),
             scroll_offset: 0.0,
+            backfill_requested: false,
         }
     }
 
@@ -108,7 +114,7 @@ impl TimeAndSales {
         };
 
         let market_type = self.ticker_info.market_type();
-        let size_in_quote_ccy = volume_size_unit() == exchange::SizeUnit::Quote;
+        let size_unit = self.config.size_unit;
 
         for trade in trades_buffer {
             let trade_time_ms = trade.time;
@@ -124,14 +130,11 @@ impl TimeAndSales {
                     is_sell: trade.is_sell,
                 };
 
-                let trade_size_value = market_type.qty_in_quote_value(
-                    trade_display.qty,
-                    trade.price,
-                    size_in_quote_ccy,
-                );
+                let trade_size_value = market_type.qty_in_quote_value(trade.qty, trade.price);
 
-                if trade_size_value >= size_filter {
-                    self.max_filtered_qty = self.max_filtered_qty.max(trade_display.qty);
+                if trade_size_value.to_f32() >= size_filter {
+                    let shown_qty = market_type.qty_in_size_unit(trade.qty, trade.price, size_unit);
+                    self.max_filtered_qty = self.max_filtered_qty.max(shown_qty.to_f32());
                 }
 
                 target_trades.push_back(TradeEntry {
@@ -153,11 +156,24 @@ impl TimeAndSales {
         self.prune_paused_by_time(None);
     }
 
+    /// All retained trades, including the ones buffered while paused
+    pub fn export_trades(&self) -> data::export::Table {
+        data::export::time_and_sales(
+            self.recent_trades
+                .iter()
+                .chain(self.paused_trades_buffer.iter()),
+        )
+    }
+
     pub fn last_update(&self) -> Instant {
         self.last_tick
     }
 
     pub fn invalidate(&mut self, now: Option<Instant>) -> Option<super::Action> {
+        if let Some(action) = self.request_backfill() {
+            return Some(action);
+        }
+
         if !self.is_paused {
             self.prune_by_time(None);
         }
@@ -170,6 +186,26 @@ impl TimeAndSales {
         None
     }
 
+    /// Imported tickers have no live feed, so the latest retention window is loaded once
+    fn request_backfill(&mut self) -> Option<super::Action> {
+        if self.backfill_requested || self.ticker_info.exchange() != Exchange::Local {
+            return None;
+        }
+        self.backfill_requested = true;
+
+        let (_, latest) = local::trades_range(&self.ticker_info.ticker)?;
+        let from = latest.saturating_sub(self.config.trade_retention.as_millis() as u64);
+
+        let fetch = FetchSpec {
+            req_id: uuid::Uuid::new_v4(),
+            fetch: FetchRange::Trades(from, latest),
+            stream: None,
+        };
+        Some(super::Action::RequestFetch(
+            std::iter::once(fetch).collect(),
+        ))
+    }
+
     fn stacked_bar_height(&self) -> f32 {
         match &self.config.stacked_bar {
             Some(StackedBar::Compact(_)) => METRICS_HEIGHT_COMPACT,
@@ -182,15 +218,29 @@ impl TimeAndSales {
         self.stacked_bar_height().max(METRICS_HEIGHT_COMPACT) + TRADE_ROW_HEIGHT
     }
 
+    /// Imported tickers are pruned relative to their newest trade instead of the wall clock
+    fn now_ms(&self) -> u64 {
+        if self.ticker_info.exchange() == Exchange::Local {
+            return self
+                .recent_trades
+                .back()
+                .into_iter()
+                .chain(self.paused_trades_buffer.back())
+                .map(|t| t.ts_ms)
+                .max()
+                .unwrap_or(0);
+        }
+
+        let ts = chrono::Utc::now().timestamp_millis();
+        if ts < 0 { 0 } else { ts as u64 }
+    }
+
     fn prune_by_time(&mut self, now_epoch_ms: Option<u64>) {
         if self.recent_trades.is_empty() {
             return;
         }
 
-        let now_ms = now_epoch_ms.unwrap_or_else(|| {
-            let ts = chrono::Utc::now().timestamp_millis();
-            if ts < 0 { 0 } else { ts as u64 }
-        });
+        let now_ms = now_epoch_ms.unwrap_or_else(|| self.now_ms());
 
         let trade_retention_ms = self.config.trade_retention.as_millis() as u64;
         let prune_slack_ms = trade_retention_ms / 10;
@@ -206,8 +256,6 @@ impl TimeAndSales {
             return;
         }
 
-        let size_filter = self.config.trade_size_filter;
-
         let mut popped_any = false;
         while let Some(front) = self.recent_trades.front() {
             if front.ts_ms >= low_cutoff {
@@ -219,22 +267,7 @@ impl TimeAndSales {
         }
 
         if popped_any {
-            let market_type = self.ticker_info.market_type();
-            let size_in_quote_ccy = volume_size_unit() == exchange::SizeUnit::Quote;
-
-            self.max_filtered_qty = self
-                .recent_trades
-                .iter()
-                .filter(|t| {
-                    let trade_size = market_type.qty_in_quote_value(
-                        t.display.qty,
-                        t.display.price,
-                        size_in_quote_ccy,
-                    );
-                    trade_size >= size_filter
-                })
-                .map(|e| e.display.qty)
-                .fold(0.0, f32::max);
+            self.refresh_max_filtered_qty();
 
             let stacked_bar_h = self.stacked_bar_height();
             let total_content_height =
@@ -244,6 +277,33 @@ impl TimeAndSales {
         }
     }
 
+    /// Largest visible trade in the display unit, used to scale row colors
+    fn refresh_max_filtered_qty(&mut self) {
+        let market_type = self.ticker_info.market_type();
+        let size_filter = self.config.trade_size_filter;
+        let size_unit = self.config.size_unit;
+
+        self.max_filtered_qty = self
+            .recent_trades
+            .iter()
+            .filter(|t| {
+                let trade_size = market_type.qty_in_quote_value(t.display.qty, t.display.price);
+                trade_size.to_f32() >= size_filter
+            })
+            .map(|e| {
+                market_type
+                    .qty_in_size_unit(e.display.qty, e.display.price, size_unit)
+                    .to_f32()
+            })
+            .fold(0.0, f32::max);
+    }
+
+    pub fn set_config(&mut self, config: Config) {
+        self.config = config;
+        self.refresh_max_filtered_qty();
+        self.cache.clear();
+    }
+
     fn prune_paused_by_time(&mut self, now_epoch_ms: Option<u64>) {
         if self.paused_trades_buffer.is_empty() {
             return;
@@ -252,10 +312,7 @@ impl TimeAndSales {
         let trade_retention_ms = self.config.trade_retention.as_millis() as u64;
         let prune_slack_ms = trade_retention_ms / 10;
 
-        let now_ms = now_epoch_ms.unwrap_or_else(|| {
-            let ts = chrono::Utc::now().timestamp_millis();
-            if ts < 0 { 0 } else { ts as u64 }
-        });
+        let now_ms = now_epoch_ms.unwrap_or_else(|| self.now_ms());
 
         let low_cutoff = now_ms.saturating_sub(trade_retention_ms);
         let high_cutoff = now_ms.saturating_sub(trade_retention_ms.saturating_add(prune_slack_ms));
@@ -356,7 +413,9 @@ impl canvas::Program<Message> for TimeAndSales {
                     StackedBar::Compact(r) | StackedBar::Full(r) => *r,
                 };
 
-                if let Some((buy_val, sell_val, buy_ratio)) = self.hist_agg.values_for(ratio_kind) {
+                if let Some((buy_val, sell_val, buy_ratio)) =
+                    self.hist_agg.values_for(ratio_kind, self.config.size_unit)
+                {
                     let draw_stacked_bar =
                         |frame: &mut canvas::Frame, buy_bar_width: f32, sell_bar_width: f32| {
                             frame.fill_rectangle(
@@ -445,18 +504,12 @@ impl canvas::Program<Message> for TimeAndSales {
             let start_index = (row_scroll_offset / row_height).floor() as usize;
             let visible_rows = (bounds.height / row_height).ceil() as usize;
 
-            let size_in_quote_ccy = volume_size_unit() == exchange::SizeUnit::Quote;
-
             let trades_to_draw = self
                 .recent_trades
                 .iter()
                 .filter(|t| {
-                    let trade_size = market_type.qty_in_quote_value(
-                        t.display.qty,
-                        t.display.price,
-                        size_in_quote_ccy,
-                    );
-                    trade_size >= self.config.trade_size_filter
+                    let trade_size = market_type.qty_in_quote_value(t.display.qty, t.display.price);
+                    trade_size.to_f32() >= self.config.trade_size_filter
                 })
                 .rev()
                 .skip(start_index)
@@ -488,8 +541,11 @@ impl canvas::Program<Message> for TimeAndSales {
                     palette.success.weak.color
                 };
 
+                let shown_qty =
+                    market_type.qty_in_size_unit(trade.qty, trade.price, self.config.size_unit);
+
                 let bg_color_alpha = if self.max_filtered_qty > 0.0 {
-                    (trade.qty / self.max_filtered_qty).clamp(0.02, 1.0)
+                    (shown_qty.to_f32() / self.max_filtered_qty).clamp(0.02, 1.0)
                 } else {
                     0.02
                 };
@@ -542,7 +598,7 @@ impl canvas::Program<Message> for TimeAndSales {
                 frame.fill_text(trade_price);
 
                 let trade_qty = create_text(
-                    data::util::abbr_large_numbers(trade.qty),
+                    data::util::abbr_large_numbers(shown_qty.to_f32()),
                     Point {
                         x: row_width * 0.9,
                         y: y_position,
@@ -189,10 +189,25 @@ impl Sidebar {
             )
         };
 
+        let import_btn = {
+            let is_active = self.is_menu_active(sidebar::Menu::Import);
+
+            button_with_tooltip(
+                icon_text(Icon::Folder, 14)
+                    .width(24)
+                    .align_x(Alignment::Center),
+                Message::ToggleSidebarMenu(Some(sidebar::Menu::Import)),
+                None,
+                tooltip_position,
+                move |theme, status| crate::style::button::transparent(theme, status, is_active),
+            )
+        };
+
         column![
             ticker_search_button,
             layout_modal_button,
             audio_btn,
+            import_btn,
             space::vertical(),
             settings_modal_button,
         ]
@@ -7,11 +7,11 @@ use data::{
     layout::pane::ContentKind,
     tickers_table::{
         PriceChangeDirection, Settings, SortOptions, TickerDisplayData, TickerRowData,
-        compute_display_data,
+        compute_display_data, load_tickers_cache, save_tickers_cache, usd_prices,
     },
 };
 use exchange::{
-    Ticker, TickerInfo, TickerStats,
+    Asset, Ticker, TickerInfo, TickerStats,
     adapter::{Exchange, ExchangeInclusive, MarketKind, fetch_ticker_info, fetch_ticker_prices},
 };
 use iced::{
@@ -19,7 +19,7 @@ use iced::{
     alignment::{self, Horizontal, Vertical},
     padding,
     widget::{
-        Button, Space, button, column, container, row, rule,
+        Button, Space, button, column, container, pick_list, row, rule,
         scrollable::{self, AbsoluteOffset},
         space, text, text_input,
     },
@@ -29,6 +29,8 @@ use std::{cmp::Ordering, collections::HashMap};
 
 const ACTIVE_UPDATE_INTERVAL: u64 = 13;
 const INACTIVE_UPDATE_INTERVAL: u64 = 300;
+/// How often ticker metadata is re-fetched for exchanges that failed, e.g. while offline
+const TICKERS_INFO_RETRY_INTERVAL: u64 = 30;
 
 /// Number of extra cards to render for visibility during scrolling
 const OVERSCAN_BUFFER: isize = 3;
@@ -38,7 +40,7 @@ const FAVORITES_SEPARATOR_HEIGHT: f32 = 12.0;
 const FAVORITES_EMPTY_HINT_HEIGHT: f32 = 32.0;
 
 const TOP_BAR_HEIGHT: f32 = 40.0;
-const SORT_AND_FILTER_HEIGHT: f32 = 200.0;
+const SORT_AND_FILTER_HEIGHT: f32 = 240.0;
 
 const COMPACT_ROW_HEIGHT: f32 = 28.0;
 
@@ -58,12 +60,22 @@ const EXCHANGE_FILTERS: [(ExchangeInclusive, Exchange, &str); 4] = [
 ];
 
 pub fn fetch_tickers_info() -> Task<Message> {
-    let fetch_tasks = Exchange::ALL
-        .iter()
+    fetch_tickers_info_of(Exchange::ALL)
+}
+
+/// Fetches ticker metadata and caches it on disk, so the next launch can start offline
+fn fetch_tickers_info_of(exchanges: impl IntoIterator<Item = Exchange>) -> Task<Message> {
+    let fetch_tasks = exchanges
+        .into_iter()
         .map(|exchange| {
-            Task::perform(fetch_ticker_info(*exchange), move |result| match result {
-                Ok(ticker_info) => Message::UpdateTickersInfo(*exchange, ticker_info),
-                Err(err) => Message::ErrorOccurred(InternalError::Fetch(err.to_string())),
+            Task::perform(fetch_ticker_info(exchange), move |result| match result {
+                Ok(ticker_info) => {
+                    if let Err(e) = save_tickers_cache(exchange, &ticker_info) {
+                        log::warn!("Failed to cache tickers of {exchange}: {e}");
+                    }
+                    Message::UpdateTickersInfo(exchange, ticker_info)
+                }
+                Err(err) => Message::TickersInfoFailed(exchange, err.to_string()),
             })
         })
         .collect::<Vec<Task<Message>>>();
@@ -71,6 +83,15 @@ pub fn fetch_tickers_info() -> Task<Message> {
     Task::batch(fetch_tasks)
 }
 
+/// Ticker metadata cached by previous sessions, used until the live fetch succeeds
+fn cached_tickers_info() -> FxHashMap<Ticker, Option<TickerInfo>> {
+    Exchange::ALL
+        .into_iter()
+        .filter_map(load_tickers_cache)
+        .flatten()
+        .collect()
+}
+
 pub enum Action {
     TickerSelected(TickerInfo, Option<ContentKind>),
     ErrorOccurred(data::InternalError),
@@ -89,10 +110,13 @@ pub enum Message {
     Scrolled(scrollable::Viewport),
     ToggleMarketFilter(MarketKind),
     ToggleExchangeFilter(ExchangeInclusive),
+    SelectQuoteFilter(QuoteFilter),
     ToggleTable,
     ToggleFavorites,
     FetchForTickerStats(Option<Exchange>),
     UpdateTickersInfo(Exchange, HashMap<Ticker, Option<TickerInfo>>),
+    TickersInfoFailed(Exchange, String),
+    RetryTickersInfo,
     UpdateTickerStats(Exchange, HashMap<Ticker, TickerStats>),
     ErrorOccurred(data::InternalError),
 }
@@ -110,9 +134,12 @@ pub struct TickersTable {
     pub tickers_info: FxHashMap<Ticker, Option<TickerInfo>>,
     selected_exchanges: FxHashSet<ExchangeInclusive>,
     selected_markets: FxHashSet<MarketKind>,
+    selected_quote: Option<Asset>,
     show_favorites: bool,
     row_index: FxHashMap<Ticker, usize>,
     pending_stats_batches: usize,
+    /// Exchanges whose last metadata fetch failed, retried in the background
+    failed_tickers_info: FxHashSet<Exchange>,
 }
 
 impl TickersTable {
@@ -132,12 +159,14 @@ impl TickersTable {
                 expand_ticker_card: None,
                 scroll_offset: AbsoluteOffset::default(),
                 is_shown: false,
-                tickers_info: FxHashMap::default(),
+                tickers_info: cached_tickers_info(),
                 selected_exchanges: settings.selected_exchanges.iter().cloned().collect(),
                 selected_markets: settings.selected_markets.iter().cloned().collect(),
+                selected_quote: settings.selected_quote,
                 show_favorites: settings.show_favorites,
                 row_index: FxHashMap::default(),
                 pending_stats_batches: 0,
+                failed_tickers_info: FxHashSet::default(),
             },
             fetch_tickers_info(),
         )
@@ -150,6 +179,7 @@ impl TickersTable {
             selected_sort_option: self.selected_sort_option,
             selected_exchanges: self.selected_exchanges.iter().cloned().collect(),
             selected_markets: self.selected_markets.iter().cloned().collect(),
+            selected_quote: self.selected_quote,
         }
     }
 
@@ -187,6 +217,9 @@ impl TickersTable {
                     self.selected_exchanges.insert(exch);
                 }
             }
+            Message::SelectQuoteFilter(QuoteFilter(quote)) => {
+                self.selected_quote = quote;
+            }
             Message::ToggleFavorites => {
                 self.show_favorites = !self.show_favorites;
             }
@@ -209,10 +242,8 @@ impl TickersTable {
                     self.display_cache.clear();
                     for row in self.ticker_rows.iter_mut() {
                         row.previous_stats = None;
-                        self.display_cache.insert(
-                            row.ticker,
-                            compute_display_data(&row.ticker, &row.stats, None),
-                        );
+                        self.display_cache
+                            .insert(row.ticker, compute_display_data(row, None));
                     }
 
                     return Some(Action::FocusWidget("full_ticker_search_box".into()));
@@ -262,6 +293,7 @@ impl TickersTable {
                 }
             }
             Message::UpdateTickersInfo(exchange, info) => {
+                self.failed_tickers_info.remove(&exchange);
                 self.update_ticker_info(exchange, info);
 
                 let task =
@@ -272,6 +304,21 @@ impl TickersTable {
 
                 return Some(Action::Fetch(task));
             }
+            Message::TickersInfoFailed(exchange, err) => {
+                self.failed_tickers_info.insert(exchange);
+
+                let has_cached = self.tickers_info.keys().any(|t| t.exchange == exchange);
+                if has_cached {
+                    log::warn!("Using cached tickers of {exchange}, fetch failed: {err}");
+                } else {
+                    log::error!("Failed to fetch tickers of {exchange}: {err}");
+                    return Some(Action::ErrorOccurred(InternalError::Fetch(err)));
+                }
+            }
+            Message::RetryTickersInfo => {
+                let exchanges = self.failed_tickers_info.iter().copied().collect::<Vec<_>>();
+                return Some(Action::Fetch(fetch_tickers_info_of(exchanges)));
+            }
             Message::ErrorOccurred(err) => {
                 log::error!("Error occurred: {err}");
                 return Some(Action::ErrorOccurred(err));
@@ -364,6 +411,11 @@ impl TickersTable {
             selected_set.insert(bt.ticker);
         }
 
+        let other_venues = base_ticker
+            .map(|bt| self.other_venues(&bt))
+            .unwrap_or_default();
+        selected_set.extend(other_venues.iter().map(|info| info.ticker));
+
         let (fav_rows, rest_rows) = self.filtered_rows_compact(&injected_q, &selected_set);
 
         let base_ticker_id = base_ticker.map(|bt| bt.ticker);
@@ -376,7 +428,8 @@ impl TickersTable {
                     .collect()
             })
             .unwrap_or_default();
-        let selected_count = selected_list.len() + if base_ticker_id.is_some() { 1 } else { 0 };
+        let selected_count =
+            selected_list.len() + other_venues.len() + if base_ticker_id.is_some() { 1 } else { 0 };
 
         let virtual_list = VirtualListConfig {
             row_height: COMPACT_ROW_HEIGHT,
@@ -388,8 +441,13 @@ impl TickersTable {
         let win = virtual_list.window(scroll_offset.y, bounds.height, total_n);
 
         let top_bar = self.compact_top_bar(search_query, search_box_id, on_search);
-        let selected_section =
-            self.compact_selected_section(base_ticker, selected_list, on_select, selection_enabled);
+        let selected_section = self.compact_selected_section(
+            base_ticker,
+            other_venues,
+            selected_list,
+            on_select,
+            selection_enabled,
+        );
 
         let list = self.compact_list(
             &virtual_list,
@@ -423,31 +481,32 @@ impl TickersTable {
     }
 
     pub fn subscription(&self) -> Subscription<Message> {
-        iced::time::every(std::time::Duration::from_secs(if self.is_shown {
+        let stats = iced::time::every(std::time::Duration::from_secs(if self.is_shown {
             ACTIVE_UPDATE_INTERVAL
         } else {
             INACTIVE_UPDATE_INTERVAL
         }))
-        .map(|_| Message::FetchForTickerStats(None))
+        .map(|_| Message::FetchForTickerStats(None));
+
+        if self.failed_tickers_info.is_empty() {
+            stats
+        } else {
+            let retry =
+                iced::time::every(std::time::Duration::from_secs(TICKERS_INFO_RETRY_INTERVAL))
+                    .map(|_| Message::RetryTickersInfo);
+            Subscription::batch([stats, retry])
+        }
     }
 
     fn sort_ticker_rows(&mut self) {
         match self.selected_sort_option {
             SortOptions::VolumeDesc => {
-                self.ticker_rows.sort_unstable_by(|a, b| {
-                    b.stats
-                        .daily_volume
-                        .total_cmp(&a.stats.daily_volume)
-                        .then_with(|| Ordering::Equal)
-                });
+                self.ticker_rows
+                    .sort_unstable_by(|a, b| b.cmp_volume(a).then_with(|| Ordering::Equal));
             }
             SortOptions::VolumeAsc => {
-                self.ticker_rows.sort_unstable_by(|a, b| {
-                    a.stats
-                        .daily_volume
-                        .total_cmp(&b.stats.daily_volume)
-                        .then_with(|| Ordering::Equal)
-                });
+                self.ticker_rows
+                    .sort_unstable_by(|a, b| a.cmp_volume(b).then_with(|| Ordering::Equal));
             }
             SortOptions::ChangeDesc => {
                 self.ticker_rows.sort_unstable_by(|a, b| {
@@ -533,6 +592,52 @@ impl TickersTable {
             .style(move |theme, status| style::button::transparent(theme, status, selected))
     }
 
+    /// Closest listing of the same market on each other venue, in [`Exchange::ALL`] order
+    fn other_venues(&self, info: &TickerInfo) -> Vec<TickerInfo> {
+        let Some(instrument) = info.instrument() else {
+            return vec![];
+        };
+
+        let candidates: Vec<&TickerInfo> = self
+            .tickers_info
+            .values()
+            .flatten()
+            .filter(|other| {
+                other.exchange() != info.exchange()
+                    && other
+                        .instrument()
+                        .is_some_and(|other| other.same_market(&instrument))
+            })
+            .collect();
+
+        Exchange::ALL
+            .into_iter()
+            .filter(|exchange| candidates.iter().any(|c| c.exchange() == *exchange))
+            .filter_map(|exchange| info.equivalent_on(exchange, candidates.iter().copied()))
+            .collect()
+    }
+
+    /// Quote assets of the listed pairs, the most common ones first
+    fn quote_filters(&self) -> Vec<QuoteFilter> {
+        let mut counts: FxHashMap<Asset, usize> = FxHashMap::default();
+        for info in self.tickers_info.values().flatten() {
+            if !info.quote.is_unknown() {
+                *counts.entry(info.quote).or_default() += 1;
+            }
+        }
+
+        let mut quotes: Vec<(Asset, usize)> = counts.into_iter().collect();
+        quotes.sort_by(|(a, a_n), (b, b_n)| b_n.cmp(a_n).then_with(|| a.cmp(b)));
+
+        std::iter::once(QuoteFilter(None))
+            .chain(
+                quotes
+                    .into_iter()
+                    .map(|(quote, _)| QuoteFilter(Some(quote))),
+            )
+            .collect()
+    }
+
     fn exchange_filter_btn<'a>(
         &'a self,
         exch_inc: ExchangeInclusive,
@@ -568,48 +673,75 @@ impl TickersTable {
 
     fn update_ticker_info(
         &mut self,
-        _exchange: Exchange,
+        exchange: Exchange,
         info: HashMap<Ticker, Option<TickerInfo>>,
     ) {
+        // imported datasets can be removed, so their listing replaces the previous one
+        if exchange == Exchange::Local {
+            self.tickers_info
+                .retain(|ticker, _| ticker.exchange != exchange || info.contains_key(ticker));
+            self.ticker_rows
+                .retain(|row| row.exchange != exchange || info.contains_key(&row.ticker));
+            self.row_index = self
+                .ticker_rows
+                .iter()
+                .enumerate()
+                .map(|(idx, row)| (row.ticker, idx))
+                .collect();
+        }
+
         for (ticker, ticker_info) in info.into_iter() {
             self.tickers_info.insert(ticker, ticker_info);
         }
     }
 
     fn update_ticker_rows(&mut self, exchange: Exchange, stats: HashMap<Ticker, TickerStats>) {
-        let iter = stats
-            .into_iter()
-            .filter(|(t, _)| self.tickers_info.contains_key(t));
+        let mut updated = Vec::with_capacity(stats.len());
+
+        for (ticker, new_stats) in stats {
+            let Some(info) = self.tickers_info.get(&ticker) else {
+                continue;
+            };
+            let quote = info.map(|info| info.quote).unwrap_or_default();
 
-        for (ticker, new_stats) in iter {
             if let Some(&idx) = self.row_index.get(&ticker) {
                 let row = &mut self.ticker_rows[idx];
-                let previous_price = Some(row.stats.mark_price);
+                updated.push((idx, Some(row.stats.mark_price)));
                 row.previous_stats = Some(row.stats);
                 row.stats = new_stats;
-
-                self.display_cache.insert(
-                    ticker,
-                    compute_display_data(&ticker, &row.stats, previous_price),
-                );
+                row.quote = quote;
             } else {
-                let new_row = TickerRowData {
+                self.ticker_rows.push(TickerRowData {
                     exchange,
                     ticker,
                     stats: new_stats,
                     previous_stats: None,
                     is_favorited: self.favorited_tickers.contains(&ticker),
-                };
-                self.ticker_rows.push(new_row);
+                    quote,
+                    volume_usd: None,
+                });
                 let idx = self.ticker_rows.len() - 1;
                 self.row_index.insert(ticker, idx);
-
-                self.display_cache.insert(
-                    ticker,
-                    compute_display_data(&ticker, &self.ticker_rows[idx].stats, None),
-                );
+                updated.push((idx, None));
             }
         }
+
+        // quote assets are priced from the pairs of every exchange, so all rows are converted again
+        let usd_prices = usd_prices(self.ticker_rows.iter().filter_map(|row| {
+            self.tickers_info
+                .get(&row.ticker)
+                .and_then(Option::as_ref)
+                .map(|info| (info, &row.stats))
+        }));
+        for row in self.ticker_rows.iter_mut() {
+            row.update_volume_usd(&usd_prices);
+        }
+
+        for (idx, previous_price) in updated {
+            let row = &self.ticker_rows[idx];
+            self.display_cache
+                .insert(row.ticker, compute_display_data(row, previous_price));
+        }
     }
 
     fn sep_block_height(&self, fav_n: usize) -> f32 {
@@ -758,6 +890,13 @@ impl TickersTable {
             ]
             .spacing(4),
             rule::horizontal(1.0).style(style::split_ruler),
+            pick_list(
+                self.quote_filters(),
+                Some(QuoteFilter(self.selected_quote)),
+                Message::SelectQuoteFilter,
+            )
+            .width(Length::Fill),
+            rule::horizontal(1.0).style(style::split_ruler),
             exchange_filters,
             rule::horizontal(1.0).style(style::split_ruler),
             text(if total == 0 {
@@ -893,6 +1032,7 @@ impl TickersTable {
     fn compact_selected_section<'a, M, FSelect>(
         &'a self,
         base_ticker: Option<TickerInfo>,
+        other_venues: Vec<TickerInfo>,
         selected_list: Vec<TickerInfo>,
         on_select: FSelect,
         selection_enabled: bool,
@@ -919,6 +1059,19 @@ impl TickersTable {
             ));
         }
 
+        for info in other_venues {
+            let label = self.label_with_suffix(info.ticker);
+
+            col = col.push(mini_ticker_card(
+                info.ticker.exchange,
+                label,
+                Some(RowSelection::Switch(info)),
+                None,
+                Some("Same market"),
+                on_select,
+            ));
+        }
+
         for info in selected_list {
             let label = self.label_with_suffix(info.ticker);
 
@@ -1026,9 +1179,33 @@ impl TickersTable {
     ) -> (Vec<&'a TickerRowData>, Vec<&'a TickerRowData>) {
         let matches_market =
             |row: &TickerRowData| self.selected_markets.contains(&row.ticker.market_type());
+        let canonical_pair = |row: &TickerRowData| {
+            if search_upper.is_empty() {
+                return None;
+            }
+            self.tickers_info
+                .get(&row.ticker)
+                .copied()
+                .flatten()
+                .and_then(|info| info.instrument())
+                .map(|instrument| instrument.canonical_pair())
+        };
+
+        // imported datasets and stale cached metadata have no known quote asset
+        let matches_quote = |row: &TickerRowData| {
+            self.selected_quote.is_none_or(|quote| {
+                self.tickers_info
+                    .get(&row.ticker)
+                    .copied()
+                    .flatten()
+                    .is_none_or(|info| info.quote.is_unknown() || info.quote == quote)
+            })
+        };
         let matches_exchange = |row: &TickerRowData| {
-            self.selected_exchanges
-                .contains(&ExchangeInclusive::of(row.exchange))
+            row.exchange == Exchange::Local
+                || self
+                    .selected_exchanges
+                    .contains(&ExchangeInclusive::of(row.exchange))
         };
 
         // Collect fav_rows with search ranks
@@ -1039,9 +1216,13 @@ impl TickersTable {
                     row.is_favorited
                         && !excluded.is_some_and(|ex| ex.contains(&row.ticker))
                         && matches_market(row)
+                        && matches_quote(row)
                         && matches_exchange(row)
                 })
-                .filter_map(|row| calc_search_rank(row, search_upper).map(|rank| (row, rank)))
+                .filter_map(|row| {
+                    calc_search_rank(row, search_upper, canonical_pair(row).as_deref())
+                        .map(|rank| (row, rank))
+                })
                 .collect()
         } else {
             Vec::new()
@@ -1052,10 +1233,8 @@ impl TickersTable {
             (ra.bucket, ra.pos)
                 .cmp(&(rb.bucket, rb.pos))
                 .then_with(|| match self.selected_sort_option {
-                    SortOptions::VolumeDesc => {
-                        b.stats.daily_volume.total_cmp(&a.stats.daily_volume)
-                    }
-                    SortOptions::VolumeAsc => a.stats.daily_volume.total_cmp(&b.stats.daily_volume),
+                    SortOptions::VolumeDesc => b.cmp_volume(a),
+                    SortOptions::VolumeAsc => a.cmp_volume(b),
                     SortOptions::ChangeDesc => {
                         b.stats.daily_price_chg.total_cmp(&a.stats.daily_price_chg)
                     }
@@ -1075,9 +1254,13 @@ impl TickersTable {
                 (!self.show_favorites || !row.is_favorited)
                     && !excluded.is_some_and(|ex| ex.contains(&row.ticker))
                     && matches_market(row)
+                    && matches_quote(row)
                     && matches_exchange(row)
             })
-            .filter_map(|row| calc_search_rank(row, search_upper).map(|rank| (row, rank)))
+            .filter_map(|row| {
+                calc_search_rank(row, search_upper, canonical_pair(row).as_deref())
+                    .map(|rank| (row, rank))
+            })
             .collect();
 
         // Sort by (match bucket/pos), then selected sort, then length as last resort
@@ -1085,10 +1268,8 @@ impl TickersTable {
             (ra.bucket, ra.pos)
                 .cmp(&(rb.bucket, rb.pos))
                 .then_with(|| match self.selected_sort_option {
-                    SortOptions::VolumeDesc => {
-                        b.stats.daily_volume.total_cmp(&a.stats.daily_volume)
-                    }
-                    SortOptions::VolumeAsc => a.stats.daily_volume.total_cmp(&b.stats.daily_volume),
+                    SortOptions::VolumeDesc => b.cmp_volume(a),
+                    SortOptions::VolumeAsc => a.cmp_volume(b),
                     SortOptions::ChangeDesc => {
                         b.stats.daily_price_chg.total_cmp(&a.stats.daily_price_chg)
                     }
@@ -1116,6 +1297,19 @@ impl TickersTable {
     }
 }
 
+/// Quote asset choice of the tickers table filter, `None` lists every quote
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct QuoteFilter(Option<Asset>);
+
+impl std::fmt::Display for QuoteFilter {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self.0 {
+            Some(quote) => write!(f, "Quoted in {quote}"),
+            None => write!(f, "All quote assets"),
+        }
+    }
+}
+
 /// Rank for search matching (lower = better).
 ///
 /// Bucket match kind first, then apply selected sort as the primary tiebreaker:
@@ -1131,7 +1325,14 @@ struct SearchRank {
 }
 
 /// Calculates a search rank for matching (lower = better match).
-fn calc_search_rank(row: &TickerRowData, query: &str) -> Option<SearchRank> {
+///
+/// `canonical_pair` lets queries like "BTC/USD" find the same market on every venue,
+/// whatever its symbol looks like there.
+fn calc_search_rank(
+    row: &TickerRowData,
+    query: &str,
+    canonical_pair: Option<&str>,
+) -> Option<SearchRank> {
     if query.is_empty() {
         return Some(SearchRank {
             bucket: 0,
@@ -1154,7 +1355,14 @@ fn calc_search_rank(row: &TickerRowData, query: &str) -> Option<SearchRank> {
 
     // For perps: do NOT allow "exact match" on the unsuffixed candidates, since the UI
     // label is effectively suffixed (e.g., "...P") and unsuffixed exact hits are misleading.
-    let score_candidate = |cand: &str, allow_exact: bool| -> Option<SearchRank> {
+    let pair_query: String = query.chars().filter(char::is_ascii_alphanumeric).collect();
+    let pair_suffixed = canonical_pair.map(|pair| format!("{pair}{suffix}"));
+
+    let score_candidate = |cand: &str, query: &str, allow_exact: bool| -> Option<SearchRank> {
+        if query.is_empty() {
+            return None;
+        }
+
         let (bucket, pos) = if allow_exact && cand == query {
             (0_u8, 0_usize) // exact
         } else if cand.starts_with(query) {
@@ -1178,13 +1386,22 @@ fn calc_search_rank(row: &TickerRowData, query: &str) -> Option<SearchRank> {
 
     // consider both "display" and "raw" representations, but with
     // explicit match-kind bucketing + a perp exact-match rule.
-    for (cand, allow_exact) in [
-        (display_str.as_str(), !is_perp),
-        (display_suffixed.as_str(), true),
-        (raw_str.as_str(), !is_perp),
-        (raw_suffixed.as_str(), true),
-    ] {
-        let Some(rank) = score_candidate(cand, allow_exact) else {
+    let candidates = [
+        (display_str.as_str(), query, !is_perp),
+        (display_suffixed.as_str(), query, true),
+        (raw_str.as_str(), query, !is_perp),
+        (raw_suffixed.as_str(), query, true),
+    ]
+    .into_iter()
+    .chain(canonical_pair.map(|pair| (pair, pair_query.as_str(), !is_perp)))
+    .chain(
+        pair_suffixed
+            .as_deref()
+            .map(|pair| (pair, pair_query.as_str(), true)),
+    );
+
+    for (cand, query, allow_exact) in candidates {
+        let Some(rank) = score_candidate(cand, query, allow_exact) else {
             continue;
         };
 